@@ -582,7 +582,7 @@ fn refs_from_aggregate(entries: &BTreeMap<String, AggregateEntry>) -> Vec<CrateR
     grouped
         .into_iter()
         .map(|(storage_name, mut entries)| {
-            entries.sort_by(|left, right| compare_entries_for_newest(left, right));
+            entries.sort_by(compare_entries_for_newest);
             let newest = entries[0];
             let mut aliases = BTreeMap::new();
             if entries.iter().any(|entry| entry.source == Source::Sysroot) {
@@ -707,7 +707,7 @@ fn select_planned_work(
 }
 
 fn has_work_capacity(current: usize, max_total: Option<usize>) -> bool {
-    max_total.map_or(true, |max| current < max)
+    max_total.is_none_or(|max| current < max)
 }
 
 fn fairness_sequence(mode: PlanMode) -> &'static [FairnessLane] {
@@ -717,6 +717,7 @@ fn fairness_sequence(mode: PlanMode) -> &'static [FairnessLane] {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn take_from_lane(
     lane: FairnessLane,
     popular: &mut VecDeque<WorkItem>,
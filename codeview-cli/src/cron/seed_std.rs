@@ -147,7 +147,7 @@ pub async fn run(args: SeedStd) -> Result<()> {
         }
 
         let alias_strs = aliases_for_toolchain(toolchain);
-        let aliases: Vec<&str> = alias_strs.iter().copied().collect();
+        let aliases: Vec<&str> = alias_strs.to_vec();
 
         for &crate_name in STD_JSON_CRATES {
             let Some(json_path) = info.json_path_for(crate_name) else {
@@ -0,0 +1,393 @@
+//! Structural diff between two workspace `graph.json` files.
+//!
+//! Nodes are matched by `id`. A node counts as changed when its
+//! `signature`, `visibility`, `effective_visibility`, `fields`, or
+//! `variants` differ; docs, spans, and other presentation-only data are
+//! ignored so that moving code around does not show up as an API change.
+//! Edges are matched by `(from, to, kind)`, so a confidence upgrade is not
+//! reported.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use codeview_core::{
+    CrateGraph, EdgeKind, FieldInfo, FunctionSignature, Node, NodeKind, SCHEMA_VERSION,
+    VariantInfo, Visibility, Workspace,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize)]
+pub struct GraphDiff {
+    pub added_nodes: Vec<NodeRef>,
+    pub removed_nodes: Vec<NodeRef>,
+    pub changed_nodes: Vec<NodeChange>,
+    pub added_edges: Vec<EdgeRef>,
+    pub removed_edges: Vec<EdgeRef>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NodeRef {
+    pub id: String,
+    pub kind: NodeKind,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NodeChange {
+    pub id: String,
+    pub kind: NodeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Change<Option<FunctionSignature>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Change<Visibility>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_visibility: Option<Change<Option<Visibility>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Change<Option<Vec<FieldInfo>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variants: Option<Change<Option<Vec<VariantInfo>>>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Change<T> {
+    pub old: T,
+    pub new: T,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct EdgeRef {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+impl NodeChange {
+    /// Names of the compared properties that differ, in a stable order.
+    pub fn changed_properties(&self) -> Vec<&'static str> {
+        let mut props = Vec::new();
+        if self.signature.is_some() {
+            props.push("signature");
+        }
+        if self.visibility.is_some() {
+            props.push("visibility");
+        }
+        if self.effective_visibility.is_some() {
+            props.push("effective_visibility");
+        }
+        if self.fields.is_some() {
+            props.push("fields");
+        }
+        if self.variants.is_some() {
+            props.push("variants");
+        }
+        props
+    }
+}
+
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+/// Load a `graph.json`. Accepts both a workspace (from `analyze`) and a
+/// single crate graph (from `parse-json`), which is wrapped as a one-crate
+/// workspace.
+pub fn load_workspace(path: &Path) -> Result<Workspace> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("failed to read graph {}", path.display()))?;
+    let value: serde_json::Value = serde_json::from_str(&json)
+        .with_context(|| format!("failed to parse graph {}", path.display()))?;
+    if value.get("crates").is_some() {
+        return codeview_core::migrate_workspace(value)
+            .with_context(|| format!("failed to parse workspace graph {}", path.display()));
+    }
+    let crate_graph: CrateGraph = serde_json::from_value(value)
+        .with_context(|| format!("failed to parse crate graph {}", path.display()))?;
+    Ok(Workspace {
+        version: SCHEMA_VERSION,
+        crates: vec![crate_graph],
+        external_crates: Vec::new(),
+        cross_crate_edges: Vec::new(),
+        repo: None,
+        ref_: None,
+    })
+}
+
+/// Fail early on a `graph.json` from a newer codeview, before serving it.
+/// Only the `version` field is looked at; single-crate graphs carry none
+/// and always pass. Older graphs are migrated when the UI loads them.
+pub fn check_graph_version(path: &Path) -> Result<()> {
+    #[derive(Deserialize)]
+    struct Header {
+        version: Option<u32>,
+    }
+    let file =
+        fs::File::open(path).with_context(|| format!("failed to read graph {}", path.display()))?;
+    let header: Header = serde_json::from_reader(std::io::BufReader::new(file))
+        .with_context(|| format!("failed to parse graph {}", path.display()))?;
+    codeview_core::check_schema_version(header.version.unwrap_or(SCHEMA_VERSION))
+        .with_context(|| format!("can't serve {}", path.display()))
+}
+
+/// Compare the workspace crates of two graphs. External crate stubs are
+/// skipped; they only mirror what the workspace references.
+pub fn diff_workspaces(old: &Workspace, new: &Workspace) -> GraphDiff {
+    let old_nodes = workspace_nodes(old);
+    let new_nodes = workspace_nodes(new);
+    let mut diff = GraphDiff::default();
+
+    for (id, node) in &new_nodes {
+        match old_nodes.get(id) {
+            None => diff.added_nodes.push(node_ref(node)),
+            Some(old_node) => {
+                if let Some(change) = node_change(old_node, node) {
+                    diff.changed_nodes.push(change);
+                }
+            }
+        }
+    }
+    for (id, node) in &old_nodes {
+        if !new_nodes.contains_key(id) {
+            diff.removed_nodes.push(node_ref(node));
+        }
+    }
+
+    let old_edges = workspace_edges(old);
+    let new_edges = workspace_edges(new);
+    diff.added_edges = new_edges.difference(&old_edges).cloned().collect();
+    diff.removed_edges = old_edges.difference(&new_edges).cloned().collect();
+
+    diff
+}
+
+fn workspace_nodes(workspace: &Workspace) -> BTreeMap<&str, &Node> {
+    workspace
+        .crates
+        .iter()
+        .flat_map(|krate| &krate.nodes)
+        .map(|node| (node.id.as_str(), node))
+        .collect()
+}
+
+fn workspace_edges(workspace: &Workspace) -> BTreeSet<EdgeRef> {
+    workspace
+        .crates
+        .iter()
+        .flat_map(|krate| &krate.edges)
+        .chain(&workspace.cross_crate_edges)
+        .map(|edge| EdgeRef {
+            from: edge.from.clone(),
+            to: edge.to.clone(),
+            kind: edge.kind,
+        })
+        .collect()
+}
+
+fn node_ref(node: &Node) -> NodeRef {
+    NodeRef {
+        id: node.id.clone(),
+        kind: node.kind,
+    }
+}
+
+fn node_change(old: &Node, new: &Node) -> Option<NodeChange> {
+    let change = NodeChange {
+        id: new.id.clone(),
+        kind: new.kind,
+        signature: changed(&old.signature, &new.signature),
+        visibility: changed(&old.visibility, &new.visibility),
+        effective_visibility: changed(&old.effective_visibility, &new.effective_visibility),
+        fields: changed(&old.fields, &new.fields),
+        variants: changed(&old.variants, &new.variants),
+    };
+    (!change.changed_properties().is_empty()).then_some(change)
+}
+
+fn changed<T: PartialEq + Clone>(old: &T, new: &T) -> Option<Change<T>> {
+    (old != new).then(|| Change {
+        old: old.clone(),
+        new: new.clone(),
+    })
+}
+
+/// Human-readable summary for terminal output.
+pub fn render_summary(diff: &GraphDiff) -> String {
+    let mut out = format!(
+        "nodes: +{} -{} ~{}\nedges: +{} -{}\n",
+        diff.added_nodes.len(),
+        diff.removed_nodes.len(),
+        diff.changed_nodes.len(),
+        diff.added_edges.len(),
+        diff.removed_edges.len(),
+    );
+    if diff.is_empty() {
+        return out;
+    }
+
+    if !diff.added_nodes.is_empty() {
+        out.push_str("\nadded nodes:\n");
+        for node in &diff.added_nodes {
+            out.push_str(&format!("  + {:?} {}\n", node.kind, node.id));
+        }
+    }
+    if !diff.removed_nodes.is_empty() {
+        out.push_str("\nremoved nodes:\n");
+        for node in &diff.removed_nodes {
+            out.push_str(&format!("  - {:?} {}\n", node.kind, node.id));
+        }
+    }
+    if !diff.changed_nodes.is_empty() {
+        out.push_str("\nchanged nodes:\n");
+        for node in &diff.changed_nodes {
+            out.push_str(&format!(
+                "  ~ {:?} {} ({})\n",
+                node.kind,
+                node.id,
+                node.changed_properties().join(", ")
+            ));
+        }
+    }
+    if !diff.added_edges.is_empty() {
+        out.push_str("\nadded edges:\n");
+        for edge in &diff.added_edges {
+            out.push_str(&format!(
+                "  + {} -{:?}-> {}\n",
+                edge.from, edge.kind, edge.to
+            ));
+        }
+    }
+    if !diff.removed_edges.is_empty() {
+        out.push_str("\nremoved edges:\n");
+        for edge in &diff.removed_edges {
+            out.push_str(&format!(
+                "  - {} -{:?}-> {}\n",
+                edge.from, edge.kind, edge.to
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codeview_core::{Confidence, Edge, TypeRef};
+
+    fn node(id: &str, kind: NodeKind) -> Node {
+        let name = id.rsplit("::").next().unwrap();
+        Node::new(id, name, kind, Visibility::Public)
+    }
+
+    fn edge(from: &str, to: &str, kind: EdgeKind) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+            confidence: Confidence::Static,
+            occurrences: Vec::new(),
+            is_glob: false,
+            label: None,
+        }
+    }
+
+    fn workspace(nodes: Vec<Node>, edges: Vec<Edge>) -> Workspace {
+        Workspace {
+            version: 1,
+            crates: vec![CrateGraph {
+                id: "demo".to_string(),
+                name: "demo".to_string(),
+                version: "0.1.0".to_string(),
+                nodes,
+                edges,
+                aliases: Default::default(),
+                doc_link_table: Vec::new(),
+            }],
+            external_crates: Vec::new(),
+            cross_crate_edges: Vec::new(),
+            repo: None,
+            ref_: None,
+        }
+    }
+
+    #[test]
+    fn reports_added_removed_and_changed_nodes() {
+        let mut field_struct = node("demo::Config", NodeKind::Struct);
+        field_struct.fields = Some(vec![FieldInfo {
+            name: "port".to_string(),
+            type_: TypeRef::Primitive {
+                name: "u16".to_string(),
+            },
+            visibility: Visibility::Public,
+        }]);
+        let old = workspace(
+            vec![
+                node("demo::run", NodeKind::Function),
+                node("demo::legacy", NodeKind::Function),
+                field_struct.clone(),
+            ],
+            vec![edge("demo::run", "demo::legacy", EdgeKind::CallsStatic)],
+        );
+
+        let mut run = node("demo::run", NodeKind::Function);
+        run.visibility = Visibility::Crate;
+        field_struct.fields.as_mut().unwrap()[0].type_ = TypeRef::Primitive {
+            name: "u32".to_string(),
+        };
+        let new = workspace(
+            vec![run, node("demo::serve", NodeKind::Function), field_struct],
+            vec![edge("demo::run", "demo::serve", EdgeKind::CallsStatic)],
+        );
+
+        let diff = diff_workspaces(&old, &new);
+        assert_eq!(
+            diff.added_nodes,
+            vec![NodeRef {
+                id: "demo::serve".to_string(),
+                kind: NodeKind::Function,
+            }]
+        );
+        assert_eq!(diff.removed_nodes.len(), 1);
+        assert_eq!(diff.removed_nodes[0].id, "demo::legacy");
+
+        let changed: Vec<_> = diff
+            .changed_nodes
+            .iter()
+            .map(|change| (change.id.as_str(), change.changed_properties()))
+            .collect();
+        assert_eq!(
+            changed,
+            vec![
+                ("demo::Config", vec!["fields"]),
+                ("demo::run", vec!["visibility"]),
+            ]
+        );
+
+        assert_eq!(diff.added_edges.len(), 1);
+        assert_eq!(diff.added_edges[0].to, "demo::serve");
+        assert_eq!(diff.removed_edges.len(), 1);
+        assert_eq!(diff.removed_edges[0].to, "demo::legacy");
+    }
+
+    #[test]
+    fn ignores_docs_spans_and_edge_confidence() {
+        let old = workspace(
+            vec![node("demo::run", NodeKind::Function)],
+            vec![edge("demo::run", "demo::run", EdgeKind::CallsStatic)],
+        );
+        let mut run = node("demo::run", NodeKind::Function);
+        run.docs = Some("Now documented.".to_string());
+        let mut recursive = edge("demo::run", "demo::run", EdgeKind::CallsStatic);
+        recursive.confidence = Confidence::Inferred;
+        let new = workspace(vec![run], vec![recursive]);
+
+        let diff = diff_workspaces(&old, &new);
+        assert!(diff.is_empty());
+        assert_eq!(render_summary(&diff), "nodes: +0 -0 ~0\nedges: +0 -0\n");
+    }
+}
@@ -1,18 +1,24 @@
 mod cron;
+mod diff;
 mod publisher;
 mod sysroot;
 
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand, ValueEnum};
-use codeview_core::CrateGraph;
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use codeview_core::export::{self, MermaidFlowOptions, MermaidKind};
+use codeview_core::{CrateGraph, EdgeKind, NodeKind};
 use codeview_rustdoc::{
-    CallMode, generate_workspace_rustdoc_json, load_graph_from_path,
-    load_graph_from_path_with_sources, load_workspace_graph,
+    BlanketImpls, CallMode, CrateFilter, DEFAULT_MAX_MODULE_DEPTH, DEFAULT_SKIPPED_EXTERNAL_CRATES,
+    DocTargetKind, ExtractOptions, RustdocJson, RustdocOptions, WorkspaceCratePlan,
+    extract_graph_reader, generate_workspace_rustdoc_json, load_graph_from_path,
+    load_graph_from_path_with_sources, load_workspace_graph, load_workspace_graph_from_json,
+    plan_workspace_crates,
 };
 use serde::{Deserialize, Serialize};
 
@@ -47,58 +53,80 @@ enum Commands {
         graph: Option<PathBuf>,
         #[arg(long, value_enum, default_value = "strict")]
         call_mode: CallModeArg,
+        /// Reuse cached rustdoc JSON for crates whose sources are unchanged
+        #[arg(long)]
+        incremental: bool,
+        /// Stop at the first crate `cargo rustdoc` fails on instead of
+        /// leaving it out of the graph
+        #[arg(long)]
+        strict_docs: bool,
+        /// Re-analyze (incrementally) when a member's `src/` or manifest
+        /// changes, rewriting the graph the server reads. The server picks
+        /// it up on its next poll; nothing is pushed to open pages
+        #[arg(long, conflicts_with = "graph")]
+        watch: bool,
+        #[command(flatten)]
+        crates: CrateFilterArgs,
         /// Extra arguments to pass to cargo rustdoc (e.g. --all-features, --features "uuid")
         #[arg(last = true)]
         cargo_args: Vec<String>,
     },
     /// List running codeview server instances
-    Ps,
-    /// Analyze without opening UI (just generate graph.json)
-    Analyze {
+    Ps {
+        /// Print the instances as a JSON array instead of a table
         #[arg(long)]
-        manifest_path: Option<PathBuf>,
-        #[arg(long)]
-        out: Option<PathBuf>,
-        /// Show cargo rustdoc output and detailed progress
-        #[arg(long, short)]
-        verbose: bool,
-        #[arg(long, value_enum, default_value = "strict")]
-        call_mode: CallModeArg,
-        /// Extra arguments to pass to cargo rustdoc (e.g. --all-features, --features "uuid")
-        #[arg(last = true)]
-        cargo_args: Vec<String>,
+        json: bool,
+        /// Shut down the instance with this pid and remove its registration
+        #[arg(long, value_name = "PID", conflicts_with = "json")]
+        kill: Option<u32>,
     },
+    /// Analyze without opening UI (just generate graph.json)
+    Analyze(AnalyzeArgs),
     /// Parse an existing rustdoc JSON file into a single-crate graph
-    ParseJson {
-        /// Path to rustdoc JSON
-        #[arg(long)]
-        json: PathBuf,
-        /// Package/crate name to use in graph IDs
+    ParseJson(ParseJsonArgs),
+    /// Build a workspace graph.json from pre-generated rustdoc JSON, without
+    /// running cargo. Sources aren't read, so there are no call edges
+    FromJson {
+        /// Rustdoc JSON for the first crate
+        path: Option<PathBuf>,
+        /// Rustdoc JSON for another crate. Repeatable; pairs with
+        /// `--crate-name` in order, after `PATH`
         #[arg(long)]
-        crate_name: String,
-        /// Version string to write into the graph
-        #[arg(long)]
-        version: String,
+        json: Vec<PathBuf>,
+        /// Crate name to use in graph IDs, one per JSON file. Repeatable
+        #[arg(long, required = true)]
+        crate_name: Vec<String>,
         /// Output graph.json path
         #[arg(long)]
         out: PathBuf,
-        /// Cargo.toml for source-backed parsing. Enables call-edge extraction.
-        #[arg(long)]
-        manifest_path: Option<PathBuf>,
-        /// Crate root source file for source-backed parsing.
+        /// What to build for blanket impls such as `impl<T> Any for T`
+        #[arg(long, value_enum, default_value = "per-impl")]
+        blanket_impls: BlanketImplsArg,
+        /// Keep `#[doc(hidden)]` items and those under `__`-prefixed paths
         #[arg(long)]
-        root_file: Option<PathBuf>,
-        /// Target name rustdoc used internally when it differs from package name.
+        include_hidden: bool,
+        /// Write the JSON without indentation
         #[arg(long)]
-        rustdoc_name: Option<String>,
-        #[arg(long, value_enum, default_value = "strict")]
-        call_mode: CallModeArg,
+        compact: bool,
+    },
+    /// Compare two graph.json files and report API and call-structure changes
+    Diff {
+        /// Baseline graph.json
+        old: PathBuf,
+        /// Updated graph.json
+        new: PathBuf,
+        #[arg(long, value_enum, default_value = "summary")]
+        format: DiffFormat,
     },
     /// Emit the canonical JSON Schema for Codeview graph data
     Schema {
         /// Output schema path. Writes to stdout when omitted.
         #[arg(long)]
         out: Option<PathBuf>,
+        /// Describe a single-crate graph (`parse-json` output) instead of a
+        /// workspace
+        #[arg(long)]
+        graph: bool,
     },
     /// Cron pipeline — sweep crates.io, parse stale ones, upload to R2.
     ///
@@ -107,10 +135,306 @@ enum Commands {
     Cron(cron::CronArgs),
 }
 
+/// `codeview analyze`. The report flags (`--dead-code`, `--cycles`, ...)
+/// each replace the graph output, so at most one is accepted.
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("report").multiple(false)))]
+struct AnalyzeArgs {
+    #[arg(long)]
+    manifest_path: Option<PathBuf>,
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Show cargo rustdoc output and detailed progress
+    #[arg(long, short)]
+    verbose: bool,
+    #[arg(long, value_enum, default_value = "strict")]
+    call_mode: CallModeArg,
+    /// Store doc-link targets once per crate in `doc_link_table`
+    /// instead of inline on every node (the UI expands them on load;
+    /// other readers must do the same)
+    #[arg(long)]
+    intern_doc_links: bool,
+    /// Reuse cached rustdoc JSON for crates whose sources are unchanged
+    #[arg(long)]
+    incremental: bool,
+    /// Stop at the first crate `cargo rustdoc` fails on instead of
+    /// leaving it out of the graph
+    #[arg(long)]
+    strict_docs: bool,
+    #[command(flatten)]
+    crates: CrateFilterArgs,
+    /// Print the workspace members that would be documented, then exit
+    /// without running rustdoc
+    #[arg(long, group = "report")]
+    list_crates: bool,
+    /// Output format [default: json]. Non-JSON formats flatten the
+    /// workspace into one graph and write to stdout when `--out` is
+    /// omitted.
+    #[arg(long, value_enum)]
+    format: Option<AnalyzeFormat>,
+    /// Diagram style for `--format mermaid`
+    #[arg(long, value_enum, default_value = "flow")]
+    mermaid_kind: MermaidKindArg,
+    /// For `--mermaid-kind flow`, fold modules at this depth (the crate
+    /// root is 0) into single nodes
+    #[arg(long)]
+    collapse_depth: Option<usize>,
+    /// Print functions that no call path reaches from an entry point or
+    /// the public API, one id per line, instead of writing the graph
+    #[arg(long, group = "report")]
+    dead_code: bool,
+    /// Write `--format json` output without indentation
+    #[arg(long)]
+    compact: bool,
+    /// Print each cycle over these edge kinds (e.g. `uses-type`), one
+    /// sorted, comma-separated group of node ids per line, instead of
+    /// writing the graph
+    #[arg(long, value_enum, value_delimiter = ',', group = "report")]
+    cycles: Vec<EdgeKindArg>,
+    /// Print node, edge and confidence counts, call fan-in/fan-out and
+    /// module depth as a table (or JSON with `--format json`) instead
+    /// of writing the graph
+    #[arg(long, group = "report")]
+    metrics: bool,
+    /// Print dangling edges, duplicate or empty node ids, self-loops
+    /// and orphan nodes, one per line, instead of writing the graph
+    #[arg(long, group = "report")]
+    validate: bool,
+    /// Print one row per local function (id, visibility, async, unsafe
+    /// and const flags, signature) as CSV or a Markdown table instead of
+    /// writing the graph
+    #[arg(long, value_enum, value_name = "FORMAT", group = "report")]
+    list_functions: Option<FunctionListFormat>,
+    /// What to build for blanket impls such as `impl<T> Any for T`
+    #[arg(long, value_enum, default_value = "per-impl")]
+    blanket_impls: BlanketImplsArg,
+    /// Where the items of an impl block hang: off the impl only, also
+    /// straight off the implementing type, or off the type with
+    /// inherent impl nodes left out
+    #[arg(long, value_enum, default_value = "impl")]
+    impl_items: ImplItemsArg,
+    /// Add inferred call edges from documented items to the functions
+    /// their doc-test examples call
+    #[arg(long)]
+    doctest_calls: bool,
+    /// Record each function's approximate cyclomatic complexity
+    /// (branches, loops, extra match arms, `&&`/`||` and `?`) on its node
+    #[arg(long)]
+    complexity: bool,
+    /// Also put the crate's public items under every module that
+    /// `pub use`s them, with an inferred `Contains` edge
+    #[arg(long)]
+    flatten_reexports: bool,
+    /// Leave out `std`, `core`, `alloc` and `proc_macro` items and the
+    /// edges into them, keeping other external crates
+    #[arg(long)]
+    no_std_nodes: bool,
+    /// Stop following nested `mod` declarations this deep when
+    /// extracting call edges
+    #[arg(long, default_value_t = DEFAULT_MAX_MODULE_DEPTH)]
+    max_module_depth: usize,
+    /// Keep `#[doc(hidden)]` items and those under `__`-prefixed paths
+    #[arg(long)]
+    include_hidden: bool,
+    /// Also treat functions with this attribute (e.g. `my_app::handler`,
+    /// matched by trailing path segments) as entry points. Repeatable
+    #[arg(long = "entry-attribute", value_name = "PATH")]
+    entry_attributes: Vec<String>,
+    /// Parse and extract at most this many crates at once [default: one
+    /// per core]. Each holds its rustdoc JSON in memory while in flight
+    #[arg(long, short)]
+    jobs: Option<std::num::NonZeroUsize>,
+    /// Also document the workspace with this feature set (a
+    /// comma-separated list, or `all` for `--all-features`) and tag
+    /// items the default build lacks with the smallest set that has
+    /// them. Each member is built with the listed features it declares.
+    /// Repeatable
+    #[arg(long = "feature-set", value_name = "FEATURES")]
+    feature_sets: Vec<String>,
+    /// Drop functions outside the public API, linking each caller to the
+    /// public functions it reached through them with inferred call edges
+    #[arg(long)]
+    contract_private_calls: bool,
+    /// Drop items outside the public API, keeping the modules that lead
+    /// to public items, along with every edge that touches them
+    #[arg(long)]
+    only_public: bool,
+    /// With `--only-public`, keep `pub(crate)` items too
+    #[arg(long, requires = "only_public")]
+    include_crate_visible: bool,
+    /// Keep only nodes of these kinds (e.g. `struct,enum,trait`) and
+    /// the edges between them
+    #[arg(long, value_enum, value_delimiter = ',')]
+    kinds: Vec<NodeKindArg>,
+    /// Extra arguments to pass to cargo rustdoc (e.g. --all-features, --features "uuid")
+    #[arg(last = true)]
+    cargo_args: Vec<String>,
+}
+
+/// `codeview parse-json`.
+#[derive(Args, Debug)]
+struct ParseJsonArgs {
+    /// Path to rustdoc JSON, or `-` to stream it from stdin (without
+    /// sources, and only in the format_version this build reads)
+    #[arg(long)]
+    json: PathBuf,
+    /// Package/crate name to use in graph IDs
+    #[arg(long)]
+    crate_name: String,
+    /// Version string to write into the graph. Defaults to the version
+    /// rustdoc or cargo recorded, else `0.0.0`
+    #[arg(long)]
+    version: Option<String>,
+    /// Output graph.json path
+    #[arg(long)]
+    out: PathBuf,
+    /// Cargo.toml for source-backed parsing. Enables call-edge extraction.
+    #[arg(long)]
+    manifest_path: Option<PathBuf>,
+    /// Crate root source file for source-backed parsing.
+    #[arg(long)]
+    root_file: Option<PathBuf>,
+    /// Target name rustdoc used internally when it differs from package name.
+    #[arg(long)]
+    rustdoc_name: Option<String>,
+    #[arg(long, value_enum, default_value = "strict")]
+    call_mode: CallModeArg,
+    /// Store doc-link targets once per crate in `doc_link_table`
+    /// instead of inline on every node (the UI expands them on load;
+    /// other readers must do the same)
+    #[arg(long)]
+    intern_doc_links: bool,
+}
+
+/// Workspace members to document. Excluded members are skipped before
+/// rustdoc runs; edges into them still reach external-crate stubs.
+#[derive(Args, Clone, Debug, Default)]
+struct CrateFilterArgs {
+    /// Only document members matching this glob (`-` and `_` are
+    /// interchangeable). Repeatable
+    #[arg(long = "include-crate", value_name = "GLOB")]
+    include: Vec<String>,
+    /// Skip members matching this glob. Repeatable; wins over
+    /// `--include-crate`
+    #[arg(long = "exclude-crate", value_name = "GLOB")]
+    exclude: Vec<String>,
+}
+
+impl From<CrateFilterArgs> for CrateFilter {
+    fn from(value: CrateFilterArgs) -> Self {
+        CrateFilter {
+            include: value.include,
+            exclude: value.exclude,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, ValueEnum)]
 enum CallModeArg {
+    /// Only link calls that resolve to exactly one function
     Strict,
+    /// Link every candidate of an ambiguous call as `Inferred`
     Ambiguous,
+    /// Strict, but calls through `dyn Trait` link to every impl as runtime
+    /// calls
+    Heuristic,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum BlanketImplsArg {
+    /// One set of item nodes per concrete impl
+    PerImpl,
+    /// One shared node per trait item, defined by every blanket impl
+    Collapse,
+    /// No nodes for blanket or auto-trait impls
+    Skip,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum FunctionListFormat {
+    /// Comma-separated values under a header row
+    Csv,
+    /// A Markdown table
+    Markdown,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum ImplItemsArg {
+    /// `Defines` edges from the impl node only
+    Impl,
+    /// Also a `Defines` edge from the implementing type to each item
+    Linked,
+    /// Like `linked`, dropping inherent impl nodes
+    Reparent,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum AnalyzeFormat {
+    /// The workspace graph.json
+    Json,
+    /// A Mermaid diagram (see `--mermaid-kind`)
+    Mermaid,
+    /// Graphviz DOT
+    Dot,
+    /// SVG rendered from the DOT output; needs Graphviz's `dot` on PATH
+    Svg,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum MermaidKindArg {
+    /// Every node and edge as a flowchart
+    Flow,
+    /// Types and traits as a class diagram
+    Class,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum NodeKindArg {
+    Crate,
+    Module,
+    Struct,
+    StructField,
+    Union,
+    Enum,
+    Variant,
+    Trait,
+    TraitAlias,
+    Impl,
+    Function,
+    TypeAlias,
+    AssocType,
+    Constant,
+    AssocConst,
+    Static,
+    Macro,
+    Primitive,
+    ExternCrate,
+    Import,
+    ProcMacro,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum EdgeKindArg {
+    Contains,
+    Defines,
+    Implements,
+    Supertrait,
+    UsesType,
+    Returns,
+    CallsStatic,
+    CallsRuntime,
+    CallsMacro,
+    Derives,
+    ReExports,
+    HasField,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum DiffFormat {
+    /// Counts followed by one line per added/removed/changed item
+    Summary,
+    /// The full delta, including old and new values of changed properties
+    Json,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -155,10 +479,15 @@ fn main() -> Result<()> {
             verbose,
             graph,
             call_mode,
+            incremental,
+            strict_docs,
+            watch,
+            crates,
             cargo_args,
         } => {
             // If --graph is provided, just serve that directly
             if let Some(graph_path) = graph {
+                diff::check_graph_version(&graph_path)?;
                 let workspace_root = workspace_root_from_graph(&graph_path);
                 return serve_ui(port, open, verbose, graph_path, workspace_root);
             }
@@ -174,43 +503,74 @@ fn main() -> Result<()> {
                 anyhow::bail!("No Cargo.toml found at {}", manifest_path.display());
             }
 
-            let graph_path = analyze_workspace(&manifest_path, call_mode, &cargo_args, verbose)?;
+            let rustdoc = RustdocOptions {
+                cargo_args,
+                filter: crates.into(),
+                verbose,
+                incremental,
+                fail_fast: strict_docs,
+                cancel: None,
+            };
+            let graph_path = analyze_workspace(&manifest_path, call_mode, &rustdoc)?;
             let workspace_root = manifest_path.parent().map(|p| p.to_path_buf());
+            // Dropping the watcher stops it, so it lives as long as the server.
+            let _watcher = if watch {
+                Some(watch_workspace(manifest_path, call_mode, rustdoc)?)
+            } else {
+                None
+            };
             serve_ui(port, open, verbose, graph_path, workspace_root)
         }
-        Commands::Ps => list_instances(),
-        Commands::Analyze {
-            manifest_path,
-            out,
-            verbose,
-            call_mode,
-            cargo_args,
-        } => analyze(manifest_path, out, verbose, call_mode, cargo_args),
-        Commands::ParseJson {
+        Commands::Ps { json, kill } => match kill {
+            Some(pid) => kill_instance(pid),
+            None => list_instances(json),
+        },
+        Commands::Analyze(args) if args.list_crates => {
+            let manifest_path = args
+                .manifest_path
+                .unwrap_or_else(|| PathBuf::from("Cargo.toml"));
+            list_workspace_crates(&manifest_path, &args.cargo_args, &args.crates.into())
+        }
+        Commands::Analyze(args) => analyze(args),
+        Commands::ParseJson(args) => parse_json(args),
+        Commands::FromJson {
+            path,
             json,
             crate_name,
-            version,
             out,
-            manifest_path,
-            root_file,
-            rustdoc_name,
-            call_mode,
-        } => parse_json(
-            json,
+            blanket_impls,
+            include_hidden,
+            compact,
+        } => from_json(
+            path.into_iter().chain(json).collect(),
             crate_name,
-            version,
             out,
-            manifest_path,
-            root_file,
-            rustdoc_name,
-            call_mode,
+            blanket_impls,
+            include_hidden,
+            compact,
         ),
-        Commands::Schema { out } => export_schema(out),
+        Commands::Diff { old, new, format } => diff_graphs(&old, &new, format),
+        Commands::Schema { out, graph } => export_schema(out, graph),
+    }
+}
+
+fn diff_graphs(old: &Path, new: &Path, format: DiffFormat) -> Result<()> {
+    let old = diff::load_workspace(old)?;
+    let new = diff::load_workspace(new)?;
+    let delta = diff::diff_workspaces(&old, &new);
+    match format {
+        DiffFormat::Summary => print!("{}", diff::render_summary(&delta)),
+        DiffFormat::Json => println!("{}", serde_json::to_string_pretty(&delta)?),
     }
+    Ok(())
 }
 
-fn export_schema(out: Option<PathBuf>) -> Result<()> {
-    let schema = schemars::schema_for!(codeview_core::Workspace);
+fn export_schema(out: Option<PathBuf>, graph: bool) -> Result<()> {
+    let schema = if graph {
+        codeview_core::graph_schema()
+    } else {
+        codeview_core::workspace_schema()
+    };
     let json = serde_json::to_string_pretty(&schema)?;
     if let Some(path) = out {
         if let Some(parent) = path.parent()
@@ -231,10 +591,10 @@ fn export_schema(out: Option<PathBuf>) -> Result<()> {
 fn analyze_workspace(
     manifest_path: &Path,
     call_mode: CallModeArg,
-    cargo_args: &[String],
-    verbose: bool,
+    rustdoc: &RustdocOptions<'_>,
 ) -> Result<PathBuf> {
-    let rustdoc_jsons = generate_workspace_rustdoc_json(manifest_path, cargo_args, verbose)?;
+    let verbose = rustdoc.verbose;
+    let rustdoc_jsons = generate_workspace_rustdoc_json(manifest_path, rustdoc)?;
     if rustdoc_jsons.is_empty() {
         anyhow::bail!("No crates were successfully documented");
     }
@@ -243,7 +603,12 @@ fn analyze_workspace(
         eprintln!("Merging {} crate graphs...", rustdoc_jsons.len());
     }
 
-    let workspace = load_workspace_graph(&rustdoc_jsons, manifest_path, call_mode.into())?;
+    let workspace = load_workspace_graph(
+        &rustdoc_jsons,
+        manifest_path,
+        call_mode.into(),
+        &ExtractOptions::default(),
+    )?;
 
     if verbose {
         let total_nodes: usize = workspace
@@ -277,9 +642,7 @@ fn analyze_workspace(
         fs::create_dir_all(parent)
             .with_context(|| format!("failed to create output dir {}", parent.display()))?;
     }
-    let json = serde_json::to_string_pretty(&workspace)?;
-    fs::write(&out_path, &json)
-        .with_context(|| format!("failed to write graph to {}", out_path.display()))?;
+    write_workspace_json(&out_path, &workspace, false)?;
 
     if verbose {
         eprintln!("Wrote graph to {}", out_path.display());
@@ -287,25 +650,248 @@ fn analyze_workspace(
     Ok(out_path)
 }
 
-fn analyze(
-    manifest_path: Option<PathBuf>,
-    out: Option<PathBuf>,
-    verbose: bool,
+/// Watch each selected member's `src/` tree and manifest, re-running
+/// [`analyze_workspace`] (always incrementally) once changes settle. The
+/// graph is rewritten in place, and the UI server reloads it when it sees
+/// the new modification time. Analysis failures are reported and the
+/// previous graph is kept. There is no websocket or SSE channel to tell the
+/// sidecar or the browser a new graph is ready; reloading stays on the
+/// server's polling.
+fn watch_workspace(
+    manifest_path: PathBuf,
     call_mode: CallModeArg,
-    cargo_args: Vec<String>,
-) -> Result<()> {
-    let manifest_path = manifest_path.unwrap_or_else(|| PathBuf::from("Cargo.toml"));
+    rustdoc: RustdocOptions<'static>,
+) -> Result<notify_debouncer_mini::Debouncer<notify_debouncer_mini::notify::RecommendedWatcher>> {
+    use notify_debouncer_mini::notify::RecursiveMode;
 
-    let rustdoc_jsons = generate_workspace_rustdoc_json(&manifest_path, &cargo_args, verbose)?;
-    if rustdoc_jsons.is_empty() {
-        anyhow::bail!("No crates were successfully documented");
+    let rustdoc = RustdocOptions {
+        incremental: true,
+        ..rustdoc
+    };
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut debouncer =
+        notify_debouncer_mini::new_debouncer(std::time::Duration::from_millis(500), tx)
+            .context("failed to start file watcher")?;
+    let mut watched = 0;
+    for plan in plan_workspace_crates(&manifest_path)? {
+        if !rustdoc.filter.matches(&plan.crate_name) {
+            continue;
+        }
+        let Some(dir) = plan.manifest_path.parent() else {
+            continue;
+        };
+        let src = dir.join("src");
+        if src.is_dir() {
+            debouncer
+                .watcher()
+                .watch(&src, RecursiveMode::Recursive)
+                .with_context(|| format!("failed to watch {}", src.display()))?;
+            watched += 1;
+        }
+        debouncer
+            .watcher()
+            .watch(&plan.manifest_path, RecursiveMode::NonRecursive)
+            .with_context(|| format!("failed to watch {}", plan.manifest_path.display()))?;
+    }
+    eprintln!("Watching {watched} source directories for changes");
+
+    std::thread::spawn(move || {
+        let mut since = std::time::SystemTime::now();
+        for events in &rx {
+            let events = match events {
+                Ok(events) => events,
+                Err(err) => {
+                    eprintln!("Warning: file watcher error: {err}");
+                    continue;
+                }
+            };
+            // Analysis itself reads every watched file, and those reads
+            // come through as events too; only count paths that were
+            // written (or removed) since the last run started.
+            let changed: Vec<_> = events
+                .iter()
+                .filter(|event| {
+                    fs::metadata(&event.path)
+                        .and_then(|metadata| metadata.modified())
+                        .map_or(true, |modified| modified >= since)
+                })
+                .collect();
+            if changed.is_empty() {
+                continue;
+            }
+            if rustdoc.verbose {
+                for event in &changed {
+                    eprintln!("Changed: {}", event.path.display());
+                }
+            }
+            // Batches that queued up while the last run was busy are all
+            // covered by this one.
+            while rx.try_recv().is_ok() {}
+            since = std::time::SystemTime::now();
+            eprintln!("Sources changed, re-analyzing...");
+            match analyze_workspace(&manifest_path, call_mode, &rustdoc) {
+                Ok(path) => eprintln!("Updated {}", path.display()),
+                Err(err) => eprintln!("Error: re-analysis failed: {err:#}"),
+            }
+        }
+    });
+    Ok(debouncer)
+}
+
+fn list_workspace_crates(
+    manifest_path: &Path,
+    cargo_args: &[String],
+    crates: &CrateFilter,
+) -> Result<()> {
+    let mut plans = plan_workspace_crates(manifest_path)?;
+    plans.retain(|plan| crates.matches(&plan.crate_name));
+    if !cargo_args.is_empty() {
+        println!("cargo rustdoc args: {}", cargo_args.join(" "));
+    }
+    println!(
+        "{:<28} {:<12} {:<24} DOCUMENTED",
+        "CRATE", "VERSION", "TARGET"
+    );
+    for plan in &plans {
+        let (target, documented) = match &plan.target {
+            Some(target) => {
+                let kind = match target.kind {
+                    DocTargetKind::Lib => "lib",
+                    DocTargetKind::ProcMacro => "proc-macro",
+                    DocTargetKind::Bin => "bin",
+                    DocTargetKind::Example => "example",
+                    DocTargetKind::Test => "test",
+                    DocTargetKind::Bench => "bench",
+                };
+                (format!("{kind} ({})", target.name), "yes")
+            }
+            None => ("-".to_string(), "no (no documentable target)"),
+        };
+        println!(
+            "{:<28} {:<12} {:<24} {}",
+            plan.crate_name, plan.version, target, documented
+        );
     }
+    Ok(())
+}
 
-    if verbose {
-        eprintln!("Merging {} crate graphs...", rustdoc_jsons.len());
+fn analyze(args: AnalyzeArgs) -> Result<()> {
+    let AnalyzeArgs {
+        manifest_path,
+        out,
+        verbose,
+        call_mode,
+        intern_doc_links,
+        incremental,
+        strict_docs,
+        crates,
+        format,
+        mermaid_kind,
+        collapse_depth,
+        dead_code,
+        compact,
+        cycles,
+        metrics,
+        validate,
+        list_functions,
+        blanket_impls,
+        impl_items,
+        doctest_calls,
+        complexity,
+        flatten_reexports,
+        no_std_nodes,
+        max_module_depth,
+        include_hidden,
+        entry_attributes,
+        jobs,
+        feature_sets,
+        contract_private_calls,
+        only_public,
+        include_crate_visible,
+        kinds,
+        cargo_args,
+        list_crates: _,
+    } = args;
+    let crates: CrateFilter = crates.into();
+    let manifest_path = manifest_path.unwrap_or_else(|| PathBuf::from("Cargo.toml"));
+    let mut options = ExtractOptions {
+        max_module_depth,
+        include_hidden,
+        blanket_impls: blanket_impls.into(),
+        doctest_calls,
+        complexity,
+        flatten_reexports,
+        jobs: jobs.map(std::num::NonZeroUsize::get),
+        ..ExtractOptions::default()
+    };
+    if no_std_nodes {
+        options.skip_external_crates = DEFAULT_SKIPPED_EXTERNAL_CRATES
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
     }
+    options.entry_point_attributes.extend(entry_attributes);
+
+    let build = |passes: &[FeaturePass]| -> Result<(Vec<RustdocJson>, codeview_core::Workspace)> {
+        let mut rustdoc_jsons = Vec::new();
+        for (crates, cargo_args) in passes {
+            rustdoc_jsons.extend(generate_workspace_rustdoc_json(
+                &manifest_path,
+                &RustdocOptions {
+                    cargo_args: cargo_args.clone(),
+                    filter: crates.clone(),
+                    verbose,
+                    incremental,
+                    fail_fast: strict_docs,
+                    cancel: None,
+                },
+            )?);
+        }
+        if rustdoc_jsons.is_empty() {
+            anyhow::bail!("No crates were successfully documented");
+        }
 
-    let workspace = load_workspace_graph(&rustdoc_jsons, &manifest_path, call_mode.into())?;
+        if verbose {
+            eprintln!("Merging {} crate graphs...", rustdoc_jsons.len());
+        }
+
+        let workspace =
+            load_workspace_graph(&rustdoc_jsons, &manifest_path, call_mode.into(), &options)?;
+        Ok((rustdoc_jsons, workspace))
+    };
+    let (rustdoc_jsons, mut workspace) = build(&[(crates.clone(), cargo_args.clone())])?;
+    if !feature_sets.is_empty() {
+        // Each build overwrites the last one's JSON, so load as we go.
+        let mut variants = Vec::new();
+        for feature_set in &feature_sets {
+            let (features, mut passes) = feature_set_args(&manifest_path, &crates, feature_set)?;
+            if verbose {
+                eprintln!("Documenting with features: {}", features.join(", "));
+            }
+            for (_, flags) in &mut passes {
+                flags.extend(cargo_args.iter().cloned());
+            }
+            variants.push((features, build(&passes)?.1));
+        }
+        workspace = workspace.merge_feature_variants(variants);
+    }
+    if impl_items != ImplItemsArg::Impl {
+        workspace.link_impl_items(impl_items == ImplItemsArg::Reparent);
+    }
+    if contract_private_calls {
+        workspace.contract_private_calls();
+    }
+    if only_public {
+        workspace.retain_public(include_crate_visible);
+    }
+    if !kinds.is_empty() {
+        workspace.retain_kinds(&kinds.into_iter().map(NodeKind::from).collect());
+    }
+    if intern_doc_links {
+        for crate_graph in &mut workspace.crates {
+            crate_graph.intern_doc_links();
+        }
+    }
 
     if verbose {
         let total_nodes: usize = workspace
@@ -334,31 +920,361 @@ fn analyze(
         );
     }
 
+    if dead_code {
+        let graph = workspace.merged_graph();
+        let roots: Vec<&str> = graph
+            .entry_points()
+            .into_iter()
+            .map(|node| node.id.as_str())
+            .collect();
+        let dead = graph.unreachable_functions(&roots, true);
+        let report: String = dead.iter().map(|node| format!("{}\n", node.id)).collect();
+        if verbose {
+            eprintln!("{} likely dead functions", dead.len());
+        }
+        return write_rendered(out, &report);
+    }
+
+    if !cycles.is_empty() {
+        let kinds: Vec<EdgeKind> = cycles.into_iter().map(EdgeKind::from).collect();
+        let found = workspace.merged_graph().find_cycles(&kinds);
+        let report: String = found
+            .iter()
+            .map(|cycle| format!("{}\n", cycle.join(", ")))
+            .collect();
+        if verbose {
+            eprintln!("{} cycles", found.len());
+        }
+        return write_rendered(out, &report);
+    }
+
+    if validate {
+        let warnings = workspace.merged_graph().validate();
+        let report: String = warnings
+            .iter()
+            .map(|warning| format!("{warning}\n"))
+            .collect();
+        if verbose {
+            eprintln!("{} graph warnings", warnings.len());
+        }
+        return write_rendered(out, &report);
+    }
+
+    if let Some(list_format) = list_functions {
+        let graph = workspace.merged_graph();
+        let report = match list_format {
+            FunctionListFormat::Csv => export::export_functions_csv(&graph),
+            FunctionListFormat::Markdown => export::export_functions_markdown(&graph),
+        };
+        return write_rendered(out, &report);
+    }
+
+    if metrics {
+        let metrics = workspace.merged_graph().graph_metrics();
+        let report = match format {
+            Some(AnalyzeFormat::Json) if compact => serde_json::to_string(&metrics)? + "\n",
+            Some(AnalyzeFormat::Json) => serde_json::to_string_pretty(&metrics)? + "\n",
+            _ => render_metrics(&metrics),
+        };
+        return write_rendered(out, &report);
+    }
+
+    let format = format.unwrap_or(AnalyzeFormat::Json);
+    if format != AnalyzeFormat::Json {
+        let rendered = render_workspace(
+            &workspace,
+            format,
+            mermaid_kind,
+            &MermaidFlowOptions { collapse_depth },
+        )?;
+        return write_rendered(out, &rendered);
+    }
+
     let out_path = out.unwrap_or_else(|| default_graph_path(&rustdoc_jsons[0].json_path));
     if let Some(parent) = out_path.parent() {
         fs::create_dir_all(parent)
             .with_context(|| format!("failed to create output dir {}", parent.display()))?;
     }
-    let json = serde_json::to_string_pretty(&workspace)?;
-    fs::write(&out_path, json)
-        .with_context(|| format!("failed to write graph to {}", out_path.display()))?;
+    write_workspace_json(&out_path, &workspace, compact)?;
 
     eprintln!("Wrote graph to {}", out_path.display());
     Ok(())
 }
 
-fn parse_json(
-    json: PathBuf,
-    crate_name: String,
-    version: String,
-    out: PathBuf,
-    manifest_path: Option<PathBuf>,
-    root_file: Option<PathBuf>,
-    rustdoc_name: Option<String>,
-    call_mode: CallModeArg,
+/// The members one `cargo rustdoc` pass of an `analyze --feature-set`
+/// documents, and the cargo flags it passes them.
+type FeaturePass = (CrateFilter, Vec<String>);
+
+/// The features an `analyze --feature-set` value names, sorted, and the
+/// passes that document the selected members with them. See
+/// [`feature_set_passes`].
+fn feature_set_args(
+    manifest_path: &Path,
+    crates: &CrateFilter,
+    feature_set: &str,
+) -> Result<(Vec<String>, Vec<FeaturePass>)> {
+    feature_set_passes(&plan_workspace_crates(manifest_path)?, crates, feature_set)
+}
+
+/// `all` is one `--all-features` pass over every selected member and
+/// expands to every feature they declare. A list can't go to every member
+/// as is, since cargo rejects `--features` naming a feature the package
+/// doesn't declare. So each member gets only the listed features it has,
+/// one pass per distinct subset, and members with none of them are left
+/// out: their docs would match the default build.
+fn feature_set_passes(
+    plans: &[WorkspaceCratePlan],
+    crates: &CrateFilter,
+    feature_set: &str,
+) -> Result<(Vec<String>, Vec<FeaturePass>)> {
+    let selected = plans.iter().filter(|plan| crates.matches(&plan.crate_name));
+    if feature_set.trim() == "all" {
+        let mut features: Vec<String> = selected.flat_map(|plan| plan.features.clone()).collect();
+        features.sort();
+        features.dedup();
+        return Ok((
+            features,
+            vec![(crates.clone(), vec!["--all-features".to_string()])],
+        ));
+    }
+    let mut features: Vec<String> = feature_set
+        .split(',')
+        .map(str::trim)
+        .filter(|feature| !feature.is_empty())
+        .map(str::to_string)
+        .collect();
+    if features.is_empty() {
+        anyhow::bail!("--feature-set needs at least one feature, or `all`");
+    }
+    features.sort();
+    features.dedup();
+
+    let mut members_by_subset: BTreeMap<Vec<String>, Vec<String>> = BTreeMap::new();
+    for plan in selected {
+        let subset: Vec<String> = features
+            .iter()
+            .filter(|feature| plan.features.contains(feature))
+            .cloned()
+            .collect();
+        if !subset.is_empty() {
+            members_by_subset
+                .entry(subset)
+                .or_default()
+                .push(plan.crate_name.clone());
+        }
+    }
+    let undeclared: Vec<&str> = features
+        .iter()
+        .filter(|feature| {
+            !members_by_subset
+                .keys()
+                .flatten()
+                .any(|have| have == *feature)
+        })
+        .map(String::as_str)
+        .collect();
+    if !undeclared.is_empty() {
+        anyhow::bail!(
+            "no selected workspace member declares feature(s) {}",
+            undeclared.join(", ")
+        );
+    }
+    let passes = members_by_subset
+        .into_iter()
+        .map(|(subset, members)| {
+            let filter = CrateFilter {
+                include: members,
+                exclude: Vec::new(),
+            };
+            (filter, vec!["--features".to_string(), subset.join(",")])
+        })
+        .collect();
+    Ok((features, passes))
+}
+
+/// `--metrics` as an aligned two-column table.
+fn render_metrics(metrics: &codeview_core::GraphMetrics) -> String {
+    let mut rows: Vec<(String, String)> = Vec::new();
+    let total: usize = metrics.nodes.values().sum();
+    rows.push((
+        "Nodes".into(),
+        format!("{total} ({} external)", metrics.external_nodes),
+    ));
+    rows.extend(
+        metrics
+            .nodes
+            .iter()
+            .map(|(kind, count)| (format!("  {kind:?}"), count.to_string())),
+    );
+    rows.push((
+        "Edges".into(),
+        metrics.edges.values().sum::<usize>().to_string(),
+    ));
+    rows.extend(
+        metrics
+            .edges
+            .iter()
+            .map(|(kind, count)| (format!("  {kind:?}"), count.to_string())),
+    );
+    rows.push(("Edge confidence".into(), String::new()));
+    rows.extend(
+        metrics
+            .confidence
+            .iter()
+            .map(|(confidence, count)| (format!("  {confidence:?}"), count.to_string())),
+    );
+    for (label, fan) in [
+        ("Function fan-in", &metrics.fan_in),
+        ("Function fan-out", &metrics.fan_out),
+    ] {
+        let max = match &fan.max_id {
+            Some(id) => format!("{} ({id})", fan.max),
+            None => fan.max.to_string(),
+        };
+        rows.push((label.into(), format!("avg {:.2}, max {max}", fan.average)));
+    }
+    rows.push((
+        "Max module depth".into(),
+        metrics.max_module_depth.to_string(),
+    ));
+
+    let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    rows.iter()
+        .map(|(label, value)| format!("{label:width$}  {value}").trim_end().to_string() + "\n")
+        .collect()
+}
+
+/// Serialize straight into a buffered file, so the JSON text never has to
+/// sit in memory next to the workspace it was built from. The file is
+/// written beside `path` and renamed into place, so a server reading the
+/// graph (see `ui --watch`) never sees it half-written. It ends up with the
+/// mode a plain create would give it (`0o666` less the umask), or that of
+/// the file it replaces, rather than the temp file's owner-only `0o600`.
+fn write_workspace_json(
+    path: &Path,
+    workspace: &codeview_core::Workspace,
+    compact: bool,
 ) -> Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut builder = tempfile::Builder::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        // Passed to open(2), so the umask applies.
+        builder.permissions(fs::Permissions::from_mode(0o666));
+    }
+    let file = builder
+        .tempfile_in(dir)
+        .with_context(|| format!("failed to create {}", path.display()))?;
+    if let Ok(existing) = fs::metadata(path) {
+        file.as_file()
+            .set_permissions(existing.permissions())
+            .with_context(|| format!("failed to set permissions on {}", path.display()))?;
+    }
+    let mut writer = std::io::BufWriter::new(file);
+    if compact {
+        serde_json::to_writer(&mut writer, workspace)
+    } else {
+        serde_json::to_writer_pretty(&mut writer, workspace)
+    }
+    .with_context(|| format!("failed to write graph to {}", path.display()))?;
+    writer
+        .into_inner()
+        .map_err(|err| err.into_error())
+        .with_context(|| format!("failed to write graph to {}", path.display()))?
+        .persist(path)
+        .with_context(|| format!("failed to write graph to {}", path.display()))?;
+    Ok(())
+}
+
+/// Flatten the workspace (including cross-crate edges) and export it in a
+/// non-JSON format.
+fn render_workspace(
+    workspace: &codeview_core::Workspace,
+    format: AnalyzeFormat,
+    mermaid_kind: MermaidKindArg,
+    flow: &MermaidFlowOptions,
+) -> Result<String> {
+    Ok(match format {
+        AnalyzeFormat::Json => serde_json::to_string_pretty(workspace)?,
+        AnalyzeFormat::Mermaid => {
+            export::export_mermaid(&workspace.merged_graph(), mermaid_kind.into(), flow)
+        }
+        AnalyzeFormat::Dot => export::export_dot(&workspace.merged_graph()),
+        AnalyzeFormat::Svg => dot_to_svg(&export::export_dot(&workspace.merged_graph()))?,
+    })
+}
+
+/// Pipe DOT through Graphviz. We don't lay graphs out ourselves, so SVG
+/// output is only as available as `dot` is.
+fn dot_to_svg(dot: &str) -> Result<String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new("dot")
+        .arg("-Tsvg")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("--format svg needs Graphviz's `dot` on PATH")?;
+    // Feed stdin from a thread so a large graph can't deadlock against
+    // `dot` filling its stdout pipe.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = dot.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output().context("failed to run `dot`")?;
+    writer
+        .join()
+        .map_err(|_| anyhow::anyhow!("dot input writer panicked"))?
+        .context("failed to write DOT to `dot`")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "`dot -Tsvg` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout).context("`dot` produced non-UTF-8 output")
+}
+
+fn write_rendered(out: Option<PathBuf>, rendered: &str) -> Result<()> {
+    let Some(path) = out else {
+        print!("{rendered}");
+        return Ok(());
+    };
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create output dir {}", parent.display()))?;
+    }
+    fs::write(&path, rendered)
+        .with_context(|| format!("failed to write output to {}", path.display()))?;
+    eprintln!("Wrote output to {}", path.display());
+    Ok(())
+}
+
+fn parse_json(args: ParseJsonArgs) -> Result<()> {
+    let ParseJsonArgs {
+        json,
+        crate_name,
+        version,
+        out,
+        manifest_path,
+        root_file,
+        rustdoc_name,
+        call_mode,
+        intern_doc_links,
+    } = args;
     let normalized = crate_name.replace('-', "_");
+    let from_stdin = json.as_os_str() == "-";
     let graph = match (manifest_path, root_file) {
+        (Some(_), Some(_)) if from_stdin => {
+            anyhow::bail!("--json - can't be combined with --manifest-path/--root-file")
+        }
         (Some(manifest_path), Some(root_file)) => load_graph_from_path_with_sources(
             &json,
             &normalized,
@@ -374,18 +1290,27 @@ fn parse_json(
                 root_file.display()
             )
         })?,
+        (None, None) if from_stdin => extract_graph_reader(std::io::stdin().lock(), &normalized)
+            .context("failed to parse rustdoc JSON from stdin")?,
         (None, None) => load_graph_from_path(&json, &normalized)
             .with_context(|| format!("failed to parse rustdoc JSON {}", json.display()))?,
         _ => anyhow::bail!("--manifest-path and --root-file must be provided together"),
     };
-    let crate_graph = CrateGraph {
+    let version = version
+        .or_else(|| graph.crate_versions.get(&normalized).cloned())
+        .unwrap_or_else(|| "0.0.0".to_string());
+    let mut crate_graph = CrateGraph {
         id: normalized.clone(),
         name: normalized,
         version,
         nodes: graph.nodes,
         edges: graph.edges,
         aliases: graph.aliases,
+        doc_link_table: graph.doc_link_table,
     };
+    if intern_doc_links {
+        crate_graph.intern_doc_links();
+    }
     if let Some(parent) = out.parent() {
         fs::create_dir_all(parent)
             .with_context(|| format!("failed to create output dir {}", parent.display()))?;
@@ -396,11 +1321,112 @@ fn parse_json(
     Ok(())
 }
 
+fn from_json(
+    jsons: Vec<PathBuf>,
+    crate_names: Vec<String>,
+    out: PathBuf,
+    blanket_impls: BlanketImplsArg,
+    include_hidden: bool,
+    compact: bool,
+) -> Result<()> {
+    if jsons.len() != crate_names.len() {
+        anyhow::bail!(
+            "got {} rustdoc JSON files but {} --crate-name values; pass one name per file",
+            jsons.len(),
+            crate_names.len()
+        );
+    }
+    let rustdoc_jsons: Vec<(String, PathBuf)> = crate_names.into_iter().zip(jsons).collect();
+    let options = ExtractOptions {
+        include_hidden,
+        blanket_impls: blanket_impls.into(),
+        ..ExtractOptions::default()
+    };
+    let workspace = load_workspace_graph_from_json(&rustdoc_jsons, &options)
+        .context("failed to build workspace from rustdoc JSON")?;
+    if let Some(parent) = out.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create output dir {}", parent.display()))?;
+    }
+    write_workspace_json(&out, &workspace, compact)?;
+    eprintln!("Wrote graph to {}", out.display());
+    Ok(())
+}
+
+impl From<MermaidKindArg> for MermaidKind {
+    fn from(value: MermaidKindArg) -> Self {
+        match value {
+            MermaidKindArg::Flow => MermaidKind::Flow,
+            MermaidKindArg::Class => MermaidKind::Class,
+        }
+    }
+}
+
+impl From<NodeKindArg> for NodeKind {
+    fn from(value: NodeKindArg) -> Self {
+        match value {
+            NodeKindArg::Crate => NodeKind::Crate,
+            NodeKindArg::Module => NodeKind::Module,
+            NodeKindArg::Struct => NodeKind::Struct,
+            NodeKindArg::StructField => NodeKind::StructField,
+            NodeKindArg::Union => NodeKind::Union,
+            NodeKindArg::Enum => NodeKind::Enum,
+            NodeKindArg::Variant => NodeKind::Variant,
+            NodeKindArg::Trait => NodeKind::Trait,
+            NodeKindArg::TraitAlias => NodeKind::TraitAlias,
+            NodeKindArg::Impl => NodeKind::Impl,
+            NodeKindArg::Function => NodeKind::Function,
+            NodeKindArg::TypeAlias => NodeKind::TypeAlias,
+            NodeKindArg::AssocType => NodeKind::AssocType,
+            NodeKindArg::Constant => NodeKind::Constant,
+            NodeKindArg::AssocConst => NodeKind::AssocConst,
+            NodeKindArg::Static => NodeKind::Static,
+            NodeKindArg::Macro => NodeKind::Macro,
+            NodeKindArg::Primitive => NodeKind::Primitive,
+            NodeKindArg::ExternCrate => NodeKind::ExternCrate,
+            NodeKindArg::Import => NodeKind::Import,
+            NodeKindArg::ProcMacro => NodeKind::ProcMacro,
+        }
+    }
+}
+
+impl From<EdgeKindArg> for EdgeKind {
+    fn from(value: EdgeKindArg) -> Self {
+        match value {
+            EdgeKindArg::Contains => EdgeKind::Contains,
+            EdgeKindArg::Defines => EdgeKind::Defines,
+            EdgeKindArg::Implements => EdgeKind::Implements,
+            EdgeKindArg::Supertrait => EdgeKind::Supertrait,
+            EdgeKindArg::UsesType => EdgeKind::UsesType,
+            EdgeKindArg::Returns => EdgeKind::Returns,
+            EdgeKindArg::CallsStatic => EdgeKind::CallsStatic,
+            EdgeKindArg::CallsRuntime => EdgeKind::CallsRuntime,
+            EdgeKindArg::CallsMacro => EdgeKind::CallsMacro,
+            EdgeKindArg::Derives => EdgeKind::Derives,
+            EdgeKindArg::ReExports => EdgeKind::ReExports,
+            EdgeKindArg::HasField => EdgeKind::HasField,
+        }
+    }
+}
+
+impl From<BlanketImplsArg> for BlanketImpls {
+    fn from(value: BlanketImplsArg) -> Self {
+        match value {
+            BlanketImplsArg::PerImpl => BlanketImpls::PerImpl,
+            BlanketImplsArg::Collapse => BlanketImpls::Collapse,
+            BlanketImplsArg::Skip => BlanketImpls::Skip,
+        }
+    }
+}
+
 impl From<CallModeArg> for CallMode {
     fn from(value: CallModeArg) -> Self {
         match value {
             CallModeArg::Strict => CallMode::Strict,
             CallModeArg::Ambiguous => CallMode::Ambiguous,
+            CallModeArg::Heuristic => CallMode::Heuristic,
         }
     }
 }
@@ -490,56 +1516,162 @@ fn is_process_alive(pid: u32) -> bool {
     }
 }
 
-fn list_instances() -> Result<()> {
-    let dir = instances_dir();
-    let entries = match fs::read_dir(&dir) {
-        Ok(e) => e,
-        Err(_) => {
-            println!("No running instances.");
-            return Ok(());
-        }
+/// Registered instances whose process is still running. Registrations that
+/// don't parse or whose process has exited are removed along the way.
+fn live_instances() -> Vec<Instance> {
+    let Ok(entries) = fs::read_dir(instances_dir()) else {
+        return Vec::new();
     };
-
-    let mut found = false;
+    let mut instances = Vec::new();
     for entry in entries.flatten() {
         let path = entry.path();
-        if path.extension().is_some_and(|e| e == "json") {
-            let data = match fs::read_to_string(&path) {
-                Ok(d) => d,
-                Err(_) => continue,
-            };
-            let inst: Instance = match serde_json::from_str(&data) {
-                Ok(i) => i,
-                Err(_) => {
-                    let _ = fs::remove_file(&path);
-                    continue;
-                }
-            };
-            if !is_process_alive(inst.pid) {
+        if path.extension().is_none_or(|e| e != "json") {
+            continue;
+        }
+        let Ok(data) = fs::read_to_string(&path) else {
+            continue;
+        };
+        match serde_json::from_str::<Instance>(&data) {
+            Ok(inst) if is_process_alive(inst.pid) => instances.push(inst),
+            _ => {
                 let _ = fs::remove_file(&path);
-                continue;
             }
-            if !found {
-                println!("{:<8} {:<8} {:<30} WORKSPACE", "PID", "PORT", "URL");
-                found = true;
-            }
-            println!(
-                "{:<8} {:<8} {:<30} {}",
-                inst.pid,
-                inst.port,
-                inst.url,
-                inst.workspace.as_deref().unwrap_or("-"),
-            );
         }
     }
+    instances.sort_by_key(|inst| inst.pid);
+    instances
+}
 
-    if !found {
+fn list_instances(json: bool) -> Result<()> {
+    let instances = live_instances();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&instances)?);
+        return Ok(());
+    }
+    if instances.is_empty() {
         println!("No running instances.");
+        return Ok(());
+    }
+
+    println!("{:<8} {:<8} {:<30} WORKSPACE", "PID", "PORT", "URL");
+    for inst in &instances {
+        println!(
+            "{:<8} {:<8} {:<30} {}",
+            inst.pid,
+            inst.port,
+            inst.url,
+            inst.workspace.as_deref().unwrap_or("-"),
+        );
+    }
+    Ok(())
+}
+
+/// Stop a registered instance. A live pid alone isn't trusted, since pids
+/// get reused: the server on the instance's port must still identify itself
+/// as that pid, or the registration is treated as stale and dropped without
+/// signalling.
+fn kill_instance(pid: u32) -> Result<()> {
+    let path = instances_dir().join(format!("{pid}.json"));
+    let data = fs::read_to_string(&path)
+        .with_context(|| format!("no codeview instance registered with pid {pid}"))?;
+    let inst: Instance = serde_json::from_str(&data)
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+
+    if !is_process_alive(inst.pid) {
+        unregister_instance(pid);
+        anyhow::bail!("instance {pid} is no longer running; removed its registration");
+    }
+    if served_instance_pid(inst.port) != Some(pid) {
+        unregister_instance(pid);
+        anyhow::bail!(
+            "{} isn't served by codeview instance {pid} any more, so the pid may belong to \
+             another process; removed the registration without signalling it",
+            inst.url
+        );
     }
 
+    terminate_process(pid).with_context(|| format!("failed to stop instance {pid}"))?;
+    unregister_instance(pid);
+    eprintln!("Stopped codeview instance {pid} ({})", inst.url);
     Ok(())
 }
 
+/// Pid the codeview server on `port` reports for itself, or `None` when
+/// nothing answers there or what answers isn't a codeview server.
+fn served_instance_pid(port: u16) -> Option<u32> {
+    use std::io::{Read, Write};
+    use std::time::Duration;
+
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+    let mut stream =
+        std::net::TcpStream::connect_timeout(&addr, Duration::from_millis(500)).ok()?;
+    stream.set_read_timeout(Some(Duration::from_secs(2))).ok()?;
+    write!(
+        stream,
+        "GET /api/instance HTTP/1.0\r\nHost: 127.0.0.1:{port}\r\nConnection: close\r\n\r\n"
+    )
+    .ok()?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).ok()?;
+    instance_pid_from_response(&response)
+}
+
+/// The pid in a `/api/instance` response. The body is one small JSON object,
+/// so taking it from the first `{` to the last `}` also copes with a server
+/// that answers in chunks.
+fn instance_pid_from_response(response: &str) -> Option<u32> {
+    #[derive(Deserialize)]
+    struct Identity {
+        pid: u32,
+    }
+
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    if head.split_whitespace().nth(1) != Some("200") {
+        return None;
+    }
+    let json = &body[body.find('{')?..=body.rfind('}')?];
+    serde_json::from_str::<Identity>(json)
+        .ok()
+        .map(|identity| identity.pid)
+}
+
+/// Ask a process to exit. On Unix this is `SIGTERM`, which the server's
+/// shutdown handler catches to stop the sidecar and clean up; Windows has no
+/// equivalent, so the process is terminated and its job object takes the
+/// sidecar down with it.
+fn terminate_process(pid: u32) -> std::io::Result<()> {
+    #[cfg(windows)]
+    {
+        use std::ffi::c_void;
+        #[link(name = "kernel32")]
+        unsafe extern "system" {
+            fn OpenProcess(access: u32, inherit: i32, pid: u32) -> *mut c_void;
+            fn TerminateProcess(handle: *mut c_void, code: u32) -> i32;
+            fn CloseHandle(handle: *mut c_void) -> i32;
+        }
+        const PROCESS_TERMINATE: u32 = 0x0001;
+        unsafe {
+            let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+            if handle.is_null() {
+                return Err(std::io::Error::last_os_error());
+            }
+            let ok = TerminateProcess(handle, 1);
+            CloseHandle(handle);
+            if ok == 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+    #[cfg(unix)]
+    {
+        if unsafe { libc::kill(pid as i32, libc::SIGTERM) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
 fn serve_ui(
     port: Option<u16>,
     open: bool,
@@ -569,6 +1701,9 @@ fn serve_ui(
 
     let mut cmd = Command::new(&sidecar_path);
     cmd.env("PORT", port.to_string());
+    // Served at `/api/instance` so `codeview ps --kill` can tell this
+    // instance from whatever else later holds the port or pid.
+    cmd.env("CODEVIEW_INSTANCE", pid.to_string());
 
     if let Some(root) = &workspace_root {
         cmd.env("CODEVIEW_WORKSPACE", root);
@@ -764,3 +1899,129 @@ fn sidecar_name() -> &'static str {
         "codeview-server"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instance_identity_comes_from_a_codeview_response() {
+        let ok = "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\r\n{\"pid\":4242}";
+        assert_eq!(instance_pid_from_response(ok), Some(4242));
+        let chunked =
+            "HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\nc\r\n{\"pid\":4242}\r\n0\r\n\r\n";
+        assert_eq!(instance_pid_from_response(chunked), Some(4242));
+
+        // Another server that took the port over.
+        let missing = "HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\n\r\n";
+        assert_eq!(instance_pid_from_response(missing), None);
+        let other = "HTTP/1.1 200 OK\r\n\r\n<!doctype html><p>{hello}</p>";
+        assert_eq!(instance_pid_from_response(other), None);
+        assert_eq!(instance_pid_from_response("garbage"), None);
+    }
+
+    fn plan(crate_name: &str, features: &[&str]) -> WorkspaceCratePlan {
+        WorkspaceCratePlan {
+            crate_name: crate_name.to_string(),
+            version: "0.1.0".to_string(),
+            manifest_path: PathBuf::from(format!("{crate_name}/Cargo.toml")),
+            target: None,
+            features: features.iter().map(|feature| feature.to_string()).collect(),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn workspace_json_gets_default_or_existing_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("codeview-write-json-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        let workspace = codeview_core::Workspace {
+            version: 1,
+            crates: Vec::new(),
+            external_crates: Vec::new(),
+            cross_crate_edges: Vec::new(),
+            repo: None,
+            ref_: None,
+        };
+        let mode = |path: &Path| {
+            fs::metadata(path)
+                .expect("file exists")
+                .permissions()
+                .mode()
+                & 0o777
+        };
+
+        // Whatever the umask makes of a plain create.
+        let plain = dir.join("plain.json");
+        fs::write(&plain, "").expect("write plain file");
+        let graph = dir.join("graph.json");
+        write_workspace_json(&graph, &workspace, true).expect("write graph");
+        assert_eq!(mode(&graph), mode(&plain));
+
+        fs::set_permissions(&graph, fs::Permissions::from_mode(0o640)).expect("chmod graph");
+        write_workspace_json(&graph, &workspace, false).expect("rewrite graph");
+        assert_eq!(mode(&graph), 0o640);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn feature_set_passes_give_each_member_only_its_own_features() {
+        let plans = [
+            plan("app", &["cli", "serde"]),
+            plan("core", &["serde"]),
+            plan("util", &[]),
+        ];
+        let everything = CrateFilter::default();
+
+        let (features, passes) =
+            feature_set_passes(&plans, &everything, "serde, cli").expect("features declared");
+        assert_eq!(features, ["cli", "serde"]);
+        let passes: Vec<(Vec<String>, Vec<String>)> = passes
+            .into_iter()
+            .map(|(filter, flags)| (filter.include, flags))
+            .collect();
+        assert_eq!(
+            passes,
+            [
+                (
+                    vec!["app".to_string()],
+                    vec!["--features".to_string(), "cli,serde".to_string()]
+                ),
+                (
+                    vec!["core".to_string()],
+                    vec!["--features".to_string(), "serde".to_string()]
+                ),
+            ]
+        );
+
+        let (features, passes) =
+            feature_set_passes(&plans, &everything, "all").expect("all always works");
+        assert_eq!(features, ["cli", "serde"]);
+        assert_eq!(passes.len(), 1);
+        assert_eq!(passes[0].1, ["--all-features"]);
+
+        let only_core = CrateFilter {
+            include: vec!["core".to_string()],
+            exclude: Vec::new(),
+        };
+        let error = feature_set_passes(&plans, &only_core, "cli").unwrap_err();
+        assert!(error.to_string().contains("cli"), "{error}");
+    }
+
+    #[test]
+    fn analyze_accepts_at_most_one_report_flag() {
+        let parse = |args: &[&str]| Cli::try_parse_from(["codeview", "analyze"].iter().chain(args));
+        assert!(parse(&["--dead-code"]).is_ok());
+        assert!(parse(&["--cycles", "uses-type", "--compact"]).is_ok());
+
+        let Err(error) = parse(&["--dead-code", "--metrics"]) else {
+            panic!("--dead-code and --metrics were both accepted");
+        };
+        assert_eq!(error.kind(), clap::error::ErrorKind::ArgumentConflict);
+        assert!(parse(&["--validate", "--list-functions", "csv"]).is_err());
+        assert!(parse(&["--list-crates", "--cycles", "calls-static"]).is_err());
+    }
+}
@@ -252,6 +252,7 @@ pub async fn publish_one(opts: PublishOptions<'_>) -> Result<Outcome, PublishErr
         nodes: graph.nodes,
         edges: graph.edges,
         aliases: graph.aliases,
+        doc_link_table: graph.doc_link_table,
     };
 
     let graph_hash = shards::graph_hash(&crate_graph);
@@ -337,7 +338,7 @@ pub async fn publish_one(opts: PublishOptions<'_>) -> Result<Outcome, PublishErr
     while let Some(result) = stream.next().await {
         result.map_err(PublishError::Transient)?;
         completed += 1;
-        if completed % 25 == 0 || completed == total {
+        if completed.is_multiple_of(25) || completed == total {
             eprint!(".");
         }
     }
@@ -548,6 +549,9 @@ fn classify_rustdoc_error(err: RustdocError) -> PublishError {
         RustdocError::MissingRootPackage => {
             PublishError::Transient(anyhow::anyhow!("parser missing root package"))
         }
+        RustdocError::Cancelled => PublishError::Transient(anyhow::anyhow!("parser cancelled")),
+        // A parser bug; the same input would panic again.
+        RustdocError::WorkerPanicked(_) => PublishError::Permanent(msg),
     }
 }
 
@@ -705,6 +705,7 @@ mod tests {
             confidence: Confidence::Static,
             occurrences: Vec::new(),
             is_glob: false,
+            label: None,
         }
     }
 
@@ -729,6 +730,7 @@ mod tests {
                 edge("demo::make", "demo::Thing", EdgeKind::UsesType),
             ],
             aliases: HashMap::from([("demo::Alias".to_string(), "demo::Thing".to_string())]),
+            doc_link_table: Vec::new(),
         }
     }
 
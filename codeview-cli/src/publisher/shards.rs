@@ -317,7 +317,7 @@ pub(crate) fn add_alias_tree_nodes(
         .collect();
     let known_aliases: HashSet<&str> = graph.aliases.keys().map(String::as_str).collect();
     let mut aliases: Vec<_> = graph.aliases.iter().collect();
-    aliases.sort_by(|(left, _), (right, _)| left.cmp(right));
+    aliases.sort_by_key(|(alias, _)| *alias);
 
     let mut alias_nodes = Vec::new();
     for (alias, canonical) in aliases {
@@ -1021,6 +1021,7 @@ mod tests {
             confidence: Confidence::Static,
             occurrences: Vec::new(),
             is_glob: false,
+            label: None,
         }
     }
 
@@ -1054,6 +1055,7 @@ mod tests {
                 edge("demo::make", "core::clone::Clone", EdgeKind::UsesType),
             ],
             aliases: HashMap::new(),
+            doc_link_table: Vec::new(),
         }
     }
 
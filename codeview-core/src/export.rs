@@ -0,0 +1,1280 @@
+//! Text exporters for [`Graph`]: Mermaid flowcharts and class diagrams,
+//! Graphviz DOT, and flat CSV or Markdown listings of the functions.
+//!
+//! Exporters only emit edges whose endpoints are both present in the graph,
+//! so a filtered or partially merged graph never produces implicit nodes.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::{
+    AssocItemConstraintKind, Confidence, EdgeKind, FunctionSignature, GenericArg, GenericArgs,
+    GenericBound, GenericParam, GenericParamKind, Generics, Graph, ImplCategory, ImplType, Node,
+    NodeKind, PreciseCapture, Term, TraitBoundModifier, TypeRef, Visibility, WherePredicate,
+};
+
+/// Which Mermaid diagram [`export_mermaid`] produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MermaidKind {
+    /// Every node and edge as a `flowchart`.
+    Flow,
+    /// Types and traits only, as a `classDiagram`.
+    Class,
+}
+
+/// Options for [`export_mermaid_flow`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MermaidFlowOptions {
+    /// Fold every module at this depth (the crate root is depth 0) into a
+    /// single node, re-pointing edges from anything inside it. `None`
+    /// draws the full module tree.
+    pub collapse_depth: Option<usize>,
+}
+
+pub fn export_mermaid(graph: &Graph, kind: MermaidKind, flow: &MermaidFlowOptions) -> String {
+    match kind {
+        MermaidKind::Flow => export_mermaid_flow(graph, flow),
+        MermaidKind::Class => export_mermaid_class(graph),
+    }
+}
+
+/// Flowchart of the whole graph. Crates and modules become nested
+/// `subgraph` blocks following the `Contains` tree, so their `Contains`
+/// edges are implied rather than drawn. Edges are labelled with their
+/// [`Edge::label`] when set and [`edge_label`] otherwise; inferred edges are
+/// dotted.
+pub fn export_mermaid_flow(graph: &Graph, options: &MermaidFlowOptions) -> String {
+    let tree = ModuleTree::new(graph, options.collapse_depth);
+    let mut out = String::from("flowchart LR\n");
+    tree.write_members(&mut out, None, 1);
+
+    let mut seen = HashSet::new();
+    for edge in &graph.edges {
+        let (Some(from), Some(to)) = (tree.drawn_as(&edge.from), tree.drawn_as(&edge.to)) else {
+            continue;
+        };
+        if from == to
+            || (edge.kind == EdgeKind::Contains && tree.group_of(to) == Some(from))
+            || !seen.insert((from, to, edge.kind, edge.label.as_deref()))
+        {
+            continue;
+        }
+        let label = match &edge.label {
+            Some(label) => mermaid_label(label),
+            None => edge_label(edge.kind).to_string(),
+        };
+        let arrow = if edge.confidence == Confidence::Inferred {
+            "-.->"
+        } else {
+            "-->"
+        };
+        let _ = writeln!(
+            out,
+            "    {} {arrow}|\"{label}\"| {}",
+            mermaid_id(from),
+            mermaid_id(to)
+        );
+    }
+    out
+}
+
+/// Crate/module nesting derived from `Contains` (then `Defines`) edges,
+/// with collapsed modules standing in for everything beneath them.
+struct ModuleTree<'a> {
+    nodes: HashMap<&'a str, &'a Node>,
+    /// Visible node → the id it is drawn as (itself unless collapsed away).
+    drawn_as: HashMap<&'a str, &'a str>,
+    /// Visible node → innermost enclosing container drawn as a subgraph.
+    group: HashMap<&'a str, &'a str>,
+    /// Container drawn as a subgraph → its visible members, in graph order.
+    members: HashMap<Option<&'a str>, Vec<&'a str>>,
+}
+
+impl<'a> ModuleTree<'a> {
+    fn new(graph: &'a Graph, collapse_depth: Option<usize>) -> Self {
+        let nodes: HashMap<&str, &Node> = graph
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node))
+            .collect();
+        // Impls, their items and trait methods hang off `Defines` rather
+        // than `Contains`; follow those only where there's no module parent.
+        let mut parent: HashMap<&str, &str> = HashMap::new();
+        for kind in [EdgeKind::Contains, EdgeKind::Defines] {
+            for edge in graph.edges.iter().filter(|edge| edge.kind == kind) {
+                if nodes.contains_key(edge.from.as_str()) && nodes.contains_key(edge.to.as_str()) {
+                    parent.entry(edge.to.as_str()).or_insert(edge.from.as_str());
+                }
+            }
+        }
+        let is_container = |id: &str| {
+            nodes
+                .get(id)
+                .is_some_and(|node| matches!(node.kind, NodeKind::Crate | NodeKind::Module))
+        };
+        // Enclosing containers, innermost first. The visited set guards
+        // against malformed graphs with `Contains` cycles.
+        let containers_above = |id: &'a str| {
+            let mut chain = Vec::new();
+            let mut visited = HashSet::from([id]);
+            let mut current = id;
+            while let Some(&next) = parent.get(current) {
+                if !visited.insert(next) {
+                    break;
+                }
+                if is_container(next) {
+                    chain.push(next);
+                }
+                current = next;
+            }
+            chain
+        };
+
+        let mut drawn_as = HashMap::new();
+        let mut container_of = HashMap::new();
+        let mut collapsed = HashSet::new();
+        for node in &graph.nodes {
+            let id = node.id.as_str();
+            let above = containers_above(id);
+            // `above` is innermost first, so depth d sits at index len-1-d.
+            let folded_into = collapse_depth
+                .and_then(|depth| above.len().checked_sub(depth + 1))
+                .map(|index| above[index]);
+            match folded_into {
+                Some(ancestor) => {
+                    drawn_as.insert(id, ancestor);
+                }
+                None => {
+                    drawn_as.insert(id, id);
+                    if let Some(&container) = above.first() {
+                        container_of.insert(id, container);
+                    }
+                    if collapse_depth == Some(above.len()) {
+                        collapsed.insert(id);
+                    }
+                }
+            }
+        }
+
+        let mut members: HashMap<Option<&str>, Vec<&str>> = HashMap::new();
+        for node in &graph.nodes {
+            let id = node.id.as_str();
+            if drawn_as.get(id) == Some(&id) {
+                members
+                    .entry(container_of.get(id).copied())
+                    .or_default()
+                    .push(id);
+            }
+        }
+        // A collapsed container has no visible members, so only containers
+        // with something inside are drawn as subgraphs.
+        let group = container_of
+            .into_iter()
+            .filter(|(_, container)| !collapsed.contains(container))
+            .collect();
+
+        Self {
+            nodes,
+            drawn_as,
+            group,
+            members,
+        }
+    }
+
+    fn drawn_as(&self, id: &str) -> Option<&'a str> {
+        self.drawn_as.get(id).copied()
+    }
+
+    fn group_of(&self, id: &str) -> Option<&'a str> {
+        self.group.get(id).copied()
+    }
+
+    fn write_members(&self, out: &mut String, container: Option<&'a str>, level: usize) {
+        let indent = "    ".repeat(level);
+        for &id in self.members.get(&container).into_iter().flatten() {
+            let node = self.nodes[id];
+            if self.members.contains_key(&Some(id)) {
+                let _ = writeln!(
+                    out,
+                    "{indent}subgraph {}[\"{}\"]",
+                    mermaid_id(id),
+                    mermaid_label(&node.name)
+                );
+                self.write_members(out, Some(id), level + 1);
+                let _ = writeln!(out, "{indent}end");
+            } else {
+                let (open, close) = mermaid_flow_shape(node.kind);
+                let _ = writeln!(
+                    out,
+                    "{indent}{}{open}\"{}\"{close}",
+                    mermaid_id(id),
+                    mermaid_label(&node.name)
+                );
+            }
+        }
+    }
+}
+
+/// Class diagram of structs, unions, enums and traits. Fields, variants and
+/// methods (reached through `Defines`) become members; `Implements`,
+/// `Derives` and `Supertrait` become realization/inheritance, and direct
+/// type uses between classes become associations.
+pub fn export_mermaid_class(graph: &Graph) -> String {
+    let classes: HashMap<&str, &Node> = graph
+        .nodes
+        .iter()
+        .filter(|node| is_class_kind(node.kind))
+        .map(|node| (node.id.as_str(), node))
+        .collect();
+    let nodes: HashMap<&str, &Node> = graph
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), node))
+        .collect();
+    let mut defines: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in graph
+        .edges
+        .iter()
+        .filter(|edge| edge.kind == EdgeKind::Defines)
+    {
+        defines
+            .entry(edge.from.as_str())
+            .or_default()
+            .push(edge.to.as_str());
+    }
+
+    let mut out = String::from("classDiagram\n");
+    for node in graph.nodes.iter().filter(|node| is_class_kind(node.kind)) {
+        let id = mermaid_id(&node.id);
+        let _ = writeln!(out, "    class {id}[\"{}\"]", mermaid_label(&node.name));
+        match node.kind {
+            NodeKind::Trait | NodeKind::TraitAlias => {
+                let _ = writeln!(out, "    <<trait>> {id}");
+            }
+            NodeKind::Enum => {
+                let _ = writeln!(out, "    <<enumeration>> {id}");
+            }
+            NodeKind::Union => {
+                let _ = writeln!(out, "    <<union>> {id}");
+            }
+            _ => {}
+        }
+        for field in node.fields.iter().flatten() {
+            let _ = writeln!(
+                out,
+                "    {id} : {}{}",
+                visibility_marker(&field.visibility),
+                mermaid_label(&field.name)
+            );
+        }
+        for variant in node.variants.iter().flatten() {
+            let _ = writeln!(out, "    {id} : {}", mermaid_label(&variant.name));
+        }
+        // Trait methods hang directly off the trait; type methods hang off
+        // the type's impl blocks. Blanket and auto-trait impls (`From`,
+        // `Borrow`, ...) would bury every class, so they are left out.
+        let is_trait = matches!(node.kind, NodeKind::Trait | NodeKind::TraitAlias);
+        let owned = defines.get(node.id.as_str()).into_iter().flatten();
+        let methods = owned.flat_map(|child| match nodes.get(child) {
+            Some(impl_node) if impl_node.kind == NodeKind::Impl => {
+                let listed = !matches!(
+                    impl_node.impl_category,
+                    Some(ImplCategory::Blanket | ImplCategory::Synthetic)
+                );
+                let via_trait = impl_node.impl_type == Some(ImplType::Trait);
+                let items = defines.get(child).filter(|_| listed);
+                items
+                    .into_iter()
+                    .flatten()
+                    .map(move |item| (*item, via_trait))
+                    .collect::<Vec<_>>()
+            }
+            _ => vec![(*child, is_trait)],
+        });
+        for (method, via_trait) in methods {
+            let Some(method) = nodes.get(method) else {
+                continue;
+            };
+            if method.kind == NodeKind::Function {
+                // Trait items carry inherited visibility but are as public
+                // as the trait itself.
+                let marker = if via_trait {
+                    "+"
+                } else {
+                    visibility_marker(&method.visibility)
+                };
+                let _ = writeln!(out, "    {id} : {marker}{}()", mermaid_label(&method.name));
+            }
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for edge in &graph.edges {
+        if !classes.contains_key(edge.from.as_str()) || !classes.contains_key(edge.to.as_str()) {
+            continue;
+        }
+        let from = mermaid_id(&edge.from);
+        let to = mermaid_id(&edge.to);
+        let line = match edge.kind {
+            EdgeKind::Implements | EdgeKind::Derives => format!("{to} <|.. {from}"),
+            EdgeKind::Supertrait => format!("{to} <|-- {from}"),
+            EdgeKind::UsesType | EdgeKind::Returns => format!("{from} --> {to}"),
+            _ => continue,
+        };
+        if seen.insert(line.clone()) {
+            let _ = writeln!(out, "    {line}");
+        }
+    }
+    out
+}
+
+/// Graphviz DOT of the whole graph, with node shapes by kind and inferred
+/// edges dashed. Edges between the same pair of nodes stay separate
+/// statements, which Graphviz draws side by side.
+pub fn export_dot(graph: &Graph) -> String {
+    let node_ids = node_id_set(graph);
+    let mut out = String::from("digraph codeview {\n    rankdir=LR;\n    node [shape=box];\n");
+    for node in &graph.nodes {
+        let _ = writeln!(
+            out,
+            "    {} [label={}, shape={}];",
+            dot_quote(&node.id),
+            dot_quote(&node.name),
+            dot_shape(node.kind)
+        );
+    }
+    for edge in graph
+        .edges
+        .iter()
+        .filter(|edge| node_ids.contains(edge.from.as_str()) && node_ids.contains(edge.to.as_str()))
+    {
+        let style = if edge.confidence == Confidence::Inferred {
+            ", style=dashed"
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            out,
+            "    {} -> {} [label={}{style}];",
+            dot_quote(&edge.from),
+            dot_quote(&edge.to),
+            dot_quote(edge_label(edge.kind))
+        );
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// One CSV row per local function or method: id, visibility, the
+/// `async`/`unsafe`/`const` flags and the signature as
+/// [`render_signature`] prints it, under a header row. The signature is
+/// left empty when the graph has none. Fields holding commas, quotes or
+/// newlines are quoted.
+pub fn export_functions_csv(graph: &Graph) -> String {
+    let mut out = String::from("id,visibility,async,unsafe,const,signature\n");
+    for row in function_rows(graph) {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{}",
+            csv_field(row.id),
+            csv_field(&row.visibility),
+            row.is_async,
+            row.is_unsafe,
+            row.is_const,
+            csv_field(row.signature.as_deref().unwrap_or_default())
+        );
+    }
+    out
+}
+
+/// The rows of [`export_functions_csv`] as a Markdown table, for pasting
+/// into issues and pull requests. Flags read `yes` or stay blank.
+pub fn export_functions_markdown(graph: &Graph) -> String {
+    let mut out = String::from(
+        "| id | visibility | async | unsafe | const | signature |\n| --- | --- | --- | --- | --- | --- |\n",
+    );
+    let flag = |set: bool| if set { "yes" } else { "" };
+    for row in function_rows(graph) {
+        let signature = row
+            .signature
+            .map(|signature| format!("`{}`", markdown_cell(&signature)))
+            .unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "| `{}` | {} | {} | {} | {} | {signature} |",
+            markdown_cell(row.id),
+            row.visibility,
+            flag(row.is_async),
+            flag(row.is_unsafe),
+            flag(row.is_const),
+        );
+    }
+    out
+}
+
+/// A function's signature on one line, the way `rustfmt` would write it
+/// without the visibility: `const async unsafe extern "C" fn name<T>(x: T)
+/// -> R where T: Clone`. Paths print as their last segment.
+pub fn render_signature(name: &str, signature: &FunctionSignature) -> String {
+    let mut out = String::new();
+    for (set, keyword) in [
+        (signature.is_const, "const "),
+        (signature.is_async, "async "),
+        (signature.is_unsafe, "unsafe "),
+    ] {
+        if set {
+            out.push_str(keyword);
+        }
+    }
+    if let Some(abi) = &signature.abi {
+        let _ = write!(out, "extern \"{abi}\" ");
+    }
+    let inputs: Vec<String> = signature
+        .inputs
+        .iter()
+        .map(|input| argument_text(&input.name, &input.type_))
+        .collect();
+    let variadic = match (signature.is_c_variadic, inputs.is_empty()) {
+        (false, _) => "",
+        (true, true) => "...",
+        (true, false) => ", ...",
+    };
+    let _ = write!(
+        out,
+        "fn {name}{}({}{variadic})",
+        generic_params_text(&signature.generics),
+        inputs.join(", ")
+    );
+    if let Some(output) = &signature.output {
+        let _ = write!(out, " -> {}", render_type(output));
+    }
+    if !signature.generics.where_predicates.is_empty() {
+        let predicates: Vec<String> = signature
+            .generics
+            .where_predicates
+            .iter()
+            .map(where_predicate_text)
+            .collect();
+        let _ = write!(out, " where {}", predicates.join(", "));
+    }
+    out
+}
+
+/// A type expression as Rust source, with paths shortened to their last
+/// segment (`Vec<String>`, not `alloc::vec::Vec<alloc::string::String>`).
+pub fn render_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::ResolvedPath { path, args, .. } => {
+            let name = path.rsplit("::").next().unwrap_or(path);
+            format!("{name}{}", generic_args_text(args.as_deref()))
+        }
+        TypeRef::DynTrait { traits, lifetime } => {
+            let mut parts: Vec<String> = traits
+                .iter()
+                .map(|poly| {
+                    format!(
+                        "{}{}",
+                        hrtb_text(&poly.hrtb_params),
+                        render_type(&poly.trait_)
+                    )
+                })
+                .collect();
+            parts.extend(lifetime.clone());
+            format!("dyn {}", parts.join(" + "))
+        }
+        TypeRef::Generic { name } | TypeRef::Primitive { name } => name.clone(),
+        TypeRef::BorrowedRef {
+            lifetime,
+            mutable,
+            inner,
+        } => {
+            let lifetime = lifetime
+                .as_deref()
+                .map(|lifetime| format!("{lifetime} "))
+                .unwrap_or_default();
+            let mutable = if *mutable { "mut " } else { "" };
+            format!("&{lifetime}{mutable}{}", render_type(inner))
+        }
+        TypeRef::Tuple { elements } if elements.len() == 1 => {
+            format!("({},)", render_type(&elements[0]))
+        }
+        TypeRef::Tuple { elements } => format!("({})", types_text(elements)),
+        TypeRef::Slice { element } => format!("[{}]", render_type(element)),
+        TypeRef::Array { element, len } => format!("[{}; {len}]", render_type(element)),
+        TypeRef::ImplTrait { bounds } => format!("impl {}", bounds_text(bounds)),
+        TypeRef::RawPointer { mutable, inner } => {
+            let mutable = if *mutable { "mut" } else { "const" };
+            format!("*{mutable} {}", render_type(inner))
+        }
+        TypeRef::QualifiedPath {
+            name,
+            args,
+            self_type,
+            trait_,
+        } => {
+            let as_trait = trait_
+                .as_deref()
+                .map(|trait_| format!(" as {}", render_type(trait_)))
+                .unwrap_or_default();
+            format!(
+                "<{}{as_trait}>::{name}{}",
+                render_type(self_type),
+                generic_args_text(args.as_deref())
+            )
+        }
+        TypeRef::FunctionPointer { sig } => {
+            let unsafe_ = if sig.is_unsafe { "unsafe " } else { "" };
+            let abi = sig
+                .abi
+                .as_deref()
+                .map(|abi| format!("extern \"{abi}\" "))
+                .unwrap_or_default();
+            let inputs: Vec<String> = sig
+                .inputs
+                .iter()
+                .map(|input| match input.name.as_str() {
+                    "" | "_" => render_type(&input.type_),
+                    name => format!("{name}: {}", render_type(&input.type_)),
+                })
+                .collect();
+            let variadic = match (sig.is_c_variadic, inputs.is_empty()) {
+                (false, _) => "",
+                (true, true) => "...",
+                (true, false) => ", ...",
+            };
+            let output = sig
+                .output
+                .as_ref()
+                .map(|output| format!(" -> {}", render_type(output)))
+                .unwrap_or_default();
+            format!(
+                "{}{unsafe_}{abi}fn({}{variadic}){output}",
+                hrtb_text(&sig.hrtb_params),
+                inputs.join(", ")
+            )
+        }
+        TypeRef::Infer => "_".to_string(),
+        TypeRef::Pat { base, pat } => format!("{} is {pat}", render_type(base)),
+    }
+}
+
+/// Short human label for an edge kind, used on exported edges.
+pub fn edge_label(kind: EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::Contains => "contains",
+        EdgeKind::Defines => "defines",
+        EdgeKind::Implements => "implements",
+        EdgeKind::Supertrait => "supertrait",
+        EdgeKind::UsesType => "uses",
+        EdgeKind::Returns => "returns",
+        EdgeKind::CallsStatic => "calls",
+        EdgeKind::CallsRuntime => "calls (dyn)",
+        EdgeKind::CallsMacro => "invokes",
+        EdgeKind::Derives => "derives",
+        EdgeKind::ReExports => "re-exports",
+        EdgeKind::HasField => "has field",
+    }
+}
+
+/// Mermaid-safe identifier for a node id. Letters and digits pass through,
+/// `::` becomes `__`, and everything else is escaped (`_` as `_u`, other
+/// characters as `_x<hex>_`), so distinct node ids never collide. The `n_`
+/// prefix keeps ids like `end` or `graph` clear of Mermaid keywords.
+pub fn mermaid_id(id: &str) -> String {
+    let mut out = String::with_capacity(id.len() + 2);
+    out.push_str("n_");
+    let mut chars = id.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            ch if ch.is_ascii_alphanumeric() => out.push(ch),
+            ':' if chars.peek() == Some(&':') => {
+                chars.next();
+                out.push_str("__");
+            }
+            '_' => out.push_str("_u"),
+            other => {
+                let _ = write!(out, "_x{:x}_", other as u32);
+            }
+        }
+    }
+    out
+}
+
+/// Escape text for use inside a quoted Mermaid label.
+pub fn mermaid_label(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("#quot;"),
+            '<' => out.push_str("#lt;"),
+            '>' => out.push_str("#gt;"),
+            '#' => out.push_str("#35;"),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+struct FunctionRow<'a> {
+    id: &'a str,
+    visibility: String,
+    is_async: bool,
+    is_unsafe: bool,
+    is_const: bool,
+    /// `None` when rustdoc recorded no signature for the node.
+    signature: Option<String>,
+}
+
+/// Every local `Function` node in graph order; external stubs are left out.
+fn function_rows(graph: &Graph) -> impl Iterator<Item = FunctionRow<'_>> {
+    graph
+        .nodes
+        .iter()
+        .filter(|node| node.kind == NodeKind::Function && !node.is_external)
+        .map(|node| {
+            let signature = node.signature.as_ref();
+            FunctionRow {
+                id: &node.id,
+                visibility: visibility_text(&node.visibility),
+                is_async: signature.is_some_and(|sig| sig.is_async || sig.is_async_trait),
+                is_unsafe: signature.is_some_and(|sig| sig.is_unsafe),
+                is_const: signature.is_some_and(|sig| sig.is_const),
+                signature: signature.map(|sig| render_signature(&node.name, sig)),
+            }
+        })
+}
+
+fn visibility_text(visibility: &Visibility) -> String {
+    match visibility {
+        Visibility::Public => "pub".to_string(),
+        Visibility::Crate => "pub(crate)".to_string(),
+        Visibility::Restricted { path } => format!("pub(in {path})"),
+        Visibility::Inherited => "inherited".to_string(),
+        Visibility::Unknown => "unknown".to_string(),
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// `name: Type`, with `self` receivers in their short forms (`&mut self`).
+fn argument_text(name: &str, ty: &TypeRef) -> String {
+    let is_self = |ty: &TypeRef| matches!(ty, TypeRef::Generic { name } if name == "Self");
+    match ty {
+        _ if name != "self" => format!("{name}: {}", render_type(ty)),
+        TypeRef::Generic { .. } if is_self(ty) => "self".to_string(),
+        TypeRef::BorrowedRef {
+            lifetime,
+            mutable,
+            inner,
+        } if is_self(inner) => {
+            let lifetime = lifetime
+                .as_deref()
+                .map(|lifetime| format!("{lifetime} "))
+                .unwrap_or_default();
+            let mutable = if *mutable { "mut " } else { "" };
+            format!("&{lifetime}{mutable}self")
+        }
+        _ => format!("self: {}", render_type(ty)),
+    }
+}
+
+fn types_text(types: &[TypeRef]) -> String {
+    types.iter().map(render_type).collect::<Vec<_>>().join(", ")
+}
+
+fn generic_args_text(args: Option<&GenericArgs>) -> String {
+    match args {
+        None => String::new(),
+        Some(GenericArgs::AngleBracketed { args, constraints }) => {
+            let parts: Vec<String> = args
+                .iter()
+                .map(|arg| match arg {
+                    GenericArg::Lifetime { name } => name.clone(),
+                    GenericArg::Type { value } => render_type(value),
+                    GenericArg::Const { expr, .. } => expr.clone(),
+                    GenericArg::Infer => "_".to_string(),
+                })
+                .chain(constraints.iter().map(|constraint| {
+                    let name = format!(
+                        "{}{}",
+                        constraint.name,
+                        generic_args_text(constraint.args.as_deref())
+                    );
+                    match &constraint.binding {
+                        AssocItemConstraintKind::Equality { value } => {
+                            format!("{name} = {}", term_text(value))
+                        }
+                        AssocItemConstraintKind::Constraint { bounds } if bounds.is_empty() => name,
+                        AssocItemConstraintKind::Constraint { bounds } => {
+                            format!("{name}: {}", bounds_text(bounds))
+                        }
+                    }
+                }))
+                .collect();
+            if parts.is_empty() {
+                String::new()
+            } else {
+                format!("<{}>", parts.join(", "))
+            }
+        }
+        Some(GenericArgs::Parenthesized { inputs, output }) => {
+            let output = output
+                .as_deref()
+                .map(|output| format!(" -> {}", render_type(output)))
+                .unwrap_or_default();
+            format!("({}){output}", types_text(inputs))
+        }
+        Some(GenericArgs::ReturnTypeNotation) => "(..)".to_string(),
+    }
+}
+
+fn term_text(term: &Term) -> String {
+    match term {
+        Term::Type { value } => render_type(value),
+        Term::Const { expr, .. } => expr.clone(),
+    }
+}
+
+fn bounds_text(bounds: &[GenericBound]) -> String {
+    bounds
+        .iter()
+        .map(|bound| match bound {
+            GenericBound::Trait {
+                trait_,
+                modifier,
+                hrtb_params,
+            } => {
+                let modifier = match modifier {
+                    TraitBoundModifier::None => "",
+                    TraitBoundModifier::Maybe => "?",
+                    TraitBoundModifier::MaybeConst => "~const ",
+                };
+                format!(
+                    "{}{modifier}{}",
+                    hrtb_text(hrtb_params),
+                    render_type(trait_)
+                )
+            }
+            GenericBound::Outlives { lifetime } => lifetime.clone(),
+            GenericBound::Use { captures } => {
+                let captures: Vec<&str> = captures
+                    .iter()
+                    .map(|capture| match capture {
+                        PreciseCapture::Lifetime { name } | PreciseCapture::Param { name } => {
+                            name.as_str()
+                        }
+                    })
+                    .collect();
+                format!("use<{}>", captures.join(", "))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+fn generic_param_text(param: &GenericParam) -> String {
+    match &param.kind {
+        GenericParamKind::Lifetime { outlives } if outlives.is_empty() => param.name.clone(),
+        GenericParamKind::Lifetime { outlives } => {
+            format!("{}: {}", param.name, outlives.join(" + "))
+        }
+        GenericParamKind::Type {
+            bounds, default, ..
+        } => {
+            let mut out = param.name.clone();
+            if !bounds.is_empty() {
+                let _ = write!(out, ": {}", bounds_text(bounds));
+            }
+            if let Some(default) = default {
+                let _ = write!(out, " = {}", render_type(default));
+            }
+            out
+        }
+        GenericParamKind::Const { type_, default } => {
+            let mut out = format!("const {}: {}", param.name, render_type(type_));
+            if let Some(default) = default {
+                let _ = write!(out, " = {default}");
+            }
+            out
+        }
+    }
+}
+
+fn generic_params_text(generics: &Generics) -> String {
+    // `impl Trait` arguments show up as synthetic params; the argument
+    // types already print them.
+    let params: Vec<String> = generics
+        .params
+        .iter()
+        .filter(|param| {
+            !matches!(
+                param.kind,
+                GenericParamKind::Type {
+                    synthetic: true,
+                    ..
+                }
+            )
+        })
+        .map(generic_param_text)
+        .collect();
+    if params.is_empty() {
+        return String::new();
+    }
+    format!("<{}>", params.join(", "))
+}
+
+fn hrtb_text(params: &[GenericParam]) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    let params: Vec<String> = params.iter().map(generic_param_text).collect();
+    format!("for<{}> ", params.join(", "))
+}
+
+fn where_predicate_text(predicate: &WherePredicate) -> String {
+    match predicate {
+        WherePredicate::Bound {
+            type_,
+            bounds,
+            hrtb_params,
+        } => format!(
+            "{}{}: {}",
+            hrtb_text(hrtb_params),
+            render_type(type_),
+            bounds_text(bounds)
+        ),
+        WherePredicate::Lifetime { lifetime, outlives } => {
+            format!("{lifetime}: {}", outlives.join(" + "))
+        }
+        WherePredicate::Eq { lhs, rhs } => format!("{} = {}", render_type(lhs), term_text(rhs)),
+    }
+}
+
+fn node_id_set(graph: &Graph) -> HashSet<&str> {
+    graph.nodes.iter().map(|node| node.id.as_str()).collect()
+}
+
+fn is_class_kind(kind: NodeKind) -> bool {
+    matches!(
+        kind,
+        NodeKind::Struct
+            | NodeKind::Union
+            | NodeKind::Enum
+            | NodeKind::Trait
+            | NodeKind::TraitAlias
+    )
+}
+
+fn mermaid_flow_shape(kind: NodeKind) -> (&'static str, &'static str) {
+    match kind {
+        NodeKind::Crate | NodeKind::Module => ("[[", "]]"),
+        NodeKind::Trait | NodeKind::TraitAlias => ("{{", "}}"),
+        NodeKind::Function => ("([", "])"),
+        _ => ("[", "]"),
+    }
+}
+
+fn visibility_marker(visibility: &Visibility) -> &'static str {
+    match visibility {
+        Visibility::Public => "+",
+        Visibility::Crate | Visibility::Restricted { .. } => "~",
+        Visibility::Inherited | Visibility::Unknown => "-",
+    }
+}
+
+fn dot_shape(kind: NodeKind) -> &'static str {
+    match kind {
+        NodeKind::Crate => "folder",
+        NodeKind::Module => "tab",
+        NodeKind::Trait | NodeKind::TraitAlias => "hexagon",
+        NodeKind::Function => "ellipse",
+        _ => "box",
+    }
+}
+
+fn dot_quote(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            other => out.push(other),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArgumentInfo, Edge, FieldInfo, VariantInfo};
+
+    fn node(id: &str, kind: NodeKind) -> Node {
+        let name = id.rsplit("::").next().unwrap();
+        Node::new(id, name, kind, Visibility::Public)
+    }
+
+    fn edge(from: &str, to: &str, kind: EdgeKind) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+            confidence: Confidence::Static,
+            occurrences: Vec::new(),
+            is_glob: false,
+            label: None,
+        }
+    }
+
+    fn two_level_modules() -> Graph {
+        let mut graph = Graph::new();
+        graph.nodes = vec![
+            node("demo", NodeKind::Crate),
+            node("demo::outer", NodeKind::Module),
+            node("demo::outer::inner", NodeKind::Module),
+            node("demo::outer::inner::run", NodeKind::Function),
+            node("demo::main", NodeKind::Function),
+        ];
+        graph.edges = vec![
+            edge("demo", "demo::outer", EdgeKind::Contains),
+            edge("demo::outer", "demo::outer::inner", EdgeKind::Contains),
+            edge(
+                "demo::outer::inner",
+                "demo::outer::inner::run",
+                EdgeKind::Contains,
+            ),
+            edge("demo", "demo::main", EdgeKind::Contains),
+            edge(
+                "demo::main",
+                "demo::outer::inner::run",
+                EdgeKind::CallsStatic,
+            ),
+        ];
+        graph
+    }
+
+    #[test]
+    fn flow_nests_module_subgraphs() {
+        let out = export_mermaid_flow(&two_level_modules(), &MermaidFlowOptions::default());
+        assert_eq!(
+            out,
+            "flowchart LR
+    subgraph n_demo[\"demo\"]
+        subgraph n_demo__outer[\"outer\"]
+            subgraph n_demo__outer__inner[\"inner\"]
+                n_demo__outer__inner__run([\"run\"])
+            end
+        end
+        n_demo__main([\"main\"])
+    end
+    n_demo__main -->|\"calls\"| n_demo__outer__inner__run
+"
+        );
+    }
+
+    #[test]
+    fn flow_collapses_modules_at_depth() {
+        let options = MermaidFlowOptions {
+            collapse_depth: Some(1),
+        };
+        let out = export_mermaid_flow(&two_level_modules(), &options);
+        assert_eq!(
+            out,
+            "flowchart LR
+    subgraph n_demo[\"demo\"]
+        n_demo__outer[[\"outer\"]]
+        n_demo__main([\"main\"])
+    end
+    n_demo__main -->|\"calls\"| n_demo__outer
+"
+        );
+    }
+
+    #[test]
+    fn flow_prefers_edge_labels() {
+        let mut graph = Graph::new();
+        graph.nodes = vec![
+            node("demo", NodeKind::Crate),
+            node("demo::Line", NodeKind::Struct),
+            node("demo::Point", NodeKind::Struct),
+        ];
+        let field = |name: &str| Edge {
+            label: Some(name.to_string()),
+            ..edge("demo::Line", "demo::Point", EdgeKind::HasField)
+        };
+        graph.edges = vec![
+            edge("demo", "demo::Line", EdgeKind::Contains),
+            edge("demo", "demo::Point", EdgeKind::Contains),
+            field("start"),
+            field("end"),
+            edge("demo::Line", "demo::Point", EdgeKind::UsesType),
+        ];
+        let out = export_mermaid_flow(&graph, &MermaidFlowOptions::default());
+        assert!(out.ends_with(
+            "    n_demo__Line -->|\"start\"| n_demo__Point
+    n_demo__Line -->|\"end\"| n_demo__Point
+    n_demo__Line -->|\"uses\"| n_demo__Point
+"
+        ));
+    }
+
+    #[test]
+    fn class_diagram_lists_members_and_relations() {
+        let mut named = node("demo::Named", NodeKind::Trait);
+        named.visibility = Visibility::Crate;
+        let mut circle = node("demo::Circle", NodeKind::Struct);
+        circle.fields = Some(vec![FieldInfo {
+            name: "radius".to_string(),
+            type_: TypeRef::Primitive {
+                name: "f64".to_string(),
+            },
+            visibility: Visibility::Crate,
+        }]);
+        let mut kind = node("demo::Kind", NodeKind::Enum);
+        kind.variants = Some(vec![VariantInfo {
+            name: "Round".to_string(),
+            fields: Vec::new(),
+        }]);
+        let mut shape_impl = node("demo::Circle::impl-Shape", NodeKind::Impl);
+        shape_impl.impl_type = Some(ImplType::Trait);
+        let mut blanket = node("demo::Circle::impl-Into", NodeKind::Impl);
+        blanket.impl_type = Some(ImplType::Trait);
+        blanket.impl_category = Some(ImplCategory::Blanket);
+        let mut area = node("demo::Circle::area", NodeKind::Function);
+        area.visibility = Visibility::Inherited;
+        let mut graph = Graph::new();
+        graph.nodes = vec![
+            node("demo", NodeKind::Crate),
+            named,
+            node("demo::Shape", NodeKind::Trait),
+            node("demo::Shape::area", NodeKind::Function),
+            circle,
+            kind,
+            shape_impl,
+            area,
+            blanket,
+            node("demo::Circle::into", NodeKind::Function),
+        ];
+        graph.edges = vec![
+            edge("demo", "demo::Circle", EdgeKind::Contains),
+            edge("demo::Shape", "demo::Shape::area", EdgeKind::Defines),
+            edge(
+                "demo::Circle",
+                "demo::Circle::impl-Shape",
+                EdgeKind::Defines,
+            ),
+            edge(
+                "demo::Circle::impl-Shape",
+                "demo::Circle::area",
+                EdgeKind::Defines,
+            ),
+            edge("demo::Circle", "demo::Circle::impl-Into", EdgeKind::Defines),
+            edge(
+                "demo::Circle::impl-Into",
+                "demo::Circle::into",
+                EdgeKind::Defines,
+            ),
+            edge("demo::Shape", "demo::Named", EdgeKind::Supertrait),
+            edge("demo::Circle", "demo::Shape", EdgeKind::Implements),
+            edge("demo::Circle", "demo::Shape", EdgeKind::Implements),
+            edge("demo::Circle", "demo::Kind", EdgeKind::UsesType),
+        ];
+
+        assert_eq!(
+            export_mermaid_class(&graph),
+            "classDiagram
+    class n_demo__Named[\"Named\"]
+    <<trait>> n_demo__Named
+    class n_demo__Shape[\"Shape\"]
+    <<trait>> n_demo__Shape
+    n_demo__Shape : +area()
+    class n_demo__Circle[\"Circle\"]
+    n_demo__Circle : ~radius
+    n_demo__Circle : +area()
+    class n_demo__Kind[\"Kind\"]
+    <<enumeration>> n_demo__Kind
+    n_demo__Kind : Round
+    n_demo__Named <|-- n_demo__Shape
+    n_demo__Shape <|.. n_demo__Circle
+    n_demo__Circle --> n_demo__Kind
+"
+        );
+        assert_eq!(
+            export_mermaid(&graph, MermaidKind::Class, &MermaidFlowOptions::default()),
+            export_mermaid_class(&graph)
+        );
+    }
+
+    #[test]
+    fn dot_keeps_parallel_edges_as_separate_statements() {
+        let mut graph = Graph::new();
+        graph.nodes = vec![
+            node("demo::run", NodeKind::Function),
+            node("demo::Config", NodeKind::Struct),
+        ];
+        graph.edges = vec![
+            edge("demo::run", "demo::Config", EdgeKind::UsesType),
+            edge("demo::run", "demo::Config", EdgeKind::CallsStatic),
+            edge("demo::Config", "demo::run", EdgeKind::CallsRuntime),
+        ];
+        let out = export_dot(&graph);
+        // Not `strict`, so Graphviz fans each of these out on its own spline.
+        assert!(out.starts_with("digraph "));
+        assert_eq!(out.matches(" -> ").count(), 3);
+        assert!(out.contains("\"demo::run\" -> \"demo::Config\" [label=\"uses\"];"));
+        assert!(out.contains("\"demo::run\" -> \"demo::Config\" [label=\"calls\"];"));
+    }
+
+    #[test]
+    fn function_listings_render_flags_and_signatures() {
+        let path = |path: &str, args: Vec<TypeRef>| TypeRef::ResolvedPath {
+            id: "0".to_string(),
+            path: path.to_string(),
+            args: (!args.is_empty()).then(|| {
+                Box::new(GenericArgs::AngleBracketed {
+                    args: args
+                        .into_iter()
+                        .map(|value| GenericArg::Type { value })
+                        .collect(),
+                    constraints: Vec::new(),
+                })
+            }),
+        };
+        let generic = |name: &str| TypeRef::Generic {
+            name: name.to_string(),
+        };
+        let clone_bound = GenericBound::Trait {
+            trait_: path("core::clone::Clone", Vec::new()),
+            modifier: TraitBoundModifier::None,
+            hrtb_params: Vec::new(),
+        };
+        let mut parse = node("demo::parse", NodeKind::Function);
+        parse.signature = Some(FunctionSignature {
+            inputs: vec![
+                ArgumentInfo {
+                    name: "input".to_string(),
+                    type_: TypeRef::BorrowedRef {
+                        lifetime: Some("'a".to_string()),
+                        mutable: false,
+                        inner: Box::new(TypeRef::Primitive {
+                            name: "str".to_string(),
+                        }),
+                    },
+                },
+                ArgumentInfo {
+                    name: "items".to_string(),
+                    type_: path("alloc::vec::Vec", vec![generic("T")]),
+                },
+            ],
+            output: Some(path(
+                "core::result::Result",
+                vec![generic("T"), path("demo::Error", Vec::new())],
+            )),
+            is_async: true,
+            is_unsafe: true,
+            is_const: false,
+            abi: None,
+            is_c_variadic: false,
+            is_async_trait: false,
+            generics: Generics {
+                params: vec![
+                    GenericParam {
+                        name: "'a".to_string(),
+                        kind: GenericParamKind::Lifetime {
+                            outlives: Vec::new(),
+                        },
+                    },
+                    GenericParam {
+                        name: "T".to_string(),
+                        kind: GenericParamKind::Type {
+                            bounds: vec![clone_bound],
+                            default: None,
+                            synthetic: false,
+                        },
+                    },
+                ],
+                where_predicates: vec![WherePredicate::Bound {
+                    type_: generic("T"),
+                    bounds: vec![GenericBound::Trait {
+                        trait_: path("core::default::Default", Vec::new()),
+                        modifier: TraitBoundModifier::None,
+                        hrtb_params: Vec::new(),
+                    }],
+                    hrtb_params: Vec::new(),
+                }],
+            },
+        });
+        let mut helper = node("demo::helper", NodeKind::Function);
+        helper.visibility = Visibility::Crate;
+        let mut external = node("dep::run", NodeKind::Function);
+        external.is_external = true;
+        let mut graph = Graph::new();
+        graph.nodes = vec![
+            node("demo", NodeKind::Crate),
+            parse,
+            helper,
+            node("demo::Error", NodeKind::Struct),
+            external,
+        ];
+
+        assert_eq!(
+            export_functions_csv(&graph),
+            "id,visibility,async,unsafe,const,signature
+demo::parse,pub,true,true,false,\"async unsafe fn parse<'a, T: Clone>(input: &'a str, items: Vec<T>) -> Result<T, Error> where T: Default\"
+demo::helper,pub(crate),false,false,false,
+"
+        );
+        assert_eq!(
+            export_functions_markdown(&graph),
+            "| id | visibility | async | unsafe | const | signature |
+| --- | --- | --- | --- | --- | --- |
+| `demo::parse` | pub | yes | yes |  | `async unsafe fn parse<'a, T: Clone>(input: &'a str, items: Vec<T>) -> Result<T, Error> where T: Default` |
+| `demo::helper` | pub(crate) |  |  |  |  |
+"
+        );
+
+        let scale = FunctionSignature {
+            inputs: vec![
+                ArgumentInfo {
+                    name: "self".to_string(),
+                    type_: TypeRef::BorrowedRef {
+                        lifetime: None,
+                        mutable: true,
+                        inner: Box::new(generic("Self")),
+                    },
+                },
+                ArgumentInfo {
+                    name: "by".to_string(),
+                    type_: TypeRef::Primitive {
+                        name: "f64".to_string(),
+                    },
+                },
+            ],
+            output: None,
+            is_async: false,
+            is_unsafe: false,
+            is_const: true,
+            abi: Some("C".to_string()),
+            is_c_variadic: false,
+            is_async_trait: false,
+            generics: Generics::default(),
+        };
+        assert_eq!(
+            render_signature("scale", &scale),
+            "const extern \"C\" fn scale(&mut self, by: f64)"
+        );
+    }
+}
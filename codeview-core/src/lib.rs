@@ -1,6 +1,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+pub mod export;
+
 /// Current graph schema version.
 ///
 /// **Pinned at 1 pre-release.** We're iterating on the shape of the
@@ -16,6 +18,77 @@ use serde::{Deserialize, Serialize};
 /// to (not for every internal parser tweak).
 pub const SCHEMA_VERSION: u32 = 1;
 
+/// The schema version a serialized workspace was written with. Files from
+/// before the field existed count as [`SCHEMA_VERSION`].
+pub fn stored_schema_version(value: &serde_json::Value) -> Result<u32, SchemaError> {
+    match value.get("version") {
+        None => Ok(SCHEMA_VERSION),
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| SchemaError::InvalidVersion(version.clone())),
+    }
+}
+
+/// Whether this build can read a graph written with schema `version`:
+/// anything up to [`SCHEMA_VERSION`] is, since [`migrate_workspace`]
+/// rewrites older graphs. Graphs from a newer codeview are refused rather
+/// than half-read.
+pub fn check_schema_version(version: u32) -> Result<(), SchemaError> {
+    if version > SCHEMA_VERSION {
+        return Err(SchemaError::TooNew(version));
+    }
+    Ok(())
+}
+
+/// Deserialize a workspace written with any schema version this build
+/// knows, rewriting older JSON up to [`SCHEMA_VERSION`] first. Every reader
+/// of `codeview analyze` output should load it through here.
+pub fn migrate_workspace(mut value: serde_json::Value) -> Result<Workspace, SchemaError> {
+    let version = stored_schema_version(&value)?;
+    check_schema_version(version)?;
+    // One step per format change goes here, oldest first, e.g.
+    // `if version < 2 { migrate_v1_to_v2(&mut value); }`.
+    value["version"] = SCHEMA_VERSION.into();
+    serde_json::from_value(value).map_err(SchemaError::Json)
+}
+
+/// Why a serialized workspace couldn't be loaded.
+#[derive(Debug)]
+pub enum SchemaError {
+    /// The `version` field isn't a `u32`
+    InvalidVersion(serde_json::Value),
+    /// Written by a newer codeview than this one
+    TooNew(u32),
+    /// Doesn't match the schema once migrated
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaError::InvalidVersion(version) => {
+                write!(f, "schema version {version} is not a number")
+            }
+            SchemaError::TooNew(version) => write!(
+                f,
+                "graph has schema version {version}, but this codeview reads up to \
+                 {SCHEMA_VERSION}; upgrade codeview or regenerate the graph"
+            ),
+            SchemaError::Json(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SchemaError::Json(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
 /// Top-level workspace: per-crate graphs with cross-crate edges.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Workspace {
@@ -35,6 +108,350 @@ pub struct Workspace {
     pub ref_: Option<String>,
 }
 
+impl Workspace {
+    /// Flatten member crates and cross-crate edges into one [`Graph`],
+    /// keeping only the external stubs some edge actually points at. Doc
+    /// links come back inline even if a crate was interned, since per-crate
+    /// tables can't share one index space.
+    pub fn merged_graph(&self) -> Graph {
+        let mut graph = Graph::new();
+        for krate in &self.crates {
+            let mut nodes = krate.nodes.clone();
+            let mut table = krate.doc_link_table.clone();
+            expand_doc_links(&mut nodes, &mut table);
+            graph.nodes.extend(nodes);
+            graph.edges.extend(krate.edges.iter().cloned());
+            graph
+                .aliases
+                .extend(krate.aliases.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        graph.edges.extend(self.cross_crate_edges.iter().cloned());
+        let referenced: std::collections::HashSet<&str> = graph
+            .edges
+            .iter()
+            .flat_map(|edge| [edge.from.as_str(), edge.to.as_str()])
+            .collect();
+        let externals: Vec<Node> = self
+            .external_crates
+            .iter()
+            .flat_map(|external| &external.nodes)
+            .filter(|node| referenced.contains(node.id.as_str()))
+            .cloned()
+            .collect();
+        graph.nodes.extend(externals);
+        graph
+    }
+
+    /// Union of two workspaces, e.g. to view several independent ones
+    /// together. Nodes and edges merge as in [`Graph::merge_all`], then
+    /// everything is split back into crates by id prefix, so an edge that
+    /// now joins two crates lands in `cross_crate_edges` and an external
+    /// stub becomes part of the crate if either side analyzed it. Crate
+    /// names and versions come from `self` first. `repo` and `ref` survive
+    /// only when both sides agree.
+    pub fn merge(self, other: Workspace) -> Workspace {
+        let mut members: std::collections::BTreeMap<String, (String, String)> =
+            std::collections::BTreeMap::new();
+        let mut externals: std::collections::BTreeMap<String, (String, Option<String>)> =
+            std::collections::BTreeMap::new();
+        let mut graphs = Vec::new();
+        let same_source = self.repo == other.repo && self.ref_ == other.ref_;
+        let (repo, ref_) = (self.repo.clone(), self.ref_.clone());
+        for workspace in [self, other] {
+            for krate in workspace.crates {
+                members
+                    .entry(krate.id.clone())
+                    .or_insert((krate.name, krate.version));
+                graphs.push(Graph {
+                    nodes: krate.nodes,
+                    edges: krate.edges,
+                    aliases: krate.aliases,
+                    doc_link_table: krate.doc_link_table,
+                    crate_versions: std::collections::HashMap::new(),
+                });
+            }
+            for external in workspace.external_crates {
+                externals
+                    .entry(external.id.clone())
+                    .or_insert((external.name, external.version));
+                let mut graph = Graph::new();
+                graph.nodes = external.nodes;
+                graphs.push(graph);
+            }
+            let mut graph = Graph::new();
+            graph.edges = workspace.cross_crate_edges;
+            graphs.push(graph);
+        }
+        let merged = Graph::merge_all(graphs);
+
+        let crate_of = |id: &str| id.split("::").next().unwrap_or(id).to_string();
+        let mut nodes_by_crate: std::collections::HashMap<String, Vec<Node>> =
+            std::collections::HashMap::new();
+        for node in merged.nodes {
+            nodes_by_crate
+                .entry(crate_of(&node.id))
+                .or_default()
+                .push(node);
+        }
+        let mut edges_by_crate: std::collections::HashMap<String, Vec<Edge>> =
+            std::collections::HashMap::new();
+        let mut cross_crate_edges = Vec::new();
+        for edge in merged.edges {
+            let from_crate = crate_of(&edge.from);
+            if from_crate == crate_of(&edge.to) {
+                edges_by_crate.entry(from_crate).or_default().push(edge);
+            } else {
+                cross_crate_edges.push(edge);
+            }
+        }
+        let mut aliases_by_crate: std::collections::HashMap<
+            String,
+            std::collections::HashMap<String, String>,
+        > = std::collections::HashMap::new();
+        for (alias, target) in merged.aliases {
+            aliases_by_crate
+                .entry(crate_of(&target))
+                .or_default()
+                .insert(alias, target);
+        }
+
+        let crates = members
+            .into_iter()
+            .map(|(id, (name, version))| CrateGraph {
+                nodes: nodes_by_crate.remove(&id).unwrap_or_default(),
+                edges: edges_by_crate.remove(&id).unwrap_or_default(),
+                aliases: aliases_by_crate.remove(&id).unwrap_or_default(),
+                doc_link_table: Vec::new(),
+                id,
+                name,
+                version,
+            })
+            .collect();
+        let external_crates = externals
+            .into_iter()
+            .filter_map(|(id, (name, version))| {
+                Some(ExternalCrate {
+                    nodes: nodes_by_crate.remove(&id)?,
+                    id,
+                    name,
+                    version,
+                })
+            })
+            .collect();
+
+        let mut workspace = Workspace {
+            version: SCHEMA_VERSION,
+            crates,
+            external_crates,
+            cross_crate_edges,
+            repo: repo.filter(|_| same_source),
+            ref_: ref_.filter(|_| same_source),
+        };
+        workspace.sort_stable();
+        workspace
+    }
+
+    /// Union of this workspace, built with default features, and the same
+    /// workspace built with each of `variants`' feature sets. Nodes missing
+    /// from the default build get the smallest set they appear under in
+    /// [`Node::features`]; sets of equal size keep their given order.
+    pub fn merge_feature_variants(self, variants: Vec<(Vec<String>, Workspace)>) -> Workspace {
+        let node_ids = |workspace: &Workspace| -> Vec<String> {
+            workspace
+                .crates
+                .iter()
+                .flat_map(|krate| &krate.nodes)
+                .chain(workspace.external_crates.iter().flat_map(|ext| &ext.nodes))
+                .map(|node| node.id.clone())
+                .collect()
+        };
+        let mut seen: std::collections::HashSet<String> = node_ids(&self).into_iter().collect();
+        let mut variants = variants;
+        variants.sort_by_key(|(features, _)| features.len());
+
+        let mut tags: std::collections::HashMap<String, Vec<String>> =
+            std::collections::HashMap::new();
+        let mut merged = self;
+        for (features, variant) in variants {
+            for id in node_ids(&variant) {
+                if seen.insert(id.clone()) {
+                    tags.insert(id, features.clone());
+                }
+            }
+            merged = merged.merge(variant);
+        }
+        let nodes = merged
+            .crates
+            .iter_mut()
+            .flat_map(|krate| &mut krate.nodes)
+            .chain(
+                merged
+                    .external_crates
+                    .iter_mut()
+                    .flat_map(|ext| &mut ext.nodes),
+            );
+        for node in nodes {
+            if let Some(features) = tags.remove(&node.id) {
+                node.features = features;
+            }
+        }
+        merged
+    }
+
+    /// [`Graph::sort_stable`] for every crate, external stub and the
+    /// cross-crate edges.
+    pub fn sort_stable(&mut self) {
+        for crate_graph in &mut self.crates {
+            sort_nodes(&mut crate_graph.nodes);
+            sort_edges(&mut crate_graph.edges);
+        }
+        for external in &mut self.external_crates {
+            sort_nodes(&mut external.nodes);
+        }
+        sort_edges(&mut self.cross_crate_edges);
+    }
+
+    /// [`Graph::retain_public`] for every member crate. Cross-crate edges
+    /// touching a dropped node go too; external stubs are left alone.
+    pub fn retain_public(&mut self, include_crate_visible: bool) {
+        let mut removed = std::collections::HashSet::new();
+        for krate in &mut self.crates {
+            removed.extend(retain_public(
+                &mut krate.nodes,
+                &mut krate.edges,
+                &mut krate.aliases,
+                include_crate_visible,
+            ));
+        }
+        self.cross_crate_edges
+            .retain(|edge| !removed.contains(&edge.from) && !removed.contains(&edge.to));
+    }
+
+    /// Types with an `Implements` edge to `trait_id`, from any member crate
+    /// or the cross-crate edges, in edge order without repeats.
+    pub fn implementors_of(&self, trait_id: &str) -> Vec<&Node> {
+        self.implements_neighbours(|edge| (edge.to == trait_id).then_some(&edge.from))
+    }
+
+    /// Traits `type_id` implements, wherever the impl lives; the inverse of
+    /// [`Workspace::implementors_of`]. External traits come back as their
+    /// stubs.
+    pub fn trait_impls_of(&self, type_id: &str) -> Vec<&Node> {
+        self.implements_neighbours(|edge| (edge.from == type_id).then_some(&edge.to))
+    }
+
+    fn implements_neighbours<'a>(
+        &'a self,
+        other_end: impl Fn(&'a Edge) -> Option<&'a String>,
+    ) -> Vec<&'a Node> {
+        let nodes: std::collections::HashMap<&str, &Node> = self
+            .crates
+            .iter()
+            .flat_map(|krate| &krate.nodes)
+            .chain(self.external_crates.iter().flat_map(|ext| &ext.nodes))
+            .map(|node| (node.id.as_str(), node))
+            .collect();
+        let mut seen = std::collections::HashSet::new();
+        self.crates
+            .iter()
+            .flat_map(|krate| &krate.edges)
+            .chain(&self.cross_crate_edges)
+            .filter(|edge| edge.kind == EdgeKind::Implements)
+            .filter_map(other_end)
+            .filter(|other| seen.insert(other.as_str()))
+            .filter_map(|other| nodes.get(other.as_str()).copied())
+            .collect()
+    }
+
+    /// [`Graph::link_impl_items`] across the workspace. A new edge goes
+    /// into the member crate both of its ends belong to, or the cross-crate
+    /// edges otherwise (a trait impl for an external type, say).
+    pub fn link_impl_items(&mut self, elide_inherent: bool) {
+        let (links, inherent) = impl_item_links(
+            self.crates.iter().flat_map(|krate| &krate.nodes),
+            self.crates
+                .iter()
+                .flat_map(|krate| &krate.edges)
+                .chain(&self.cross_crate_edges),
+        );
+        self.push_routed_edges(links);
+        if elide_inherent {
+            self.remove_nodes(&inherent);
+        }
+    }
+
+    /// [`Graph::contract_private_calls`] across the workspace, following
+    /// calls between crates too. New edges are placed as in
+    /// [`Workspace::link_impl_items`].
+    pub fn contract_private_calls(&mut self) {
+        let (links, private) = contracted_calls(
+            self.crates.iter().flat_map(|krate| &krate.nodes),
+            self.crates
+                .iter()
+                .flat_map(|krate| &krate.edges)
+                .chain(&self.cross_crate_edges),
+        );
+        self.push_routed_edges(links);
+        self.remove_nodes(&private);
+    }
+
+    /// Add `edges` that aren't already present to the member crate both
+    /// ends belong to, or to the cross-crate edges.
+    fn push_routed_edges(&mut self, edges: Vec<Edge>) {
+        let crate_of = |id: &str| id.split("::").next().unwrap_or(id).to_string();
+        let mut by_crate: std::collections::HashMap<String, Vec<Edge>> =
+            std::collections::HashMap::new();
+        let mut cross = Vec::new();
+        for edge in edges {
+            let krate = crate_of(&edge.from);
+            if krate == crate_of(&edge.to) && self.crates.iter().any(|c| c.id == krate) {
+                by_crate.entry(krate).or_default().push(edge);
+            } else {
+                cross.push(edge);
+            }
+        }
+        for krate in &mut self.crates {
+            if let Some(edges) = by_crate.remove(&krate.id) {
+                push_new_edges(&mut krate.edges, edges);
+            }
+        }
+        push_new_edges(&mut self.cross_crate_edges, cross);
+    }
+
+    /// Drop the `ids` member-crate nodes everywhere they're referred to.
+    fn remove_nodes(&mut self, ids: &std::collections::HashSet<String>) {
+        for krate in &mut self.crates {
+            remove_nodes(&mut krate.nodes, &mut krate.edges, &mut krate.aliases, ids);
+        }
+        self.cross_crate_edges
+            .retain(|edge| !ids.contains(&edge.from) && !ids.contains(&edge.to));
+    }
+
+    /// [`Graph::retain_kinds`] for every member crate and external stub,
+    /// plus the cross-crate edges touching a dropped node.
+    pub fn retain_kinds(&mut self, keep: &std::collections::HashSet<NodeKind>) {
+        let mut removed = std::collections::HashSet::new();
+        for krate in &mut self.crates {
+            removed.extend(retain_kinds(
+                &mut krate.nodes,
+                &mut krate.edges,
+                &mut krate.aliases,
+                keep,
+            ));
+        }
+        for external in &mut self.external_crates {
+            removed.extend(
+                external
+                    .nodes
+                    .extract_if(.., |node| !keep.contains(&node.kind))
+                    .map(|node| node.id),
+            );
+        }
+        self.cross_crate_edges
+            .retain(|edge| !removed.contains(&edge.from) && !removed.contains(&edge.to));
+    }
+}
+
 /// A single crate's graph data.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CrateGraph {
@@ -51,6 +468,21 @@ pub struct CrateGraph {
     /// Public re-export path → canonical node ID (see `Graph::aliases`).
     #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
     pub aliases: std::collections::HashMap<String, String>,
+    /// Shared doc-link target table (see `Graph::doc_link_table`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub doc_link_table: Vec<String>,
+}
+
+impl CrateGraph {
+    /// See [`Graph::intern_doc_links`].
+    pub fn intern_doc_links(&mut self) {
+        intern_doc_links(&mut self.nodes, &mut self.doc_link_table);
+    }
+
+    /// See [`Graph::expand_doc_links`].
+    pub fn expand_doc_links(&mut self) {
+        expand_doc_links(&mut self.nodes, &mut self.doc_link_table);
+    }
 }
 
 /// Stub for an external crate referenced by workspace crates.
@@ -71,7 +503,27 @@ const fn default_version() -> u32 {
     SCHEMA_VERSION
 }
 
-/// Internal flat graph used during per-crate graph building.
+/// JSON Schema for a serialized [`Workspace`] (`codeview analyze` output),
+/// carrying [`SCHEMA_VERSION`] as `x-schema-version`. It describes the
+/// deserialize side, so a field is only required when the serializer always
+/// writes it.
+pub fn workspace_schema() -> schemars::Schema {
+    versioned(schemars::schema_for!(Workspace))
+}
+
+/// JSON Schema for a serialized single-crate [`Graph`] (`codeview
+/// parse-json` output). See [`workspace_schema`].
+pub fn graph_schema() -> schemars::Schema {
+    versioned(schemars::schema_for!(Graph))
+}
+
+fn versioned(mut schema: schemars::Schema) -> schemars::Schema {
+    schema.insert("x-schema-version".to_string(), SCHEMA_VERSION.into());
+    schema
+}
+
+/// Flat graph of one crate, as per-crate building produces it and
+/// `parse-json` writes it.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Graph {
     pub nodes: Vec<Node>,
@@ -88,6 +540,18 @@ pub struct Graph {
     /// to an empty map.
     #[serde(default)]
     pub aliases: std::collections::HashMap<String, String>,
+    /// Interned doc-link targets referenced by `Node::doc_link_refs`.
+    ///
+    /// Empty (and omitted from JSON) unless the graph was written in the
+    /// interned form via [`Graph::intern_doc_links`]. Graphs without this
+    /// field keep their targets inline in `Node::doc_links`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub doc_link_table: Vec<String>,
+    /// Crate versions by normalized crate name (`-` spelled `_`), e.g.
+    /// `drizzle_core` → `0.1.4`. Crates whose version isn't known have no
+    /// entry.
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub crate_versions: std::collections::HashMap<String, String>,
 }
 
 impl Graph {
@@ -96,7 +560,524 @@ impl Graph {
             nodes: Vec::new(),
             edges: Vec::new(),
             aliases: std::collections::HashMap::new(),
+            doc_link_table: Vec::new(),
+            crate_versions: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Nodes flagged [`Node::is_entry`], in graph order. Useful as the root
+    /// set for applications, where the public API says little about what
+    /// actually runs.
+    pub fn entry_points(&self) -> Vec<&Node> {
+        self.nodes.iter().filter(|node| node.is_entry).collect()
+    }
+
+    /// Nodes with a call edge into `id` whose confidence is in `confidences`,
+    /// in edge order without repeats. Pass `&[Static, Runtime]` to leave out
+    /// `Inferred` guesses.
+    pub fn callers_of(&self, id: &str, confidences: &[Confidence]) -> Vec<&Node> {
+        self.call_neighbours(confidences, |edge| (edge.to == id).then_some(&edge.from))
+    }
+
+    /// Nodes that `id` calls, filtered like [`Graph::callers_of`].
+    pub fn callees_of(&self, id: &str, confidences: &[Confidence]) -> Vec<&Node> {
+        self.call_neighbours(confidences, |edge| (edge.from == id).then_some(&edge.to))
+    }
+
+    /// Local functions and methods that no call path reaches from `roots`,
+    /// following `CallsStatic` and `CallsRuntime` edges. With
+    /// `public_roots`, every effectively public function is a root too.
+    ///
+    /// Trait methods and the methods of trait impls are always roots and
+    /// never reported: they're reached through trait dispatch, which the
+    /// call edges only partly capture. Even so, treat the result as likely
+    /// dead code rather than proof.
+    pub fn unreachable_functions(&self, roots: &[&str], public_roots: bool) -> Vec<&Node> {
+        let nodes: std::collections::HashMap<&str, &Node> = self
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node))
+            .collect();
+        let mut callees: std::collections::HashMap<&str, Vec<&str>> =
+            std::collections::HashMap::new();
+        let mut dispatched = std::collections::HashSet::new();
+        for edge in &self.edges {
+            match edge.kind {
+                EdgeKind::CallsStatic | EdgeKind::CallsRuntime => callees
+                    .entry(edge.from.as_str())
+                    .or_default()
+                    .push(edge.to.as_str()),
+                EdgeKind::Defines => {
+                    let via_trait = nodes.get(edge.from.as_str()).is_some_and(|owner| {
+                        owner.kind == NodeKind::Trait || owner.impl_type == Some(ImplType::Trait)
+                    });
+                    if via_trait {
+                        dispatched.insert(edge.to.as_str());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let is_candidate = |node: &Node| {
+            node.kind == NodeKind::Function
+                && !node.is_external
+                && !dispatched.contains(node.id.as_str())
+        };
+        let mut stack: Vec<&str> = roots.to_vec();
+        stack.extend(dispatched.iter().copied());
+        if public_roots {
+            stack.extend(
+                self.nodes
+                    .iter()
+                    .filter(|node| {
+                        is_candidate(node)
+                            && node
+                                .effective_visibility
+                                .as_ref()
+                                .unwrap_or(&node.visibility)
+                                == &Visibility::Public
+                    })
+                    .map(|node| node.id.as_str()),
+            );
+        }
+        let mut reached = std::collections::HashSet::new();
+        while let Some(id) = stack.pop() {
+            if reached.insert(id) {
+                stack.extend(callees.get(id).into_iter().flatten().copied());
+            }
+        }
+
+        self.nodes
+            .iter()
+            .filter(|node| is_candidate(node) && !reached.contains(node.id.as_str()))
+            .collect()
+    }
+
+    /// Strongly connected components of the subgraph formed by edges of
+    /// `kinds` (Tarjan's algorithm). Only components of two or more nodes
+    /// are returned: `push_edge` never records self-loops, so a lone node
+    /// is never a cycle. Ids within a component are sorted, and components
+    /// are ordered by their first id.
+    pub fn find_cycles(&self, kinds: &[EdgeKind]) -> Vec<Vec<String>> {
+        let mut index_of: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+        let mut ids: Vec<&str> = Vec::new();
+        let mut successors: Vec<Vec<usize>> = Vec::new();
+        for edge in self.edges.iter().filter(|edge| kinds.contains(&edge.kind)) {
+            let [from, to] = [edge.from.as_str(), edge.to.as_str()].map(|id| {
+                *index_of.entry(id).or_insert_with(|| {
+                    ids.push(id);
+                    successors.push(Vec::new());
+                    ids.len() - 1
+                })
+            });
+            successors[from].push(to);
+        }
+
+        // Iterative, so deep `UsesType` chains can't overflow the stack.
+        // Work items are (node, next successor to look at); a node is
+        // discovered when it's first popped at position 0.
+        const UNVISITED: usize = usize::MAX;
+        let mut order = vec![UNVISITED; ids.len()];
+        let mut low = vec![0; ids.len()];
+        let mut on_stack = vec![false; ids.len()];
+        let mut stack = Vec::new();
+        let mut next_order = 0;
+        let mut cycles = Vec::new();
+        for root in 0..ids.len() {
+            if order[root] != UNVISITED {
+                continue;
+            }
+            let mut work = vec![(root, 0)];
+            while let Some((node, position)) = work.pop() {
+                if position == 0 {
+                    order[node] = next_order;
+                    low[node] = next_order;
+                    next_order += 1;
+                    stack.push(node);
+                    on_stack[node] = true;
+                }
+                if let Some(&next) = successors[node].get(position) {
+                    work.push((node, position + 1));
+                    if order[next] == UNVISITED {
+                        work.push((next, 0));
+                    } else if on_stack[next] {
+                        low[node] = low[node].min(order[next]);
+                    }
+                    continue;
+                }
+
+                if let Some(&(parent, _)) = work.last() {
+                    low[parent] = low[parent].min(low[node]);
+                }
+                if low[node] == order[node] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().expect("component root is on the stack");
+                        on_stack[member] = false;
+                        component.push(ids[member].to_string());
+                        if member == node {
+                            break;
+                        }
+                    }
+                    if component.len() > 1 {
+                        component.sort();
+                        cycles.push(component);
+                    }
+                }
+            }
         }
+        cycles.sort();
+        cycles
+    }
+
+    /// Size and shape summary: node, edge and confidence counts, call
+    /// fan-in/fan-out over local functions, and how deep local modules
+    /// nest below their crate root.
+    pub fn graph_metrics(&self) -> GraphMetrics {
+        let mut metrics = GraphMetrics::default();
+        for node in &self.nodes {
+            *metrics.nodes.entry(node.kind).or_default() += 1;
+            if node.is_external {
+                metrics.external_nodes += 1;
+            }
+        }
+        for edge in &self.edges {
+            *metrics.edges.entry(edge.kind).or_default() += 1;
+            *metrics.confidence.entry(edge.confidence).or_default() += 1;
+        }
+
+        // Distinct neighbours, so a call site seen as both static and
+        // runtime counts once.
+        let mut callers: std::collections::HashMap<&str, std::collections::HashSet<&str>> =
+            std::collections::HashMap::new();
+        let mut callees: std::collections::HashMap<&str, std::collections::HashSet<&str>> =
+            std::collections::HashMap::new();
+        for edge in self.edges.iter().filter(|edge| edge.kind.is_call()) {
+            callers
+                .entry(edge.to.as_str())
+                .or_default()
+                .insert(edge.from.as_str());
+            callees
+                .entry(edge.from.as_str())
+                .or_default()
+                .insert(edge.to.as_str());
+        }
+        let functions: Vec<&str> = self
+            .nodes
+            .iter()
+            .filter(|node| node.kind == NodeKind::Function && !node.is_external)
+            .map(|node| node.id.as_str())
+            .collect();
+        metrics.fan_in = FanStats::over(&functions, &callers);
+        metrics.fan_out = FanStats::over(&functions, &callees);
+
+        let by_id: std::collections::HashMap<&str, &Node> = self
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node))
+            .collect();
+        let mut submodules: std::collections::HashMap<&str, Vec<&str>> =
+            std::collections::HashMap::new();
+        for edge in self
+            .edges
+            .iter()
+            .filter(|edge| edge.kind == EdgeKind::Contains)
+        {
+            if by_id
+                .get(edge.to.as_str())
+                .is_some_and(|node| node.kind == NodeKind::Module && !node.is_external)
+            {
+                submodules
+                    .entry(edge.from.as_str())
+                    .or_default()
+                    .push(edge.to.as_str());
+            }
+        }
+        let mut seen = std::collections::HashSet::new();
+        let mut stack: Vec<(&str, usize)> = self
+            .nodes
+            .iter()
+            .filter(|node| node.kind == NodeKind::Crate && !node.is_external)
+            .map(|node| (node.id.as_str(), 0))
+            .collect();
+        while let Some((id, depth)) = stack.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            metrics.max_module_depth = metrics.max_module_depth.max(depth);
+            for &module in submodules.get(id).into_iter().flatten() {
+                stack.push((module, depth + 1));
+            }
+        }
+
+        metrics
+    }
+
+    /// The local crate root and everything under it, nested along
+    /// `Contains` edges with children ordered by kind, then name. An item
+    /// with more than one parent hangs under the first edge's. Below
+    /// `max_depth` (the root is depth 0) children are left out and the
+    /// node marked [`TreeNode::truncated`]. `None` without a crate root.
+    pub fn module_tree(&self, max_depth: Option<usize>) -> Option<TreeNode> {
+        let by_id: std::collections::HashMap<&str, &Node> = self
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node))
+            .collect();
+        let root = self
+            .nodes
+            .iter()
+            .find(|node| node.kind == NodeKind::Crate && !node.is_external)?;
+        let mut parented = std::collections::HashSet::new();
+        let mut children: std::collections::HashMap<&str, Vec<&Node>> =
+            std::collections::HashMap::new();
+        for edge in self
+            .edges
+            .iter()
+            .filter(|edge| edge.kind == EdgeKind::Contains)
+        {
+            let (Some(_), Some(&child)) =
+                (by_id.get(edge.from.as_str()), by_id.get(edge.to.as_str()))
+            else {
+                continue;
+            };
+            if parented.insert(child.id.as_str()) {
+                children.entry(edge.from.as_str()).or_default().push(child);
+            }
+        }
+        for nodes in children.values_mut() {
+            nodes.sort_by(|a, b| (a.kind, &a.name, &a.id).cmp(&(b.kind, &b.name, &b.id)));
+        }
+        let mut seen = std::collections::HashSet::new();
+        Some(module_tree(root, 0, max_depth, &children, &mut seen))
+    }
+
+    /// Structural problems extraction should never produce: node ids that
+    /// are empty or used twice, edges whose endpoint isn't a node, self-loops
+    /// and nodes no edge touches (crate roots excepted). Nodes are checked
+    /// first, then edges, each in graph order.
+    pub fn validate(&self) -> Vec<GraphWarning> {
+        let mut warnings = Vec::new();
+        let mut seen: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+        for node in &self.nodes {
+            if node.id.is_empty() {
+                warnings.push(GraphWarning::EmptyNodeId {
+                    name: node.name.clone(),
+                });
+                continue;
+            }
+            let count = seen.entry(node.id.as_str()).or_default();
+            *count += 1;
+            if *count == 2 {
+                warnings.push(GraphWarning::DuplicateNodeId {
+                    id: node.id.clone(),
+                });
+            }
+        }
+
+        let mut touched = std::collections::HashSet::new();
+        for edge in &self.edges {
+            touched.insert(edge.from.as_str());
+            touched.insert(edge.to.as_str());
+            if edge.from == edge.to {
+                warnings.push(GraphWarning::SelfLoop {
+                    id: edge.from.clone(),
+                    edge_kind: edge.kind,
+                });
+            }
+            for endpoint in [&edge.from, &edge.to] {
+                if !seen.contains_key(endpoint.as_str()) {
+                    warnings.push(GraphWarning::DanglingEdge {
+                        from: edge.from.clone(),
+                        to: edge.to.clone(),
+                        edge_kind: edge.kind,
+                        missing: endpoint.clone(),
+                    });
+                }
+            }
+        }
+
+        for node in &self.nodes {
+            if !node.id.is_empty()
+                && node.kind != NodeKind::Crate
+                && !touched.contains(node.id.as_str())
+            {
+                warnings.push(GraphWarning::OrphanNode {
+                    id: node.id.clone(),
+                });
+            }
+        }
+        warnings
+    }
+
+    /// Union of two graphs. See [`Graph::merge_all`].
+    pub fn merge(self, other: Graph) -> Graph {
+        Graph::merge_all([self, other])
+    }
+
+    /// Union of `graphs`. Nodes sharing an id collapse into the one
+    /// [`Node::is_more_complete_than`] prefers, earlier graphs winning ties.
+    /// Edges with the same endpoints, kind, glob flag and label collapse via
+    /// [`Edge::absorb`]. Aliases keep their first target, and doc links come
+    /// back inline like [`Workspace::merged_graph`].
+    pub fn merge_all(graphs: impl IntoIterator<Item = Graph>) -> Graph {
+        let mut merged = Graph::new();
+        let mut node_slots: std::collections::HashMap<String, usize> =
+            std::collections::HashMap::new();
+        let mut edge_slots: std::collections::HashMap<
+            (String, String, EdgeKind, bool, Option<String>),
+            usize,
+        > = std::collections::HashMap::new();
+        for mut graph in graphs {
+            graph.expand_doc_links();
+            for node in graph.nodes {
+                match node_slots.entry(node.id.clone()) {
+                    std::collections::hash_map::Entry::Vacant(entry) => {
+                        entry.insert(merged.nodes.len());
+                        merged.nodes.push(node);
+                    }
+                    std::collections::hash_map::Entry::Occupied(entry) => {
+                        let existing = &mut merged.nodes[*entry.get()];
+                        if node.is_more_complete_than(existing) {
+                            *existing = node;
+                        }
+                    }
+                }
+            }
+            for edge in graph.edges {
+                let key = (
+                    edge.from.clone(),
+                    edge.to.clone(),
+                    edge.kind,
+                    edge.is_glob,
+                    edge.label.clone(),
+                );
+                match edge_slots.entry(key) {
+                    std::collections::hash_map::Entry::Vacant(entry) => {
+                        entry.insert(merged.edges.len());
+                        merged.edges.push(edge);
+                    }
+                    std::collections::hash_map::Entry::Occupied(entry) => {
+                        merged.edges[*entry.get()].absorb(edge);
+                    }
+                }
+            }
+            for (alias, target) in graph.aliases {
+                merged.aliases.entry(alias).or_insert(target);
+            }
+            for (name, version) in graph.crate_versions {
+                merged.crate_versions.entry(name).or_insert(version);
+            }
+        }
+        merged
+    }
+
+    /// Drop local nodes outside the public surface, along with every edge
+    /// and alias that refers to them. With `include_crate_visible`,
+    /// `pub(crate)` items count as public too.
+    ///
+    /// A node survives when it's visible and not owned through `Defines`,
+    /// or when its `Defines` owner survives and it is visible or inherits
+    /// its visibility (trait items, variants, impl blocks). Every module
+    /// on the `Contains` path to a survivor is kept, so a private module
+    /// holding a `pub` item stays. Crate roots and external stubs are
+    /// always kept.
+    pub fn retain_public(&mut self, include_crate_visible: bool) {
+        retain_public(
+            &mut self.nodes,
+            &mut self.edges,
+            &mut self.aliases,
+            include_crate_visible,
+        );
+    }
+
+    /// Drop every node whose kind isn't in `keep`, along with the edges and
+    /// aliases that refer to it. Unlike [`Graph::retain_public`] nothing is
+    /// kept for structure: leave out `Crate` and `Module` and the survivors
+    /// lose their `Contains` parents.
+    pub fn retain_kinds(&mut self, keep: &std::collections::HashSet<NodeKind>) {
+        retain_kinds(&mut self.nodes, &mut self.edges, &mut self.aliases, keep);
+    }
+
+    /// Add a `Defines` edge from each impl's self type straight to the items
+    /// the impl defines, so a type's methods are one hop away. The impl
+    /// nodes and their own `Defines` edges stay unless `elide_inherent`,
+    /// which drops inherent impls (and every edge touching them) and clears
+    /// `parent_impl` on their items, leaving them owned by the type alone.
+    /// Trait impls always stay; impls with no known self type are left as
+    /// they are.
+    pub fn link_impl_items(&mut self, elide_inherent: bool) {
+        let (links, inherent) = impl_item_links(&self.nodes, &self.edges);
+        push_new_edges(&mut self.edges, links);
+        if elide_inherent {
+            remove_nodes(
+                &mut self.nodes,
+                &mut self.edges,
+                &mut self.aliases,
+                &inherent,
+            );
+        }
+    }
+
+    /// Drop local functions outside the public API, keeping the call paths
+    /// that ran through them: each remaining caller gets an `Inferred` call
+    /// edge to every non-private function it reached via a chain of private
+    /// ones. The edge is `CallsRuntime` when any call on the chain was, and
+    /// `CallsStatic` otherwise; a direct call already present is kept as is.
+    ///
+    /// "Private" means not effectively public, as in
+    /// [`Graph::unreachable_functions`]. Entry points, trait methods and
+    /// trait-impl methods are never contracted.
+    pub fn contract_private_calls(&mut self) {
+        let (links, private) = contracted_calls(&self.nodes, &self.edges);
+        push_new_edges(&mut self.edges, links);
+        remove_nodes(
+            &mut self.nodes,
+            &mut self.edges,
+            &mut self.aliases,
+            &private,
+        );
+    }
+
+    /// Put nodes in id order and edges in `(from, to, kind)` order, so the
+    /// same crate serializes identically run to run. Ties keep their order.
+    pub fn sort_stable(&mut self) {
+        sort_nodes(&mut self.nodes);
+        sort_edges(&mut self.edges);
+    }
+
+    fn call_neighbours<'a>(
+        &'a self,
+        confidences: &[Confidence],
+        other_end: impl Fn(&'a Edge) -> Option<&'a String>,
+    ) -> Vec<&'a Node> {
+        let nodes: std::collections::HashMap<&str, &Node> = self
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node))
+            .collect();
+        let mut seen = std::collections::HashSet::new();
+        self.edges
+            .iter()
+            .filter(|edge| edge.kind.is_call() && confidences.contains(&edge.confidence))
+            .filter_map(other_end)
+            .filter(|other| seen.insert(other.as_str()))
+            .filter_map(|other| nodes.get(other.as_str()).copied())
+            .collect()
+    }
+
+    /// Move every node's `doc_links` targets into `doc_link_table`, leaving
+    /// each node with `doc_link_refs` indices instead. Node ids repeat
+    /// heavily across doc-rich crates, so each target is stored once.
+    pub fn intern_doc_links(&mut self) {
+        intern_doc_links(&mut self.nodes, &mut self.doc_link_table);
+    }
+
+    /// Inverse of [`Graph::intern_doc_links`]: restore inline `doc_links`
+    /// and clear the table. A no-op for graphs that were never interned.
+    pub fn expand_doc_links(&mut self) {
+        expand_doc_links(&mut self.nodes, &mut self.doc_link_table);
     }
 
     pub fn add_node(&mut self, node: Node) {
@@ -114,16 +1095,471 @@ impl Default for Graph {
     }
 }
 
+/// Summary statistics for a graph. See [`Graph::graph_metrics`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphMetrics {
+    /// Node count per kind, external stubs included
+    pub nodes: std::collections::BTreeMap<NodeKind, usize>,
+    /// How many of `nodes` are external stubs
+    pub external_nodes: usize,
+    /// Edge count per kind
+    pub edges: std::collections::BTreeMap<EdgeKind, usize>,
+    /// Edge count per confidence
+    pub confidence: std::collections::BTreeMap<Confidence, usize>,
+    /// Distinct callers of each local function
+    pub fan_in: FanStats,
+    /// Distinct callees of each local function
+    pub fan_out: FanStats,
+    /// Deepest local module below its crate root (the root itself is 0)
+    pub max_module_depth: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FanStats {
+    pub average: f64,
+    pub max: usize,
+    /// A function with `max` neighbours, if any function has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_id: Option<String>,
+}
+
+/// A problem found by [`Graph::validate`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum GraphWarning {
+    /// An edge endpoint, `missing`, that isn't a node in the graph
+    DanglingEdge {
+        from: String,
+        to: String,
+        edge_kind: EdgeKind,
+        missing: String,
+    },
+    /// More than one node has this id
+    DuplicateNodeId { id: String },
+    /// A node with an empty id
+    EmptyNodeId { name: String },
+    /// An edge from a node to itself
+    SelfLoop { id: String, edge_kind: EdgeKind },
+    /// A node, other than a crate root, with no edges at all
+    OrphanNode { id: String },
+}
+
+impl std::fmt::Display for GraphWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphWarning::DanglingEdge {
+                from,
+                to,
+                edge_kind,
+                missing,
+            } => write!(
+                f,
+                "dangling {edge_kind:?} edge {from} -> {to}: no node {missing}"
+            ),
+            GraphWarning::DuplicateNodeId { id } => write!(f, "duplicate node id {id}"),
+            GraphWarning::EmptyNodeId { name } => write!(f, "node {name:?} has an empty id"),
+            GraphWarning::SelfLoop { id, edge_kind } => {
+                write!(f, "{edge_kind:?} self-loop on {id}")
+            }
+            GraphWarning::OrphanNode { id } => write!(f, "orphan node {id}"),
+        }
+    }
+}
+
+/// One node of [`Graph::module_tree`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreeNode {
+    pub id: String,
+    pub name: String,
+    pub kind: NodeKind,
+    pub visibility: Visibility,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TreeNode>,
+    /// Children were left out for being past the depth limit
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+impl FanStats {
+    fn over(
+        functions: &[&str],
+        neighbours: &std::collections::HashMap<&str, std::collections::HashSet<&str>>,
+    ) -> Self {
+        let mut stats = FanStats::default();
+        let mut total = 0;
+        for &id in functions {
+            let count = neighbours.get(id).map_or(0, |set| set.len());
+            total += count;
+            if count > stats.max {
+                stats.max = count;
+                stats.max_id = Some(id.to_string());
+            }
+        }
+        if !functions.is_empty() {
+            stats.average = total as f64 / functions.len() as f64;
+        }
+        stats
+    }
+}
+
+fn intern_doc_links(nodes: &mut [Node], table: &mut Vec<String>) {
+    let mut slots: std::collections::HashMap<String, u32> = table
+        .iter()
+        .enumerate()
+        .map(|(index, target)| (target.clone(), index as u32))
+        .collect();
+    for node in nodes {
+        // Sort link text so the table order is stable across runs.
+        let mut links: Vec<_> = std::mem::take(&mut node.doc_links).into_iter().collect();
+        links.sort_unstable_by(|(left, _), (right, _)| left.cmp(right));
+        for (text, target) in links {
+            let slot = *slots.entry(target).or_insert_with_key(|target| {
+                table.push(target.clone());
+                (table.len() - 1) as u32
+            });
+            node.doc_link_refs.insert(text, slot);
+        }
+    }
+}
+
+fn sort_nodes(nodes: &mut [Node]) {
+    nodes.sort_by(|a, b| a.id.cmp(&b.id));
+}
+
+fn sort_edges(edges: &mut [Edge]) {
+    edges.sort_by(|a, b| {
+        (&a.from, &a.to, a.kind, a.is_glob, &a.label)
+            .cmp(&(&b.from, &b.to, b.kind, b.is_glob, &b.label))
+    });
+}
+
+/// See [`Graph::retain_public`]. Returns the ids of the dropped nodes.
+fn retain_public(
+    nodes: &mut Vec<Node>,
+    edges: &mut Vec<Edge>,
+    aliases: &mut std::collections::HashMap<String, String>,
+    include_crate_visible: bool,
+) -> std::collections::HashSet<String> {
+    let is_visible = |visibility: &Visibility| match visibility {
+        Visibility::Public => true,
+        Visibility::Crate => include_crate_visible,
+        _ => false,
+    };
+    let mut parents: std::collections::HashMap<&str, &str> = std::collections::HashMap::new();
+    let mut members: std::collections::HashMap<&str, Vec<&str>> = std::collections::HashMap::new();
+    for edge in edges.iter() {
+        match edge.kind {
+            EdgeKind::Contains => {
+                parents.insert(edge.to.as_str(), edge.from.as_str());
+            }
+            EdgeKind::Defines => members
+                .entry(edge.from.as_str())
+                .or_default()
+                .push(edge.to.as_str()),
+            _ => {}
+        }
+    }
+    let owned: std::collections::HashSet<&str> = members.values().flatten().copied().collect();
+    let by_id: std::collections::HashMap<&str, &Node> =
+        nodes.iter().map(|node| (node.id.as_str(), node)).collect();
+
+    let mut kept: std::collections::HashSet<&str> = nodes
+        .iter()
+        .filter(|node| {
+            node.is_external
+                || node.kind == NodeKind::Crate
+                || (is_visible(&node.visibility) && !owned.contains(node.id.as_str()))
+        })
+        .map(|node| node.id.as_str())
+        .collect();
+
+    // Owned members follow their owner, whatever order the edges came in.
+    let mut queue: Vec<&str> = kept.iter().copied().collect();
+    while let Some(id) = queue.pop() {
+        for &member in members.get(id).into_iter().flatten() {
+            let Some(node) = by_id.get(member) else {
+                continue;
+            };
+            let follows = is_visible(&node.visibility) || node.visibility == Visibility::Inherited;
+            if follows && kept.insert(member) {
+                queue.push(member);
+            }
+        }
+    }
+
+    // Then the modules that lead to each survivor.
+    for id in kept.iter().copied().collect::<Vec<_>>() {
+        let mut current = id;
+        while let Some(&parent) = parents.get(current) {
+            if !kept.insert(parent) {
+                break;
+            }
+            current = parent;
+        }
+    }
+
+    let removed: std::collections::HashSet<String> = nodes
+        .iter()
+        .filter(|node| !kept.contains(node.id.as_str()))
+        .map(|node| node.id.clone())
+        .collect();
+    nodes.retain(|node| !removed.contains(&node.id));
+    edges.retain(|edge| !removed.contains(&edge.from) && !removed.contains(&edge.to));
+    aliases.retain(|_, target| !removed.contains(target));
+    removed
+}
+
+/// See [`Graph::module_tree`]. `seen` guards against `Contains` cycles.
+fn module_tree<'a>(
+    node: &'a Node,
+    depth: usize,
+    max_depth: Option<usize>,
+    children: &std::collections::HashMap<&str, Vec<&'a Node>>,
+    seen: &mut std::collections::HashSet<&'a str>,
+) -> TreeNode {
+    seen.insert(node.id.as_str());
+    let mut tree = TreeNode {
+        id: node.id.clone(),
+        name: node.name.clone(),
+        kind: node.kind,
+        visibility: node.visibility.clone(),
+        children: Vec::new(),
+        truncated: false,
+    };
+    for &child in children.get(node.id.as_str()).into_iter().flatten() {
+        if seen.contains(child.id.as_str()) {
+            continue;
+        }
+        if max_depth.is_some_and(|max| depth >= max) {
+            tree.truncated = true;
+            break;
+        }
+        tree.children
+            .push(module_tree(child, depth + 1, max_depth, children, seen));
+    }
+    tree
+}
+
+/// See [`Graph::retain_kinds`]. Returns the ids of the dropped nodes.
+fn retain_kinds(
+    nodes: &mut Vec<Node>,
+    edges: &mut Vec<Edge>,
+    aliases: &mut std::collections::HashMap<String, String>,
+    keep: &std::collections::HashSet<NodeKind>,
+) -> std::collections::HashSet<String> {
+    let removed: std::collections::HashSet<String> = nodes
+        .extract_if(.., |node| !keep.contains(&node.kind))
+        .map(|node| node.id)
+        .collect();
+    edges.retain(|edge| !removed.contains(&edge.from) && !removed.contains(&edge.to));
+    aliases.retain(|_, target| !removed.contains(target));
+    removed
+}
+
+/// The type → item `Defines` edges [`Graph::link_impl_items`] adds, each
+/// carrying the confidence and occurrences of the impl → item edge it
+/// shortcuts, plus the ids of the inherent impls whose self type is known.
+fn impl_item_links<'a>(
+    nodes: impl IntoIterator<Item = &'a Node>,
+    edges: impl IntoIterator<Item = &'a Edge> + Clone,
+) -> (Vec<Edge>, std::collections::HashSet<String>) {
+    let impls: std::collections::HashMap<&str, bool> = nodes
+        .into_iter()
+        .filter(|node| node.kind == NodeKind::Impl)
+        .map(|node| (node.id.as_str(), node.impl_type == Some(ImplType::Inherent)))
+        .collect();
+    let mut self_types = std::collections::HashMap::new();
+    for edge in edges.clone() {
+        if edge.kind == EdgeKind::Defines && impls.contains_key(edge.to.as_str()) {
+            self_types
+                .entry(edge.to.as_str())
+                .or_insert(edge.from.as_str());
+        }
+    }
+    let links = edges
+        .into_iter()
+        .filter(|edge| edge.kind == EdgeKind::Defines)
+        .filter_map(|edge| {
+            let self_type = self_types.get(edge.from.as_str())?;
+            Some(Edge {
+                from: self_type.to_string(),
+                ..edge.clone()
+            })
+        })
+        .collect();
+    let inherent = self_types
+        .keys()
+        .filter(|id| impls[*id])
+        .map(|id| id.to_string())
+        .collect();
+    (links, inherent)
+}
+
+/// Append `links`, skipping any that `edges` already has.
+fn push_new_edges(edges: &mut Vec<Edge>, links: Vec<Edge>) {
+    let mut seen: std::collections::HashSet<(String, String, EdgeKind)> = edges
+        .iter()
+        .map(|edge| (edge.from.clone(), edge.to.clone(), edge.kind))
+        .collect();
+    for link in links {
+        if seen.insert((link.from.clone(), link.to.clone(), link.kind)) {
+            edges.push(link);
+        }
+    }
+}
+
+/// Drop the `ids` nodes, the edges and aliases that refer to them, and any
+/// `parent_impl` back-references to them.
+fn remove_nodes(
+    nodes: &mut Vec<Node>,
+    edges: &mut Vec<Edge>,
+    aliases: &mut std::collections::HashMap<String, String>,
+    ids: &std::collections::HashSet<String>,
+) {
+    nodes.retain(|node| !ids.contains(&node.id));
+    for node in nodes.iter_mut() {
+        if node
+            .parent_impl
+            .as_ref()
+            .is_some_and(|parent| ids.contains(parent))
+        {
+            node.parent_impl = None;
+        }
+    }
+    edges.retain(|edge| !ids.contains(&edge.from) && !ids.contains(&edge.to));
+    aliases.retain(|_, target| !ids.contains(target));
+}
+
+/// The call edges [`Graph::contract_private_calls`] adds, plus the ids of
+/// the private functions it drops.
+fn contracted_calls<'a>(
+    nodes: impl IntoIterator<Item = &'a Node>,
+    edges: impl IntoIterator<Item = &'a Edge> + Clone,
+) -> (Vec<Edge>, std::collections::HashSet<String>) {
+    let nodes: std::collections::HashMap<&str, &Node> = nodes
+        .into_iter()
+        .map(|node| (node.id.as_str(), node))
+        .collect();
+    let mut dispatched = std::collections::HashSet::new();
+    let mut callees: std::collections::HashMap<&str, Vec<&Edge>> = std::collections::HashMap::new();
+    for edge in edges {
+        match edge.kind {
+            EdgeKind::CallsStatic | EdgeKind::CallsRuntime => {
+                callees.entry(edge.from.as_str()).or_default().push(edge)
+            }
+            EdgeKind::Defines => {
+                let via_trait = nodes.get(edge.from.as_str()).is_some_and(|owner| {
+                    owner.kind == NodeKind::Trait || owner.impl_type == Some(ImplType::Trait)
+                });
+                if via_trait {
+                    dispatched.insert(edge.to.as_str());
+                }
+            }
+            _ => {}
+        }
+    }
+    let private: std::collections::HashSet<&str> = nodes
+        .values()
+        .filter(|node| {
+            node.kind == NodeKind::Function
+                && !node.is_external
+                && !node.is_entry
+                && !dispatched.contains(node.id.as_str())
+                && node
+                    .effective_visibility
+                    .as_ref()
+                    .unwrap_or(&node.visibility)
+                    != &Visibility::Public
+        })
+        .map(|node| node.id.as_str())
+        .collect();
+
+    let mut links = Vec::new();
+    let mut callers: Vec<&str> = callees
+        .keys()
+        .copied()
+        .filter(|caller| !private.contains(caller))
+        .collect();
+    callers.sort_unstable();
+    for caller in callers {
+        // Walk only through private callees, remembering whether a runtime
+        // call was on the way; a node may be seen once per mode.
+        let mut seen = std::collections::HashSet::new();
+        let mut reached = Vec::new();
+        let mut stack: Vec<(&str, bool)> = callees[caller]
+            .iter()
+            .filter(|edge| private.contains(edge.to.as_str()))
+            .map(|edge| (edge.to.as_str(), edge.kind == EdgeKind::CallsRuntime))
+            .collect();
+        while let Some((id, runtime)) = stack.pop() {
+            if !seen.insert((id, runtime)) {
+                continue;
+            }
+            for edge in callees.get(id).into_iter().flatten() {
+                let runtime = runtime || edge.kind == EdgeKind::CallsRuntime;
+                if private.contains(edge.to.as_str()) {
+                    stack.push((edge.to.as_str(), runtime));
+                } else if edge.to != caller {
+                    reached.push((edge.to.as_str(), runtime));
+                }
+            }
+        }
+        reached.sort_unstable();
+        reached.dedup();
+        links.extend(reached.into_iter().map(|(callee, runtime)| Edge {
+            from: caller.to_string(),
+            to: callee.to_string(),
+            kind: if runtime {
+                EdgeKind::CallsRuntime
+            } else {
+                EdgeKind::CallsStatic
+            },
+            confidence: Confidence::Inferred,
+            occurrences: Vec::new(),
+            is_glob: false,
+            label: None,
+        }));
+    }
+    (links, private.into_iter().map(str::to_string).collect())
+}
+
+fn expand_doc_links(nodes: &mut [Node], table: &mut Vec<String>) {
+    for node in nodes {
+        for (text, slot) in std::mem::take(&mut node.doc_link_refs) {
+            if let Some(target) = table.get(slot as usize) {
+                node.doc_links.insert(text, target.clone());
+            }
+        }
+    }
+    table.clear();
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Node {
     pub id: String,
     pub name: String,
     pub kind: NodeKind,
     pub visibility: Visibility,
+    /// Visibility as seen from outside the crate: `pub` items count as
+    /// public only when reachable from the crate root through public
+    /// modules or `pub use` re-exports, and are narrowed to `Crate`
+    /// otherwise. Only computed for the analyzed crate's own nodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effective_visibility: Option<Visibility>,
     pub span: Option<Span>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub line_count: Option<u32>,
     pub attrs: Vec<String>,
+    /// The `#[cfg(...)]` predicate gating the item, e.g. `feature = "x"` or
+    /// `all(unix, feature = "x")`. Several `cfg` attributes are joined with
+    /// `all(...)`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cfg: Option<String>,
+    /// Features that must be enabled for the item to exist: the smallest
+    /// compared feature set it appeared under. Empty for items built with
+    /// the default features, or when no feature sets were compared.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub is_external: bool,
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
@@ -140,6 +1576,25 @@ pub struct Node {
     pub has_stripped_fields: bool,
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub has_stripped_variants: bool,
+    /// Publicly reachable only through a `pub use` re-export; the module
+    /// path the item is declared under is private.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_public_via_reexport: bool,
+    /// Trait method with a default body that implementors may leave out;
+    /// `false` for required trait methods and every other node.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_default: bool,
+    /// Invoked from outside the crate's own call graph: a crate-root
+    /// `fn main`, or a function carrying a recognised framework attribute
+    /// such as `#[tokio::main]`, `#[test]`, `#[wasm_bindgen]` or a route
+    /// macro like `#[get("/")]`. Only set when sources were parsed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_entry: bool,
+    /// Approximate cyclomatic complexity of the function body: one plus
+    /// each `if`, loop, extra `match` arm, guard, `&&`/`||` and `?`. Only
+    /// set when sources were parsed with complexity enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub complexity: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_dyn_compatible: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -167,6 +1622,10 @@ pub struct Node {
     /// Resolved intra-doc links: maps link text (e.g., "Vec") to node ID (e.g., "std::vec::Vec")
     #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
     pub doc_links: std::collections::HashMap<String, String>,
+    /// Interned form of `doc_links`: link text → index into the owning
+    /// graph's `doc_link_table`. Only populated in interned graphs.
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub doc_link_refs: std::collections::HashMap<String, u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub impl_type: Option<ImplType>,
     /// For methods: the ID of the parent impl block
@@ -228,9 +1687,12 @@ impl Node {
             name: name.into(),
             kind,
             visibility,
+            effective_visibility: None,
             span: None,
             line_count: None,
             attrs: Vec::new(),
+            cfg: None,
+            features: Vec::new(),
             is_external: false,
             is_deprecated: false,
             is_unsafe: false,
@@ -239,6 +1701,10 @@ impl Node {
             is_stripped: false,
             has_stripped_fields: false,
             has_stripped_variants: false,
+            is_public_via_reexport: false,
+            is_default: false,
+            is_entry: false,
+            complexity: None,
             is_dyn_compatible: None,
             deprecation: None,
             stability: None,
@@ -250,6 +1716,7 @@ impl Node {
             generics: Generics::default(),
             docs: None,
             doc_links: std::collections::HashMap::new(),
+            doc_link_refs: std::collections::HashMap::new(),
             impl_type: None,
             parent_impl: None,
             impl_trait: None,
@@ -272,6 +1739,48 @@ impl Node {
             proc_macro_helpers: Vec::new(),
         }
     }
+
+    /// Whether `self` should replace `other` when two graphs both have a
+    /// node with this id: local beats external, then whichever carries more
+    /// detail. Ties keep `other`.
+    pub fn is_more_complete_than(&self, other: &Node) -> bool {
+        if self.is_external != other.is_external {
+            return !self.is_external;
+        }
+        self.completeness_score() > other.completeness_score()
+    }
+
+    fn completeness_score(&self) -> u32 {
+        let mut score = 0;
+        if self.span.is_some() {
+            score += 1;
+        }
+        if self.fields.is_some() {
+            score += 2;
+        }
+        if self.variants.is_some() {
+            score += 2;
+        }
+        if self.signature.is_some() {
+            score += 2;
+        }
+        if !self.generics.is_empty() {
+            score += 1;
+        }
+        if self.docs.is_some() {
+            score += 1;
+        }
+        if !self.attrs.is_empty() {
+            score += 1;
+        }
+        if self.visibility != Visibility::Unknown {
+            score += 1;
+        }
+        if self.effective_visibility.is_some() {
+            score += 1;
+        }
+        score
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -325,6 +1834,12 @@ pub struct FunctionSignature {
     pub abi: Option<String>,
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub is_c_variadic: bool,
+    /// Written as `async fn` under `#[async_trait]`. rustdoc only sees the
+    /// macro's desugaring (a `Pin<Box<dyn Future + 'async_trait>>` return and
+    /// `is_async == false`), so this is inferred from that shape and is a
+    /// heuristic, not a record of the attribute.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_async_trait: bool,
     /// Generic params + where-clause specific to this function. Trait-method
     /// signatures carry their own here (separate from the impl block's
     /// generics).
@@ -339,7 +1854,9 @@ pub struct ArgumentInfo {
     pub type_: TypeRef,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
 pub enum NodeKind {
     Crate,
     Module,
@@ -413,10 +1930,14 @@ pub enum Visibility {
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct Span {
     pub file: String,
+    /// 1-based line of the first character
     pub line: u32,
+    /// 1-based column of the first character
     pub column: u32,
+    /// 1-based line of the last character
     #[serde(skip_serializing_if = "Option::is_none")]
     pub end_line: Option<u32>,
+    /// 1-based column one past the last character, as rustdoc reports it
     #[serde(skip_serializing_if = "Option::is_none")]
     pub end_column: Option<u32>,
 }
@@ -708,27 +2229,308 @@ pub struct Edge {
     /// Exact source locations that justify this relationship. Empty when
     /// source-backed occurrence extraction is unavailable; `Node::span`
     /// remains the declaration target for go-to-definition/declaration.
+    /// Call edges carry one entry per call site, spanning the callee name.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub occurrences: Vec<Span>,
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub is_glob: bool,
+    /// What the relationship is called at the source: the field of a
+    /// `HasField` edge, the derive as written for `Derives`, the alias a
+    /// renamed `ReExports` introduces, `dyn` on a `UsesType`/`Returns` edge
+    /// that names its trait through `dyn Trait`, or `generic` on one whose
+    /// target only appears as a generic argument, like `T` in `Vec<T>`. Edges
+    /// that differ only in label are distinct.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+impl Edge {
+    /// Fold `other`, a duplicate of this edge (same endpoints, kind, glob
+    /// flag and label), into it. Confidences combine via [`Confidence::merge`]
+    /// and occurrences are unioned.
+    pub fn absorb(&mut self, other: Edge) {
+        self.confidence = self.confidence.merge(other.confidence);
+        for occurrence in other.occurrences {
+            if !self.occurrences.contains(&occurrence) {
+                self.occurrences.push(occurrence);
+            }
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
 pub enum EdgeKind {
     Contains,
     Defines,
     Implements,
+    Supertrait,
     UsesType,
+    Returns,
     CallsStatic,
     CallsRuntime,
+    CallsMacro,
     Derives,
     ReExports,
+    // From a struct, union or enum to a type named in one of its fields,
+    // labelled with the field. These sit alongside the `UsesType` edges the
+    // same fields produce.
+    HasField,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+impl EdgeKind {
+    /// `CallsStatic`, `CallsRuntime` or `CallsMacro`.
+    pub fn is_call(self) -> bool {
+        matches!(
+            self,
+            Self::CallsStatic | Self::CallsRuntime | Self::CallsMacro
+        )
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
 pub enum Confidence {
     Static,
     Runtime,
     Inferred,
 }
+
+impl Confidence {
+    /// Confidence of an edge found by two passes: `Runtime` beats `Static`,
+    /// which beats `Inferred`, so a second sighting never weakens an edge.
+    pub fn merge(self, other: Confidence) -> Confidence {
+        match (self, other) {
+            (Self::Runtime, _) | (_, Self::Runtime) => Self::Runtime,
+            (Self::Static, _) | (_, Self::Static) => Self::Static,
+            _ => Self::Inferred,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function(id: &str, visibility: Visibility) -> Node {
+        let name = id.rsplit("::").next().unwrap();
+        Node::new(id, name, NodeKind::Function, visibility)
+    }
+
+    fn edge(from: &str, to: &str, kind: EdgeKind) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+            confidence: Confidence::Static,
+            occurrences: Vec::new(),
+            is_glob: false,
+            label: None,
+        }
+    }
+
+    fn ids<'a>(nodes: impl IntoIterator<Item = &'a Node>) -> Vec<&'a str> {
+        nodes.into_iter().map(|node| node.id.as_str()).collect()
+    }
+
+    #[test]
+    fn callers_and_callees_filter_by_confidence() {
+        let mut graph = Graph::new();
+        for id in ["demo::a", "demo::b", "demo::c", "demo::d"] {
+            graph.add_node(function(id, Visibility::Public));
+        }
+        graph.add_edge(edge("demo::a", "demo::c", EdgeKind::CallsStatic));
+        graph.add_edge(Edge {
+            confidence: Confidence::Inferred,
+            ..edge("demo::b", "demo::c", EdgeKind::CallsStatic)
+        });
+        graph.add_edge(Edge {
+            confidence: Confidence::Runtime,
+            ..edge("demo::c", "demo::d", EdgeKind::CallsRuntime)
+        });
+        // A second call edge to the same node is reported once, and
+        // non-call edges are ignored.
+        graph.add_edge(edge("demo::a", "demo::c", EdgeKind::CallsMacro));
+        graph.add_edge(edge("demo::d", "demo::c", EdgeKind::UsesType));
+
+        let all = [
+            Confidence::Static,
+            Confidence::Runtime,
+            Confidence::Inferred,
+        ];
+        let known = [Confidence::Static, Confidence::Runtime];
+        assert_eq!(
+            ids(graph.callers_of("demo::c", &all)),
+            ["demo::a", "demo::b"]
+        );
+        assert_eq!(ids(graph.callers_of("demo::c", &known)), ["demo::a"]);
+        assert_eq!(
+            ids(graph.callers_of("demo::c", &[Confidence::Inferred])),
+            ["demo::b"]
+        );
+        assert_eq!(ids(graph.callees_of("demo::c", &known)), ["demo::d"]);
+        assert!(
+            graph
+                .callees_of("demo::c", &[Confidence::Static])
+                .is_empty()
+        );
+        assert_eq!(ids(graph.callees_of("demo::b", &all)), ["demo::c"]);
+        assert!(graph.callees_of("demo::b", &known).is_empty());
+    }
+
+    /// `main -> run -> helper`, plus a public `api` nothing calls and a
+    /// private `orphan`.
+    fn call_chain() -> Graph {
+        let mut graph = Graph::new();
+        for id in ["demo::main", "demo::run", "demo::helper", "demo::orphan"] {
+            graph.add_node(function(id, Visibility::Crate));
+        }
+        graph.add_node(function("demo::api", Visibility::Public));
+        graph.add_edge(edge("demo::main", "demo::run", EdgeKind::CallsStatic));
+        graph.add_edge(edge("demo::run", "demo::helper", EdgeKind::CallsRuntime));
+        graph
+    }
+
+    #[test]
+    fn unreachable_functions_follow_calls_from_explicit_roots() {
+        let graph = call_chain();
+        assert_eq!(
+            ids(graph.unreachable_functions(&["demo::main"], false)),
+            ["demo::orphan", "demo::api"]
+        );
+        assert_eq!(
+            ids(graph.unreachable_functions(&["demo::run"], false)),
+            ["demo::main", "demo::orphan", "demo::api"]
+        );
+    }
+
+    #[test]
+    fn unreachable_functions_with_public_roots_keep_the_public_api() {
+        let mut graph = call_chain();
+        graph.add_node(function("demo::api_helper", Visibility::Crate));
+        graph.add_edge(edge("demo::api", "demo::api_helper", EdgeKind::CallsStatic));
+        // Re-exported from a private module: public through the re-export.
+        let mut exported = function("demo::inner::exported", Visibility::Crate);
+        exported.effective_visibility = Some(Visibility::Public);
+        graph.add_node(exported);
+
+        assert_eq!(
+            ids(graph.unreachable_functions(&["demo::main"], true)),
+            ["demo::orphan"]
+        );
+        assert_eq!(
+            ids(graph.unreachable_functions(&[], true)),
+            ["demo::main", "demo::run", "demo::helper", "demo::orphan"]
+        );
+    }
+
+    #[test]
+    fn unreachable_functions_treat_trait_impl_methods_as_roots() {
+        let mut graph = Graph::new();
+        let mut impl_block = Node::new(
+            "demo::impl-Display-for-Thing",
+            "impl Display for Thing",
+            NodeKind::Impl,
+            Visibility::Public,
+        );
+        impl_block.impl_type = Some(ImplType::Trait);
+        graph.add_node(impl_block);
+        graph.add_node(function("demo::Thing::fmt", Visibility::Public));
+        graph.add_node(function("demo::write_thing", Visibility::Crate));
+        graph.add_node(function("demo::unused", Visibility::Crate));
+        graph.add_edge(edge(
+            "demo::impl-Display-for-Thing",
+            "demo::Thing::fmt",
+            EdgeKind::Defines,
+        ));
+        // Only reachable through `fmt`, which nothing calls directly.
+        graph.add_edge(edge(
+            "demo::Thing::fmt",
+            "demo::write_thing",
+            EdgeKind::CallsStatic,
+        ));
+
+        assert_eq!(
+            ids(graph.unreachable_functions(&[], false)),
+            ["demo::unused"]
+        );
+    }
+
+    #[test]
+    fn find_cycles_reports_each_component_of_two_or_more() {
+        let mut graph = Graph::new();
+        for (from, to) in [
+            // A 2-cycle.
+            ("demo::a", "demo::b"),
+            ("demo::b", "demo::a"),
+            // A self-loop, never a cycle on its own.
+            ("demo::c", "demo::c"),
+            // A disjoint 3-cycle, reached from the 2-cycle but not back.
+            ("demo::b", "demo::z"),
+            ("demo::z", "demo::y"),
+            ("demo::y", "demo::x"),
+            ("demo::x", "demo::z"),
+        ] {
+            graph.add_edge(edge(from, to, EdgeKind::UsesType));
+        }
+
+        assert_eq!(
+            graph.find_cycles(&[EdgeKind::UsesType]),
+            [
+                vec!["demo::a", "demo::b"],
+                vec!["demo::x", "demo::y", "demo::z"],
+            ]
+        );
+    }
+
+    #[test]
+    fn find_cycles_ignore_edges_of_other_kinds() {
+        let mut graph = Graph::new();
+        graph.add_edge(edge("demo::a", "demo::b", EdgeKind::UsesType));
+        graph.add_edge(edge("demo::b", "demo::a", EdgeKind::Returns));
+
+        assert!(graph.find_cycles(&[EdgeKind::UsesType]).is_empty());
+        assert_eq!(
+            graph.find_cycles(&[EdgeKind::UsesType, EdgeKind::Returns]),
+            [vec!["demo::a", "demo::b"]]
+        );
+    }
+
+    #[test]
+    fn workspaces_up_to_the_schema_version_are_migrated() {
+        let mut value = serde_json::json!({
+            "version": SCHEMA_VERSION,
+            "crates": [],
+            "external_crates": [],
+            "cross_crate_edges": [],
+        });
+        assert_eq!(stored_schema_version(&value).unwrap(), SCHEMA_VERSION);
+        assert!(migrate_workspace(value.clone()).is_ok());
+
+        value.as_object_mut().unwrap().remove("version");
+        assert_eq!(stored_schema_version(&value).unwrap(), SCHEMA_VERSION);
+        assert_eq!(
+            migrate_workspace(value.clone()).unwrap().version,
+            SCHEMA_VERSION
+        );
+
+        value["version"] = (SCHEMA_VERSION - 1).into();
+        assert_eq!(
+            migrate_workspace(value.clone()).unwrap().version,
+            SCHEMA_VERSION
+        );
+
+        value["version"] = (SCHEMA_VERSION + 1).into();
+        let err = migrate_workspace(value.clone()).unwrap_err();
+        assert!(matches!(err, SchemaError::TooNew(_)), "{err}");
+        assert!(err.to_string().contains("upgrade codeview"), "{err}");
+
+        value["version"] = "one".into();
+        assert!(matches!(
+            migrate_workspace(value),
+            Err(SchemaError::InvalidVersion(_))
+        ));
+    }
+}
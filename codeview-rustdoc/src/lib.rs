@@ -11,6 +11,8 @@ use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 #[cfg(feature = "native")]
 use std::process::Command;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[cfg(feature = "native")]
 use cargo_metadata::{MetadataCommand, TargetKind};
@@ -29,6 +31,11 @@ use thiserror::Error;
 #[cfg(feature = "wasm")]
 mod wasm;
 
+#[cfg(feature = "github")]
+mod github;
+#[cfg(feature = "github")]
+pub use github::GitHubSourceProvider;
+
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
@@ -81,6 +88,18 @@ pub enum RustdocError {
     RustdocFailed(std::process::ExitStatus),
     #[error("missing root package in workspace metadata")]
     MissingRootPackage,
+    #[error("analysis cancelled")]
+    Cancelled,
+    #[cfg(feature = "native")]
+    #[error("graph extraction worker panicked: {0}")]
+    WorkerPanicked(String),
+}
+
+fn check_cancelled(cancel: Option<&AtomicBool>) -> Result<(), RustdocError> {
+    match cancel {
+        Some(flag) if flag.load(Ordering::Relaxed) => Err(RustdocError::Cancelled),
+        _ => Ok(()),
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
@@ -239,6 +258,55 @@ pub struct RustdocJson {
     pub src_path: PathBuf,
 }
 
+/// Kind of target `generate_workspace_rustdoc_json` documents for a member.
+#[cfg(feature = "native")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocTargetKind {
+    Lib,
+    ProcMacro,
+    Bin,
+    Example,
+    Test,
+    Bench,
+}
+
+#[cfg(feature = "native")]
+impl DocTargetKind {
+    /// The `cargo rustdoc` target-selection flag. Every kind but the lib
+    /// ones also takes the target name.
+    pub fn cargo_flag(self) -> &'static str {
+        match self {
+            Self::Lib | Self::ProcMacro => "--lib",
+            Self::Bin => "--bin",
+            Self::Example => "--example",
+            Self::Test => "--test",
+            Self::Bench => "--bench",
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Clone)]
+pub struct DocTarget {
+    pub name: String,
+    pub kind: DocTargetKind,
+    pub src_path: PathBuf,
+}
+
+/// A workspace member as `generate_workspace_rustdoc_json` would see it,
+/// without running rustdoc.
+#[cfg(feature = "native")]
+#[derive(Debug, Clone)]
+pub struct WorkspaceCratePlan {
+    pub crate_name: String,
+    pub version: String,
+    pub manifest_path: PathBuf,
+    /// `None` when the member has no lib or bin target and will be skipped.
+    pub target: Option<DocTarget>,
+    /// Features the member declares, sorted, without `default`.
+    pub features: Vec<String>,
+}
+
 #[cfg(feature = "native")]
 #[derive(Debug, Clone, Copy, Default)]
 pub struct RustdocBuildOptions {
@@ -262,10 +330,23 @@ struct RustdocCommandPlan {
     target: Option<String>,
 }
 
+/// How calls that match more than one known function are linked.
+///
+/// `Strict` favours precision: only unambiguous calls get edges, so every
+/// call edge is real but dynamic dispatch and common method names go
+/// missing. `Ambiguous` favours recall: every candidate gets an `Inferred`
+/// edge, which finds all real callees along with many that aren't.
+/// `Heuristic` sits between them: it resolves like `Strict`, except that a
+/// method called on a receiver declared as `dyn Trait` (`&dyn T`,
+/// `Box<dyn T>`, `Rc`/`Arc<dyn T>`) gets a `CallsRuntime` edge with
+/// `Runtime` confidence to that method in every impl of the trait. Only
+/// receivers whose type is written on a parameter or `let` binding are
+/// recognised, and impls outside the graph are never reached.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CallMode {
     Strict,
     Ambiguous,
+    Heuristic,
 }
 
 impl CallMode {
@@ -274,6 +355,139 @@ impl CallMode {
     }
 }
 
+/// A call in the parsed sources that produced no call edge: the callee is in
+/// a crate that wasn't documented, is a closure or local binding, or (in
+/// strict mode) matched more than one function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedCall {
+    /// Node id of the calling function.
+    pub caller: String,
+    /// The callee as written: `a::b`, a method name, or `m!` for a macro.
+    pub name: String,
+}
+
+/// How blanket impls (`impl<T> Any for T`), which rustdoc repeats once per
+/// concrete type, end up in the graph.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlanketImpls {
+    /// An impl node and its own method nodes for every type.
+    #[default]
+    PerImpl,
+    /// An impl node per type, all sharing one node per `(trait, item)`
+    /// pair, e.g. `core::any::Any::blanket::type_id`.
+    Collapse,
+    /// No nodes or edges at all for blanket impls, nor for the synthetic
+    /// auto-trait impls (`Send`, `Sync`, …) rustdoc adds to every type.
+    Skip,
+}
+
+/// How deep call-edge extraction follows nested `mod` declarations before
+/// giving up on a branch. Real crates stay far below this; it only guards
+/// against generated code recursing us off the end of the stack.
+pub const DEFAULT_MAX_MODULE_DEPTH: usize = 256;
+
+/// Delimiter nesting within one file past which groups are emptied before
+/// syn sees them. syn recurses once per group, so this is what bounds its
+/// stack use; it sits well above any module nesting `max_module_depth`
+/// allows, so only expressions no person would write get cut.
+const MAX_TOKEN_NESTING: usize = 1024;
+
+/// Size of each stack segment the source pass grows into. syn and our
+/// visitors take a few KiB per nesting level in debug builds, so
+/// [`MAX_TOKEN_NESTING`] levels need far more than a thread's default 2 MiB.
+const PARSE_STACK_SIZE: usize = 64 * 1024 * 1024;
+
+/// Stack left below which the source pass moves to a fresh
+/// [`PARSE_STACK_SIZE`] segment. Our own recursion, including the syn
+/// visitors, checks it at every level.
+const RED_ZONE: usize = 128 * 1024;
+
+/// Stack a single syn parse is given. syn recurses without checking, so
+/// this covers [`MAX_TOKEN_NESTING`] levels up front.
+const SYN_PARSE_STACK: usize = PARSE_STACK_SIZE / 2;
+
+/// Extraction settings that most callers leave at their defaults.
+#[derive(Debug, Clone)]
+pub struct ExtractOptions {
+    /// Nested `mod` depth at which call-edge extraction stops descending,
+    /// with a warning.
+    pub max_module_depth: usize,
+    /// Keep items marked `#[doc(hidden)]`, and those under a `__`-prefixed
+    /// path, which are dropped by default.
+    pub include_hidden: bool,
+    /// Attribute paths that flag a function as an entry point, matched as
+    /// described for [`DEFAULT_ENTRY_POINT_ATTRIBUTES`]. Extend the defaults
+    /// to recognise a framework's own handler attributes.
+    pub entry_point_attributes: Vec<String>,
+    /// What to build for blanket and synthetic impls.
+    pub blanket_impls: BlanketImpls,
+    /// Add `Inferred` call edges from each documented item to the
+    /// functions its doc-test examples call.
+    pub doctest_calls: bool,
+    /// Record each parsed function's approximate cyclomatic complexity in
+    /// `Node::complexity`. Costs a second walk over every function body.
+    pub complexity: bool,
+    /// Also add an `Inferred` `Contains` edge from each module to the
+    /// crate's own public items it `pub use`s, so they show up under it.
+    pub flatten_reexports: bool,
+    /// External crates, like `std`, whose nodes and every edge into them
+    /// are left out (see [`DEFAULT_SKIPPED_EXTERNAL_CRATES`]). Workspace
+    /// members are never skipped.
+    pub skip_external_crates: HashSet<String>,
+    /// How many workspace crates are parsed and extracted at once; `None`
+    /// for one per available core. Each crate in flight holds its parsed
+    /// rustdoc JSON, so lowering this caps peak memory on large workspaces.
+    pub jobs: Option<usize>,
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        Self {
+            max_module_depth: DEFAULT_MAX_MODULE_DEPTH,
+            include_hidden: false,
+            entry_point_attributes: DEFAULT_ENTRY_POINT_ATTRIBUTES
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
+            blanket_impls: BlanketImpls::default(),
+            doctest_calls: false,
+            complexity: false,
+            flatten_reexports: false,
+            skip_external_crates: HashSet::new(),
+            jobs: None,
+        }
+    }
+}
+
+/// The standard-library crates `--no-std-nodes` leaves out of a graph.
+pub const DEFAULT_SKIPPED_EXTERNAL_CRATES: &[&str] = &["std", "core", "alloc", "proc_macro"];
+
+/// Attribute paths that mark a function as an entry point
+/// ([`Node::is_entry`]). A pattern matches any attribute whose path ends with
+/// the same segments, so `main` covers `#[tokio::main]`, `#[actix_web::main]`
+/// and `#[async_std::main]`. A crate-root `fn main` is always an entry point.
+/// Attributes on an impl block apply to its `pub` methods, which is how
+/// `#[wasm_bindgen] impl` exports them.
+pub const DEFAULT_ENTRY_POINT_ATTRIBUTES: &[&str] = &[
+    // Runtime entry macros.
+    "main",
+    "rocket::launch",
+    // Test harnesses: `#[test]`, `#[tokio::test]`, `#[bench]`.
+    "test",
+    "bench",
+    // JS exports.
+    "wasm_bindgen",
+    // Route handlers (actix-web, rocket).
+    "get",
+    "post",
+    "put",
+    "patch",
+    "delete",
+    "head",
+    "options",
+    "route",
+];
+
 #[cfg(feature = "native")]
 fn is_lib_target(kind: &TargetKind) -> bool {
     matches!(
@@ -287,15 +501,79 @@ fn is_lib_target(kind: &TargetKind) -> bool {
     )
 }
 
+/// Prefer the lib (or proc-macro) target, falling back to the first bin,
+/// then — for members that are only examples, tests or benches — the first
+/// of those.
+#[cfg(feature = "native")]
+fn select_doc_target(package: &cargo_metadata::Package) -> Option<DocTarget> {
+    let lib_target = package
+        .targets
+        .iter()
+        .find(|t| t.kind.iter().any(is_lib_target));
+    if let Some(target) = lib_target {
+        let kind = if target.kind.contains(&TargetKind::ProcMacro) {
+            DocTargetKind::ProcMacro
+        } else {
+            DocTargetKind::Lib
+        };
+        return Some(DocTarget {
+            name: target.name.clone(),
+            kind,
+            src_path: target.src_path.clone().into_std_path_buf(),
+        });
+    }
+    [
+        (TargetKind::Bin, DocTargetKind::Bin),
+        (TargetKind::Example, DocTargetKind::Example),
+        (TargetKind::Test, DocTargetKind::Test),
+        (TargetKind::Bench, DocTargetKind::Bench),
+    ]
+    .into_iter()
+    .find_map(|(target_kind, kind)| {
+        let target = package
+            .targets
+            .iter()
+            .find(|t| t.kind.contains(&target_kind))?;
+        Some(DocTarget {
+            name: target.name.clone(),
+            kind,
+            src_path: target.src_path.clone().into_std_path_buf(),
+        })
+    })
+}
+
+/// List workspace members and the target each would be documented with.
+/// Runs only `cargo metadata`.
 #[cfg(feature = "native")]
-fn get_workspace_members(manifest_path: &Path) -> Result<HashSet<String>, RustdocError> {
+pub fn plan_workspace_crates(
+    manifest_path: &Path,
+) -> Result<Vec<WorkspaceCratePlan>, RustdocError> {
     let metadata = MetadataCommand::new().manifest_path(manifest_path).exec()?;
-    let members: HashSet<String> = metadata
+    Ok(metadata
+        .workspace_packages()
+        .into_iter()
+        .map(|package| WorkspaceCratePlan {
+            crate_name: package.name.replace('-', "_"),
+            version: package.version.to_string(),
+            manifest_path: package.manifest_path.clone().into_std_path_buf(),
+            target: select_doc_target(package),
+            features: package
+                .features
+                .keys()
+                .filter(|feature| *feature != "default")
+                .cloned()
+                .collect(),
+        })
+        .collect())
+}
+
+#[cfg(feature = "native")]
+fn workspace_member_names(metadata: &cargo_metadata::Metadata) -> HashSet<String> {
+    metadata
         .workspace_packages()
         .iter()
         .map(|pkg| pkg.name.replace('-', "_"))
-        .collect();
-    Ok(members)
+        .collect()
 }
 
 #[cfg(feature = "native")]
@@ -323,7 +601,7 @@ pub fn generate_rustdoc_json_with_options(
     let lib_target = package
         .targets
         .iter()
-        .find(|t| t.kind.iter().any(|k| is_lib_target(k)));
+        .find(|t| t.kind.iter().any(is_lib_target));
     let primary_target = lib_target
         .or_else(|| {
             package
@@ -605,45 +883,299 @@ fn install_nightly_target(target: &str) -> Result<(), RustdocError> {
     }
 }
 
-/// Generate rustdoc JSON for all workspace members
+/// File under `<target>/codeview/` (next to the default `graph.json`) that
+/// records which rustdoc JSON each crate was last built from.
+#[cfg(feature = "native")]
+const RUSTDOC_CACHE_FILE: &str = "rustdoc-cache.json";
+
+#[cfg(feature = "native")]
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct RustdocCache {
+    entries: Vec<CachedRustdoc>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct CachedRustdoc {
+    crate_name: String,
+    /// Different cargo args (features, targets) produce different docs.
+    cargo_args: Vec<String>,
+    rustdoc_name: String,
+    json_path: PathBuf,
+    src_path: PathBuf,
+    /// [`source_fingerprint`] of the crate and its path dependencies when
+    /// the JSON was built.
+    source_fingerprint: u64,
+}
+
+#[cfg(feature = "native")]
+impl RustdocCache {
+    fn load(path: &Path) -> Self {
+        fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    fn get(&self, crate_name: &str, cargo_args: &[String]) -> Option<&CachedRustdoc> {
+        self.entries
+            .iter()
+            .find(|entry| entry.crate_name == crate_name && entry.cargo_args == cargo_args)
+    }
+
+    /// Drop every entry recorded for `json_path`, whatever args built it:
+    /// rustdoc writes one file per crate, so rebuilding replaces them all.
+    /// Returns whether any were dropped.
+    fn forget(&mut self, json_path: &Path) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|entry| entry.json_path != json_path);
+        self.entries.len() != before
+    }
+
+    fn insert(&mut self, entry: CachedRustdoc) {
+        self.forget(&entry.json_path);
+        self.entries.push(entry);
+    }
+
+    fn save(&self, path: &Path) -> Result<(), RustdocError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Hash of the sorted paths and modification times of every `.rs` and
+/// `Cargo.toml` under `dirs`, skipping `target/` and hidden directories.
+/// Editing, adding, deleting or renaming any of those files changes it.
+/// `None` when there are no such files.
+#[cfg(feature = "native")]
+fn source_fingerprint(dirs: &[PathBuf]) -> Option<u64> {
+    let mut files = Vec::new();
+    let mut stack = dirs.to_vec();
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if file_type.is_dir() {
+                if name != "target" && !name.starts_with('.') {
+                    stack.push(path);
+                }
+                continue;
+            }
+            if name != "Cargo.toml" && path.extension().is_none_or(|ext| ext != "rs") {
+                continue;
+            }
+            let Some(mtime) = entry
+                .metadata()
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            else {
+                continue;
+            };
+            files.push((path, mtime.as_millis()));
+        }
+    }
+    if files.is_empty() {
+        return None;
+    }
+    // Nested `dirs` would list a file twice.
+    files.sort_unstable();
+    files.dedup();
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    files.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// The directories whose sources `package`'s docs depend on: its own, and
+/// those of the local path dependencies it reaches, directly or through
+/// other path dependencies. Registry and git dependencies don't change
+/// under us, so they're left out.
+#[cfg(feature = "native")]
+fn local_source_dirs(
+    metadata: &cargo_metadata::Metadata,
+    package: &cargo_metadata::Package,
+) -> Vec<PathBuf> {
+    let local: HashMap<PathBuf, &cargo_metadata::Package> = metadata
+        .packages
+        .iter()
+        .filter(|package| package.source.is_none())
+        .filter_map(|package| {
+            let dir = package.manifest_path.parent()?.as_std_path().to_path_buf();
+            Some((dir, package))
+        })
+        .collect();
+    let mut dirs = Vec::new();
+    let mut stack = vec![package];
+    let mut seen = HashSet::new();
+    while let Some(package) = stack.pop() {
+        let Some(dir) = package.manifest_path.parent() else {
+            continue;
+        };
+        let dir = dir.as_std_path().to_path_buf();
+        if !seen.insert(dir.clone()) {
+            continue;
+        }
+        stack.extend(
+            package
+                .dependencies
+                .iter()
+                .filter_map(|dependency| local.get(dependency.path.as_ref()?.as_std_path()))
+                .copied(),
+        );
+        dirs.push(dir);
+    }
+    dirs
+}
+
+/// Which workspace members [`generate_workspace_rustdoc_json`] documents.
+/// Patterns are globs (`*` and `?`) matched against the crate name, with
+/// `-` normalised to `_` on both sides. An empty `include` admits every
+/// member, and `exclude` wins over `include`.
+#[cfg(feature = "native")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CrateFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+#[cfg(feature = "native")]
+impl CrateFilter {
+    pub fn matches(&self, crate_name: &str) -> bool {
+        let crate_name = crate_name.replace('-', "_");
+        let any_match = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|pattern| glob_matches(&pattern.replace('-', "_"), &crate_name))
+        };
+        (self.include.is_empty() || any_match(&self.include)) && !any_match(&self.exclude)
+    }
+}
+
+/// `*` matches any run of characters, `?` any single one.
+#[cfg(feature = "native")]
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Where the last `*` was, and how much text it has swallowed so far.
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, t));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// How [`generate_workspace_rustdoc_json`] runs `cargo rustdoc`.
+#[cfg(feature = "native")]
+#[derive(Debug, Clone, Default)]
+pub struct RustdocOptions<'a> {
+    /// Extra arguments for every `cargo rustdoc` (e.g. `--all-features`).
+    pub cargo_args: Vec<String>,
+    /// Which workspace members to document.
+    pub filter: CrateFilter,
+    /// Show cargo's output and report each member as it's documented.
+    pub verbose: bool,
+    /// Skip `cargo rustdoc` for members whose sources, and those of their
+    /// local path dependencies, haven't changed since the JSON recorded in
+    /// `<target>/codeview/rustdoc-cache.json` was built. Every run,
+    /// incremental or not, keeps that record in step with the JSON it
+    /// rewrites.
+    pub incremental: bool,
+    /// Abort the whole run with [`RustdocError::RustdocFailed`] at the
+    /// first member `cargo rustdoc` fails on. Otherwise failed members are
+    /// left out of the result, and their output is printed together with a
+    /// summary once every member has been tried.
+    pub fail_fast: bool,
+    /// Once set, the run stops with [`RustdocError::Cancelled`]. Checked
+    /// between crates and while `cargo rustdoc` runs; an in-flight build is
+    /// killed rather than waited on.
+    pub cancel: Option<&'a AtomicBool>,
+}
+
+/// Generate rustdoc JSON for the workspace members `options.filter` admits.
 #[cfg(feature = "native")]
 pub fn generate_workspace_rustdoc_json(
     manifest_path: &Path,
-    cargo_args: &[String],
-    verbose: bool,
+    options: &RustdocOptions<'_>,
 ) -> Result<Vec<RustdocJson>, RustdocError> {
     let metadata = MetadataCommand::new().manifest_path(manifest_path).exec()?;
     let workspace_root = metadata.workspace_root.as_std_path().to_path_buf();
     let target_dir = metadata.target_directory.as_std_path().to_path_buf();
+    let cache_path = target_dir.join("codeview").join(RUSTDOC_CACHE_FILE);
+    // Loaded and saved even when not `incremental`: every JSON rustdoc
+    // rewrites must be recorded, or a later incremental run would trust an
+    // entry for the JSON it replaced.
+    let mut cache = RustdocCache::load(&cache_path);
 
     let mut results = Vec::new();
+    // Members rustdoc failed on, with their captured stderr, reported
+    // together at the end instead of between the other members' progress.
+    let mut failed: Vec<(String, String)> = Vec::new();
 
     for package in metadata.workspace_packages() {
+        check_cancelled(options.cancel)?;
         // Normalize crate name: Cargo uses hyphens but Rust uses underscores internally
         let crate_name = package.name.replace('-', "_");
         let pkg_manifest = package.manifest_path.as_std_path();
-
-        if verbose {
-            eprintln!("Documenting {} ...", crate_name);
+        if !options.filter.matches(&crate_name) {
+            continue;
         }
 
-        // Determine the right target flag: prefer --lib, fall back to first bin
-        let lib_target = package
-            .targets
-            .iter()
-            .find(|t| t.kind.iter().any(|k| is_lib_target(k)));
-        let bin_target = package
-            .targets
-            .iter()
-            .find(|t| t.kind.iter().any(|k| matches!(k, TargetKind::Bin)));
-        let primary_target = lib_target.or(bin_target);
-        let Some(primary_target) = primary_target else {
-            if verbose {
-                eprintln!("Warning: no lib or bin target for {}", crate_name);
+        let Some(target) = select_doc_target(package) else {
+            if options.verbose {
+                eprintln!("Warning: no documentable target for {}", crate_name);
             }
             continue;
         };
-        let src_path = primary_target.src_path.clone().into_std_path_buf();
+        let is_lib = matches!(target.kind, DocTargetKind::Lib | DocTargetKind::ProcMacro);
+
+        // Taken before rustdoc runs, so edits made during the build show
+        // up as changes next time.
+        let fingerprint = source_fingerprint(&local_source_dirs(&metadata, package));
+        if options.incremental
+            && let Some(fingerprint) = fingerprint
+            && let Some(cached) = cache.get(&crate_name, &options.cargo_args)
+            && cached.source_fingerprint == fingerprint
+            && cached.json_path.exists()
+        {
+            if options.verbose {
+                eprintln!("Reusing cached rustdoc JSON for {} ...", crate_name);
+            }
+            results.push(RustdocJson {
+                crate_name,
+                rustdoc_name: cached.rustdoc_name.clone(),
+                json_path: cached.json_path.clone(),
+                manifest_path: pkg_manifest.to_path_buf(),
+                src_path: cached.src_path.clone(),
+            });
+            continue;
+        }
+
+        if options.verbose {
+            eprintln!("Documenting {} ...", crate_name);
+        }
 
         let mut cmd = Command::new("cargo");
         cmd.arg("+nightly")
@@ -651,14 +1183,13 @@ pub fn generate_workspace_rustdoc_json(
             .arg("--manifest-path")
             .arg(pkg_manifest);
 
-        if lib_target.is_some() {
-            cmd.arg("--lib");
-        } else {
-            cmd.arg("--bin").arg(&primary_target.name);
+        cmd.arg(target.kind.cargo_flag());
+        if !is_lib {
+            cmd.arg(&target.name);
         }
 
         // Add user-provided cargo args (e.g. --all-features, --features "uuid")
-        for arg in cargo_args {
+        for arg in &options.cargo_args {
             cmd.arg(arg);
         }
 
@@ -668,82 +1199,199 @@ pub fn generate_workspace_rustdoc_json(
             .arg("json")
             .current_dir(&workspace_root);
 
-        if !verbose {
+        if !options.verbose {
             cmd.stdout(std::process::Stdio::null())
                 .stderr(std::process::Stdio::piped());
         }
 
-        if verbose {
-            let status = cmd.status()?;
-            if !status.success() {
-                eprintln!("Warning: rustdoc failed for {}", crate_name);
-                continue;
-            }
-        } else {
-            let output = cmd.output()?;
-            if !output.status.success() {
-                eprintln!("Warning: rustdoc failed for {}", crate_name);
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                if !stderr.is_empty() {
-                    eprintln!("{}", stderr.trim_end());
-                }
-                continue;
-            }
-        }
-
-        // For lib crates the rustdoc name matches the crate name; for binary crates
-        // rustdoc uses the target (binary) name which may differ from the package name.
-        let rustdoc_name = if lib_target.is_some() {
+        // For lib crates the rustdoc name matches the crate name; for other targets
+        // rustdoc uses the target name which may differ from the package name.
+        let rustdoc_name = if is_lib {
             crate_name.replace('-', "_")
         } else {
-            primary_target.name.replace('-', "_")
+            target.name.replace('-', "_")
         };
         let crate_file = format!("{rustdoc_name}.json");
         let json_path = target_dir.join("doc").join(crate_file);
 
+        // rustdoc may replace the recorded JSON even if it then fails or
+        // we're cancelled, so forget it on disk before starting.
+        if cache.forget(&json_path) {
+            cache.save(&cache_path)?;
+        }
+        let (status, stderr) = run_cancellable(&mut cmd, options.cancel)?;
+        if !status.success() {
+            let stderr = String::from_utf8_lossy(&stderr).trim_end().to_string();
+            if options.fail_fast {
+                eprintln!("Error: rustdoc failed for {}", crate_name);
+                if !stderr.is_empty() {
+                    eprintln!("{stderr}");
+                }
+                return Err(RustdocError::RustdocFailed(status));
+            }
+            failed.push((crate_name, stderr));
+            continue;
+        }
+
         if json_path.exists() {
+            if let Some(source_fingerprint) = fingerprint {
+                cache.insert(CachedRustdoc {
+                    crate_name: crate_name.clone(),
+                    cargo_args: options.cargo_args.clone(),
+                    rustdoc_name: rustdoc_name.clone(),
+                    json_path: json_path.clone(),
+                    src_path: target.src_path.clone(),
+                    source_fingerprint,
+                });
+            }
             results.push(RustdocJson {
                 crate_name,
                 rustdoc_name,
                 json_path,
                 manifest_path: pkg_manifest.to_path_buf(),
-                src_path: src_path.clone(),
+                src_path: target.src_path,
             });
         }
     }
 
+    cache.save(&cache_path)?;
+
+    if !failed.is_empty() {
+        for (crate_name, stderr) in &failed {
+            eprintln!("Warning: rustdoc failed for {}", crate_name);
+            if !stderr.is_empty() {
+                eprintln!("{stderr}");
+            }
+        }
+        let names: Vec<&str> = failed.iter().map(|(name, _)| name.as_str()).collect();
+        eprintln!(
+            "Warning: rustdoc failed for {} of {} crates: {}",
+            failed.len(),
+            failed.len() + results.len(),
+            names.join(", ")
+        );
+    }
+
     Ok(results)
 }
 
+/// How often [`run_cancellable`] polls the child and the cancel flag.
+#[cfg(feature = "native")]
+const CANCEL_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Run `cmd` to completion, returning its status and any piped stderr.
+/// When `cancel` is set while the child runs, the child is killed along
+/// with everything it started, and [`RustdocError::Cancelled`] is returned
+/// once it has exited.
+#[cfg(feature = "native")]
+fn run_cancellable(
+    cmd: &mut Command,
+    cancel: Option<&AtomicBool>,
+) -> Result<(std::process::ExitStatus, Vec<u8>), RustdocError> {
+    check_cancelled(cancel)?;
+    // `cargo rustdoc` does its work in a `rustdoc` child, which a plain
+    // kill would leave running, still writing the JSON. A process group of
+    // its own lets cancelling take both down. Only when cancellable, since
+    // it also keeps a terminal's Ctrl-C from reaching cargo.
+    #[cfg(unix)]
+    if cancel.is_some() {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    let mut child = cmd.spawn()?;
+    // Drain stderr on a separate thread so a chatty build can't fill the
+    // pipe and stall while we poll.
+    let stderr_reader = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = std::io::Read::read_to_end(&mut stderr, &mut buf);
+            buf
+        })
+    });
+    loop {
+        if let Some(status) = child.try_wait()? {
+            let stderr = stderr_reader
+                .map(|reader| reader.join().unwrap_or_default())
+                .unwrap_or_default();
+            return Ok((status, stderr));
+        }
+        if let Err(err) = check_cancelled(cancel) {
+            #[cfg(unix)]
+            // SAFETY: kill(2) only sends a signal. The group is the one
+            // `child` leads, which can't be reused before we reap it below.
+            unsafe {
+                libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+            }
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(err);
+        }
+        std::thread::sleep(CANCEL_POLL_INTERVAL);
+    }
+}
+
 /// Load and merge graphs from multiple rustdoc JSON files into a Workspace.
+///
+/// Only members with an entry in `rustdoc_jsons` become [`CrateGraph`]s.
+/// Members that weren't documented (filtered out by a [`CrateFilter`], or
+/// rustdoc failed) are treated like dependencies, so edges into them still
+/// land on external-crate stubs.
 #[cfg(feature = "native")]
 pub fn load_workspace_graph(
     rustdoc_jsons: &[RustdocJson],
     manifest_path: &Path,
     call_mode: CallMode,
+    options: &ExtractOptions,
 ) -> Result<Workspace, RustdocError> {
-    let mut nodes_by_id: HashMap<String, Node> = HashMap::new();
-    let mut edge_index = EdgeIndex::default();
-    let mut edges = Vec::new();
-
-    // Collect crate versions from cargo metadata
-    let mut all_crate_versions: HashMap<String, String> = HashMap::new();
-    let workspace_members: HashSet<String> =
-        if let Ok(metadata) = MetadataCommand::new().manifest_path(manifest_path).exec() {
-            // Collect versions for ALL packages (including dependencies)
-            for package in &metadata.packages {
-                let crate_name = package.name.replace('-', "_");
-                all_crate_versions.insert(crate_name, package.version.to_string());
-            }
-            // Workspace members are the subset we fully analyze
-            metadata
-                .workspace_packages()
-                .iter()
-                .map(|pkg| pkg.name.replace('-', "_"))
-                .collect()
-        } else {
-            HashSet::new()
-        };
+    load_workspace_graph_inner(rustdoc_jsons, manifest_path, call_mode, options, None)
+}
+
+/// Like [`load_workspace_graph`], but returns [`RustdocError::Cancelled`]
+/// once `cancel` is set. The flag is checked between crates and between
+/// source files during call-edge extraction.
+#[cfg(feature = "native")]
+pub fn load_workspace_graph_cancellable(
+    rustdoc_jsons: &[RustdocJson],
+    manifest_path: &Path,
+    call_mode: CallMode,
+    options: &ExtractOptions,
+    cancel: &AtomicBool,
+) -> Result<Workspace, RustdocError> {
+    load_workspace_graph_inner(
+        rustdoc_jsons,
+        manifest_path,
+        call_mode,
+        options,
+        Some(cancel),
+    )
+}
+
+#[cfg(feature = "native")]
+fn load_workspace_graph_inner(
+    rustdoc_jsons: &[RustdocJson],
+    manifest_path: &Path,
+    call_mode: CallMode,
+    options: &ExtractOptions,
+    cancel: Option<&AtomicBool>,
+) -> Result<Workspace, RustdocError> {
+    // Collect versions for ALL packages (including dependencies)
+    let metadata = MetadataCommand::new().manifest_path(manifest_path).exec()?;
+    let all_crate_versions: HashMap<String, String> = metadata
+        .packages
+        .iter()
+        .map(|package| (package.name.replace('-', "_"), package.version.to_string()))
+        .collect();
+    // Workspace members are the documented subset we fully analyze
+    let documented: HashSet<&str> = rustdoc_jsons
+        .iter()
+        .map(|rustdoc| rustdoc.crate_name.as_str())
+        .collect();
+    let workspace_members: HashSet<String> = metadata
+        .workspace_packages()
+        .iter()
+        .map(|pkg| pkg.name.replace('-', "_"))
+        .filter(|name| documented.contains(name.as_str()))
+        .collect();
 
     // Resolve rustc version for std-lib crates (std, core, alloc).
     // Cargo metadata doesn't include these, so we fall back to `rustc +nightly --version`.
@@ -758,45 +1406,77 @@ pub fn load_workspace_graph(
             text.split_whitespace().nth(1).map(|v| v.to_string())
         });
 
-    for rustdoc in rustdoc_jsons {
-        let rustdoc_name_opt = if rustdoc.rustdoc_name != rustdoc.crate_name {
-            Some(rustdoc.rustdoc_name.as_str())
-        } else {
-            None
-        };
-        let graph = load_graph_from_path_with_sources(
-            &rustdoc.json_path,
-            &rustdoc.crate_name,
-            manifest_path,
-            &rustdoc.src_path,
-            call_mode,
-            rustdoc_name_opt,
-        )?;
-
-        // Merge nodes, preferring nodes with more complete data
-        for node in graph.nodes {
-            match nodes_by_id.entry(node.id.clone()) {
-                std::collections::hash_map::Entry::Vacant(entry) => {
-                    entry.insert(node);
-                }
-                std::collections::hash_map::Entry::Occupied(mut entry) => {
-                    // Prefer the node with more complete data (fields, span, etc.)
-                    let existing = entry.get();
-                    if node_is_more_complete(&node, existing) {
-                        entry.insert(node);
-                    }
-                }
-            }
-        }
+    // Extraction runs in parallel, but the merge walks crates in input
+    // order, so ties in `is_more_complete_than` always resolve the same way.
+    let graphs = extract_crate_graphs(
+        rustdoc_jsons,
+        &workspace_members,
+        call_mode,
+        options,
+        cancel,
+    )?;
+    Ok(partition_workspace(
+        graphs,
+        &workspace_members,
+        &all_crate_versions,
+        rustc_version,
+    ))
+}
 
-        // Merge edges without dropping confidence, glob status, or occurrences.
-        for edge in graph.edges {
-            edge_index.insert_or_merge(&mut edges, edge);
-        }
+/// Build a workspace from rustdoc JSON files that are already on disk,
+/// without cargo: no `cargo metadata`, no sources, and so no call edges.
+/// Each entry pairs a crate name with its JSON; every listed crate is
+/// treated as a workspace member. Member versions come from the JSON's
+/// `crate_version` (`0.0.0` when rustdoc didn't record one).
+#[cfg(feature = "native")]
+pub fn load_workspace_graph_from_json(
+    rustdoc_jsons: &[(String, PathBuf)],
+    options: &ExtractOptions,
+) -> Result<Workspace, RustdocError> {
+    let workspace_members: HashSet<String> = rustdoc_jsons
+        .iter()
+        .map(|(crate_name, _)| crate_name.replace('-', "_"))
+        .collect();
+    let mut versions = HashMap::new();
+    let mut graphs = Vec::with_capacity(rustdoc_jsons.len());
+    for (crate_name, json_path) in rustdoc_jsons {
+        let crate_name = crate_name.replace('-', "_");
+        let krate = parse_rustdoc_file(json_path)?;
+        if let Some(version) = &krate.crate_version {
+            versions.insert(crate_name.clone(), version.clone());
+        }
+        graphs.push(build_graph(
+            &krate,
+            &crate_name,
+            BuildGraphOptions {
+                workspace_members: Some(workspace_members.clone()),
+                extract: options.clone(),
+                ..Default::default()
+            },
+        )?);
     }
+    Ok(partition_workspace(
+        graphs,
+        &workspace_members,
+        &versions,
+        None,
+    ))
+}
+
+/// Merge per-crate graphs and split the result back out by the crate each
+/// node id names: members become [`CrateGraph`]s, everything else an
+/// [`ExternalCrate`] stub, and edges between crates `cross_crate_edges`.
+#[cfg(feature = "native")]
+fn partition_workspace(
+    graphs: Vec<Graph>,
+    workspace_members: &HashSet<String>,
+    all_crate_versions: &HashMap<String, String>,
+    rustc_version: Option<String>,
+) -> Workspace {
+    let merged = Graph::merge_all(graphs);
 
     // Partition nodes and edges into per-crate graphs
-    let all_nodes: Vec<Node> = nodes_by_id.into_values().collect();
+    let all_nodes = merged.nodes;
 
     // Determine which crate a node belongs to by its ID prefix
     let node_crate = |id: &str| -> String {
@@ -815,7 +1495,7 @@ pub fn load_workspace_graph(
     // Build per-crate edge lists and cross-crate edge list
     let mut crate_edges: HashMap<String, Vec<Edge>> = HashMap::new();
     let mut cross_crate_edges = Vec::new();
-    for edge in edges {
+    for edge in merged.edges {
         let from_crate = node_crate(&edge.from);
         let to_crate = node_crate(&edge.to);
         if from_crate == to_crate {
@@ -827,7 +1507,7 @@ pub fn load_workspace_graph(
 
     // Build workspace member CrateGraphs
     let mut crate_graphs = Vec::new();
-    for member in &workspace_members {
+    for member in workspace_members {
         let nodes = crate_nodes.remove(member).unwrap_or_default();
         let edges = crate_edges.remove(member).unwrap_or_default();
         let version = all_crate_versions
@@ -841,6 +1521,7 @@ pub fn load_workspace_graph(
             nodes,
             edges,
             aliases: std::collections::HashMap::new(),
+            doc_link_table: Vec::new(),
         });
     }
     crate_graphs.sort_by(|a, b| a.id.cmp(&b.id));
@@ -870,65 +1551,186 @@ pub fn load_workspace_graph(
         });
     }
 
-    Ok(Workspace {
+    let mut workspace = Workspace {
         version: codeview_core::SCHEMA_VERSION,
         crates: crate_graphs,
         external_crates,
         cross_crate_edges,
         repo: None,
         ref_: None,
+    };
+    workspace.sort_stable();
+    workspace
+}
+
+/// Extract every crate's graph. Calls from one member into another are
+/// resolved against every member's functions (see
+/// [`workspace_function_index`]), which takes a first pass over the JSON;
+/// each crate is parsed again for extraction, so only the crates being
+/// worked on are ever in memory. Graphs come back in `rustdoc_jsons` order.
+#[cfg(feature = "native")]
+fn extract_crate_graphs(
+    rustdoc_jsons: &[RustdocJson],
+    workspace_members: &HashSet<String>,
+    call_mode: CallMode,
+    options: &ExtractOptions,
+    cancel: Option<&AtomicBool>,
+) -> Result<Vec<Graph>, RustdocError> {
+    let workspace_functions = Arc::new(workspace_function_index(rustdoc_jsons, options, cancel)?);
+    map_parallel(rustdoc_jsons, options.jobs, |rustdoc| {
+        check_cancelled(cancel)?;
+        let krate = parse_rustdoc_file(&rustdoc.json_path)?;
+        extract_crate_graph(
+            rustdoc,
+            &krate,
+            workspace_members,
+            call_mode,
+            &workspace_functions,
+            options,
+            cancel,
+        )
     })
 }
 
-/// Returns true if `new` has more complete data than `existing`
-fn node_is_more_complete(new: &Node, existing: &Node) -> bool {
-    // Prefer non-external nodes over external ones
-    if !new.is_external && existing.is_external {
-        return true;
-    }
-    if new.is_external && !existing.is_external {
-        return false;
-    }
+/// Run `f` over `items` on a pool of scoped threads, `jobs` of them (one
+/// per available core by default). Crates are independent until they're
+/// merged, and each one is a large JSON parse or a `syn` walk of its
+/// sources. Results come back in `items` order. After the first failure no
+/// new items are started, and the error from the earliest failing item is
+/// returned. A panic in `f` counts as a failure, reported as
+/// [`RustdocError::WorkerPanicked`].
+#[cfg(feature = "native")]
+fn map_parallel<T: Sync, R: Send>(
+    items: &[T],
+    jobs: Option<usize>,
+    f: impl Fn(&T) -> Result<R, RustdocError> + Sync,
+) -> Result<Vec<R>, RustdocError> {
+    let workers = jobs
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+        .clamp(1, items.len().max(1));
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let mut slots: Vec<Option<Result<R, RustdocError>>> = items.iter().map(|_| None).collect();
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    while !failed.load(Ordering::Relaxed) {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+                        let result =
+                            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(item)))
+                                .unwrap_or_else(|payload| {
+                                    Err(RustdocError::WorkerPanicked(panic_message(
+                                        payload.as_ref(),
+                                    )))
+                                });
+                        if result.is_err() {
+                            failed.store(true, Ordering::Relaxed);
+                        }
+                        done.push((index, result));
+                    }
+                    done
+                })
+            })
+            .collect();
+        for handle in handles {
+            // `f`'s panics are caught above; nothing else in a worker panics.
+            let done = handle
+                .join()
+                .unwrap_or_else(|payload| std::panic::resume_unwind(payload));
+            for (index, result) in done {
+                slots[index] = Some(result);
+            }
+        }
+    });
 
-    // Count how much data each node has
-    let new_score = node_completeness_score(new);
-    let existing_score = node_completeness_score(existing);
-    new_score > existing_score
+    // Items are started in order, so every item before a failing one has
+    // finished, and the first error found is the earliest.
+    let mut results = Vec::with_capacity(slots.len());
+    for slot in slots.into_iter().flatten() {
+        results.push(slot?);
+    }
+    Ok(results)
 }
 
-fn node_completeness_score(node: &Node) -> u32 {
-    let mut score = 0;
-    if node.span.is_some() {
-        score += 1;
-    }
-    if node.fields.is_some() {
-        score += 2;
-    }
-    if node.variants.is_some() {
-        score += 2;
-    }
-    if node.signature.is_some() {
-        score += 2;
-    }
-    if !node.generics.is_empty() {
-        score += 1;
-    }
-    if node.docs.is_some() {
-        score += 1;
-    }
-    if !node.attrs.is_empty() {
-        score += 1;
+#[cfg(feature = "native")]
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Every workspace member's own functions, methods and macros, which a
+/// crate's call resolution falls back to when a callee isn't one of the
+/// functions rustdoc listed for it. Binary targets can't be called into and
+/// are left out. Each crate is dropped as soon as it's indexed. With a
+/// single crate there's nothing to fall back to, and no JSON is read.
+#[cfg(feature = "native")]
+fn workspace_function_index(
+    rustdoc_jsons: &[RustdocJson],
+    options: &ExtractOptions,
+    cancel: Option<&AtomicBool>,
+) -> Result<FunctionIndex, RustdocError> {
+    let mut index = FunctionIndex::new();
+    if rustdoc_jsons.len() < 2 {
+        return Ok(index);
     }
-    if node.visibility != Visibility::Unknown {
-        score += 1;
+    let libraries: Vec<&RustdocJson> = rustdoc_jsons
+        .iter()
+        .filter(|rustdoc| rustdoc.rustdoc_name == rustdoc.crate_name)
+        .collect();
+    let crate_indexes = map_parallel(&libraries, options.jobs, |rustdoc| {
+        check_cancelled(cancel)?;
+        let krate = parse_rustdoc_file(&rustdoc.json_path)?;
+        Ok(build_function_index(
+            &krate,
+            &collect_method_ids(&krate),
+            &rustdoc.crate_name,
+            &HiddenPaths::new(&krate, options.include_hidden),
+        ))
+    })?;
+    for (rustdoc, crate_index) in libraries.iter().zip(&crate_indexes) {
+        index.extend_from_crate(crate_index, &rustdoc.crate_name);
     }
-    score
+    Ok(index)
+}
+
+#[cfg(feature = "native")]
+fn extract_crate_graph(
+    rustdoc: &RustdocJson,
+    krate: &rdt::Crate,
+    workspace_members: &HashSet<String>,
+    call_mode: CallMode,
+    workspace_functions: &Arc<FunctionIndex>,
+    options: &ExtractOptions,
+    cancel: Option<&AtomicBool>,
+) -> Result<Graph, RustdocError> {
+    check_cancelled(cancel)?;
+    let rustdoc_name =
+        (rustdoc.rustdoc_name != rustdoc.crate_name).then_some(rustdoc.rustdoc_name.as_str());
+    extract_graph_with_sources_inner(
+        krate,
+        &rustdoc.crate_name,
+        workspace_members.clone(),
+        &rustdoc.src_path,
+        call_mode,
+        Some(workspace_functions.clone()),
+        rustdoc_name,
+        options,
+        cancel,
+    )
 }
 
 #[cfg(feature = "native")]
 pub fn load_graph_from_path(path: &Path, crate_name: &str) -> Result<Graph, RustdocError> {
-    let content = fs::read_to_string(path)?;
-    extract_graph(&content, crate_name)
+    build_standalone_graph(&parse_rustdoc_file(path)?, crate_name)
 }
 
 #[cfg(feature = "native")]
@@ -940,9 +1742,8 @@ pub fn load_graph_from_path_with_sources(
     call_mode: CallMode,
     rustdoc_name: Option<&str>,
 ) -> Result<Graph, RustdocError> {
-    let content = fs::read_to_string(path)?;
-    extract_graph_with_sources(
-        &content,
+    build_graph_with_sources(
+        &parse_rustdoc_file(path)?,
         crate_name,
         workspace_manifest_path,
         root_file,
@@ -951,6 +1752,39 @@ pub fn load_graph_from_path_with_sources(
     )
 }
 
+/// [`extract_graph`] for rustdoc JSON streamed from `reader`, such as
+/// stdin, without holding the whole document in memory as text. Only the
+/// `format_version` this build targets can be streamed; older or newer
+/// documents need [`extract_graph`], whose compatibility layer works on
+/// the full text.
+#[cfg(feature = "native")]
+pub fn extract_graph_reader<R: std::io::Read>(
+    reader: R,
+    crate_name: &str,
+) -> Result<Graph, RustdocError> {
+    build_standalone_graph(&parse_rustdoc_reader(reader)?, crate_name)
+}
+
+#[cfg(feature = "native")]
+fn parse_rustdoc_reader<R: std::io::Read>(reader: R) -> Result<rdt::Crate, RustdocError> {
+    let mut deserializer = serde_json::Deserializer::from_reader(std::io::BufReader::new(reader));
+    deserializer.disable_recursion_limit();
+    let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
+    Ok(serde::Deserialize::deserialize(deserializer)?)
+}
+
+/// Stream a rustdoc JSON file, re-reading it whole through
+/// [`parse_rustdoc_lenient`] only when it isn't in the current format.
+#[cfg(feature = "native")]
+fn parse_rustdoc_file(path: &Path) -> Result<rdt::Crate, RustdocError> {
+    match parse_rustdoc_reader(fs::File::open(path)?) {
+        Err(RustdocError::Json(err)) if !err.is_io() => {
+            parse_rustdoc_lenient(&fs::read_to_string(path)?)
+        }
+        result => result,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Multi-version rustdoc JSON compatibility layer
 //
@@ -1224,11 +2058,8 @@ pub fn extract_graph_validated(
         &validated.krate,
         crate_name,
         BuildGraphOptions {
-            workspace_members: None,
-            source: None,
-            call_mode: CallMode::Strict,
             skip_external_nodes: true,
-            rustdoc_name: None,
+            ..Default::default()
         },
     )?;
     let mut report = validated.report;
@@ -1496,16 +2327,17 @@ fn validate_graph_quality(
 }
 
 pub fn extract_graph(json: &str, crate_name: &str) -> Result<Graph, RustdocError> {
-    let krate = parse_rustdoc_lenient(json)?;
+    build_standalone_graph(&parse_rustdoc_lenient(json)?, crate_name)
+}
+
+/// The graph [`extract_graph`] builds: no sources, external items left out.
+fn build_standalone_graph(krate: &rdt::Crate, crate_name: &str) -> Result<Graph, RustdocError> {
     build_graph(
-        &krate,
+        krate,
         crate_name,
         BuildGraphOptions {
-            workspace_members: None,
-            source: None,
-            call_mode: CallMode::Strict,
             skip_external_nodes: true,
-            rustdoc_name: None,
+            ..Default::default()
         },
     )
 }
@@ -1521,21 +2353,69 @@ pub fn extract_graph_with_source_map(
     root_file: &str,
     call_mode: CallMode,
 ) -> Result<Graph, RustdocError> {
-    let krate = parse_rustdoc_lenient(json)?;
     let provider = MemorySourceProvider::new(source_files);
+    extract_graph_with_source_provider(
+        json,
+        crate_name,
+        &provider,
+        root_file,
+        call_mode,
+        &ExtractOptions::default(),
+    )
+}
+
+/// Extract a crate graph with call edges, reading sources through
+/// `provider` as module traversal reaches them. Only files reachable from
+/// `root_file` are ever requested.
+pub fn extract_graph_with_source_provider(
+    json: &str,
+    crate_name: &str,
+    provider: &dyn SourceProvider,
+    root_file: &str,
+    call_mode: CallMode,
+    options: &ExtractOptions,
+) -> Result<Graph, RustdocError> {
+    let krate = parse_rustdoc_lenient(json)?;
     build_graph(
         &krate,
         crate_name,
         BuildGraphOptions {
-            workspace_members: None,
-            source: Some((Path::new(root_file), &provider)),
+            source: Some((Path::new(root_file), provider)),
             call_mode,
             skip_external_nodes: true,
-            rustdoc_name: None,
+            extract: options.clone(),
+            ..Default::default()
         },
     )
 }
 
+/// [`extract_graph_with_source_provider`], also returning every call in
+/// the sources that didn't resolve to a known function, so call-graph
+/// coverage can be measured.
+pub fn extract_graph_with_diagnostics(
+    json: &str,
+    crate_name: &str,
+    provider: &dyn SourceProvider,
+    root_file: &str,
+    call_mode: CallMode,
+    options: &ExtractOptions,
+) -> Result<(Graph, Vec<UnresolvedCall>), RustdocError> {
+    let krate = parse_rustdoc_lenient(json)?;
+    let (graph, stats) = build_graph_with_stats(
+        &krate,
+        crate_name,
+        BuildGraphOptions {
+            source: Some((Path::new(root_file), provider)),
+            call_mode,
+            skip_external_nodes: true,
+            report_unresolved_calls: true,
+            extract: options.clone(),
+            ..Default::default()
+        },
+    )?;
+    Ok((graph, stats.unresolved_calls))
+}
+
 #[cfg(feature = "native")]
 pub fn extract_graph_with_sources(
     json: &str,
@@ -1545,17 +2425,79 @@ pub fn extract_graph_with_sources(
     call_mode: CallMode,
     rustdoc_name: Option<&str>,
 ) -> Result<Graph, RustdocError> {
-    let krate = parse_rustdoc_lenient(json)?;
-    let workspace_members = get_workspace_members(workspace_manifest_path)?;
+    build_graph_with_sources(
+        &parse_rustdoc_lenient(json)?,
+        crate_name,
+        workspace_manifest_path,
+        root_file,
+        call_mode,
+        rustdoc_name,
+    )
+}
+
+#[cfg(feature = "native")]
+fn build_graph_with_sources(
+    krate: &rdt::Crate,
+    crate_name: &str,
+    workspace_manifest_path: &Path,
+    root_file: &Path,
+    call_mode: CallMode,
+    rustdoc_name: Option<&str>,
+) -> Result<Graph, RustdocError> {
+    let metadata = MetadataCommand::new()
+        .manifest_path(workspace_manifest_path)
+        .exec()?;
+    let mut graph = extract_graph_with_sources_inner(
+        krate,
+        crate_name,
+        workspace_member_names(&metadata),
+        root_file,
+        call_mode,
+        None,
+        rustdoc_name,
+        &ExtractOptions::default(),
+        None,
+    )?;
+    // Cargo knows the local version even when rustdoc didn't record one.
+    let normalized = crate_name.replace('-', "_");
+    if let Some(package) = metadata
+        .packages
+        .iter()
+        .find(|package| package.name.replace('-', "_") == normalized)
+    {
+        graph
+            .crate_versions
+            .insert(normalized, package.version.to_string());
+    }
+    Ok(graph)
+}
+
+#[cfg(feature = "native")]
+#[allow(clippy::too_many_arguments)]
+fn extract_graph_with_sources_inner(
+    krate: &rdt::Crate,
+    crate_name: &str,
+    workspace_members: HashSet<String>,
+    root_file: &Path,
+    call_mode: CallMode,
+    workspace_functions: Option<Arc<FunctionIndex>>,
+    rustdoc_name: Option<&str>,
+    options: &ExtractOptions,
+    cancel: Option<&AtomicBool>,
+) -> Result<Graph, RustdocError> {
+    check_cancelled(cancel)?;
     build_graph(
-        &krate,
+        krate,
         crate_name,
         BuildGraphOptions {
             workspace_members: Some(workspace_members),
             source: Some((root_file, &FsSourceProvider)),
             call_mode,
-            skip_external_nodes: false,
+            workspace_functions,
             rustdoc_name: rustdoc_name.map(|s| s.to_string()),
+            cancel,
+            extract: options.clone(),
+            ..Default::default()
         },
     )
 }
@@ -1573,17 +2515,44 @@ struct BuildGraphOptions<'a> {
     /// Edges referencing external nodes are still created (as cross-crate references)
     /// but no Node entries or module hierarchies are built for them.
     skip_external_nodes: bool,
+    /// Other workspace members' functions, for calls that don't resolve
+    /// within the crate. See [`workspace_function_index`].
+    workspace_functions: Option<Arc<FunctionIndex>>,
     /// The name rustdoc uses internally for the root crate. For binary crates this may
     /// differ from `crate_name` (e.g. crate "codeview_cli" has rustdoc name "codeview").
     rustdoc_name: Option<String>,
+    /// Checked between source files during call-edge extraction.
+    cancel: Option<&'a AtomicBool>,
+    /// Caller-tunable limits, filters and extras.
+    extract: ExtractOptions,
+    /// Collect the source calls that produced no call edge into
+    /// `BuildGraphStats::unresolved_calls`.
+    report_unresolved_calls: bool,
+}
+
+impl Default for BuildGraphOptions<'_> {
+    fn default() -> Self {
+        Self {
+            workspace_members: None,
+            source: None,
+            call_mode: CallMode::Strict,
+            skip_external_nodes: false,
+            workspace_functions: None,
+            rustdoc_name: None,
+            cancel: None,
+            extract: ExtractOptions::default(),
+            report_unresolved_calls: false,
+        }
+    }
 }
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone)]
 struct BuildGraphStats {
     pruned_edges: usize,
     raw_doc_links: usize,
     resolved_doc_links: usize,
     unresolved_doc_links: usize,
+    unresolved_calls: Vec<UnresolvedCall>,
 }
 
 fn build_graph(
@@ -1615,12 +2584,11 @@ fn build_graph_with_stats(
     let krate = if let Some(ref rdn) = opts.rustdoc_name {
         let mut patched = krate.clone();
         for summary in patched.paths.values_mut() {
-            if summary.crate_id == 0 {
-                if let Some(first) = summary.path.first_mut() {
-                    if first.as_str() == rdn.as_str() {
-                        *first = crate_name.to_string();
-                    }
-                }
+            if summary.crate_id == 0
+                && let Some(first) = summary.path.first_mut()
+                && first.as_str() == rdn.as_str()
+            {
+                *first = crate_name.to_string();
             }
         }
         owned_krate = patched;
@@ -1634,7 +2602,9 @@ fn build_graph_with_stats(
     let mut edge_cache = EdgeIndex::with_capacity(krate.index.len().saturating_mul(4));
     let method_ids = collect_method_ids(krate);
     let mut path_index = build_path_index(krate, crate_name);
-    let function_index = build_function_index(krate, &method_ids, crate_name);
+    let hidden_paths = HiddenPaths::new(krate, opts.extract.include_hidden);
+    let mut function_index = build_function_index(krate, &method_ids, crate_name, &hidden_paths);
+    function_index.workspace = opts.workspace_functions;
     let trait_lookup = build_trait_lookup(krate, crate_name, &path_index);
     let mut placeholder_module_nodes = HashSet::new();
 
@@ -1683,8 +2653,7 @@ fn build_graph_with_stats(
     );
 
     for (item_id, summary) in &krate.paths {
-        let is_method = method_ids.contains(item_id);
-        let node_kind = match map_item_kind(&summary.kind, is_method) {
+        let node_kind = match map_item_kind(&summary.kind) {
             Some(kind) => kind,
             None => continue,
         };
@@ -1693,14 +2662,9 @@ fn build_graph_with_stats(
             continue;
         }
 
-        // Skip internal/generated paths (e.g., serde derive macro internals)
-        // - Paths containing "_" as a segment (serde's internal module)
-        // - Paths with segments starting with "__" (internal types like __FieldVisitor)
-        if summary
-            .path
-            .iter()
-            .any(|seg| seg == "_" || seg.starts_with("__"))
-        {
+        // Skip generated paths under `const _` (serde derive internals) and
+        // `#[doc(hidden)]` items.
+        if summary.path.iter().any(|seg| seg == "_") || hidden_paths.contains(&summary.path) {
             continue;
         }
 
@@ -1729,6 +2693,7 @@ fn build_graph_with_stats(
             &item_crate_name,
             &summary.path,
             &path_index,
+            (summary.crate_id == 0).then_some(krate),
             is_external,
         );
 
@@ -1770,6 +2735,7 @@ fn build_graph_with_stats(
             node.line_count = line_count(&span);
             node.span = span;
             node.attrs = attrs;
+            node.cfg = item.and_then(|item| extract_cfg(&item.attrs));
             node.is_external = is_external;
             node.is_deprecated = deprecation.is_some();
             node.is_unsafe = details.is_unsafe;
@@ -1837,7 +2803,7 @@ fn build_graph_with_stats(
 
     // item_to_parent was built earlier (top of build_graph) so doc-link
     // alias rewriting could use it. Just reuse it here.
-    add_use_import_edges_with_parent_map(
+    let public_reexports = add_use_import_edges_with_parent_map(
         &mut graph,
         &mut edge_cache,
         krate,
@@ -1847,6 +2813,9 @@ fn build_graph_with_stats(
     );
 
     for item in krate.index.values() {
+        if !opts.extract.include_hidden && is_doc_hidden(&item.attrs) {
+            continue;
+        }
         let owner_id = match &item.inner {
             rdt::ItemEnum::Impl(impl_block) => {
                 let item_crate_name = crate_name_for_id(krate, item.crate_id, crate_name);
@@ -1856,6 +2825,12 @@ fn build_graph_with_stats(
                 if is_external && opts.skip_external_nodes {
                     continue;
                 }
+                let is_blanket = impl_block.blanket_impl.is_some();
+                if opts.extract.blanket_impls == BlanketImpls::Skip
+                    && (is_blanket || impl_block.is_synthetic)
+                {
+                    continue;
+                }
 
                 ensure_crate_node(
                     &mut graph,
@@ -1866,9 +2841,17 @@ fn build_graph_with_stats(
                 );
                 let impl_id = impl_node_id(&item_crate_name, item.id);
                 let impl_span = item.span.as_ref().map(map_span);
-                // Resolve the trait ID for trait impls
+                // Resolve the trait ID for trait impls, stubbing out traits
+                // from crates rustdoc has no paths for
                 let impl_trait_id = impl_block.trait_.as_ref().and_then(|trait_path| {
-                    resolve_id(krate, crate_name, &path_index, trait_path.id)
+                    resolve_id(krate, crate_name, &path_index, trait_path.id).or_else(|| {
+                        ensure_external_trait_node(
+                            &mut graph,
+                            &mut node_cache,
+                            &workspace_members,
+                            &trait_path.path,
+                        )
+                    })
                 });
 
                 if !node_cache.contains(&impl_id) {
@@ -1882,7 +2865,7 @@ fn build_graph_with_stats(
                         ImplCategory::Synthetic
                     } else if impl_block.is_negative {
                         ImplCategory::Negative
-                    } else if impl_block.blanket_impl.is_some() {
+                    } else if is_blanket || is_blanket_declaration(impl_block) {
                         ImplCategory::Blanket
                     } else if impl_block.trait_.is_some() {
                         ImplCategory::Trait
@@ -1900,6 +2883,7 @@ fn build_graph_with_stats(
                     node.line_count = line_count(&span);
                     node.span = span;
                     node.attrs = format_attributes(&item.attrs);
+                    node.cfg = extract_cfg(&item.attrs);
                     node.is_external = is_external;
                     node.is_deprecated = deprecation.is_some();
                     node.is_unsafe = impl_block.is_unsafe;
@@ -1943,8 +2927,8 @@ fn build_graph_with_stats(
                     );
                 }
 
-                if let Some(for_id) = type_to_id(&impl_block.for_)
-                    && let Some(type_node_id) = resolve_id(krate, crate_name, &path_index, for_id)
+                if let Some(type_node_id) =
+                    impl_target_id(krate, crate_name, &path_index, &impl_block.for_)
                 {
                     push_edge_with_occurrence(
                         &mut graph,
@@ -1956,10 +2940,7 @@ fn build_graph_with_stats(
                         impl_span.clone(),
                     );
 
-                    if let Some(trait_path) = impl_block.trait_.as_ref()
-                        && let Some(trait_node_id) =
-                            resolve_id(krate, crate_name, &path_index, trait_path.id)
-                    {
+                    if let Some(trait_node_id) = impl_trait_id.clone() {
                         push_edge_with_occurrence(
                             &mut graph,
                             &mut edge_cache,
@@ -1976,6 +2957,9 @@ fn build_graph_with_stats(
                     let Some(assoc_item) = krate.index.get(assoc_id) else {
                         continue;
                     };
+                    if !opts.extract.include_hidden && is_doc_hidden(&assoc_item.attrs) {
+                        continue;
+                    }
 
                     let (kind, assoc_prefix) = match &assoc_item.inner {
                         rdt::ItemEnum::Function(_) => (NodeKind::Function, "method"),
@@ -1989,12 +2973,27 @@ fn build_graph_with_stats(
                     // Inherent associated items have semantic paths
                     // (`Type::method`) in rustdoc links. Trait-impl items
                     // stay per-impl so repeated rustdoc IDs across blanket
-                    // impls do not collapse into one shared child.
-                    let assoc_node_id = path_index
-                        .node_ids_by_rustdoc_id
-                        .get(assoc_id)
-                        .cloned()
-                        .unwrap_or_else(|| format!("{}::{}-{}", impl_id, assoc_prefix, assoc_id.0));
+                    // impls do not collapse into one shared child, unless
+                    // `BlanketImpls::Collapse` asks for a single node under
+                    // the trait that every blanket impl points at.
+                    let shared_trait_id = impl_trait_id
+                        .as_ref()
+                        .filter(|_| {
+                            is_blanket && opts.extract.blanket_impls == BlanketImpls::Collapse
+                        })
+                        .filter(|_| !path_index.node_ids_by_rustdoc_id.contains_key(assoc_id))
+                        .filter(|_| assoc_item.name.is_some());
+                    let assoc_node_id = match (
+                        path_index.node_ids_by_rustdoc_id.get(assoc_id),
+                        shared_trait_id,
+                    ) {
+                        (Some(id), _) => id.clone(),
+                        (None, Some(trait_id)) => format!(
+                            "{trait_id}::blanket::{}",
+                            assoc_item.name.as_deref().unwrap_or_default()
+                        ),
+                        (None, None) => format!("{}::{}-{}", impl_id, assoc_prefix, assoc_id.0),
+                    };
                     let assoc_span = assoc_item.span.as_ref().map(map_span);
                     if !node_cache.contains(&assoc_node_id) {
                         let name = assoc_item
@@ -2013,7 +3012,12 @@ fn build_graph_with_stats(
                         node.line_count = line_count(&span);
                         node.span = span;
                         node.attrs = format_attributes(&assoc_item.attrs);
-                        node.is_external = is_external;
+                        node.cfg = extract_cfg(&assoc_item.attrs);
+                        node.is_external = match shared_trait_id {
+                            Some(trait_id) => !workspace_members
+                                .contains(trait_id.split("::").next().unwrap_or(trait_id)),
+                            None => is_external,
+                        };
                         node.is_deprecated = deprecation.is_some();
                         node.is_unsafe = details.is_unsafe;
                         node.is_auto = details.is_auto;
@@ -2038,7 +3042,7 @@ fn build_graph_with_stats(
                             &path_index,
                             &canonical_to_alias,
                         );
-                        node.parent_impl = Some(impl_id.clone());
+                        node.parent_impl = shared_trait_id.is_none().then(|| impl_id.clone());
                         node.required_trait_methods = details.required_trait_methods;
                         node.default_trait_methods = details.default_trait_methods;
                         node.type_ = details.type_;
@@ -2078,6 +3082,8 @@ fn build_graph_with_stats(
         };
 
         let mut type_ids = HashSet::new();
+        let mut return_ids = HashSet::new();
+        let mut supertrait_ids = HashSet::new();
         match &item.inner {
             rdt::ItemEnum::Struct(item_struct) => {
                 collect_generics_ids(&item_struct.generics, &mut type_ids);
@@ -2094,6 +3100,14 @@ fn build_graph_with_stats(
             rdt::ItemEnum::Trait(item_trait) => {
                 collect_generics_ids(&item_trait.generics, &mut type_ids);
                 collect_bounds_ids(&item_trait.bounds, &mut type_ids);
+                // Supertraits get their own `Supertrait` edges; types in their
+                // generic args (`trait Foo: Into<Bar>`) stay `UsesType`.
+                for bound in &item_trait.bounds {
+                    if let rdt::GenericBound::TraitBound { trait_, .. } = bound {
+                        supertrait_ids.insert(trait_.id);
+                    }
+                }
+                type_ids.retain(|id| !supertrait_ids.contains(id));
                 let owner_crate = owner_id.split("::").next().unwrap_or(crate_name);
                 let owner_is_external = !workspace_members.contains(owner_crate);
 
@@ -2102,6 +3116,7 @@ fn build_graph_with_stats(
                         resolve_id(krate, crate_name, &path_index, *assoc_id)
                     {
                         if let Some(assoc_item) = krate.index.get(assoc_id)
+                            && (opts.extract.include_hidden || !is_doc_hidden(&assoc_item.attrs))
                             && let Some(kind) = associated_item_kind(assoc_item)
                             && !node_cache.contains(&assoc_node_id)
                         {
@@ -2121,6 +3136,7 @@ fn build_graph_with_stats(
                             node.line_count = line_count(&span);
                             node.span = span;
                             node.attrs = format_attributes(&assoc_item.attrs);
+                            node.cfg = extract_cfg(&assoc_item.attrs);
                             node.is_external = owner_is_external;
                             node.is_deprecated = deprecation.is_some();
                             node.is_unsafe = details.is_unsafe;
@@ -2149,6 +3165,10 @@ fn build_graph_with_stats(
                             );
                             node.required_trait_methods = details.required_trait_methods;
                             node.default_trait_methods = details.default_trait_methods;
+                            node.is_default = matches!(
+                                &assoc_item.inner,
+                                rdt::ItemEnum::Function(function) if function.has_body
+                            );
                             node.type_ = details.type_;
                             node.variant_kind = details.variant_kind;
                             node.discriminant = details.discriminant;
@@ -2190,7 +3210,14 @@ fn build_graph_with_stats(
                 collect_generics_ids(&alias.generics, &mut type_ids);
             }
             rdt::ItemEnum::Function(function) => {
-                collect_signature_ids(&function.sig, &mut type_ids);
+                // Parameters stay `UsesType`; the output type gets its own
+                // `Returns` edges so constructors can be told apart.
+                for (_, ty) in &function.sig.inputs {
+                    collect_type_ids(ty, &mut type_ids);
+                }
+                if let Some(output) = function.sig.output.as_ref() {
+                    collect_type_ids(output, &mut return_ids);
+                }
                 collect_generics_ids(&function.generics, &mut type_ids);
             }
             rdt::ItemEnum::Impl(impl_block) => {
@@ -2211,12 +3238,41 @@ fn build_graph_with_stats(
             }
             _ => {}
         }
-
-        add_uses_edges(
+        // Traits named through `dyn`, and types only named as generic
+        // arguments, get labelled edges instead, so dynamic dispatch and
+        // instantiation stand apart from concrete type use.
+        let (types, return_types) = item_type_positions(&krate.index, &item.inner);
+        let uses = type_uses(&types);
+        let return_uses = type_uses(&return_types);
+        uses.take_labelled(&mut type_ids);
+        return_uses.take_labelled(&mut return_ids);
+
+        for (ids, kind, label) in [
+            (type_ids, EdgeKind::UsesType, None),
+            (uses.dyn_traits, EdgeKind::UsesType, Some("dyn")),
+            (uses.generic, EdgeKind::UsesType, Some("generic")),
+            (return_ids, EdgeKind::Returns, None),
+            (return_uses.dyn_traits, EdgeKind::Returns, Some("dyn")),
+            (return_uses.generic, EdgeKind::Returns, Some("generic")),
+            (supertrait_ids, EdgeKind::Supertrait, None),
+        ] {
+            add_type_edges(
+                &mut graph,
+                &mut edge_cache,
+                &owner_id,
+                ids,
+                kind,
+                label,
+                krate,
+                crate_name,
+                &path_index,
+            );
+        }
+        add_field_edges(
             &mut graph,
             &mut edge_cache,
             &owner_id,
-            type_ids,
+            field_type_ids(&krate.index, &item.inner),
             krate,
             crate_name,
             &path_index,
@@ -2231,16 +3287,35 @@ fn build_graph_with_stats(
         );
     }
 
+    let mut unresolved_calls = Vec::new();
     if let Some((root_file, source_provider)) = opts.source {
-        add_call_edges(
+        unresolved_calls = add_call_edges(
             &mut graph,
             &mut edge_cache,
             root_file,
             &function_index,
             opts.call_mode,
             source_provider,
+            opts.cancel,
+            opts.extract.max_module_depth,
+            &opts.extract.entry_point_attributes,
+            opts.report_unresolved_calls,
+            opts.extract.complexity,
         )?;
     }
+    if opts.extract.doctest_calls {
+        let crate_names: Vec<&str> = std::iter::once(crate_name)
+            .chain(opts.rustdoc_name.as_deref())
+            .collect();
+        add_doctest_call_edges(
+            &mut graph,
+            &mut edge_cache,
+            &function_index,
+            opts.call_mode,
+            opts.extract.max_module_depth,
+            &crate_names,
+        );
+    }
 
     materialize_missing_external_edge_nodes(
         &mut graph,
@@ -2248,13 +3323,24 @@ fn build_graph_with_stats(
         &workspace_members,
         &path_index,
     );
-    let pruned_edges = prune_dangling_edges(&mut graph, &node_cache);
+    drop_skipped_crates(
+        &mut graph,
+        &mut node_cache,
+        &opts.extract.skip_external_crates,
+    );
+    let pruned_edges = prune_dangling_edges(&mut graph, &node_cache, &function_index);
+    assign_effective_visibility(&mut graph, crate_name, &public_reexports);
+    if opts.extract.flatten_reexports {
+        flatten_public_reexports(&mut graph, &public_reexports);
+    }
     let (raw_doc_links, resolved_doc_links, unresolved_doc_links) =
         doc_link_resolution_stats(krate, crate_name, &path_index);
 
     // Persist the alias map so server URL routing can resolve user-friendly
     // paths back to their canonical node IDs.
     graph.aliases = aliases;
+    graph.crate_versions = crate_versions(krate, crate_name);
+    graph.sort_stable();
 
     Ok((
         graph,
@@ -2263,10 +3349,44 @@ fn build_graph_with_stats(
             raw_doc_links,
             resolved_doc_links,
             unresolved_doc_links,
+            unresolved_calls,
         },
     ))
 }
 
+/// Versions rustdoc recorded: the crate's own `crate_version`, and for
+/// external crates the version segment of a docs.rs-style `html_root_url`
+/// (`https://docs.rs/serde/1.0.197/`). Standard-library crates link to
+/// `doc.rust-lang.org` without a version and are left out.
+fn crate_versions(krate: &rdt::Crate, crate_name: &str) -> HashMap<String, String> {
+    let mut versions = HashMap::new();
+    for external in krate.external_crates.values() {
+        let name = external.name.replace('-', "_");
+        if let Some(version) = external
+            .html_root_url
+            .as_deref()
+            .and_then(|url| version_from_doc_url(url, &name))
+        {
+            versions.insert(name, version);
+        }
+    }
+    if let Some(version) = &krate.crate_version {
+        versions.insert(crate_name.replace('-', "_"), version.clone());
+    }
+    versions
+}
+
+/// The path segment after the crate's name in a doc URL, when it looks like
+/// a version rather than `latest`.
+fn version_from_doc_url(url: &str, crate_name: &str) -> Option<String> {
+    let mut segments = url.split('/').filter(|segment| !segment.is_empty());
+    segments.find(|segment| segment.replace('-', "_") == crate_name)?;
+    segments
+        .next()
+        .filter(|version| version.starts_with(|c: char| c.is_ascii_digit()))
+        .map(str::to_string)
+}
+
 fn collect_method_ids(krate: &rdt::Crate) -> HashSet<rdt::Id> {
     let mut method_ids = HashSet::new();
     for item in krate.index.values() {
@@ -2289,6 +3409,8 @@ struct PathIndex {
     module_paths: HashSet<String>,
     node_kinds: HashMap<String, NodeKind>,
     node_ids_by_rustdoc_id: HashMap<rdt::Id, String>,
+    /// Primitive node ids by name (`u32`, `slice`, …).
+    primitive_ids: HashMap<String, String>,
 }
 
 impl PathIndex {
@@ -2342,7 +3464,7 @@ fn build_path_index(krate: &rdt::Crate, default_crate_name: &str) -> PathIndex {
         if summary.kind == rdt::ItemKind::Module {
             index.module_paths.insert(id.clone());
         }
-        if let Some(kind) = map_item_kind(&summary.kind, false) {
+        if let Some(kind) = map_item_kind(&summary.kind) {
             entries_by_path
                 .entry(id)
                 .or_default()
@@ -2386,6 +3508,47 @@ fn build_path_index(krate: &rdt::Crate, default_crate_name: &str) -> PathIndex {
         }
     }
 
+    // `impl Trait for u32` names the primitive instead of pointing at an id,
+    // so primitives are also indexed by name. Impls on a primitive the paths
+    // don't list get a `core::<name>` node of their own.
+    for (item_id, summary) in &krate.paths {
+        if summary.kind != rdt::ItemKind::Primitive {
+            continue;
+        }
+        let (Some(name), Some(node_id)) = (
+            summary.path.last(),
+            index.node_ids_by_rustdoc_id.get(item_id),
+        ) else {
+            continue;
+        };
+        let entry = index
+            .primitive_ids
+            .entry(name.clone())
+            .or_insert_with(|| node_id.clone());
+        if *node_id < *entry {
+            *entry = node_id.clone();
+        }
+    }
+    for item in krate.index.values() {
+        let rdt::ItemEnum::Impl(impl_block) = &item.inner else {
+            continue;
+        };
+        if type_to_id(&impl_block.for_).is_some() {
+            continue;
+        }
+        let Some(name) = impl_target_primitive(&impl_block.for_) else {
+            continue;
+        };
+        if !index.primitive_ids.contains_key(name) {
+            let node_id = format!("core::{name}");
+            index.known_paths.insert(node_id.clone());
+            index
+                .node_kinds
+                .insert(node_id.clone(), NodeKind::Primitive);
+            index.primitive_ids.insert(name.to_string(), node_id);
+        }
+    }
+
     index
 }
 
@@ -2587,7 +3750,7 @@ fn build_trait_lookup(
     lookup
 }
 
-fn map_item_kind(kind: &rdt::ItemKind, is_method: bool) -> Option<NodeKind> {
+fn map_item_kind(kind: &rdt::ItemKind) -> Option<NodeKind> {
     match kind {
         rdt::ItemKind::Module => Some(NodeKind::Module),
         rdt::ItemKind::ExternCrate => Some(NodeKind::ExternCrate),
@@ -2600,11 +3763,7 @@ fn map_item_kind(kind: &rdt::ItemKind, is_method: bool) -> Option<NodeKind> {
         rdt::ItemKind::Trait => Some(NodeKind::Trait),
         rdt::ItemKind::TraitAlias => Some(NodeKind::TraitAlias),
         rdt::ItemKind::Impl => Some(NodeKind::Impl),
-        rdt::ItemKind::Function => Some(if is_method {
-            NodeKind::Function
-        } else {
-            NodeKind::Function
-        }),
+        rdt::ItemKind::Function => Some(NodeKind::Function),
         rdt::ItemKind::TypeAlias => Some(NodeKind::TypeAlias),
         rdt::ItemKind::Constant => Some(NodeKind::Constant),
         rdt::ItemKind::Static => Some(NodeKind::Static),
@@ -2647,6 +3806,46 @@ fn line_count(span: &Option<Span>) -> Option<u32> {
     )
 }
 
+/// Read the source text `span` covers, with its file taken relative to
+/// `root`. Columns count characters; the end column is one past the last
+/// one, as rustdoc reports it. Lines and columns past the end of the file
+/// are clamped, so a stale span gives whatever is left rather than an
+/// error.
+#[cfg(feature = "native")]
+pub fn read_span(span: &Span, root: &Path) -> Result<String, RustdocError> {
+    let source = fs::read_to_string(root.join(&span.file))?;
+    Ok(span_text(&source, span))
+}
+
+#[cfg(feature = "native")]
+fn span_text(source: &str, span: &Span) -> String {
+    let lines: Vec<&str> = source.lines().collect();
+    let first = span.line.max(1) as usize;
+    let last = (span.end_line.unwrap_or(span.line).max(span.line) as usize).min(lines.len());
+    if first > last {
+        return String::new();
+    }
+    let mut text = Vec::with_capacity(last - first + 1);
+    for (number, line) in (first..=last).zip(&lines[first - 1..last]) {
+        let start = if number == first {
+            span.column.saturating_sub(1) as usize
+        } else {
+            0
+        };
+        let end = match span.end_column {
+            Some(column) if number == last => column.saturating_sub(1) as usize,
+            _ => usize::MAX,
+        };
+        text.push(
+            line.chars()
+                .skip(start)
+                .take(end.saturating_sub(start))
+                .collect::<String>(),
+        );
+    }
+    text.join("\n")
+}
+
 fn map_deprecation(deprecation: Option<&rdt::Deprecation>) -> Option<Deprecation> {
     deprecation.map(|deprecation| Deprecation {
         since: deprecation.since.clone(),
@@ -2956,6 +4155,9 @@ fn map_bounds(bounds: &[rdt::GenericBound]) -> Vec<CvGenericBound> {
     bounds.iter().map(map_generic_bound).collect()
 }
 
+/// Fields in declaration order. rustdoc lists them as written even when
+/// their ids aren't ascending, so no sorting is needed (or safe: fields a
+/// macro generates all share the invocation's span).
 fn extract_struct_fields(
     index: &HashMap<rdt::Id, rdt::Item>,
     kind: &rdt::StructKind,
@@ -3034,6 +4236,7 @@ fn extract_field_list(
     }
 }
 
+/// Variants in declaration order, as for [`extract_struct_fields`].
 fn extract_enum_variants(
     index: &HashMap<rdt::Id, rdt::Item>,
     variants: &[rdt::Id],
@@ -3118,6 +4321,8 @@ fn extract_function_signature(
     header: &rdt::FunctionHeader,
     generics: &rdt::Generics,
 ) -> FunctionSignature {
+    let output = sig.output.as_ref().map(map_type);
+    let generics = map_generics(generics);
     FunctionSignature {
         inputs: sig
             .inputs
@@ -3127,20 +4332,75 @@ fn extract_function_signature(
                 type_: map_type(ty),
             })
             .collect(),
-        output: sig.output.as_ref().map(map_type),
         is_async: header.is_async,
         is_unsafe: header.is_unsafe,
         is_const: header.is_const,
         abi: format_abi(&header.abi),
         is_c_variadic: sig.is_c_variadic,
-        generics: map_generics(generics),
+        is_async_trait: !header.is_async && is_async_trait_desugaring(output.as_ref(), &generics),
+        output,
+        generics,
+    }
+}
+
+/// Name of the lifetime `#[async_trait]` threads through every method it
+/// rewrites.
+const ASYNC_TRAIT_LIFETIME: &str = "'async_trait";
+
+/// Heuristic: does this signature look like `#[async_trait]` output? The
+/// attribute itself is consumed by expansion before rustdoc runs, so we
+/// match what it leaves behind instead: an `'async_trait` lifetime param and
+/// a `Pin<Box<dyn Future<Output = _> + 'async_trait>>` return type. A
+/// hand-written method with exactly that shape is misreported, which is
+/// harmless since it is an async method in all but syntax.
+fn is_async_trait_desugaring(output: Option<&TypeRef>, generics: &CvGenerics) -> bool {
+    let has_lifetime = generics.params.iter().any(|param| {
+        param.name == ASYNC_TRAIT_LIFETIME
+            && matches!(param.kind, CvGenericParamKind::Lifetime { .. })
+    });
+    if !has_lifetime {
+        return false;
     }
+    let Some(boxed) = output.and_then(|ty| single_type_arg(ty, "Pin")) else {
+        return false;
+    };
+    let Some(TypeRef::DynTrait { traits, lifetime }) = single_type_arg(boxed, "Box") else {
+        return false;
+    };
+    lifetime.as_deref() == Some(ASYNC_TRAIT_LIFETIME)
+        && traits.iter().any(|poly| {
+            matches!(&poly.trait_, TypeRef::ResolvedPath { path, .. }
+                if last_segment(path) == "Future")
+        })
 }
 
-fn format_abi(abi: &rdt::Abi) -> Option<String> {
-    let text = match abi {
-        rdt::Abi::Rust => return None,
-        rdt::Abi::C { unwind } => abi_with_unwind("C", *unwind),
+/// The first type argument of `ty` if it is a path whose last segment is
+/// `name`, e.g. `T` for `std::boxed::Box<T>` when `name == "Box"`.
+fn single_type_arg<'a>(ty: &'a TypeRef, name: &str) -> Option<&'a TypeRef> {
+    let TypeRef::ResolvedPath {
+        path,
+        args: Some(args),
+        ..
+    } = ty
+    else {
+        return None;
+    };
+    if last_segment(path) != name {
+        return None;
+    }
+    let CvGenericArgs::AngleBracketed { args, .. } = args.as_ref() else {
+        return None;
+    };
+    args.iter().find_map(|arg| match arg {
+        CvGenericArg::Type { value } => Some(value),
+        _ => None,
+    })
+}
+
+fn format_abi(abi: &rdt::Abi) -> Option<String> {
+    let text = match abi {
+        rdt::Abi::Rust => return None,
+        rdt::Abi::C { unwind } => abi_with_unwind("C", *unwind),
         rdt::Abi::Cdecl { unwind } => abi_with_unwind("cdecl", *unwind),
         rdt::Abi::Stdcall { unwind } => abi_with_unwind("stdcall", *unwind),
         rdt::Abi::Fastcall { unwind } => abi_with_unwind("fastcall", *unwind),
@@ -3176,6 +4436,34 @@ fn clean_path(path: &str) -> String {
     path.rsplit("::").next().unwrap_or(path).to_string()
 }
 
+/// Link `path` to `node_id` under its [`clean_path`] display. When two
+/// targets would share a display (`io::Error` next to a local `Error`),
+/// both are keyed by their full node id instead, and so is any later
+/// target with that display.
+fn insert_link(links: &mut HashMap<String, String>, path: &str, node_id: String) {
+    if links.contains_key(&node_id) {
+        return;
+    }
+    let display = clean_path(path);
+    match links.get(&display) {
+        Some(existing) if *existing == node_id => {}
+        Some(_) => {
+            let existing = links.remove(&display).expect("display was just found");
+            links.insert(existing.clone(), existing);
+            links.insert(node_id.clone(), node_id);
+        }
+        None if links
+            .keys()
+            .any(|key| *key != display && clean_path(key) == display) =>
+        {
+            links.insert(node_id.clone(), node_id);
+        }
+        None => {
+            links.insert(display, node_id);
+        }
+    }
+}
+
 /// Get the generics from an item's inner data, if any. Used by the
 /// link extractors.
 fn item_generics(item: &rdt::Item) -> Option<&rdt::Generics> {
@@ -3193,7 +4481,8 @@ fn item_generics(item: &rdt::Item) -> Option<&rdt::Generics> {
 }
 
 /// Collect resolved type links from a Type tree.
-/// Maps cleaned display name → resolved node ID for every ResolvedPath encountered.
+/// Maps cleaned display name → resolved node ID for every ResolvedPath
+/// encountered, disambiguated by [`insert_link`].
 fn collect_type_links(
     ty: &rdt::Type,
     krate: &rdt::Crate,
@@ -3203,9 +4492,8 @@ fn collect_type_links(
 ) {
     match ty {
         rdt::Type::ResolvedPath(path) => {
-            let display = clean_path(&path.path);
             if let Some(node_id) = resolve_id(krate, crate_name, path_index, path.id) {
-                links.insert(display, node_id);
+                insert_link(links, &path.path, node_id);
             }
             if let Some(args) = &path.args {
                 collect_generic_args_links(args, krate, crate_name, path_index, links);
@@ -3213,9 +4501,8 @@ fn collect_type_links(
         }
         rdt::Type::DynTrait(dyn_trait) => {
             for poly in &dyn_trait.traits {
-                let display = clean_path(&poly.trait_.path);
                 if let Some(node_id) = resolve_id(krate, crate_name, path_index, poly.trait_.id) {
-                    links.insert(display, node_id);
+                    insert_link(links, &poly.trait_.path, node_id);
                 }
                 if let Some(args) = &poly.trait_.args {
                     collect_generic_args_links(args, krate, crate_name, path_index, links);
@@ -3245,9 +4532,8 @@ fn collect_type_links(
         rdt::Type::ImplTrait(bounds) => {
             for bound in bounds {
                 if let rdt::GenericBound::TraitBound { trait_, .. } = bound {
-                    let display = clean_path(&trait_.path);
                     if let Some(node_id) = resolve_id(krate, crate_name, path_index, trait_.id) {
-                        links.insert(display, node_id);
+                        insert_link(links, &trait_.path, node_id);
                     }
                     if let Some(args) = &trait_.args {
                         collect_generic_args_links(args, krate, crate_name, path_index, links);
@@ -3263,9 +4549,8 @@ fn collect_type_links(
         } => {
             collect_type_links(self_type, krate, crate_name, path_index, links);
             if let Some(trait_path) = trait_ {
-                let display = clean_path(&trait_path.path);
                 if let Some(node_id) = resolve_id(krate, crate_name, path_index, trait_path.id) {
-                    links.insert(display, node_id);
+                    insert_link(links, &trait_path.path, node_id);
                 }
                 if let Some(args) = &trait_path.args {
                     collect_generic_args_links(args, krate, crate_name, path_index, links);
@@ -3324,9 +4609,7 @@ fn collect_assoc_constraint_links(
             collect_term_links(term, krate, crate_name, path_index, links);
         }
         rdt::AssocItemConstraintKind::Constraint(bounds) => {
-            for (display, node_id) in collect_bound_links(bounds, krate, crate_name, path_index) {
-                links.insert(display, node_id);
-            }
+            collect_bound_links_into(bounds, krate, crate_name, path_index, links);
         }
     }
 }
@@ -3388,6 +4671,17 @@ fn collect_bound_links(
     path_index: &PathIndex,
 ) -> HashMap<String, String> {
     let mut links = HashMap::new();
+    collect_bound_links_into(bounds, krate, crate_name, path_index, &mut links);
+    links
+}
+
+fn collect_bound_links_into(
+    bounds: &[rdt::GenericBound],
+    krate: &rdt::Crate,
+    crate_name: &str,
+    path_index: &PathIndex,
+    links: &mut HashMap<String, String>,
+) {
     for bound in bounds {
         if let rdt::GenericBound::TraitBound {
             trait_,
@@ -3395,23 +4689,15 @@ fn collect_bound_links(
             ..
         } = bound
         {
-            let display = clean_path(&trait_.path);
             if let Some(node_id) = resolve_id(krate, crate_name, path_index, trait_.id) {
-                links.insert(display, node_id);
+                insert_link(links, &trait_.path, node_id);
             }
             if let Some(args) = &trait_.args {
-                collect_generic_args_links(args, krate, crate_name, path_index, &mut links);
+                collect_generic_args_links(args, krate, crate_name, path_index, links);
             }
-            collect_generic_param_def_links(
-                generic_params,
-                krate,
-                crate_name,
-                path_index,
-                &mut links,
-            );
+            collect_generic_param_def_links(generic_params, krate, crate_name, path_index, links);
         }
     }
-    links
 }
 
 fn collect_generic_param_def_links(
@@ -3427,10 +4713,7 @@ fn collect_generic_param_def_links(
             rdt::GenericParamDefKind::Type {
                 bounds, default, ..
             } => {
-                for (display, node_id) in collect_bound_links(bounds, krate, crate_name, path_index)
-                {
-                    links.insert(display, node_id);
-                }
+                collect_bound_links_into(bounds, krate, crate_name, path_index, links);
                 if let Some(default) = default {
                     collect_type_links(default, krate, crate_name, path_index, links);
                 }
@@ -3811,6 +5094,7 @@ fn enrich_crate_node(
     node.line_count = line_count(&span);
     node.span = span;
     node.attrs = format_attributes(&item.attrs);
+    node.cfg = extract_cfg(&item.attrs);
     node.is_deprecated = deprecation.is_some();
     node.is_stripped = details.is_stripped;
     node.deprecation = deprecation;
@@ -3820,6 +5104,7 @@ fn enrich_crate_node(
     node.doc_links = extract_doc_links(item, krate, crate_name, path_index, canonical_to_alias);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn ensure_module_nodes(
     graph: &mut Graph,
     node_cache: &mut HashSet<String>,
@@ -3828,24 +5113,36 @@ fn ensure_module_nodes(
     crate_name: &str,
     path: &[String],
     path_index: &PathIndex,
+    local_krate: Option<&rdt::Crate>,
     is_external: bool,
 ) {
     if path.len() <= 1 {
         return;
     }
 
+    // Track the module item alongside the path so placeholders for modules
+    // missing from `paths` still get their declared visibility.
+    let mut module_item = local_krate.and_then(|krate| krate.index.get(&krate.root));
     let mut parent_id = crate_name.to_string();
     for (index, segment) in path[..path.len() - 1].iter().enumerate() {
+        if index > 0 {
+            module_item = module_item
+                .zip(local_krate)
+                .and_then(|(item, krate)| child_module(krate, item, segment));
+        }
         let module_id = join_path(crate_name, &path[..=index]);
         if path_index.is_known_non_module(&module_id) {
             break;
         }
         if !node_cache.contains(&module_id) {
+            let visibility = module_item
+                .map(|item| map_visibility(&item.visibility))
+                .unwrap_or(Visibility::Unknown);
             let mut node = Node::new(
                 module_id.clone(),
                 segment.clone(),
                 NodeKind::Module,
-                Visibility::Unknown,
+                visibility,
             );
             node.is_external = is_external;
             graph.add_node(node);
@@ -3869,6 +5166,22 @@ fn ensure_module_nodes(
     }
 }
 
+/// The `mod` named `name` declared directly inside `module`.
+fn child_module<'a>(
+    krate: &'a rdt::Crate,
+    module: &rdt::Item,
+    name: &str,
+) -> Option<&'a rdt::Item> {
+    let rdt::ItemEnum::Module(module) = &module.inner else {
+        return None;
+    };
+    module.items.iter().find_map(|id| {
+        let item = krate.index.get(id)?;
+        (matches!(item.inner, rdt::ItemEnum::Module(_)) && item.name.as_deref() == Some(name))
+            .then_some(item)
+    })
+}
+
 fn upsert_node(
     graph: &mut Graph,
     node_cache: &mut HashSet<String>,
@@ -3922,11 +5235,68 @@ fn materialize_missing_external_edge_nodes(
     }
 }
 
-fn prune_dangling_edges(graph: &mut Graph, node_cache: &HashSet<String>) -> usize {
-    let before = graph.edges.len();
+/// Stub node for a trait that rustdoc left out of `paths` (typically one
+/// from an undocumented dependency), keyed by the path as written in the
+/// impl. Only crate-qualified paths outside the workspace qualify: a bare
+/// `Serialize` doesn't say which crate it came from.
+fn ensure_external_trait_node(
+    graph: &mut Graph,
+    node_cache: &mut HashSet<String>,
+    workspace_members: &HashSet<String>,
+    path: &str,
+) -> Option<String> {
+    let path = path.trim_start_matches("::");
+    let (crate_name, rest) = path.split_once("::")?;
+    if rest.is_empty()
+        || matches!(crate_name, "crate" | "self" | "super" | "Self")
+        || workspace_members.contains(crate_name)
+    {
+        return None;
+    }
+    ensure_crate_node(graph, node_cache, crate_name, Visibility::Public, true);
+    let id = path.to_string();
+    if node_cache.insert(id.clone()) {
+        graph.add_node(external_stub_node(id.clone(), NodeKind::Trait));
+    }
+    Some(id)
+}
+
+/// Remove the external nodes of `skip` crates, and every edge into or out of
+/// them, before dangling edges are counted.
+fn drop_skipped_crates(
+    graph: &mut Graph,
+    node_cache: &mut HashSet<String>,
+    skip: &HashSet<String>,
+) {
+    if skip.is_empty() {
+        return;
+    }
+    let in_skipped_crate = |id: &str| skip.contains(id.split("::").next().unwrap_or(id));
+    for node in graph
+        .nodes
+        .extract_if(.., |node| node.is_external && in_skipped_crate(&node.id))
+    {
+        node_cache.remove(&node.id);
+    }
+    let skipped = |id: &String| in_skipped_crate(id) && !node_cache.contains(id);
     graph
         .edges
-        .retain(|edge| node_cache.contains(&edge.from) && node_cache.contains(&edge.to));
+        .retain(|edge| !skipped(&edge.from) && !skipped(&edge.to));
+}
+
+/// Drop edges whose ends have no node, except calls into another workspace
+/// member, whose target is in that member's graph.
+fn prune_dangling_edges(
+    graph: &mut Graph,
+    node_cache: &HashSet<String>,
+    function_index: &FunctionIndex,
+) -> usize {
+    let before = graph.edges.len();
+    graph.edges.retain(|edge| {
+        node_cache.contains(&edge.from)
+            && (node_cache.contains(&edge.to)
+                || (edge.kind.is_call() && function_index.is_workspace_callable(&edge.to)))
+    });
     before - graph.edges.len()
 }
 
@@ -4134,6 +5504,54 @@ fn type_to_id(ty: &rdt::Type) -> Option<rdt::Id> {
     }
 }
 
+/// The node an impl hangs off: the type it names, else the primitive its
+/// type is built from, else the first trait of a `dyn` type. `None` for a
+/// bare generic parameter, as in a blanket impl.
+fn impl_target_id(
+    krate: &rdt::Crate,
+    default_crate_name: &str,
+    path_index: &PathIndex,
+    ty: &rdt::Type,
+) -> Option<String> {
+    if let Some(id) = type_to_id(ty) {
+        return resolve_id(krate, default_crate_name, path_index, id);
+    }
+    if let rdt::Type::DynTrait(dyn_trait) = ty {
+        let poly_trait = dyn_trait.traits.first()?;
+        return resolve_id(krate, default_crate_name, path_index, poly_trait.trait_.id);
+    }
+    let name = impl_target_primitive(ty)?;
+    path_index.primitive_ids.get(name).cloned()
+}
+
+/// The primitive behind an impl's type when it names no path: `u32` for
+/// `u32` or `&u32`, `slice` for `[T]`, `tuple` for `(A, B)`, and so on.
+fn impl_target_primitive(ty: &rdt::Type) -> Option<&str> {
+    match ty {
+        rdt::Type::Primitive(name) => Some(name),
+        rdt::Type::BorrowedRef { type_, .. } | rdt::Type::RawPointer { type_, .. } => {
+            impl_target_primitive(type_)
+        }
+        rdt::Type::Slice(_) => Some("slice"),
+        rdt::Type::Array { .. } => Some("array"),
+        rdt::Type::Tuple(types) if types.is_empty() => Some("unit"),
+        rdt::Type::Tuple(_) => Some("tuple"),
+        rdt::Type::FunctionPointer(_) => Some("fn"),
+        _ => None,
+    }
+}
+
+/// `impl<T> Trait for T` as written in the crate. rustdoc marks only the
+/// copies it lists under each concrete type with `blanket_impl`.
+fn is_blanket_declaration(impl_block: &rdt::Impl) -> bool {
+    let rdt::Type::Generic(name) = &impl_block.for_ else {
+        return false;
+    };
+    impl_block.generics.params.iter().any(|param| {
+        param.name == *name && matches!(param.kind, rdt::GenericParamDefKind::Type { .. })
+    })
+}
+
 fn impl_node_id(crate_name: &str, id: rdt::Id) -> String {
     format!("{crate_name}::impl-{}", id.0)
 }
@@ -4144,9 +5562,11 @@ fn impl_node_name(
     path_index: &PathIndex,
     impl_block: &rdt::Impl,
 ) -> String {
-    let type_name = type_to_id(&impl_block.for_)
-        .and_then(|id| resolve_id(krate, default_crate_name, path_index, id))
-        .map(|path| last_segment(&path))
+    let type_name = impl_target_id(krate, default_crate_name, path_index, &impl_block.for_)
+        .map(|path| match &impl_block.for_ {
+            rdt::Type::DynTrait(_) => format!("dyn {}", last_segment(&path)),
+            _ => last_segment(&path),
+        })
         .unwrap_or_else(|| "type".to_string());
 
     if let Some(trait_path) = impl_block.trait_.as_ref() {
@@ -4275,6 +5695,197 @@ fn collect_type_ids(ty: &rdt::Type, type_ids: &mut HashSet<rdt::Id>) {
     }
 }
 
+/// The types an item is written in terms of: its fields, parameters,
+/// aliased type and so on, then a function's return type. Bounds and
+/// where clauses aren't included.
+fn item_type_positions<'a>(
+    index: &'a HashMap<rdt::Id, rdt::Item>,
+    inner: &'a rdt::ItemEnum,
+) -> (Vec<&'a rdt::Type>, Vec<&'a rdt::Type>) {
+    let mut types = Vec::new();
+    let mut return_types = Vec::new();
+    let mut field_ids: Vec<rdt::Id> = Vec::new();
+    match inner {
+        rdt::ItemEnum::Struct(item_struct) => match &item_struct.kind {
+            rdt::StructKind::Unit => {}
+            rdt::StructKind::Tuple(ids) => field_ids.extend(ids.iter().flatten()),
+            rdt::StructKind::Plain { fields, .. } => field_ids.extend(fields),
+        },
+        rdt::ItemEnum::Union(item_union) => field_ids.extend(&item_union.fields),
+        rdt::ItemEnum::Enum(item_enum) => {
+            for variant_id in &item_enum.variants {
+                let Some(rdt::ItemEnum::Variant(variant)) =
+                    index.get(variant_id).map(|item| &item.inner)
+                else {
+                    continue;
+                };
+                match &variant.kind {
+                    rdt::VariantKind::Plain => {}
+                    rdt::VariantKind::Tuple(ids) => field_ids.extend(ids.iter().flatten()),
+                    rdt::VariantKind::Struct { fields, .. } => field_ids.extend(fields),
+                }
+            }
+        }
+        rdt::ItemEnum::Function(function) => {
+            types.extend(function.sig.inputs.iter().map(|(_, ty)| ty));
+            return_types.extend(function.sig.output.as_ref());
+        }
+        rdt::ItemEnum::TypeAlias(alias) => types.push(&alias.type_),
+        rdt::ItemEnum::Impl(impl_block) => types.push(&impl_block.for_),
+        rdt::ItemEnum::Constant { type_, .. } => types.push(type_),
+        rdt::ItemEnum::Static(item_static) => types.push(&item_static.type_),
+        _ => {}
+    }
+    for field_id in field_ids {
+        if let Some(rdt::ItemEnum::StructField(ty)) = index.get(&field_id).map(|item| &item.inner) {
+            types.push(ty);
+        }
+    }
+    (types, return_types)
+}
+
+/// Where in a set of types each item they name appears. See [`type_uses`].
+#[derive(Default)]
+struct TypeUses {
+    /// Named outside any generic argument
+    direct: HashSet<rdt::Id>,
+    /// Named only inside generic arguments, like `Point` in `Vec<Point>`,
+    /// and not through `dyn`
+    generic: HashSet<rdt::Id>,
+    /// Traits named through `dyn Trait`, wherever they sit
+    dyn_traits: HashSet<rdt::Id>,
+}
+
+impl TypeUses {
+    fn name(&mut self, id: rdt::Id, in_args: bool) {
+        if in_args {
+            self.generic.insert(id);
+        } else {
+            self.direct.insert(id);
+        }
+    }
+
+    /// Drop the ids that get labelled edges from `type_ids`: `dyn` traits
+    /// and anything named only as a generic argument.
+    fn take_labelled(&self, type_ids: &mut HashSet<rdt::Id>) {
+        type_ids.retain(|id| !self.dyn_traits.contains(id) && !self.generic.contains(id));
+    }
+}
+
+/// Sort what `types` name by position, following the same paths as
+/// [`collect_type_ids`] but without bounds or generic parameters.
+fn type_uses(types: &[&rdt::Type]) -> TypeUses {
+    let mut uses = TypeUses::default();
+    for ty in types {
+        collect_type_uses(ty, false, &mut uses);
+    }
+    uses.generic
+        .retain(|id| !uses.direct.contains(id) && !uses.dyn_traits.contains(id));
+    uses
+}
+
+fn collect_type_uses(ty: &rdt::Type, in_args: bool, uses: &mut TypeUses) {
+    match ty {
+        rdt::Type::ResolvedPath(path) => {
+            uses.name(path.id, in_args);
+            if let Some(args) = path.args.as_deref() {
+                collect_generic_args_uses(args, uses);
+            }
+        }
+        rdt::Type::DynTrait(dyn_trait) => {
+            for poly in &dyn_trait.traits {
+                uses.dyn_traits.insert(poly.trait_.id);
+                if let Some(args) = poly.trait_.args.as_deref() {
+                    collect_generic_args_uses(args, uses);
+                }
+            }
+        }
+        rdt::Type::FunctionPointer(pointer) => {
+            for (_, input) in &pointer.sig.inputs {
+                collect_type_uses(input, in_args, uses);
+            }
+            if let Some(output) = pointer.sig.output.as_ref() {
+                collect_type_uses(output, in_args, uses);
+            }
+        }
+        rdt::Type::Tuple(items) => {
+            for item in items {
+                collect_type_uses(item, in_args, uses);
+            }
+        }
+        rdt::Type::Slice(inner) => collect_type_uses(inner, in_args, uses),
+        rdt::Type::Array { type_, .. }
+        | rdt::Type::Pat { type_, .. }
+        | rdt::Type::RawPointer { type_, .. }
+        | rdt::Type::BorrowedRef { type_, .. } => collect_type_uses(type_, in_args, uses),
+        rdt::Type::ImplTrait(bounds) => collect_bound_uses(bounds, in_args, uses),
+        rdt::Type::QualifiedPath {
+            self_type,
+            trait_,
+            args,
+            ..
+        } => {
+            collect_type_uses(self_type, in_args, uses);
+            if let Some(trait_path) = trait_ {
+                uses.name(trait_path.id, in_args);
+                if let Some(args) = trait_path.args.as_deref() {
+                    collect_generic_args_uses(args, uses);
+                }
+            }
+            if let Some(args) = args.as_deref() {
+                collect_generic_args_uses(args, uses);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_bound_uses(bounds: &[rdt::GenericBound], in_args: bool, uses: &mut TypeUses) {
+    for bound in bounds {
+        if let rdt::GenericBound::TraitBound { trait_, .. } = bound {
+            uses.name(trait_.id, in_args);
+            if let Some(args) = trait_.args.as_deref() {
+                collect_generic_args_uses(args, uses);
+            }
+        }
+    }
+}
+
+fn collect_generic_args_uses(args: &rdt::GenericArgs, uses: &mut TypeUses) {
+    match args {
+        rdt::GenericArgs::AngleBracketed { args, constraints } => {
+            for arg in args {
+                if let rdt::GenericArg::Type(ty) = arg {
+                    collect_type_uses(ty, true, uses);
+                }
+            }
+            for constraint in constraints {
+                if let Some(args) = constraint.args.as_deref() {
+                    collect_generic_args_uses(args, uses);
+                }
+                match &constraint.binding {
+                    rdt::AssocItemConstraintKind::Equality(rdt::Term::Type(ty)) => {
+                        collect_type_uses(ty, true, uses);
+                    }
+                    rdt::AssocItemConstraintKind::Equality(rdt::Term::Constant(_)) => {}
+                    rdt::AssocItemConstraintKind::Constraint(bounds) => {
+                        collect_bound_uses(bounds, true, uses);
+                    }
+                }
+            }
+        }
+        rdt::GenericArgs::Parenthesized { inputs, output } => {
+            for input in inputs {
+                collect_type_uses(input, true, uses);
+            }
+            if let Some(output) = output {
+                collect_type_uses(output, true, uses);
+            }
+        }
+        rdt::GenericArgs::ReturnTypeNotation => {}
+    }
+}
+
 fn collect_generic_args_ids(args: &rdt::GenericArgs, type_ids: &mut HashSet<rdt::Id>) {
     match args {
         rdt::GenericArgs::AngleBracketed { args, constraints } => {
@@ -4400,11 +6011,14 @@ fn collect_signature_ids(sig: &rdt::FunctionSignature, type_ids: &mut HashSet<rd
     }
 }
 
-fn add_uses_edges(
+#[allow(clippy::too_many_arguments)]
+fn add_type_edges(
     graph: &mut Graph,
     edge_cache: &mut EdgeIndex,
     owner_id: &str,
     type_ids: HashSet<rdt::Id>,
+    kind: EdgeKind,
+    label: Option<&str>,
     krate: &rdt::Crate,
     default_crate_name: &str,
     path_index: &PathIndex,
@@ -4414,18 +6028,143 @@ fn add_uses_edges(
             if target_id == owner_id {
                 continue;
             }
-            push_edge(
+            edge_cache.push_or_merge(
+                graph,
+                owner_id.to_string(),
+                target_id,
+                kind,
+                Confidence::Static,
+                false,
+                None,
+                label.map(str::to_string),
+            );
+        }
+    }
+}
+
+/// `HasField` edges labelled with the field, one per type each field names.
+#[allow(clippy::too_many_arguments)]
+fn add_field_edges(
+    graph: &mut Graph,
+    edge_cache: &mut EdgeIndex,
+    owner_id: &str,
+    fields: Vec<(String, HashSet<rdt::Id>)>,
+    krate: &rdt::Crate,
+    default_crate_name: &str,
+    path_index: &PathIndex,
+) {
+    for (label, type_ids) in fields {
+        for type_id in type_ids {
+            let Some(target_id) = resolve_id(krate, default_crate_name, path_index, type_id) else {
+                continue;
+            };
+            if target_id == owner_id {
+                continue;
+            }
+            push_labeled_edge(
                 graph,
                 edge_cache,
                 owner_id.to_string(),
                 target_id,
-                EdgeKind::UsesType,
+                EdgeKind::HasField,
                 Confidence::Static,
+                label.clone(),
+                None,
             );
         }
     }
 }
 
+/// The type ids named by each field of a struct, union or enum. Tuple fields
+/// are labelled by index like [`extract_struct_fields`]; enum fields are
+/// prefixed with their variant (`Move.x`, `Write.0`).
+fn field_type_ids(
+    index: &HashMap<rdt::Id, rdt::Item>,
+    inner: &rdt::ItemEnum,
+) -> Vec<(String, HashSet<rdt::Id>)> {
+    let mut fields = Vec::new();
+    match inner {
+        rdt::ItemEnum::Struct(item_struct) => match &item_struct.kind {
+            rdt::StructKind::Unit => {}
+            rdt::StructKind::Tuple(ids) => {
+                push_tuple_field_type_ids(index, ids, "", &mut fields);
+            }
+            rdt::StructKind::Plain { fields: ids, .. } => {
+                push_named_field_type_ids(index, ids, "", &mut fields);
+            }
+        },
+        rdt::ItemEnum::Union(item_union) => {
+            push_named_field_type_ids(index, &item_union.fields, "", &mut fields);
+        }
+        rdt::ItemEnum::Enum(item_enum) => {
+            for variant_id in &item_enum.variants {
+                let Some(variant_item) = index.get(variant_id) else {
+                    continue;
+                };
+                let rdt::ItemEnum::Variant(variant) = &variant_item.inner else {
+                    continue;
+                };
+                let prefix = format!("{}.", variant_item.name.as_deref().unwrap_or_default());
+                match &variant.kind {
+                    rdt::VariantKind::Plain => {}
+                    rdt::VariantKind::Tuple(ids) => {
+                        push_tuple_field_type_ids(index, ids, &prefix, &mut fields);
+                    }
+                    rdt::VariantKind::Struct { fields: ids, .. } => {
+                        push_named_field_type_ids(index, ids, &prefix, &mut fields);
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+    fields
+}
+
+fn push_tuple_field_type_ids(
+    index: &HashMap<rdt::Id, rdt::Item>,
+    ids: &[Option<rdt::Id>],
+    prefix: &str,
+    fields: &mut Vec<(String, HashSet<rdt::Id>)>,
+) {
+    for (position, id) in ids.iter().enumerate() {
+        if let Some(id) = id {
+            push_field_type_ids(index, *id, format!("{prefix}{position}"), fields);
+        }
+    }
+}
+
+fn push_named_field_type_ids(
+    index: &HashMap<rdt::Id, rdt::Item>,
+    ids: &[rdt::Id],
+    prefix: &str,
+    fields: &mut Vec<(String, HashSet<rdt::Id>)>,
+) {
+    for id in ids {
+        let name = index
+            .get(id)
+            .and_then(|item| item.name.as_deref())
+            .unwrap_or_default();
+        push_field_type_ids(index, *id, format!("{prefix}{name}"), fields);
+    }
+}
+
+fn push_field_type_ids(
+    index: &HashMap<rdt::Id, rdt::Item>,
+    id: rdt::Id,
+    label: String,
+    fields: &mut Vec<(String, HashSet<rdt::Id>)>,
+) {
+    let Some(rdt::ItemEnum::StructField(ty)) = index.get(&id).map(|item| &item.inner) else {
+        return;
+    };
+    let mut type_ids = HashSet::new();
+    collect_type_ids(ty, &mut type_ids);
+    if !type_ids.is_empty() {
+        fields.push((label, type_ids));
+    }
+}
+
 fn add_use_import_edges_with_parent_map(
     graph: &mut Graph,
     edge_cache: &mut EdgeIndex,
@@ -4433,7 +6172,11 @@ fn add_use_import_edges_with_parent_map(
     default_crate_name: &str,
     path_index: &PathIndex,
     item_to_parent: &HashMap<rdt::Id, rdt::Id>,
-) {
+) -> HashSet<(String, String)> {
+    // `(module, target)` pairs re-exported by a `pub use`; private `use`
+    // imports produce the same edges but don't widen visibility.
+    let mut public_reexports = HashSet::new();
+
     // Process use items and create edges from parent module to target
     for (item_id, item) in &krate.index {
         let rdt::ItemEnum::Use(use_item) = &item.inner else {
@@ -4458,38 +6201,203 @@ fn add_use_import_edges_with_parent_map(
         };
 
         let occurrence = item.span.as_ref().map(map_span);
+        let is_public = matches!(item.visibility, rdt::Visibility::Public);
         if use_item.is_glob {
-            for (_, exported_id) in glob_export_targets(krate, target_id) {
+            for (name, exported_id) in glob_export_targets(krate, target_id) {
                 let Some(target_node_id) =
                     resolve_id(krate, default_crate_name, path_index, exported_id)
                 else {
                     continue;
                 };
-                push_edge_with_glob_and_occurrence(
+                if is_public {
+                    public_reexports.insert((parent_node_id.clone(), target_node_id.clone()));
+                }
+                // Items the globbed module itself re-exports under another
+                // name come through the glob under that name.
+                let label = (last_segment(&target_node_id) != name).then_some(name);
+                edge_cache.push_or_merge(
                     graph,
-                    edge_cache,
                     parent_node_id.clone(),
                     target_node_id,
                     EdgeKind::ReExports,
                     Confidence::Static,
                     true,
                     occurrence.clone(),
+                    label,
                 );
             }
         } else if let Some(target_node_id) =
             resolve_id(krate, default_crate_name, path_index, target_id)
         {
-            push_edge_with_glob_and_occurrence(
-                graph,
-                edge_cache,
-                parent_node_id,
-                target_node_id,
-                EdgeKind::ReExports,
-                Confidence::Static,
-                false,
-                occurrence,
-            );
+            if is_public {
+                public_reexports.insert((parent_node_id.clone(), target_node_id.clone()));
+            }
+            // `use a::B as C` is labelled with the alias it introduces.
+            let source_name = use_item.source.rsplit("::").next();
+            if source_name != Some(use_item.name.as_str()) {
+                push_labeled_edge(
+                    graph,
+                    edge_cache,
+                    parent_node_id,
+                    target_node_id,
+                    EdgeKind::ReExports,
+                    Confidence::Static,
+                    use_item.name.clone(),
+                    occurrence,
+                );
+            } else {
+                push_edge_with_glob_and_occurrence(
+                    graph,
+                    edge_cache,
+                    parent_node_id,
+                    target_node_id,
+                    EdgeKind::ReExports,
+                    Confidence::Static,
+                    false,
+                    occurrence,
+                );
+            }
+        }
+    }
+
+    public_reexports
+}
+
+/// Fill in `effective_visibility` and `is_public_via_reexport` for the
+/// crate's own nodes by walking outwards from the crate root.
+fn assign_effective_visibility(
+    graph: &mut Graph,
+    crate_name: &str,
+    public_reexports: &HashSet<(String, String)>,
+) {
+    let canonical = publicly_reachable(graph, crate_name, None);
+    let reachable = publicly_reachable(graph, crate_name, Some(public_reexports));
+    let local_prefix = format!("{crate_name}::");
+
+    for node in &mut graph.nodes {
+        if node.is_external || (node.id != crate_name && !node.id.starts_with(&local_prefix)) {
+            continue;
+        }
+        let effective = match &node.visibility {
+            Visibility::Public if reachable.contains(&node.id) => Visibility::Public,
+            Visibility::Public => Visibility::Crate,
+            declared => declared.clone(),
+        };
+        node.is_public_via_reexport =
+            effective == Visibility::Public && !canonical.contains(&node.id);
+        node.effective_visibility = Some(effective);
+    }
+}
+
+/// Node ids reachable from `root` by following `Contains` into public
+/// items, `Defines` into public or inherited members (trait items, impl
+/// blocks, variants), and — when `reexports` is given — `pub use` edges.
+fn publicly_reachable(
+    graph: &Graph,
+    root: &str,
+    reexports: Option<&HashSet<(String, String)>>,
+) -> HashSet<String> {
+    let visibility: HashMap<&str, &Visibility> = graph
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), &node.visibility))
+        .collect();
+    let mut children: HashMap<&str, Vec<&Edge>> = HashMap::new();
+    for edge in &graph.edges {
+        children.entry(edge.from.as_str()).or_default().push(edge);
+    }
+
+    let mut reachable = HashSet::from([root.to_string()]);
+    let mut queue = vec![root];
+    while let Some(id) = queue.pop() {
+        for edge in children.get(id).into_iter().flatten() {
+            let Some(child_visibility) = visibility.get(edge.to.as_str()) else {
+                continue;
+            };
+            let follow = match edge.kind {
+                EdgeKind::Contains => **child_visibility == Visibility::Public,
+                EdgeKind::Defines => {
+                    matches!(child_visibility, Visibility::Public | Visibility::Inherited)
+                }
+                EdgeKind::ReExports => {
+                    reexports.is_some_and(|set| set.contains(&(edge.from.clone(), edge.to.clone())))
+                }
+                _ => false,
+            };
+            if follow && reachable.insert(edge.to.clone()) {
+                queue.push(edge.to.as_str());
+            }
+        }
+    }
+    reachable
+}
+
+/// Add an `Inferred` `Contains` edge from each module to the crate's own
+/// public items it `pub use`s, labelled and located like the `ReExports`
+/// edge. Items already contained by the module are skipped, and so is any
+/// edge that would close a `Contains` cycle, as `pub use super::*` or a
+/// module re-exporting one of its ancestors would.
+fn flatten_public_reexports(graph: &mut Graph, public_reexports: &HashSet<(String, String)>) {
+    let local_public: HashSet<&str> = graph
+        .nodes
+        .iter()
+        .filter(|node| !node.is_external && node.visibility == Visibility::Public)
+        .map(|node| node.id.as_str())
+        .collect();
+    let mut reexports: Vec<Edge> = graph
+        .edges
+        .iter()
+        .filter(|edge| {
+            edge.kind == EdgeKind::ReExports
+                && local_public.contains(edge.to.as_str())
+                && public_reexports.contains(&(edge.from.clone(), edge.to.clone()))
+        })
+        .cloned()
+        .collect();
+    reexports.sort_by(|a, b| (&a.from, &a.to, &a.label).cmp(&(&b.from, &b.to, &b.label)));
+
+    let mut children: HashMap<String, Vec<String>> = HashMap::new();
+    for edge in &graph.edges {
+        if edge.kind == EdgeKind::Contains && edge.from != edge.to {
+            children
+                .entry(edge.from.clone())
+                .or_default()
+                .push(edge.to.clone());
+        }
+    }
+    let reaches = |children: &HashMap<String, Vec<String>>, from: &str, to: &str| {
+        let mut seen = HashSet::from([from]);
+        let mut stack = vec![from];
+        while let Some(id) = stack.pop() {
+            if id == to {
+                return true;
+            }
+            for child in children.get(id).into_iter().flatten() {
+                if seen.insert(child.as_str()) {
+                    stack.push(child.as_str());
+                }
+            }
         }
+        false
+    };
+
+    for reexport in reexports {
+        let (module, target) = (&reexport.from, &reexport.to);
+        let already_contained = children
+            .get(module)
+            .is_some_and(|kids| kids.contains(target));
+        if already_contained || reaches(&children, target, module) {
+            continue;
+        }
+        children
+            .entry(module.clone())
+            .or_default()
+            .push(target.clone());
+        graph.edges.push(Edge {
+            kind: EdgeKind::Contains,
+            confidence: Confidence::Inferred,
+            ..reexport
+        });
     }
 }
 
@@ -4504,26 +6412,30 @@ fn add_derives_edges(
         if trait_name.contains("::") {
             if let Some(paths) = trait_lookup.get(&trait_name) {
                 for path in paths {
-                    push_edge(
+                    push_labeled_edge(
                         graph,
                         edge_cache,
                         owner_id.to_string(),
                         path.clone(),
                         EdgeKind::Derives,
                         Confidence::Inferred,
+                        trait_name.clone(),
+                        None,
                     );
                 }
             }
         } else if let Some(paths) = trait_lookup.get(&trait_name)
             && paths.len() == 1
         {
-            push_edge(
+            push_labeled_edge(
                 graph,
                 edge_cache,
                 owner_id.to_string(),
                 paths[0].clone(),
                 EdgeKind::Derives,
                 Confidence::Inferred,
+                trait_name,
+                None,
             );
         }
     }
@@ -4551,6 +6463,51 @@ fn parse_derive_traits(attrs: &[rdt::Attribute]) -> Vec<String> {
     traits
 }
 
+fn is_doc_hidden(attrs: &[rdt::Attribute]) -> bool {
+    attrs.iter().any(|attr| match attr {
+        rdt::Attribute::Other(text) => {
+            let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+            compact.starts_with("#[doc(") && compact.contains("hidden")
+        }
+        _ => false,
+    })
+}
+
+/// Which rustdoc paths are `#[doc(hidden)]`, directly or through a parent
+/// module. rustdoc strips hidden items from its index unless run with
+/// `--document-hidden-items`, but still lists their paths; for a segment
+/// whose item isn't in the index, a `__` prefix stands in for the
+/// attribute.
+struct HiddenPaths<'a> {
+    indexed: HashMap<&'a [String], bool>,
+    include_hidden: bool,
+}
+
+impl<'a> HiddenPaths<'a> {
+    fn new(krate: &'a rdt::Crate, include_hidden: bool) -> Self {
+        let indexed = krate
+            .paths
+            .iter()
+            .filter_map(|(id, summary)| {
+                let item = krate.index.get(id)?;
+                Some((summary.path.as_slice(), is_doc_hidden(&item.attrs)))
+            })
+            .collect();
+        Self {
+            indexed,
+            include_hidden,
+        }
+    }
+
+    fn contains(&self, path: &[String]) -> bool {
+        !self.include_hidden
+            && (1..=path.len()).any(|len| match self.indexed.get(&path[..len]) {
+                Some(&hidden) => hidden,
+                None => path[len - 1].starts_with("__"),
+            })
+    }
+}
+
 fn format_attributes(attrs: &[rdt::Attribute]) -> Vec<String> {
     attrs.iter().filter_map(attribute_to_string).collect()
 }
@@ -4580,6 +6537,177 @@ fn attribute_to_string(attr: &rdt::Attribute) -> Option<String> {
     }
 }
 
+/// The `#[cfg(...)]` predicate gating an item, in source syntax. Rustdoc
+/// drops the attribute from `attrs` once it's resolved, leaving either a
+/// source-shaped `#[cfg(...)]` (older formats) or a `CfgTrace` Debug dump.
+fn extract_cfg(attrs: &[rdt::Attribute]) -> Option<String> {
+    let predicates = attrs
+        .iter()
+        .filter_map(|attr| match attr {
+            rdt::Attribute::Other(value) => Some(cfg_predicates(value)),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    join_cfg_predicates(predicates)
+}
+
+/// One predicate as-is; several are `all(...)`, which is how rustc
+/// combines stacked `#[cfg]` attributes.
+fn join_cfg_predicates(mut predicates: Vec<String>) -> Option<String> {
+    match predicates.len() {
+        0 => None,
+        1 => predicates.pop(),
+        _ => Some(format!("all({})", predicates.join(", "))),
+    }
+}
+
+fn cfg_predicates(value: &str) -> Vec<String> {
+    let cleaned = clean_trace_attrs(value);
+    let one_line = cleaned.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+    if let Some(inner) = one_line
+        .strip_prefix("#[cfg(")
+        .and_then(|rest| rest.strip_suffix(")]"))
+    {
+        return vec![inner.trim().to_string()];
+    }
+    // `#[attr = CfgTrace([NameValue { name: "feature", value: Some("x"), span: … }])]`
+    let Some(list) = one_line
+        .strip_prefix("#[attr = CfgTrace([")
+        .and_then(|rest| rest.strip_suffix(")]"))
+    else {
+        return Vec::new();
+    };
+    match parse_cfg_debug_entries(list) {
+        Some((entries, rest)) if rest.trim().is_empty() => entries,
+        _ => Vec::new(),
+    }
+}
+
+/// Parses comma-separated `CfgEntry` Debug output up to and including the
+/// closing `]`, returning what follows it.
+fn parse_cfg_debug_entries(mut input: &str) -> Option<(Vec<String>, &str)> {
+    let mut entries = Vec::new();
+    loop {
+        input = input.trim_start();
+        if let Some(rest) = input.strip_prefix(']') {
+            return Some((entries, rest));
+        }
+        let (entry, rest) = parse_cfg_debug_entry(input)?;
+        entries.push(entry);
+        input = rest.trim_start();
+        input = input.strip_prefix(',').unwrap_or(input);
+    }
+}
+
+fn parse_cfg_debug_entry(input: &str) -> Option<(String, &str)> {
+    if let Some(rest) = input.strip_prefix("NameValue {") {
+        let rest = rest.trim_start().strip_prefix("name:")?;
+        let (name, rest) = parse_debug_str(rest.trim_start())?;
+        let rest = rest.trim_start().strip_prefix(',')?;
+        let rest = rest.trim_start().strip_prefix("value:")?.trim_start();
+        let (entry, rest) = match rest.strip_prefix("Some(") {
+            Some(rest) => {
+                let (value, rest) = parse_debug_str(rest.trim_start())?;
+                (
+                    format!("{name} = {value:?}"),
+                    rest.trim_start().strip_prefix(')')?,
+                )
+            }
+            None => (name, rest.strip_prefix("None")?),
+        };
+        return Some((entry, skip_debug_struct(rest)?));
+    }
+    if let Some(rest) = input.strip_prefix("Not(") {
+        let (inner, rest) = parse_cfg_debug_entry(rest.trim_start())?;
+        return Some((format!("not({inner})"), skip_debug_span(rest)?));
+    }
+    for (prefix, op) in [("All([", "all"), ("Any([", "any")] {
+        if let Some(rest) = input.strip_prefix(prefix) {
+            let (entries, rest) = parse_cfg_debug_entries(rest)?;
+            let entry = format!("{op}({})", entries.join(", "));
+            return Some((entry, skip_debug_span(rest)?));
+        }
+    }
+    if let Some(rest) = input.strip_prefix("Bool(") {
+        let (value, rest) = rest.split_once(',')?;
+        return Some((value.trim().to_string(), skip_debug_span(rest)?));
+    }
+    None
+}
+
+/// Reads one Debug-formatted string literal off the front of `input`,
+/// undoing its escapes, and returns it with whatever follows.
+fn parse_debug_str(input: &str) -> Option<(String, &str)> {
+    let mut chars = input.strip_prefix('"')?.char_indices();
+    let mut value = String::new();
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            '"' => return Some((value, &input[index + 2..])),
+            '\\' => value.push(match chars.next()?.1 {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                '0' => '\0',
+                'u' => {
+                    let (_, open) = chars.next()?;
+                    if open != '{' {
+                        return None;
+                    }
+                    let mut hex = String::new();
+                    for (_, digit) in chars.by_ref() {
+                        if digit == '}' {
+                            break;
+                        }
+                        hex.push(digit);
+                    }
+                    char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+                }
+                escaped => escaped,
+            }),
+            ch => value.push(ch),
+        }
+    }
+    None
+}
+
+/// Skips the rest of a struct variant's fields, returning what follows its
+/// closing brace. Quoted strings are skipped whole, so braces in them don't
+/// count.
+fn skip_debug_struct(mut input: &str) -> Option<&str> {
+    let mut depth = 0usize;
+    loop {
+        let index = input.find(['{', '}', '"'])?;
+        match &input[index..index + 1] {
+            "\"" => input = parse_debug_str(&input[index..])?.1,
+            "{" => {
+                depth += 1;
+                input = &input[index + 1..];
+            }
+            _ if depth == 0 => return Some(&input[index + 1..]),
+            _ => {
+                depth -= 1;
+                input = &input[index + 1..];
+            }
+        }
+    }
+}
+
+/// Skips the trailing `, span)` of a tuple variant, returning what follows
+/// its closing paren. Spans print as `file:1:2: 1:5 (#0)`.
+fn skip_debug_span(input: &str) -> Option<&str> {
+    let mut depth = 0usize;
+    for (index, ch) in input.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(&input[index + 1..]),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
 /// Clean up compiler-internal trace attributes left by `#[cfg]`/`#[cfg_attr]` expansion.
 /// Rustdoc emits `<cfg_attr_trace>` and `<cfg_trace>` as internal markers; convert them
 /// back to the user-facing `cfg_attr` / `cfg` names.
@@ -4659,18 +6787,18 @@ fn clean_other_attr(value: &str) -> Option<String> {
     }
 
     // ── Stability { stability: Stability { level: ..., feature: "..." } } ──
-    if payload.starts_with("Stability ") {
-        if let Some(rendered) = render_stability(payload) {
-            return Some(rendered);
-        }
+    if payload.starts_with("Stability ")
+        && let Some(rendered) = render_stability(payload)
+    {
+        return Some(rendered);
     }
 
     // ── ConstStability / BodyStability / DefaultBodyStability — same shape ──
     for prefix in ["ConstStability ", "DefaultBodyStability ", "BodyStability "] {
-        if payload.starts_with(prefix) {
-            if let Some(rendered) = render_stability(payload) {
-                return Some(rendered);
-            }
+        if payload.starts_with(prefix)
+            && let Some(rendered) = render_stability(payload)
+        {
+            return Some(rendered);
         }
     }
 
@@ -4801,21 +6929,18 @@ fn build_function_index(
     krate: &rdt::Crate,
     method_ids: &HashSet<rdt::Id>,
     default_crate_name: &str,
+    hidden_paths: &HiddenPaths,
 ) -> FunctionIndex {
     let mut index = FunctionIndex::new();
     for (item_id, summary) in &krate.paths {
-        if summary.kind != rdt::ItemKind::Function {
+        if !matches!(summary.kind, rdt::ItemKind::Function | rdt::ItemKind::Macro) {
             continue;
         }
         if summary.path.is_empty() {
             continue;
         }
         // Skip internal/generated paths
-        if summary
-            .path
-            .iter()
-            .any(|seg| seg == "_" || seg.starts_with("__"))
-        {
+        if summary.path.iter().any(|seg| seg == "_") || hidden_paths.contains(&summary.path) {
             continue;
         }
         let crate_name = crate_name_for_id(krate, summary.crate_id, default_crate_name);
@@ -4825,6 +6950,14 @@ fn build_function_index(
             .last()
             .cloned()
             .unwrap_or_else(|| full_path.clone());
+        // `Macro` covers `macro_rules!` and function-like `#[proc_macro]`
+        // items alike, so both resolve as `CallsMacro` targets. Attribute
+        // and derive proc macros are `ProcAttribute`/`ProcDerive`; they're
+        // applied as attributes rather than invoked, and get no edge.
+        if summary.kind == rdt::ItemKind::Macro {
+            index.add_macro(full_path, name);
+            continue;
+        }
         index.add_callable(full_path.clone(), name.clone());
         if method_ids.contains(item_id) {
             index.add_method(full_path, name);
@@ -4836,7 +6969,9 @@ fn build_function_index(
 /// Abstraction for reading source files — allows both filesystem and in-memory access.
 pub trait SourceProvider {
     fn read_file(&self, path: &Path) -> Result<String, RustdocError>;
-    fn file_exists(&self, path: &Path) -> bool;
+    /// Whether `path` exists. Errors are for failed lookups, not missing
+    /// files, so a flaky source doesn't silently drop modules.
+    fn file_exists(&self, path: &Path) -> Result<bool, RustdocError>;
 }
 
 /// Reads source files from the local filesystem.
@@ -4848,8 +6983,8 @@ impl SourceProvider for FsSourceProvider {
     fn read_file(&self, path: &Path) -> Result<String, RustdocError> {
         Ok(fs::read_to_string(path)?)
     }
-    fn file_exists(&self, path: &Path) -> bool {
-        path.exists()
+    fn file_exists(&self, path: &Path) -> Result<bool, RustdocError> {
+        Ok(path.try_exists()?)
     }
 }
 
@@ -4887,14 +7022,15 @@ impl SourceProvider for MemorySourceProvider {
             ))
         })
     }
-    fn file_exists(&self, path: &Path) -> bool {
+    fn file_exists(&self, path: &Path) -> Result<bool, RustdocError> {
         let key = normalize_memory_path(path);
-        self.files.contains_key(&key)
+        Ok(self.files.contains_key(&key))
     }
 }
 
-/// Add call edges from source files using any SourceProvider.
-#[allow(private_interfaces)]
+/// Add call edges from source files using any SourceProvider. Returns the
+/// calls that resolved to nothing when `report_unresolved` is set.
+#[allow(private_interfaces, clippy::too_many_arguments)]
 fn add_call_edges(
     graph: &mut Graph,
     edge_cache: &mut EdgeIndex,
@@ -4902,43 +7038,223 @@ fn add_call_edges(
     function_index: &FunctionIndex,
     call_mode: CallMode,
     source_provider: &dyn SourceProvider,
-) -> Result<(), RustdocError> {
+    cancel: Option<&AtomicBool>,
+    max_module_depth: usize,
+    entry_point_attributes: &[String],
+    report_unresolved: bool,
+    complexity: bool,
+) -> Result<Vec<UnresolvedCall>, RustdocError> {
     let mut parser = SourceParser::new(
         function_index,
         graph,
         edge_cache,
         call_mode,
         source_provider,
+        cancel,
+        max_module_depth,
+        entry_point_attributes,
     );
-    parser.parse_module_file(root_file, Vec::new())?;
-    Ok(())
+    parser.unresolved_calls = report_unresolved.then(Vec::new);
+    parser.complexity = complexity;
+    stacker::maybe_grow(RED_ZONE, PARSE_STACK_SIZE, || {
+        parser.parse_module_file(root_file, Vec::new())
+    })?;
+    Ok(parser.unresolved_calls.unwrap_or_default())
+}
+
+/// Add `Inferred` call edges from each documented item to the functions its
+/// doc-test examples call. Examples use the crate from outside, so a leading
+/// crate name in a path resolves like `crate::`.
+#[allow(private_interfaces)]
+fn add_doctest_call_edges(
+    graph: &mut Graph,
+    edge_cache: &mut EdgeIndex,
+    function_index: &FunctionIndex,
+    call_mode: CallMode,
+    max_module_depth: usize,
+    crate_names: &[&str],
+) {
+    let documented: Vec<(String, Vec<String>)> = graph
+        .nodes
+        .iter()
+        .filter(|node| !node.is_external)
+        .filter_map(|node| {
+            let blocks = doctest_code_blocks(node.docs.as_deref()?);
+            (!blocks.is_empty()).then(|| (node.id.clone(), blocks))
+        })
+        .collect();
+    if documented.is_empty() {
+        return;
+    }
+    let no_sources = MemorySourceProvider::new(HashMap::new());
+    let mut parser = SourceParser::new(
+        function_index,
+        graph,
+        edge_cache,
+        call_mode,
+        &no_sources,
+        None,
+        max_module_depth,
+        &[],
+    );
+    stacker::maybe_grow(RED_ZONE, PARSE_STACK_SIZE, || {
+        for (caller_id, blocks) in documented {
+            for code in blocks {
+                parser.handle_doctest(&caller_id, &code, crate_names);
+            }
+        }
+    });
+}
+
+/// Rust code blocks in `docs`, with rustdoc's hidden `# ` lines put back.
+/// Blocks rustdoc wouldn't compile (`ignore`, `compile_fail`, or another
+/// language such as `text`) are skipped; `no_run` ones still count.
+fn doctest_code_blocks(docs: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut lines = docs.lines();
+    while let Some(line) = lines.next() {
+        let Some((fence, info)) = code_fence(line) else {
+            continue;
+        };
+        let mut code = String::new();
+        for line in lines.by_ref() {
+            if code_fence(line)
+                .is_some_and(|(close, rest)| close.starts_with(fence) && rest.is_empty())
+            {
+                break;
+            }
+            code.push_str(unhide_doctest_line(line));
+            code.push('\n');
+        }
+        if is_rust_code_block(info) {
+            blocks.push(code);
+        }
+    }
+    blocks
+}
+
+/// Split a fence line into the fence itself and its info string.
+fn code_fence(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start();
+    let marker = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = trimmed.len() - trimmed.trim_start_matches(marker).len();
+    (len >= 3).then(|| (&trimmed[..len], trimmed[len..].trim()))
+}
+
+fn is_rust_code_block(info: &str) -> bool {
+    info.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .all(|token| {
+            matches!(
+                token,
+                "rust" | "no_run" | "should_panic" | "test_harness" | "standalone_crate"
+            ) || token.starts_with("edition")
+        })
+}
+
+/// rustdoc hides `# ` lines from the rendered example but still compiles
+/// them; `##` escapes a literal leading `#`.
+fn unhide_doctest_line(line: &str) -> &str {
+    let trimmed = line.trim_start();
+    if trimmed == "#" {
+        ""
+    } else if let Some(rest) = trimmed.strip_prefix("# ") {
+        rest
+    } else if trimmed.starts_with("##") {
+        &trimmed[1..]
+    } else {
+        line
+    }
 }
 
 pub(crate) struct FunctionIndex {
-    callables: Vec<String>,
+    callables: HashSet<String>,
     callables_by_name: HashMap<String, Vec<String>>,
-    methods: Vec<String>,
+    methods: HashSet<String>,
     methods_by_name: HashMap<String, Vec<String>>,
+    macros: HashSet<String>,
+    macros_by_name: HashMap<String, Vec<String>>,
+    /// Other workspace members' functions. Callee lookups that find nothing
+    /// here try these; callers are always resolved within the crate.
+    workspace: Option<Arc<FunctionIndex>>,
 }
 
 impl FunctionIndex {
     fn new() -> Self {
         Self {
-            callables: Vec::new(),
+            callables: HashSet::new(),
             callables_by_name: HashMap::new(),
-            methods: Vec::new(),
+            methods: HashSet::new(),
             methods_by_name: HashMap::new(),
+            macros: HashSet::new(),
+            macros_by_name: HashMap::new(),
+            workspace: None,
+        }
+    }
+
+    /// Add `other`'s functions, methods and macros that belong to
+    /// `crate_name`.
+    fn extend_from_crate(&mut self, other: &FunctionIndex, crate_name: &str) {
+        let prefix = format!("{crate_name}::");
+        let own = |path: &&String| path.starts_with(&prefix);
+        for path in other.callables.iter().filter(own) {
+            self.add_callable(path.clone(), last_segment(path));
+        }
+        for path in other.methods.iter().filter(own) {
+            self.add_method(path.clone(), last_segment(path));
+        }
+        for path in other.macros.iter().filter(own) {
+            self.add_macro(path.clone(), last_segment(path));
+        }
+    }
+
+    /// Whether `id` is a function or macro of another workspace member.
+    fn is_workspace_callable(&self, id: &str) -> bool {
+        self.workspace.as_ref().is_some_and(|workspace| {
+            workspace.callables.contains(id) || workspace.macros.contains(id)
+        })
+    }
+
+    /// `found`, or when that's empty, what `lookup` finds among the other
+    /// workspace members. A bare name the crate doesn't define says nothing
+    /// about which member it comes from, so with `unique` it's only linked
+    /// across crates when exactly one member function has it, whatever the
+    /// [`CallMode`].
+    fn or_workspace(
+        &self,
+        found: Vec<String>,
+        unique: bool,
+        lookup: impl FnOnce(&FunctionIndex) -> Vec<String>,
+    ) -> Vec<String> {
+        match &self.workspace {
+            Some(workspace) if found.is_empty() => {
+                let matches = lookup(workspace);
+                if unique && matches.len() > 1 {
+                    Vec::new()
+                } else {
+                    matches
+                }
+            }
+            _ => found,
         }
     }
 
     fn add_callable(&mut self, path: String, name: String) {
-        self.callables.push(path.clone());
-        self.callables_by_name.entry(name).or_default().push(path);
+        if self.callables.insert(path.clone()) {
+            self.callables_by_name.entry(name).or_default().push(path);
+        }
     }
 
     fn add_method(&mut self, path: String, name: String) {
-        self.methods.push(path.clone());
-        self.methods_by_name.entry(name).or_default().push(path);
+        if self.methods.insert(path.clone()) {
+            self.methods_by_name.entry(name).or_default().push(path);
+        }
+    }
+
+    fn add_macro(&mut self, path: String, name: String) {
+        if self.macros.insert(path.clone()) {
+            self.macros_by_name.entry(name).or_default().push(path);
+        }
     }
 
     fn resolve_callable_by_suffix(&self, segments: &[String]) -> Option<String> {
@@ -4946,7 +7262,11 @@ impl FunctionIndex {
     }
 
     fn resolve_callable_by_suffix_all(&self, segments: &[String]) -> Vec<String> {
-        resolve_all_by_suffix(&self.callables, segments)
+        self.or_workspace(
+            resolve_all_by_suffix(&self.callables, segments),
+            segments.len() == 1,
+            |workspace| workspace.resolve_callable_by_suffix_all(segments),
+        )
     }
 
     fn resolve_method_by_suffix(&self, segments: &[String]) -> Option<String> {
@@ -4954,7 +7274,11 @@ impl FunctionIndex {
     }
 
     fn resolve_method_by_suffix_all(&self, segments: &[String]) -> Vec<String> {
-        resolve_all_by_suffix(&self.methods, segments)
+        self.or_workspace(
+            resolve_all_by_suffix(&self.methods, segments),
+            segments.len() == 1,
+            |workspace| workspace.resolve_method_by_suffix_all(segments),
+        )
     }
 
     fn resolve_callable_by_name_unique(&self, name: &str) -> Option<String> {
@@ -4962,7 +7286,11 @@ impl FunctionIndex {
     }
 
     fn resolve_callable_by_name_all(&self, name: &str) -> Vec<String> {
-        resolve_by_name(&self.callables_by_name, name)
+        self.or_workspace(
+            resolve_by_name(&self.callables_by_name, name),
+            true,
+            |workspace| workspace.resolve_callable_by_name_all(name),
+        )
     }
 
     fn resolve_method_by_name_unique(&self, name: &str) -> Option<String> {
@@ -4970,11 +7298,31 @@ impl FunctionIndex {
     }
 
     fn resolve_method_by_name_all(&self, name: &str) -> Vec<String> {
-        resolve_by_name(&self.methods_by_name, name)
+        self.or_workspace(
+            resolve_by_name(&self.methods_by_name, name),
+            true,
+            |workspace| workspace.resolve_method_by_name_all(name),
+        )
+    }
+
+    fn resolve_macro_by_suffix_all(&self, segments: &[String]) -> Vec<String> {
+        self.or_workspace(
+            resolve_all_by_suffix(&self.macros, segments),
+            segments.len() == 1,
+            |workspace| workspace.resolve_macro_by_suffix_all(segments),
+        )
+    }
+
+    fn resolve_macro_by_name_all(&self, name: &str) -> Vec<String> {
+        self.or_workspace(
+            resolve_by_name(&self.macros_by_name, name),
+            true,
+            |workspace| workspace.resolve_macro_by_name_all(name),
+        )
     }
 }
 
-fn resolve_by_suffix(paths: &[String], segments: &[String]) -> Option<String> {
+fn resolve_by_suffix(paths: &HashSet<String>, segments: &[String]) -> Option<String> {
     let mut matches = resolve_all_by_suffix(paths, segments);
     if matches.len() == 1 {
         matches.pop()
@@ -4983,14 +7331,18 @@ fn resolve_by_suffix(paths: &[String], segments: &[String]) -> Option<String> {
     }
 }
 
-fn resolve_all_by_suffix(paths: &[String], segments: &[String]) -> Vec<String> {
+fn resolve_all_by_suffix<'a>(
+    paths: impl IntoIterator<Item = &'a String>,
+    segments: &[String],
+) -> Vec<String> {
     if segments.is_empty() {
         return Vec::new();
     }
     let suffix = format!("::{}", segments.join("::"));
+    // A full path matches too, for calls into another crate like `b::run()`.
     paths
-        .iter()
-        .filter(|path| path.ends_with(&suffix))
+        .into_iter()
+        .filter(|path| path.ends_with(&suffix) || **path == suffix[2..])
         .cloned()
         .collect()
 }
@@ -5015,37 +7367,269 @@ struct SourceParser<'a> {
     call_mode: CallMode,
     visited_files: HashSet<PathBuf>,
     source_provider: &'a dyn SourceProvider,
+    cancel: Option<&'a AtomicBool>,
+    max_module_depth: usize,
+    depth_limit_warned: bool,
+    entry_point_attributes: &'a [String],
+    /// Position of each node in `graph.nodes`. Source parsing never adds or
+    /// removes nodes, so this stays valid for the parser's lifetime.
+    node_positions: HashMap<String, usize>,
+    /// Calls that produced no edge, when the caller asked for them.
+    unresolved_calls: Option<Vec<UnresolvedCall>>,
+    /// Record `Node::complexity` for each function body parsed.
+    complexity: bool,
+    /// `mod name;` lookups by declaring directory and name, so `cfg`'d
+    /// duplicates don't probe the provider again. Each probe may be a
+    /// network round-trip.
+    module_files: HashMap<(PathBuf, String), Option<PathBuf>>,
+    /// Trait method implementations, built only in [`CallMode::Heuristic`].
+    dyn_dispatch: Option<DynDispatchIndex>,
+    /// Module-level `use` imports, by module path.
+    imports: HashMap<Vec<String>, ModuleImports>,
+}
+
+/// The names a module's `use` items bring into scope, each mapped to the
+/// path it stands for: `use crate::foo::bar as baz;` maps `baz` to
+/// `crate::foo::bar`. `use` items inside function bodies aren't collected.
+#[derive(Default)]
+struct ModuleImports {
+    aliases: HashMap<String, Vec<String>>,
+    /// Modules glob-imported with `use path::*;`.
+    globs: Vec<Vec<String>>,
 }
 
-impl<'a> SourceParser<'a> {
-    fn new(
-        function_index: &'a FunctionIndex,
-        graph: &'a mut Graph,
-        edge_cache: &'a mut EdgeIndex,
-        call_mode: CallMode,
-        source_provider: &'a dyn SourceProvider,
-    ) -> Self {
-        Self {
-            function_index,
-            graph,
-            edge_cache,
-            call_mode,
-            visited_files: HashSet::new(),
-            source_provider,
+impl ModuleImports {
+    fn from_items(items: &[syn::Item]) -> Self {
+        let mut imports = Self::default();
+        for item in items {
+            if let syn::Item::Use(item_use) = item {
+                imports.collect(&item_use.tree, &mut Vec::new());
+            }
         }
+        imports
     }
 
-    fn parse_module_file(
-        &mut self,
-        path: &Path,
-        module_path: Vec<String>,
-    ) -> Result<(), RustdocError> {
-        let path = path.to_path_buf();
-        if !self.visited_files.insert(path.clone()) {
-            return Ok(());
-        }
-        let content = self.source_provider.read_file(&path)?;
-        let file = syn::parse_file(&content)?;
+    fn collect(&mut self, tree: &syn::UseTree, prefix: &mut Vec<String>) {
+        match tree {
+            syn::UseTree::Path(path) => {
+                prefix.push(path.ident.to_string());
+                self.collect(&path.tree, prefix);
+                prefix.pop();
+            }
+            syn::UseTree::Name(name) => self.insert(&name.ident, &name.ident, prefix),
+            syn::UseTree::Rename(rename) => self.insert(&rename.rename, &rename.ident, prefix),
+            syn::UseTree::Group(group) => {
+                for tree in &group.items {
+                    self.collect(tree, prefix);
+                }
+            }
+            syn::UseTree::Glob(_) => {
+                if !prefix.is_empty() && !self.globs.contains(prefix) {
+                    self.globs.push(prefix.clone());
+                }
+            }
+        }
+    }
+
+    /// Record `name` as standing for `prefix::ident`, where `self` names
+    /// the prefix itself. `as _` imports a trait without naming it.
+    fn insert(&mut self, name: &syn::Ident, ident: &syn::Ident, prefix: &[String]) {
+        if name == "_" {
+            return;
+        }
+        let mut target = prefix.to_vec();
+        if ident != "self" {
+            target.push(ident.to_string());
+        }
+        let name = if name == "self" {
+            match prefix.last() {
+                Some(last) => last.clone(),
+                None => return,
+            }
+        } else {
+            name.to_string()
+        };
+        self.aliases.entry(name).or_insert(target);
+    }
+}
+
+/// Where a method called through `dyn Trait` can end up: for each trait and
+/// method name, the method in every impl of the trait in the graph, or the
+/// trait's default body for impls that don't override it.
+struct DynDispatchIndex {
+    traits: Vec<String>,
+    targets: HashMap<(String, String), Vec<String>>,
+}
+
+impl DynDispatchIndex {
+    fn new(graph: &Graph) -> Self {
+        let traits = graph
+            .nodes
+            .iter()
+            .filter(|node| node.kind == NodeKind::Trait)
+            .map(|node| node.id.clone())
+            .collect();
+        let impl_traits: HashMap<&str, &str> = graph
+            .nodes
+            .iter()
+            .filter_map(|node| Some((node.id.as_str(), node.impl_trait.as_deref()?)))
+            .collect();
+        let function_ids: HashSet<&str> = graph
+            .nodes
+            .iter()
+            .filter(|node| node.kind == NodeKind::Function)
+            .map(|node| node.id.as_str())
+            .collect();
+
+        let mut targets: HashMap<(String, String), Vec<String>> = HashMap::new();
+        for node in &graph.nodes {
+            if node.kind == NodeKind::Function
+                && let Some(trait_id) = node
+                    .parent_impl
+                    .as_deref()
+                    .and_then(|impl_id| impl_traits.get(impl_id))
+            {
+                targets
+                    .entry((trait_id.to_string(), node.name.clone()))
+                    .or_default()
+                    .push(node.id.clone());
+            }
+            if let (Some(trait_id), Some(provided)) =
+                (node.impl_trait.as_deref(), &node.provided_trait_methods)
+            {
+                for name in provided {
+                    let default_body = format!("{trait_id}::{name}");
+                    if function_ids.contains(default_body.as_str()) {
+                        targets
+                            .entry((trait_id.to_string(), name.clone()))
+                            .or_default()
+                            .push(default_body);
+                    }
+                }
+            }
+        }
+        for ids in targets.values_mut() {
+            ids.sort();
+            ids.dedup();
+        }
+        Self { traits, targets }
+    }
+
+    fn resolve(
+        &self,
+        trait_segments: &TypeSegments,
+        module_path: &[String],
+        name: &str,
+    ) -> &[String] {
+        let mut matches = resolve_all_by_suffix(&self.traits, &trait_segments.segments);
+        if matches.len() != 1 && !trait_segments.is_scoped {
+            let mut scoped = module_path.to_vec();
+            scoped.extend_from_slice(&trait_segments.segments);
+            matches = resolve_all_by_suffix(&self.traits, &scoped);
+        }
+        let [trait_id] = matches.as_slice() else {
+            return &[];
+        };
+        self.targets
+            .get(&(trait_id.clone(), name.to_string()))
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+impl<'a> SourceParser<'a> {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        function_index: &'a FunctionIndex,
+        graph: &'a mut Graph,
+        edge_cache: &'a mut EdgeIndex,
+        call_mode: CallMode,
+        source_provider: &'a dyn SourceProvider,
+        cancel: Option<&'a AtomicBool>,
+        max_module_depth: usize,
+        entry_point_attributes: &'a [String],
+    ) -> Self {
+        let dyn_dispatch = (call_mode == CallMode::Heuristic).then(|| DynDispatchIndex::new(graph));
+        let node_positions = graph
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(position, node)| (node.id.clone(), position))
+            .collect();
+        Self {
+            function_index,
+            graph,
+            edge_cache,
+            call_mode,
+            visited_files: HashSet::new(),
+            source_provider,
+            cancel,
+            max_module_depth,
+            depth_limit_warned: false,
+            entry_point_attributes,
+            node_positions,
+            unresolved_calls: None,
+            complexity: false,
+            module_files: HashMap::new(),
+            dyn_dispatch,
+            imports: HashMap::new(),
+        }
+    }
+
+    /// Parse `content`, first emptying any delimiter groups nested deeper
+    /// than [`MAX_TOKEN_NESTING`]. syn recurses once per group, so without
+    /// this a pathologically nested file overflows the stack. `None` when the
+    /// pruned file no longer parses (an emptied `[T; N]` type, say): that
+    /// file then contributes no calls rather than failing the whole graph.
+    fn parse_file_bounded(
+        &mut self,
+        content: &str,
+        path: &Path,
+    ) -> Result<Option<syn::File>, RustdocError> {
+        // Lexing is iterative; if it fails (a shebang line, say), syn's own
+        // entry point knows how to cope or will report the error.
+        let Ok(tokens) = content.parse::<proc_macro2::TokenStream>() else {
+            return Ok(Some(parse_with_stack(|| syn::parse_file(content))?));
+        };
+        if token_nesting_depth(&tokens) <= MAX_TOKEN_NESTING {
+            return Ok(Some(parse_with_stack(|| syn::parse_file(content))?));
+        }
+        self.warn_depth_limit(path, "delimiters", MAX_TOKEN_NESTING);
+        let pruned = prune_token_nesting(tokens, MAX_TOKEN_NESTING);
+        Ok(parse_with_stack(|| syn::parse2(pruned)).ok())
+    }
+
+    fn warn_depth_limit(&mut self, file: &Path, what: &str, limit: usize) {
+        if self.depth_limit_warned {
+            return;
+        }
+        self.depth_limit_warned = true;
+        #[cfg(not(feature = "wasm"))]
+        eprintln!(
+            "warning: {what} in {} nest deeper than {limit} levels; \
+             skipping call extraction below that depth",
+            file.display(),
+        );
+        #[cfg(feature = "wasm")]
+        wasm_log!(
+            "warning: {what} in {} nest deeper than {limit} levels",
+            file.display(),
+        );
+    }
+
+    fn parse_module_file(
+        &mut self,
+        path: &Path,
+        module_path: Vec<String>,
+    ) -> Result<(), RustdocError> {
+        let path = path.to_path_buf();
+        if !self.visited_files.insert(path.clone()) {
+            return Ok(());
+        }
+        check_cancelled(self.cancel)?;
+        let content = self.source_provider.read_file(&path)?;
+        let Some(file) = self.parse_file_bounded(&content, &path)? else {
+            return Ok(());
+        };
         let current_dir = path.parent().unwrap_or_else(|| Path::new("."));
         self.parse_items(&file.items, &module_path, current_dir, &path)?;
         Ok(())
@@ -5058,6 +7642,18 @@ impl<'a> SourceParser<'a> {
         current_dir: &Path,
         current_file: &Path,
     ) -> Result<(), RustdocError> {
+        // Imports can follow the functions that use them, so gather them first.
+        let imports = ModuleImports::from_items(items);
+        let scope = self.imports.entry(module_path.to_vec()).or_default();
+        for (name, target) in imports.aliases {
+            scope.aliases.entry(name).or_insert(target);
+        }
+        for glob in imports.globs {
+            if !scope.globs.contains(&glob) {
+                scope.globs.push(glob);
+            }
+        }
+
         for item in items {
             match item {
                 syn::Item::Fn(item_fn) => {
@@ -5085,6 +7681,11 @@ impl<'a> SourceParser<'a> {
         current_dir: &Path,
         current_file: &Path,
     ) -> Result<(), RustdocError> {
+        if module_path.len() >= self.max_module_depth {
+            self.warn_depth_limit(current_file, "modules", self.max_module_depth);
+            return Ok(());
+        }
+
         let name = item_mod.ident.to_string();
         let mut next_path = module_path.to_vec();
         next_path.push(name.clone());
@@ -5094,7 +7695,21 @@ impl<'a> SourceParser<'a> {
             return Ok(());
         }
 
-        let module_file = resolve_module_file(current_dir, item_mod, self.source_provider);
+        // A `#[path]` override is probed once anyway and needn't match the name.
+        let module_file = if !module_path_override(item_mod).is_empty() {
+            resolve_module_file(current_dir, item_mod, self.source_provider)?
+        } else {
+            let key = (current_dir.to_path_buf(), name);
+            match self.module_files.get(&key) {
+                Some(resolved) => resolved.clone(),
+                None => {
+                    let resolved =
+                        resolve_module_file(current_dir, item_mod, self.source_provider)?;
+                    self.module_files.insert(key, resolved.clone());
+                    resolved
+                }
+            }
+        };
         if let Some(module_file) = module_file {
             self.parse_module_file(&module_file, next_path)?;
         }
@@ -5106,8 +7721,13 @@ impl<'a> SourceParser<'a> {
         let Some(caller_id) = self.resolve_free_fn_caller(module_path, &name) else {
             return;
         };
-        let calls = collect_calls(&item_fn.block, current_file);
-        self.add_call_edges(&caller_id, module_path, None, &calls);
+        if (module_path.is_empty() && name == "main") || self.has_entry_attribute(&item_fn.attrs) {
+            self.mark_entry_point(&caller_id);
+        }
+        self.fill_missing_cfg(&caller_id, &item_fn.attrs);
+        self.record_complexity(&caller_id, &item_fn.block);
+        let calls = collect_calls(&item_fn.block, Some(&item_fn.sig), current_file);
+        self.add_call_edges(&caller_id, module_path, None, &calls, false);
     }
 
     fn handle_impl(
@@ -5119,6 +7739,7 @@ impl<'a> SourceParser<'a> {
         let type_segments = type_segments_from_syn_type(&item_impl.self_ty)
             .map(|segments| resolve_type_segments(&segments, module_path))
             .filter(|segments| !segments.segments.is_empty());
+        let impl_is_entry = self.has_entry_attribute(&item_impl.attrs);
 
         for item in &item_impl.items {
             let syn::ImplItem::Fn(impl_fn) = item else {
@@ -5130,8 +7751,21 @@ impl<'a> SourceParser<'a> {
             else {
                 continue;
             };
-            let calls = collect_calls(&impl_fn.block, current_file);
-            self.add_call_edges(&caller_id, module_path, type_segments.as_ref(), &calls);
+            if (impl_is_entry && matches!(impl_fn.vis, syn::Visibility::Public(_)))
+                || self.has_entry_attribute(&impl_fn.attrs)
+            {
+                self.mark_entry_point(&caller_id);
+            }
+            self.fill_missing_cfg(&caller_id, &impl_fn.attrs);
+            self.record_complexity(&caller_id, &impl_fn.block);
+            let calls = collect_calls(&impl_fn.block, Some(&impl_fn.sig), current_file);
+            self.add_call_edges(
+                &caller_id,
+                module_path,
+                type_segments.as_ref(),
+                &calls,
+                false,
+            );
         }
     }
 
@@ -5157,8 +7791,11 @@ impl<'a> SourceParser<'a> {
             else {
                 continue;
             };
-            let calls = collect_calls(block, current_file);
-            self.add_call_edges(&caller_id, module_path, Some(&trait_segments), &calls);
+            self.record_complexity(&caller_id, block);
+            let calls = collect_calls(block, Some(&trait_fn.sig), current_file);
+            // Impls may override the default body, so its calls are never
+            // more than `Inferred`.
+            self.add_call_edges(&caller_id, module_path, Some(&trait_segments), &calls, true);
         }
     }
 
@@ -5168,32 +7805,112 @@ impl<'a> SourceParser<'a> {
         module_path: &[String],
         self_type_segments: Option<&TypeSegments>,
         calls: &[CallExpr],
+        inferred_only: bool,
     ) {
         for call in calls {
-            let (candidates, occurrence) = match call {
+            if let CallExpr::Method {
+                name,
+                occurrence,
+                dyn_trait: Some(dyn_trait),
+                ..
+            } = call
+                && let Some(dyn_dispatch) = &self.dyn_dispatch
+            {
+                let trait_segments = resolve_type_segments(dyn_trait, module_path);
+                let targets = dyn_dispatch
+                    .resolve(&trait_segments, module_path, name)
+                    .to_vec();
+                if !targets.is_empty() {
+                    let confidence = if inferred_only {
+                        Confidence::Inferred
+                    } else {
+                        Confidence::Runtime
+                    };
+                    for callee_id in targets {
+                        if caller_id != callee_id {
+                            push_edge_with_occurrence(
+                                self.graph,
+                                self.edge_cache,
+                                caller_id.to_string(),
+                                callee_id,
+                                EdgeKind::CallsRuntime,
+                                confidence,
+                                occurrence.clone(),
+                            );
+                        }
+                    }
+                    continue;
+                }
+            }
+            let (candidates, occurrence, kind) = match call {
                 CallExpr::Path {
                     segments,
                     occurrence,
+                    ..
                 } => (
                     self.resolve_callee_path_candidates(segments, module_path),
                     occurrence,
+                    EdgeKind::CallsStatic,
                 ),
-                CallExpr::Method { name, occurrence } => (
+                CallExpr::Method {
+                    name, occurrence, ..
+                } => (
                     self.resolve_callee_method_candidates(name, module_path, self_type_segments),
                     occurrence,
+                    EdgeKind::CallsStatic,
+                ),
+                CallExpr::Macro {
+                    segments,
+                    occurrence,
+                } => (
+                    self.resolve_macro_candidates(segments, module_path),
+                    occurrence,
+                    EdgeKind::CallsMacro,
+                ),
+                CallExpr::Callback {
+                    segments,
+                    occurrence,
+                } => (
+                    self.resolve_callee_path_candidates(segments, module_path),
+                    occurrence,
+                    EdgeKind::CallsRuntime,
                 ),
             };
 
+            // Most paths passed as arguments are values, not functions, so
+            // a callback that doesn't resolve isn't an unresolved call.
+            if candidates.is_empty() && matches!(call, CallExpr::Callback { .. }) {
+                continue;
+            }
+            if candidates.is_empty() {
+                if let Some(unresolved) = &mut self.unresolved_calls {
+                    unresolved.push(UnresolvedCall {
+                        caller: caller_id.to_string(),
+                        name: call.name(),
+                    });
+                }
+                continue;
+            }
             for (callee_id, confidence) in candidates {
                 if caller_id == callee_id {
                     continue;
                 }
+                // Awaiting a future suspends the caller and resolves at
+                // runtime, so a statically resolved awaited call is marked
+                // `Runtime`. Whether a callback is ever called isn't known.
+                let confidence = if inferred_only || matches!(call, CallExpr::Callback { .. }) {
+                    Confidence::Inferred
+                } else if call.is_awaited() && confidence == Confidence::Static {
+                    Confidence::Runtime
+                } else {
+                    confidence
+                };
                 push_edge_with_occurrence(
                     self.graph,
                     self.edge_cache,
                     caller_id.to_string(),
                     callee_id,
-                    EdgeKind::CallsStatic,
+                    kind,
                     confidence,
                     occurrence.clone(),
                 );
@@ -5201,6 +7918,102 @@ impl<'a> SourceParser<'a> {
         }
     }
 
+    /// Doc-test examples are wrapped in `fn main` the way rustdoc compiles
+    /// them, unless they write their own. Their spans point into the doc
+    /// comment, not a file, so no occurrences are recorded.
+    fn handle_doctest(&mut self, caller_id: &str, code: &str, crate_names: &[&str]) {
+        let source = if code.contains("fn main") {
+            code.to_string()
+        } else {
+            format!("fn main() {{\n{code}}}")
+        };
+        let Ok(Some(file)) = self.parse_file_bounded(&source, Path::new(caller_id)) else {
+            return;
+        };
+        let mut calls = Vec::new();
+        for item in &file.items {
+            if let syn::Item::Fn(item_fn) = item {
+                calls.extend(collect_calls(
+                    &item_fn.block,
+                    Some(&item_fn.sig),
+                    Path::new(caller_id),
+                ));
+            }
+        }
+        for call in &mut calls {
+            match call {
+                CallExpr::Path {
+                    segments,
+                    occurrence,
+                    ..
+                }
+                | CallExpr::Macro {
+                    segments,
+                    occurrence,
+                }
+                | CallExpr::Callback {
+                    segments,
+                    occurrence,
+                } => {
+                    if segments
+                        .first()
+                        .is_some_and(|first| crate_names.contains(&first.as_str()))
+                    {
+                        segments[0] = "crate".to_string();
+                    }
+                    *occurrence = None;
+                }
+                CallExpr::Method { occurrence, .. } => *occurrence = None,
+            }
+        }
+        self.add_call_edges(caller_id, &[], None, &calls, true);
+    }
+
+    fn has_entry_attribute(&self, attrs: &[syn::Attribute]) -> bool {
+        attrs.iter().any(|attr| {
+            let path = path_segments(attr.path());
+            self.entry_point_attributes.iter().any(|pattern| {
+                let pattern: Vec<&str> = pattern.split("::").collect();
+                path.len() >= pattern.len()
+                    && path[path.len() - pattern.len()..]
+                        .iter()
+                        .zip(&pattern)
+                        .all(|(segment, expected)| segment == expected)
+            })
+        })
+    }
+
+    fn node_mut(&mut self, id: &str) -> Option<&mut Node> {
+        let position = *self.node_positions.get(id)?;
+        self.graph.nodes.get_mut(position)
+    }
+
+    fn mark_entry_point(&mut self, id: &str) {
+        if let Some(node) = self.node_mut(id) {
+            node.is_entry = true;
+        }
+    }
+
+    fn record_complexity(&mut self, id: &str, block: &syn::Block) {
+        if !self.complexity {
+            return;
+        }
+        let complexity = cyclomatic_complexity(block);
+        if let Some(node) = self.node_mut(id) {
+            node.complexity = Some(complexity);
+        }
+    }
+
+    /// Rustdoc doesn't always record `cfg` (older formats resolve it away),
+    /// so take it from source when the node has none.
+    fn fill_missing_cfg(&mut self, id: &str, attrs: &[syn::Attribute]) {
+        if let Some(node) = self.node_mut(id)
+            && node.cfg.is_none()
+        {
+            node.cfg = cfg_from_syn_attrs(attrs);
+        }
+    }
+
     fn resolve_free_fn_caller(&self, module_path: &[String], name: &str) -> Option<String> {
         let mut segments = module_path.to_vec();
         segments.push(name.to_string());
@@ -5234,10 +8047,50 @@ impl<'a> SourceParser<'a> {
         self.function_index.resolve_method_by_name_unique(name)
     }
 
+    /// Resolve a called path, first reading its leading segment through the
+    /// module's `use` imports so `baz()` after `use crate::foo::bar as baz;`
+    /// finds `crate::foo::bar`. An import whose target doesn't resolve (say,
+    /// a re-export path) falls back to the path as written.
     fn resolve_callee_path_candidates(
         &self,
         segments: &[String],
         module_path: &[String],
+    ) -> Vec<(String, Confidence)> {
+        if let Some(imported) = self.expand_imports(segments, module_path) {
+            let candidates = self.resolve_path_candidates_as_written(&imported, module_path);
+            if !candidates.is_empty() {
+                return candidates;
+            }
+        }
+        self.resolve_path_candidates_as_written(segments, module_path)
+    }
+
+    /// `segments` with an imported leading name replaced by its target,
+    /// repeatedly, since an import may name another import. `None` when the
+    /// path doesn't start with an imported name.
+    fn expand_imports(&self, segments: &[String], module_path: &[String]) -> Option<Vec<String>> {
+        const MAX_EXPANSIONS: usize = 8;
+        let imports = self.imports.get(module_path)?;
+        let mut expanded: Option<Vec<String>> = None;
+        for _ in 0..MAX_EXPANSIONS {
+            let current = expanded.as_deref().unwrap_or(segments);
+            let Some(target) = current.first().and_then(|first| imports.aliases.get(first)) else {
+                break;
+            };
+            let mut next = target.clone();
+            next.extend_from_slice(&current[1..]);
+            if next == current {
+                break;
+            }
+            expanded = Some(next);
+        }
+        expanded
+    }
+
+    fn resolve_path_candidates_as_written(
+        &self,
+        segments: &[String],
+        module_path: &[String],
     ) -> Vec<(String, Confidence)> {
         let (anchor, rest) = split_path_anchor(segments);
         let normalized = rest.to_vec();
@@ -5245,9 +8098,27 @@ impl<'a> SourceParser<'a> {
             return Vec::new();
         }
 
+        let bare = normalized.len() == 1 && matches!(anchor, PathAnchor::Relative);
+        let scoped = scoped_segments(anchor, rest, module_path);
+        let scoped_matches = self.function_index.resolve_callable_by_suffix_all(&scoped);
+
         let mut candidates = HashMap::new();
-        let mut found = false;
+        // A bare name the module doesn't define comes from a glob import if
+        // one has it, ahead of the crate-wide suffix and name guesses. Suffix
+        // matches also find deeper modules, so "defines" means an id exactly
+        // one segment (the crate) longer than the scoped path.
+        let defined_here = scoped_matches
+            .iter()
+            .any(|id| id.split("::").count() == scoped.len() + 1);
+        if bare && !defined_here {
+            let globbed = self.resolve_through_globs(&normalized[0], module_path);
+            if !globbed.is_empty() {
+                self.add_candidates(&mut candidates, globbed);
+                return candidates.into_iter().collect();
+            }
+        }
 
+        let mut found = false;
         if matches!(anchor, PathAnchor::Relative) {
             let direct = self
                 .function_index
@@ -5255,11 +8126,9 @@ impl<'a> SourceParser<'a> {
             found |= self.add_candidates(&mut candidates, direct);
         }
 
-        let scoped = scoped_segments(anchor, rest, module_path);
-        let scoped_matches = self.function_index.resolve_callable_by_suffix_all(&scoped);
         found |= self.add_candidates(&mut candidates, scoped_matches);
 
-        if !found && normalized.len() == 1 && matches!(anchor, PathAnchor::Relative) {
+        if !found && bare {
             let by_name = self
                 .function_index
                 .resolve_callable_by_name_all(&normalized[0]);
@@ -5269,6 +8138,30 @@ impl<'a> SourceParser<'a> {
         candidates.into_iter().collect()
     }
 
+    /// Callables named `name` in any module the caller's module glob-imports.
+    /// Matches under more than one glob are ambiguous like any other, so
+    /// [`CallMode`] decides whether they're kept: in strict mode the call
+    /// then resolves to nothing.
+    fn resolve_through_globs(&self, name: &str, module_path: &[String]) -> Vec<String> {
+        let Some(imports) = self.imports.get(module_path) else {
+            return Vec::new();
+        };
+        let mut matches = Vec::new();
+        for glob in &imports.globs {
+            let mut path = glob.clone();
+            path.push(name.to_string());
+            let (anchor, rest) = split_path_anchor(&path);
+            if matches!(anchor, PathAnchor::Relative) {
+                matches.extend(self.function_index.resolve_callable_by_suffix_all(rest));
+            }
+            let scoped = scoped_segments(anchor, rest, module_path);
+            matches.extend(self.function_index.resolve_callable_by_suffix_all(&scoped));
+        }
+        matches.sort();
+        matches.dedup();
+        matches
+    }
+
     fn resolve_callee_method_candidates(
         &self,
         name: &str,
@@ -5300,6 +8193,38 @@ impl<'a> SourceParser<'a> {
         candidates.into_iter().collect()
     }
 
+    /// Macros are only linked when they resolve to a macro item rustdoc knows
+    /// about; unresolved invocations (`println!`, `vec!`, ...) are skipped.
+    fn resolve_macro_candidates(
+        &self,
+        segments: &[String],
+        module_path: &[String],
+    ) -> Vec<(String, Confidence)> {
+        let (anchor, rest) = split_path_anchor(segments);
+        if rest.is_empty() {
+            return Vec::new();
+        }
+
+        let mut candidates = HashMap::new();
+        let mut found = false;
+
+        if matches!(anchor, PathAnchor::Relative) {
+            let direct = self.function_index.resolve_macro_by_suffix_all(rest);
+            found |= self.add_candidates(&mut candidates, direct);
+        }
+
+        let scoped = scoped_segments(anchor, rest, module_path);
+        let scoped_matches = self.function_index.resolve_macro_by_suffix_all(&scoped);
+        found |= self.add_candidates(&mut candidates, scoped_matches);
+
+        if !found && rest.len() == 1 {
+            let by_name = self.function_index.resolve_macro_by_name_all(&rest[0]);
+            self.add_candidates(&mut candidates, by_name);
+        }
+
+        candidates.into_iter().collect()
+    }
+
     fn add_candidates(
         &self,
         candidates: &mut HashMap<String, Confidence>,
@@ -5318,7 +8243,7 @@ impl<'a> SourceParser<'a> {
         }
         for candidate in matches {
             let entry = candidates.entry(candidate).or_insert(confidence);
-            *entry = merge_confidence(*entry, confidence);
+            *entry = entry.merge(confidence);
         }
         true
     }
@@ -5328,63 +8253,160 @@ fn resolve_module_file(
     current_dir: &Path,
     item_mod: &syn::ItemMod,
     source_provider: &dyn SourceProvider,
-) -> Option<PathBuf> {
-    if let Some(path_override) = module_path_override(item_mod) {
+) -> Result<Option<PathBuf>, RustdocError> {
+    for path_override in module_path_override(item_mod) {
         let full_path = if path_override.is_absolute() {
             path_override
         } else {
-            current_dir.join(path_override)
+            normalize_lexically(&current_dir.join(path_override))
         };
-        if source_provider.file_exists(&full_path) {
-            return Some(full_path);
+        if source_provider.file_exists(&full_path)? {
+            return Ok(Some(full_path));
         }
     }
 
     let name = item_mod.ident.to_string();
     let candidate = current_dir.join(format!("{name}.rs"));
-    if source_provider.file_exists(&candidate) {
-        return Some(candidate);
+    if source_provider.file_exists(&candidate)? {
+        return Ok(Some(candidate));
     }
     let mod_rs = current_dir.join(&name).join("mod.rs");
-    if source_provider.file_exists(&mod_rs) {
-        return Some(mod_rs);
+    if source_provider.file_exists(&mod_rs)? {
+        return Ok(Some(mod_rs));
     }
-    None
+    Ok(None)
 }
 
-fn module_path_override(item_mod: &syn::ItemMod) -> Option<PathBuf> {
-    for attr in &item_mod.attrs {
-        if !attr.path().is_ident("path") {
-            continue;
+/// Drop `.` segments and fold `..` into the preceding segment without
+/// touching the filesystem, so `src/sys/../shared/mod.rs` becomes
+/// `src/shared/mod.rs` for every provider. Leading `..` segments are kept.
+fn normalize_lexically(path: &Path) -> PathBuf {
+    use std::path::Component;
+
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match out.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    out.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => out.push(".."),
+            },
+            other => out.push(other),
         }
-        let syn::Meta::NameValue(meta) = &attr.meta else {
-            continue;
-        };
-        let syn::Expr::Lit(expr_lit) = &meta.value else {
-            continue;
-        };
-        let syn::Lit::Str(lit_str) = &expr_lit.lit else {
-            continue;
-        };
-        return Some(PathBuf::from(lit_str.value()));
     }
-    None
+    out
 }
 
-fn type_segments_from_syn_type(ty: &syn::Type) -> Option<Vec<String>> {
-    let syn::Type::Path(type_path) = ty else {
-        return None;
-    };
-    Some(path_segments(&type_path.path))
+/// Files a module's `#[path]` attributes point at, in attribute order. Paths
+/// inside `#[cfg_attr(pred, path = "...")]` count regardless of `pred`: without
+/// target info we can't evaluate it, so the caller takes the first candidate
+/// that exists, which may not be the one the real build picks.
+fn module_path_override(item_mod: &syn::ItemMod) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for attr in &item_mod.attrs {
+        collect_path_overrides(&attr.meta, &mut paths);
+    }
+    paths
 }
 
-fn path_segments(path: &syn::Path) -> Vec<String> {
+fn collect_path_overrides(meta: &syn::Meta, out: &mut Vec<PathBuf>) {
+    match meta {
+        syn::Meta::NameValue(meta) if meta.path.is_ident("path") => {
+            if let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit_str),
+                ..
+            }) = &meta.value
+            {
+                out.push(PathBuf::from(lit_str.value()));
+            }
+        }
+        syn::Meta::List(list) if list.path.is_ident("cfg_attr") => {
+            let Ok(nested) = list.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+            ) else {
+                return;
+            };
+            // The first entry is the cfg predicate; the rest are the attributes it guards.
+            for meta in nested.iter().skip(1) {
+                collect_path_overrides(meta, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn type_segments_from_syn_type(ty: &syn::Type) -> Option<Vec<String>> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    Some(path_segments(&type_path.path))
+}
+
+/// Segments of a callee path with any qualified self type resolved:
+/// `<T as Trait>::f` calls the trait's `Trait::f`, and `<T>::f` reads as
+/// `T::f`. A `<T>::f` whose `T` isn't a path (`<[u8]>::len`) has no
+/// segments, so it isn't mistaken for a free function `f`.
+fn qualified_path_segments(expr_path: &syn::ExprPath) -> Vec<String> {
+    let segments = path_segments(&expr_path.path);
+    match &expr_path.qself {
+        Some(qself) if qself.position == 0 => match type_segments_from_syn_type(&qself.ty) {
+            Some(mut type_segments) => {
+                type_segments.extend(segments);
+                type_segments
+            }
+            None => Vec::new(),
+        },
+        _ => segments,
+    }
+}
+
+fn path_segments(path: &syn::Path) -> Vec<String> {
     path.segments
         .iter()
         .map(|segment| segment.ident.to_string())
         .collect()
 }
 
+/// Source-side counterpart of [`extract_cfg`].
+fn cfg_from_syn_attrs(attrs: &[syn::Attribute]) -> Option<String> {
+    let predicates = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .filter_map(|attr| attr.parse_args::<syn::Meta>().ok())
+        .filter_map(|meta| format_cfg_meta(&meta))
+        .collect();
+    join_cfg_predicates(predicates)
+}
+
+fn format_cfg_meta(meta: &syn::Meta) -> Option<String> {
+    let name = path_segments(meta.path()).join("::");
+    match meta {
+        syn::Meta::Path(_) => Some(name),
+        syn::Meta::NameValue(name_value) => match &name_value.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(value),
+                ..
+            }) => Some(format!("{name} = \"{}\"", value.value())),
+            _ => None,
+        },
+        syn::Meta::List(list) => {
+            let nested = list
+                .parse_args_with(
+                    syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+                )
+                .ok()?;
+            let nested = nested
+                .iter()
+                .map(format_cfg_meta)
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("{name}({})", nested.join(", ")))
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum PathAnchor {
     Relative,
@@ -5458,53 +8480,412 @@ fn resolve_type_segments(segments: &[String], module_path: &[String]) -> TypeSeg
     }
 }
 
+impl CallExpr {
+    /// The callee as written: `a::b`, a method name, or `m!` for a macro.
+    fn name(&self) -> String {
+        match self {
+            Self::Path { segments, .. } | Self::Callback { segments, .. } => segments.join("::"),
+            Self::Method { name, .. } => name.clone(),
+            Self::Macro { segments, .. } => format!("{}!", segments.join("::")),
+        }
+    }
+
+    fn is_awaited(&self) -> bool {
+        match self {
+            Self::Path { awaited, .. } | Self::Method { awaited, .. } => *awaited,
+            Self::Macro { .. } | Self::Callback { .. } => false,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 enum CallExpr {
     Path {
         segments: Vec<String>,
         occurrence: Option<Span>,
+        /// The call's result is `.await`ed directly.
+        awaited: bool,
     },
     Method {
         name: String,
         occurrence: Option<Span>,
+        /// Trait path when the receiver is a binding declared as `dyn Trait`.
+        dyn_trait: Option<Vec<String>>,
+        /// The call's result is `.await`ed directly.
+        awaited: bool,
+    },
+    Macro {
+        segments: Vec<String>,
+        occurrence: Option<Span>,
+    },
+    /// A function named as an argument to a call, like `helper` in
+    /// `iter.map(helper)` or `spawn(task)`, to be called later.
+    Callback {
+        segments: Vec<String>,
+        occurrence: Option<Span>,
     },
 }
 
-fn collect_calls(block: &syn::Block, current_file: &Path) -> Vec<CallExpr> {
+fn collect_calls(
+    block: &syn::Block,
+    sig: Option<&syn::Signature>,
+    current_file: &Path,
+) -> Vec<CallExpr> {
+    let mut bindings = BindingNames::default();
+    for input in sig.into_iter().flat_map(|sig| &sig.inputs) {
+        bindings.visit_fn_arg(input);
+    }
+    bindings.visit_block(block);
     let mut collector = CallCollector {
         calls: Vec::new(),
         current_file,
+        dyn_bindings: HashMap::new(),
+        bound_names: bindings.names,
+        awaiting: false,
     };
+    for input in sig.into_iter().flat_map(|sig| &sig.inputs) {
+        if let syn::FnArg::Typed(pat_type) = input {
+            collector.bind(&pat_type.pat, Some(&pat_type.ty));
+        }
+    }
     collector.visit_block(block);
     collector.calls
 }
 
+/// The trait behind `dyn Trait`, `&dyn Trait`, or a `Box`, `Rc` or `Arc` of
+/// one.
+fn dyn_trait_segments(ty: &syn::Type) -> Option<Vec<String>> {
+    match ty {
+        syn::Type::TraitObject(object) => object.bounds.iter().find_map(|bound| match bound {
+            syn::TypeParamBound::Trait(bound) => Some(path_segments(&bound.path)),
+            _ => None,
+        }),
+        syn::Type::Reference(reference) => dyn_trait_segments(&reference.elem),
+        syn::Type::Paren(paren) => dyn_trait_segments(&paren.elem),
+        syn::Type::Group(group) => dyn_trait_segments(&group.elem),
+        syn::Type::Path(type_path) => {
+            let last = type_path.path.segments.last()?;
+            if !matches!(last.ident.to_string().as_str(), "Box" | "Rc" | "Arc") {
+                return None;
+            }
+            let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+                return None;
+            };
+            args.args.iter().find_map(|arg| match arg {
+                syn::GenericArgument::Type(inner) => dyn_trait_segments(inner),
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The binding a method receiver names, looking through parentheses,
+/// references and derefs.
+fn receiver_binding(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Path(expr_path) => expr_path.path.get_ident().map(ToString::to_string),
+        syn::Expr::Paren(paren) => receiver_binding(&paren.expr),
+        syn::Expr::Group(group) => receiver_binding(&group.expr),
+        syn::Expr::Reference(reference) => receiver_binding(&reference.expr),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Deref(_),
+            expr,
+            ..
+        }) => receiver_binding(expr),
+        _ => None,
+    }
+}
+
+/// Calls in a function body, each with the span of its callee path, method
+/// name or macro path (not the whole call expression). Spans come from
+/// `proc-macro2` span locations over the parsed file, so lines are 1-based
+/// within that file and columns count characters rather than bytes. Macro
+/// arguments are opaque tokens, so a call written inside `vec![..]` or
+/// `assert!(..)` is not seen, and code a macro or build script generates has
+/// no location at all. Groups pruned by `parse_file_bounded` lose their calls.
+///
+/// A call whose result is `.await`ed directly is marked `awaited`.
+///
+/// Paths passed as call arguments are recorded as callbacks, except bare
+/// names the function binds anywhere (parameters, `let`s, closure and match
+/// patterns), which are taken to be values.
+///
+/// Bindings are tracked without scopes: a parameter or `let` whose type is
+/// written as `dyn Trait` marks later method calls on that name, until a
+/// `let` rebinds it.
 struct CallCollector<'a> {
     calls: Vec<CallExpr>,
     current_file: &'a Path,
+    dyn_bindings: HashMap<String, Vec<String>>,
+    bound_names: HashSet<String>,
+    /// Set while visiting the operand of `.await` when that operand is a
+    /// call, so the call records itself as awaited.
+    awaiting: bool,
+}
+
+impl CallCollector<'_> {
+    fn push_callbacks<'ast>(&mut self, args: impl IntoIterator<Item = &'ast syn::Expr>) {
+        for arg in args {
+            let Some(expr_path) = expr_to_path(arg) else {
+                continue;
+            };
+            if let Some(ident) = expr_path.path.get_ident()
+                && expr_path.qself.is_none()
+                && self.bound_names.contains(&ident.to_string())
+            {
+                continue;
+            }
+            let segments = qualified_path_segments(expr_path);
+            if !segments.is_empty() {
+                self.calls.push(CallExpr::Callback {
+                    segments,
+                    occurrence: source_span(self.current_file, expr_path.path.span()),
+                });
+            }
+        }
+    }
+
+    fn bind(&mut self, pat: &syn::Pat, ty: Option<&syn::Type>) {
+        match pat {
+            syn::Pat::Ident(pat_ident) => {
+                let name = pat_ident.ident.to_string();
+                match ty.and_then(dyn_trait_segments) {
+                    Some(segments) => {
+                        self.dyn_bindings.insert(name, segments);
+                    }
+                    None => {
+                        self.dyn_bindings.remove(&name);
+                    }
+                }
+            }
+            syn::Pat::Type(pat_type) => self.bind(&pat_type.pat, Some(&pat_type.ty)),
+            _ => {}
+        }
+    }
 }
 
 impl<'ast> Visit<'ast> for CallCollector<'_> {
+    fn visit_expr(&mut self, node: &'ast syn::Expr) {
+        stacker::maybe_grow(RED_ZONE, PARSE_STACK_SIZE, || {
+            syn::visit::visit_expr(self, node)
+        });
+    }
+
+    fn visit_expr_await(&mut self, node: &'ast syn::ExprAwait) {
+        let mut base = &*node.base;
+        while let syn::Expr::Paren(syn::ExprParen { expr, .. })
+        | syn::Expr::Group(syn::ExprGroup { expr, .. }) = base
+        {
+            base = expr;
+        }
+        // Claimed by the call about to be visited, before its arguments.
+        self.awaiting = matches!(base, syn::Expr::Call(_) | syn::Expr::MethodCall(_));
+        syn::visit::visit_expr_await(self, node);
+    }
+
     fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
-        if let Some(path) = expr_to_path(&node.func) {
-            let segments = path_segments(path);
+        let awaited = std::mem::take(&mut self.awaiting);
+        if let Some(expr_path) = expr_to_path(&node.func) {
+            let segments = qualified_path_segments(expr_path);
             if !segments.is_empty() {
                 self.calls.push(CallExpr::Path {
                     segments,
-                    occurrence: source_span(self.current_file, path.span()),
+                    occurrence: source_span(self.current_file, expr_path.path.span()),
+                    awaited,
                 });
             }
         }
+        self.push_callbacks(&node.args);
         syn::visit::visit_expr_call(self, node);
     }
 
     fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        let awaited = std::mem::take(&mut self.awaiting);
+        let dyn_trait = receiver_binding(&node.receiver)
+            .and_then(|binding| self.dyn_bindings.get(&binding).cloned());
         self.calls.push(CallExpr::Method {
             name: node.method.to_string(),
             occurrence: source_span(self.current_file, node.method.span()),
+            dyn_trait,
+            awaited,
         });
+        self.push_callbacks(&node.args);
         syn::visit::visit_expr_method_call(self, node);
     }
+
+    fn visit_local(&mut self, node: &'ast syn::Local) {
+        // The initializer still sees the binding being shadowed.
+        syn::visit::visit_local(self, node);
+        self.bind(&node.pat, None);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        let segments = path_segments(&node.path);
+        if !segments.is_empty() {
+            self.calls.push(CallExpr::Macro {
+                segments,
+                occurrence: source_span(self.current_file, node.path.span()),
+            });
+        }
+        syn::visit::visit_macro(self, node);
+    }
+}
+
+/// Every name a function body binds, without regard to scope. Nested items
+/// are skipped.
+#[derive(Default)]
+struct BindingNames {
+    names: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for BindingNames {
+    fn visit_expr(&mut self, node: &'ast syn::Expr) {
+        stacker::maybe_grow(RED_ZONE, PARSE_STACK_SIZE, || {
+            syn::visit::visit_expr(self, node)
+        });
+    }
+
+    fn visit_pat_ident(&mut self, node: &'ast syn::PatIdent) {
+        self.names.insert(node.ident.to_string());
+        syn::visit::visit_pat_ident(self, node);
+    }
+
+    fn visit_item(&mut self, _: &'ast syn::Item) {}
+}
+
+/// Approximate cyclomatic complexity of a function body: one, plus one per
+/// `if`, `while`, `for`, `match` arm after the first, arm guard, `&&`, `||`,
+/// `?` and `let ... else`. Closures count toward the enclosing function;
+/// nested items don't, and neither does anything inside macro arguments.
+fn cyclomatic_complexity(block: &syn::Block) -> u32 {
+    let mut counter = ComplexityCounter { complexity: 1 };
+    counter.visit_block(block);
+    counter.complexity
+}
+
+struct ComplexityCounter {
+    complexity: u32,
+}
+
+impl<'ast> Visit<'ast> for ComplexityCounter {
+    fn visit_expr(&mut self, node: &'ast syn::Expr) {
+        stacker::maybe_grow(RED_ZONE, PARSE_STACK_SIZE, || {
+            syn::visit::visit_expr(self, node)
+        });
+    }
+
+    fn visit_expr_if(&mut self, node: &'ast syn::ExprIf) {
+        self.complexity += 1;
+        syn::visit::visit_expr_if(self, node);
+    }
+
+    fn visit_expr_while(&mut self, node: &'ast syn::ExprWhile) {
+        self.complexity += 1;
+        syn::visit::visit_expr_while(self, node);
+    }
+
+    fn visit_expr_for_loop(&mut self, node: &'ast syn::ExprForLoop) {
+        self.complexity += 1;
+        syn::visit::visit_expr_for_loop(self, node);
+    }
+
+    fn visit_expr_match(&mut self, node: &'ast syn::ExprMatch) {
+        let arms = node.arms.len() as u32;
+        let guards = node.arms.iter().filter(|arm| arm.guard.is_some()).count() as u32;
+        self.complexity += arms.saturating_sub(1) + guards;
+        syn::visit::visit_expr_match(self, node);
+    }
+
+    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
+        if matches!(node.op, syn::BinOp::And(_) | syn::BinOp::Or(_)) {
+            self.complexity += 1;
+        }
+        syn::visit::visit_expr_binary(self, node);
+    }
+
+    fn visit_expr_try(&mut self, node: &'ast syn::ExprTry) {
+        self.complexity += 1;
+        syn::visit::visit_expr_try(self, node);
+    }
+
+    fn visit_local(&mut self, node: &'ast syn::Local) {
+        if node
+            .init
+            .as_ref()
+            .is_some_and(|init| init.diverge.is_some())
+        {
+            self.complexity += 1;
+        }
+        syn::visit::visit_local(self, node);
+    }
+
+    fn visit_item(&mut self, _: &'ast syn::Item) {}
+}
+
+/// Run a syn parse with at least [`SYN_PARSE_STACK`] to spare.
+fn parse_with_stack<T>(parse: impl FnOnce() -> syn::Result<T>) -> syn::Result<T> {
+    stacker::maybe_grow(SYN_PARSE_STACK, PARSE_STACK_SIZE, parse)
+}
+
+/// Deepest delimiter nesting in `tokens`, measured without recursing.
+fn token_nesting_depth(tokens: &proc_macro2::TokenStream) -> usize {
+    let mut max_depth = 0;
+    let mut stack = vec![tokens.clone().into_iter()];
+    while let Some(iter) = stack.last_mut() {
+        match iter.next() {
+            Some(proc_macro2::TokenTree::Group(group)) => {
+                stack.push(group.stream().into_iter());
+                max_depth = max_depth.max(stack.len() - 1);
+            }
+            Some(_) => {}
+            None => {
+                stack.pop();
+            }
+        }
+    }
+    max_depth
+}
+
+/// Empty out every delimiter group nested deeper than `max_depth`, keeping the
+/// delimiters so the surrounding code still parses. Attribute bodies (the
+/// `[...]` after `#` or `#!`) are kept whole: syn leaves their contents as
+/// raw tokens, and `#[]` would not parse. Recursion here is bounded by
+/// `max_depth`.
+fn prune_token_nesting(
+    tokens: proc_macro2::TokenStream,
+    max_depth: usize,
+) -> proc_macro2::TokenStream {
+    let mut after_pound = false;
+    tokens
+        .into_iter()
+        .map(|tree| {
+            let in_attribute = after_pound;
+            after_pound = match &tree {
+                proc_macro2::TokenTree::Punct(punct) => {
+                    punct.as_char() == '#' || (after_pound && punct.as_char() == '!')
+                }
+                _ => false,
+            };
+            match tree {
+                proc_macro2::TokenTree::Group(group)
+                    if !(in_attribute && group.delimiter() == proc_macro2::Delimiter::Bracket) =>
+                {
+                    let stream = if max_depth == 0 {
+                        proc_macro2::TokenStream::new()
+                    } else {
+                        stacker::maybe_grow(RED_ZONE, PARSE_STACK_SIZE, || {
+                            prune_token_nesting(group.stream(), max_depth - 1)
+                        })
+                    };
+                    let mut pruned = proc_macro2::Group::new(group.delimiter(), stream);
+                    pruned.set_span(group.span());
+                    proc_macro2::TokenTree::Group(pruned)
+                }
+                other => other,
+            }
+        })
+        .collect()
 }
 
 fn source_span(file: &Path, span: proc_macro2::Span) -> Option<Span> {
@@ -5522,9 +8903,9 @@ fn source_span(file: &Path, span: proc_macro2::Span) -> Option<Span> {
     })
 }
 
-fn expr_to_path(expr: &syn::Expr) -> Option<&syn::Path> {
+fn expr_to_path(expr: &syn::Expr) -> Option<&syn::ExprPath> {
     match expr {
-        syn::Expr::Path(expr_path) => Some(&expr_path.path),
+        syn::Expr::Path(expr_path) => Some(expr_path),
         syn::Expr::Paren(expr_paren) => expr_to_path(&expr_paren.expr),
         syn::Expr::Group(expr_group) => expr_to_path(&expr_group.expr),
         syn::Expr::Reference(expr_ref) => expr_to_path(&expr_ref.expr),
@@ -5534,14 +8915,6 @@ fn expr_to_path(expr: &syn::Expr) -> Option<&syn::Path> {
     }
 }
 
-fn merge_confidence(left: Confidence, right: Confidence) -> Confidence {
-    match (left, right) {
-        (Confidence::Runtime, _) | (_, Confidence::Runtime) => Confidence::Runtime,
-        (Confidence::Static, _) | (_, Confidence::Static) => Confidence::Static,
-        _ => Confidence::Inferred,
-    }
-}
-
 #[derive(Debug)]
 enum EdgeSlot {
     One(usize),
@@ -5561,7 +8934,13 @@ impl EdgeIndex {
     }
 
     fn insert_or_merge(&mut self, edges: &mut Vec<Edge>, edge: Edge) {
-        let fingerprint = edge_fingerprint(&edge.from, &edge.to, edge.kind, edge.is_glob);
+        let fingerprint = edge_fingerprint(
+            &edge.from,
+            &edge.to,
+            edge.kind,
+            edge.is_glob,
+            edge.label.as_deref(),
+        );
         match self.by_fingerprint.entry(fingerprint) {
             std::collections::hash_map::Entry::Vacant(entry) => {
                 let edge_index = edges.len();
@@ -5570,7 +8949,7 @@ impl EdgeIndex {
             }
             std::collections::hash_map::Entry::Occupied(mut entry) => {
                 if let Some(edge_index) = matching_edge_index(entry.get(), edges, &edge) {
-                    merge_edge(&mut edges[edge_index], edge);
+                    edges[edge_index].absorb(edge);
                     return;
                 }
 
@@ -5587,6 +8966,7 @@ impl EdgeIndex {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn push_or_merge(
         &mut self,
         graph: &mut Graph,
@@ -5596,6 +8976,7 @@ impl EdgeIndex {
         confidence: Confidence,
         is_glob: bool,
         occurrence: Option<Span>,
+        label: Option<String>,
     ) {
         self.insert_or_merge(
             &mut graph.edges,
@@ -5606,17 +8987,25 @@ impl EdgeIndex {
                 confidence,
                 occurrences: occurrence.into_iter().collect(),
                 is_glob,
+                label,
             },
         );
     }
 }
 
-fn edge_fingerprint(from: &str, to: &str, kind: EdgeKind, is_glob: bool) -> u64 {
+fn edge_fingerprint(
+    from: &str,
+    to: &str,
+    kind: EdgeKind,
+    is_glob: bool,
+    label: Option<&str>,
+) -> u64 {
     let mut hasher = std::collections::hash_map::DefaultHasher::new();
     from.hash(&mut hasher);
     to.hash(&mut hasher);
     kind.hash(&mut hasher);
     is_glob.hash(&mut hasher);
+    label.hash(&mut hasher);
     hasher.finish()
 }
 
@@ -5627,6 +9016,7 @@ fn matching_edge_index(slot: &EdgeSlot, edges: &[Edge], candidate: &Edge) -> Opt
             && edge.to == candidate.to
             && edge.kind == candidate.kind
             && edge.is_glob == candidate.is_glob
+            && edge.label == candidate.label
     };
 
     match slot {
@@ -5635,15 +9025,6 @@ fn matching_edge_index(slot: &EdgeSlot, edges: &[Edge], candidate: &Edge) -> Opt
     }
 }
 
-fn merge_edge(existing: &mut Edge, edge: Edge) {
-    existing.confidence = merge_confidence(existing.confidence, edge.confidence);
-    for occurrence in edge.occurrences {
-        if !existing.occurrences.contains(&occurrence) {
-            existing.occurrences.push(occurrence);
-        }
-    }
-}
-
 fn push_edge(
     graph: &mut Graph,
     edge_cache: &mut EdgeIndex,
@@ -5683,6 +9064,30 @@ fn push_edge_with_glob(
     );
 }
 
+#[allow(clippy::too_many_arguments)]
+fn push_labeled_edge(
+    graph: &mut Graph,
+    edge_cache: &mut EdgeIndex,
+    from: String,
+    to: String,
+    kind: EdgeKind,
+    confidence: Confidence,
+    label: String,
+    occurrence: Option<Span>,
+) {
+    edge_cache.push_or_merge(
+        graph,
+        from,
+        to,
+        kind,
+        confidence,
+        false,
+        occurrence,
+        Some(label),
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
 fn push_edge_with_glob_and_occurrence(
     graph: &mut Graph,
     edge_cache: &mut EdgeIndex,
@@ -5693,7 +9098,7 @@ fn push_edge_with_glob_and_occurrence(
     is_glob: bool,
     occurrence: Option<Span>,
 ) {
-    edge_cache.push_or_merge(graph, from, to, kind, confidence, is_glob, occurrence);
+    edge_cache.push_or_merge(graph, from, to, kind, confidence, is_glob, occurrence, None);
 }
 
 #[cfg(test)]
@@ -5734,6 +9139,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn cfg_trace_values_keep_braces_and_escaped_quotes() {
+        let trace = "#[attr = CfgTrace([Any([NameValue { name: \"feature\", value: \
+            Some(\"a}b\\\"c\"), span: src/lib.rs:1:11: 1:30 (#0) }, NameValue { name: \
+            \"target_os\", value: Some(\"{x}\\\\\\u{e9}\"), span: src/lib.rs:1:32: 1:50 (#0) \
+            }], src/lib.rs:1:7: 1:51 (#0))])]";
+        assert_eq!(
+            cfg_predicates(trace),
+            vec![r#"any(feature = "a}b\"c", target_os = "{x}\\é")"#]
+        );
+    }
+
     #[test]
     fn clean_other_attr_internal_filtered() {
         assert_eq!(clean_other_attr("#[attr = CfgAttrTrace]"), None);
@@ -5774,6 +9191,7 @@ mod tests {
                 })
                 .collect(),
             node_ids_by_rustdoc_id: HashMap::new(),
+            primitive_ids: HashMap::new(),
         }
     }
 
@@ -5804,9 +9222,41 @@ mod tests {
             confidence: Confidence::Static,
             occurrences: Vec::new(),
             is_glob: false,
+            label: None,
         }
     }
 
+    #[test]
+    fn interned_doc_links_round_trip_through_json() {
+        let mut graph = Graph::new();
+        let mut first = test_node("fixture::first", NodeKind::Function);
+        first
+            .doc_links
+            .insert("Thing".to_string(), "fixture::Thing".to_string());
+        let mut second = test_node("fixture::second", NodeKind::Function);
+        second
+            .doc_links
+            .insert("Thing".to_string(), "fixture::Thing".to_string());
+        second
+            .doc_links
+            .insert("Vec".to_string(), "alloc::vec::Vec".to_string());
+        graph.add_node(first);
+        graph.add_node(second);
+        let expected: Vec<_> = graph.nodes.iter().map(|n| n.doc_links.clone()).collect();
+
+        graph.intern_doc_links();
+        assert_eq!(graph.doc_link_table, ["fixture::Thing", "alloc::vec::Vec"]);
+        assert!(graph.nodes.iter().all(|node| node.doc_links.is_empty()));
+
+        let json = serde_json::to_string(&graph).expect("graph serializes");
+        let mut reloaded: Graph = serde_json::from_str(&json).expect("graph deserializes");
+        reloaded.expand_doc_links();
+
+        assert!(reloaded.doc_link_table.is_empty());
+        let actual: Vec<_> = reloaded.nodes.iter().map(|n| n.doc_links.clone()).collect();
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn edge_index_merges_duplicates_without_dropping_metadata() {
         let mut edge_index = EdgeIndex::default();
@@ -5826,6 +9276,135 @@ mod tests {
         assert_eq!(edges[0].occurrences, vec![test_span(1), test_span(2)]);
     }
 
+    #[test]
+    fn workspace_merge_promotes_stubs_and_reclassifies_edges() {
+        let crate_graph = |id: &str, nodes: Vec<Node>, edges: Vec<Edge>| CrateGraph {
+            id: id.to_string(),
+            name: id.to_string(),
+            version: "1.0.0".to_string(),
+            nodes,
+            edges,
+            aliases: HashMap::new(),
+            doc_link_table: Vec::new(),
+        };
+        let stub = |id: &str| {
+            let mut node = test_node(id, NodeKind::Function);
+            node.is_external = true;
+            node
+        };
+        let mut inferred_call = test_edge("a::f", "b::g", EdgeKind::CallsStatic);
+        inferred_call.confidence = Confidence::Inferred;
+        let first = Workspace {
+            version: codeview_core::SCHEMA_VERSION,
+            crates: vec![crate_graph(
+                "a",
+                vec![
+                    test_node("a", NodeKind::Crate),
+                    test_node("a::f", NodeKind::Function),
+                ],
+                vec![test_edge("a", "a::f", EdgeKind::Contains)],
+            )],
+            external_crates: vec![ExternalCrate {
+                id: "b".to_string(),
+                name: "b".to_string(),
+                version: Some("2.0.0".to_string()),
+                nodes: vec![stub("b::g")],
+            }],
+            cross_crate_edges: vec![inferred_call],
+            repo: Some("owner/a".to_string()),
+            ref_: None,
+        };
+        let mut local_g = test_node("b::g", NodeKind::Function);
+        local_g.span = Some(test_span(3));
+        let second = Workspace {
+            version: codeview_core::SCHEMA_VERSION,
+            crates: vec![crate_graph(
+                "b",
+                vec![test_node("b", NodeKind::Crate), local_g],
+                vec![test_edge("b", "b::g", EdgeKind::Contains)],
+            )],
+            external_crates: Vec::new(),
+            cross_crate_edges: vec![test_edge("a::f", "b::g", EdgeKind::CallsStatic)],
+            repo: Some("owner/b".to_string()),
+            ref_: None,
+        };
+
+        let merged = first.merge(second);
+
+        let ids: Vec<&str> = merged
+            .crates
+            .iter()
+            .map(|krate| krate.id.as_str())
+            .collect();
+        assert_eq!(ids, ["a", "b"]);
+        assert!(merged.external_crates.is_empty());
+        let g = merged.crates[1]
+            .nodes
+            .iter()
+            .find(|node| node.id == "b::g")
+            .expect("promoted node");
+        assert!(!g.is_external);
+        assert_eq!(g.span, Some(test_span(3)));
+        assert_eq!(merged.cross_crate_edges.len(), 1);
+        assert_eq!(merged.cross_crate_edges[0].confidence, Confidence::Static);
+        assert_eq!(merged.repo, None);
+    }
+
+    #[test]
+    fn feature_variants_tag_items_with_the_smallest_set_that_has_them() {
+        let build = |items: &[&str]| Workspace {
+            version: codeview_core::SCHEMA_VERSION,
+            crates: vec![CrateGraph {
+                id: "feat".to_string(),
+                name: "feat".to_string(),
+                version: "0.1.0".to_string(),
+                nodes: std::iter::once(test_node("feat", NodeKind::Crate))
+                    .chain(
+                        items
+                            .iter()
+                            .map(|name| test_node(&format!("feat::{name}"), NodeKind::Function)),
+                    )
+                    .collect(),
+                edges: Vec::new(),
+                aliases: HashMap::new(),
+                doc_link_table: Vec::new(),
+            }],
+            external_crates: Vec::new(),
+            cross_crate_edges: Vec::new(),
+            repo: None,
+            ref_: None,
+        };
+        let features = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+
+        let merged = build(&["always"]).merge_feature_variants(vec![
+            (
+                features(&["alpha", "beta"]),
+                build(&["always", "only_alpha", "only_beta", "both"]),
+            ),
+            (features(&["alpha"]), build(&["always", "only_alpha"])),
+            (features(&["beta"]), build(&["always", "only_beta"])),
+        ]);
+
+        let tags: Vec<(&str, Vec<&str>)> = merged.crates[0]
+            .nodes
+            .iter()
+            .map(|node| {
+                let features = node.features.iter().map(String::as_str).collect();
+                (node.id.as_str(), features)
+            })
+            .collect();
+        assert_eq!(
+            tags,
+            [
+                ("feat", vec![]),
+                ("feat::always", vec![]),
+                ("feat::both", vec!["alpha", "beta"]),
+                ("feat::only_alpha", vec!["alpha"]),
+                ("feat::only_beta", vec!["beta"]),
+            ]
+        );
+    }
+
     #[test]
     fn edge_index_collision_bucket_keeps_distinct_edges() {
         let mut edge_index = EdgeIndex::default();
@@ -5836,6 +9415,7 @@ mod tests {
             &candidate.to,
             candidate.kind,
             candidate.is_glob,
+            candidate.label.as_deref(),
         );
         edge_index
             .by_fingerprint
@@ -6163,43 +9743,663 @@ mod tests {
     }
 
     #[test]
-    fn doc_links_resolve_trait_methods_not_listed_in_paths() {
+    fn renamed_reexports_are_labelled_with_the_alias() {
         let mut value = minimal_rustdoc_value("fixture");
-        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1]);
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 4, 5]);
         value["index"]["1"] = rustdoc_item(
             1,
             0,
-            "distr",
+            "internal",
             serde_json::json!({
                 "module": {
                     "is_crate": false,
-                    "items": [2],
+                    "items": [2, 3],
                     "is_stripped": false
                 }
             }),
         );
-        value["index"]["1"]["docs"] =
-            serde_json::json!("Use [`Rng::random_range(Range)`](RngExt::random_range).");
-        value["index"]["1"]["links"] = serde_json::json!({
-            "RngExt::random_range": 3
-        });
-        value["index"]["2"] = rustdoc_trait_item(2, "RngExt", vec![3]);
-        value["index"]["3"] = rustdoc_function_item(3, "random_range");
-        value["paths"]["1"] = serde_json::json!({
-            "crate_id": 0,
-            "path": ["fixture", "distr"],
-            "kind": "module"
-        });
-        value["paths"]["2"] = serde_json::json!({
-            "crate_id": 0,
-            "path": ["fixture", "RngExt"],
-            "kind": "trait"
-        });
+        value["index"]["2"] = rustdoc_struct_item(2, "Foo", Vec::new());
+        value["index"]["3"] = rustdoc_struct_item(3, "Bar", Vec::new());
+        value["index"]["4"] = rustdoc_use_item(4, "Foo", "internal::Foo", 2);
+        value["index"]["5"] = rustdoc_use_item(5, "Baz", "internal::Bar", 3);
+        for (id, path, kind) in [
+            ("1", vec!["fixture", "internal"], "module"),
+            ("2", vec!["fixture", "internal", "Foo"], "struct"),
+            ("3", vec!["fixture", "internal", "Bar"], "struct"),
+        ] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": path,
+                "kind": kind
+            });
+        }
 
-        let graph = extract_graph(&value.to_string(), "fixture").expect("graph extracts");
+        let graph = extract_graph(&value.to_string(), "fixture").expect("fixture graph builds");
+        let label = |to: &str| {
+            graph
+                .edges
+                .iter()
+                .find(|edge| edge.to == to && edge.kind == EdgeKind::ReExports)
+                .unwrap_or_else(|| panic!("missing re-export of {to}"))
+                .label
+                .as_deref()
+        };
+        assert_eq!(label("fixture::internal::Foo"), None);
+        assert_eq!(label("fixture::internal::Bar"), Some("Baz"));
+    }
 
-        let module = graph
-            .nodes
+    #[test]
+    fn glob_reexports_keep_renames_from_the_globbed_module() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 6]);
+        for (id, name, items) in [(1, "prelude", vec![4, 5]), (2, "internal", vec![3])] {
+            value["index"][id.to_string()] = rustdoc_item(
+                id,
+                0,
+                name,
+                serde_json::json!({
+                    "module": { "is_crate": false, "items": items, "is_stripped": false }
+                }),
+            );
+        }
+        value["index"]["3"] = rustdoc_struct_item(3, "Bar", Vec::new());
+        value["index"]["4"] = rustdoc_use_item(4, "Baz", "crate::internal::Bar", 3);
+        value["index"]["5"] = rustdoc_struct_item(5, "Foo", Vec::new());
+        value["index"]["6"] = rustdoc_glob_use_item(6, "prelude", 1);
+        for (id, path, kind) in [
+            ("1", vec!["fixture", "prelude"], "module"),
+            ("2", vec!["fixture", "internal"], "module"),
+            ("3", vec!["fixture", "internal", "Bar"], "struct"),
+            ("5", vec!["fixture", "prelude", "Foo"], "struct"),
+        ] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": path,
+                "kind": kind
+            });
+        }
+
+        let graph = extract_graph(&value.to_string(), "fixture").expect("fixture graph builds");
+        let glob_label = |to: &str| {
+            graph
+                .edges
+                .iter()
+                .find(|edge| {
+                    edge.from == "fixture"
+                        && edge.to == to
+                        && edge.kind == EdgeKind::ReExports
+                        && edge.is_glob
+                })
+                .unwrap_or_else(|| panic!("missing glob re-export of {to}"))
+                .label
+                .as_deref()
+        };
+        assert_eq!(glob_label("fixture::internal::Bar"), Some("Baz"));
+        assert_eq!(glob_label("fixture::prelude::Foo"), None);
+    }
+
+    #[test]
+    fn flatten_reexports_contains_public_reexports_without_cycles() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 5, 6]);
+        for (id, name, items) in [(1, "inner", vec![3, 7]), (2, "other", vec![4])] {
+            value["index"][id.to_string()] = rustdoc_item(
+                id,
+                0,
+                name,
+                serde_json::json!({
+                    "module": { "is_crate": false, "items": items, "is_stripped": false }
+                }),
+            );
+        }
+        value["index"]["3"] = rustdoc_struct_item(3, "Foo", Vec::new());
+        value["index"]["4"] = rustdoc_struct_item(4, "Bar", Vec::new());
+        value["index"]["5"] = rustdoc_glob_use_item(5, "inner", 1);
+        value["index"]["6"] = rustdoc_use_item(6, "Bar", "other::Bar", 4);
+        value["index"]["6"]["visibility"] = serde_json::json!("default");
+        value["index"]["7"] = rustdoc_glob_use_item(7, "super", 0);
+        for (id, path, kind) in [
+            ("1", vec!["fixture", "inner"], "module"),
+            ("2", vec!["fixture", "other"], "module"),
+            ("3", vec!["fixture", "inner", "Foo"], "struct"),
+            ("4", vec!["fixture", "other", "Bar"], "struct"),
+        ] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": path,
+                "kind": kind
+            });
+        }
+        let krate = parse_rustdoc_lenient(&value.to_string()).expect("fixture parses");
+        let build = |flatten_reexports| {
+            build_graph(
+                &krate,
+                "fixture",
+                BuildGraphOptions {
+                    workspace_members: Some(HashSet::from(["fixture".to_string()])),
+                    extract: ExtractOptions {
+                        flatten_reexports,
+                        ..ExtractOptions::default()
+                    },
+                    ..Default::default()
+                },
+            )
+            .expect("fixture graph builds")
+        };
+        let flattened = |graph: &Graph| -> Vec<(String, String)> {
+            graph
+                .edges
+                .iter()
+                .filter(|edge| {
+                    edge.kind == EdgeKind::Contains && edge.confidence == Confidence::Inferred
+                })
+                .map(|edge| (edge.from.clone(), edge.to.clone()))
+                .collect()
+        };
+
+        assert!(flattened(&build(false)).is_empty());
+
+        // The private `use other::Bar` stays put, and `pub use super::*`
+        // in `inner` brings in `other` but not `inner` itself.
+        let graph = build(true);
+        assert_eq!(
+            flattened(&graph),
+            [
+                ("fixture".to_string(), "fixture::inner::Foo".to_string()),
+                ("fixture::inner".to_string(), "fixture::other".to_string())
+            ]
+        );
+        assert!(graph.edges.iter().any(|edge| edge.from == "fixture::inner"
+            && edge.to == "fixture::inner"
+            && edge.kind == EdgeKind::ReExports));
+    }
+
+    #[test]
+    fn pub_use_from_private_module_is_effectively_public() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 4, 5]);
+        value["index"]["1"] = rustdoc_item(
+            1,
+            0,
+            "internal",
+            serde_json::json!({
+                "module": {
+                    "is_crate": false,
+                    "items": [2, 3],
+                    "is_stripped": false
+                }
+            }),
+        );
+        value["index"]["1"]["visibility"] = serde_json::json!("crate");
+        value["index"]["2"] = rustdoc_struct_item(2, "Foo", Vec::new());
+        value["index"]["3"] = rustdoc_struct_item(3, "Hidden", Vec::new());
+        value["index"]["4"] = rustdoc_use_item(4, "Foo", "internal::Foo", 2);
+        value["index"]["5"] = rustdoc_use_item(5, "Hidden", "internal::Hidden", 3);
+        value["index"]["5"]["visibility"] = serde_json::json!("default");
+        for (id, path, kind) in [
+            ("1", vec!["fixture", "internal"], "module"),
+            ("2", vec!["fixture", "internal", "Foo"], "struct"),
+            ("3", vec!["fixture", "internal", "Hidden"], "struct"),
+        ] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": path,
+                "kind": kind
+            });
+        }
+
+        let graph = extract_graph(&value.to_string(), "fixture").expect("fixture graph builds");
+        let node = |id: &str| {
+            graph
+                .nodes
+                .iter()
+                .find(|node| node.id == id)
+                .unwrap_or_else(|| panic!("missing node {id}"))
+        };
+
+        let foo = node("fixture::internal::Foo");
+        assert_eq!(foo.visibility, Visibility::Public);
+        assert_eq!(foo.effective_visibility, Some(Visibility::Public));
+        assert!(foo.is_public_via_reexport);
+
+        // A private `use` doesn't widen visibility.
+        let hidden = node("fixture::internal::Hidden");
+        assert_eq!(hidden.visibility, Visibility::Public);
+        assert_eq!(hidden.effective_visibility, Some(Visibility::Crate));
+        assert!(!hidden.is_public_via_reexport);
+
+        let root = node("fixture");
+        assert_eq!(root.effective_visibility, Some(Visibility::Public));
+        assert!(!root.is_public_via_reexport);
+    }
+
+    #[test]
+    fn retain_public_keeps_modules_leading_to_public_items() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 4]);
+        value["index"]["1"] = rustdoc_item(
+            1,
+            0,
+            "internal",
+            serde_json::json!({
+                "module": {
+                    "is_crate": false,
+                    "items": [2, 3],
+                    "is_stripped": false
+                }
+            }),
+        );
+        value["index"]["1"]["visibility"] = serde_json::json!("crate");
+        value["index"]["2"] = rustdoc_struct_item(2, "Foo", vec![5]);
+        value["index"]["3"] = rustdoc_struct_item(3, "Hidden", Vec::new());
+        value["index"]["3"]["visibility"] = serde_json::json!("crate");
+        value["index"]["4"] = rustdoc_function_item(4, "helper");
+        value["index"]["4"]["visibility"] = serde_json::json!("crate");
+        value["index"]["5"] = rustdoc_impl_item(5, 2, "Foo", vec![6]);
+        value["index"]["6"] = rustdoc_function_item(6, "get");
+        value["index"]["6"]["visibility"] = serde_json::json!("default");
+        for (id, path, kind) in [
+            ("1", vec!["fixture", "internal"], "module"),
+            ("2", vec!["fixture", "internal", "Foo"], "struct"),
+            ("3", vec!["fixture", "internal", "Hidden"], "struct"),
+            ("4", vec!["fixture", "helper"], "function"),
+        ] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": path,
+                "kind": kind
+            });
+        }
+        let graph = extract_graph(&value.to_string(), "fixture").expect("fixture graph builds");
+        let names = |graph: &Graph| {
+            let mut names: Vec<String> = graph
+                .nodes
+                .iter()
+                .filter(|node| node.kind != NodeKind::Impl)
+                .map(|node| node.name.clone())
+                .collect();
+            names.sort();
+            names
+        };
+
+        let mut public = graph.clone();
+        public.retain_public(false);
+        assert_eq!(names(&public), ["Foo", "fixture", "get", "internal"]);
+        assert!(public.edges.iter().all(|edge| {
+            public.nodes.iter().any(|node| node.id == edge.from)
+                && public.nodes.iter().any(|node| node.id == edge.to)
+        }));
+
+        let mut crate_visible = graph;
+        crate_visible.retain_public(true);
+        assert_eq!(
+            names(&crate_visible),
+            ["Foo", "Hidden", "fixture", "get", "helper", "internal"]
+        );
+    }
+
+    #[test]
+    fn implementors_are_found_across_crates() {
+        let crate_graph = |id: &str, nodes: Vec<Node>, edges: Vec<Edge>| CrateGraph {
+            id: id.to_string(),
+            name: id.to_string(),
+            version: "0.1.0".to_string(),
+            nodes,
+            edges,
+            aliases: HashMap::new(),
+            doc_link_table: Vec::new(),
+        };
+        let mut display = test_node("std::fmt::Display", NodeKind::Trait);
+        display.is_external = true;
+        let mut labelled = test_edge("app::Local", "app::Plugin", EdgeKind::Implements);
+        labelled.label = Some("generic".to_string());
+        let workspace = Workspace {
+            version: codeview_core::SCHEMA_VERSION,
+            crates: vec![
+                crate_graph(
+                    "app",
+                    vec![
+                        test_node("app::Plugin", NodeKind::Trait),
+                        test_node("app::Local", NodeKind::Struct),
+                    ],
+                    vec![
+                        test_edge("app::Local", "app::Plugin", EdgeKind::Implements),
+                        labelled,
+                        test_edge("app::Local", "std::fmt::Display", EdgeKind::Implements),
+                        test_edge("app::Local", "app::Plugin", EdgeKind::UsesType),
+                    ],
+                ),
+                crate_graph(
+                    "ext",
+                    vec![test_node("ext::Remote", NodeKind::Struct)],
+                    Vec::new(),
+                ),
+            ],
+            external_crates: vec![ExternalCrate {
+                id: "std".to_string(),
+                name: "std".to_string(),
+                version: None,
+                nodes: vec![display],
+            }],
+            cross_crate_edges: vec![test_edge(
+                "ext::Remote",
+                "app::Plugin",
+                EdgeKind::Implements,
+            )],
+            repo: None,
+            ref_: None,
+        };
+        let ids = |nodes: Vec<&Node>| -> Vec<String> {
+            nodes.into_iter().map(|node| node.id.clone()).collect()
+        };
+
+        assert_eq!(
+            ids(workspace.implementors_of("app::Plugin")),
+            ["app::Local", "ext::Remote"]
+        );
+        assert_eq!(
+            ids(workspace.trait_impls_of("app::Local")),
+            ["app::Plugin", "std::fmt::Display"]
+        );
+        assert!(workspace.implementors_of("app::Local").is_empty());
+    }
+
+    #[test]
+    fn retain_kinds_drops_other_kinds_and_the_edges_touching_them() {
+        let mut external_fn = test_node("dep::run", NodeKind::Function);
+        external_fn.is_external = true;
+        let mut external_trait = test_node("dep::Show", NodeKind::Trait);
+        external_trait.is_external = true;
+        let mut workspace = Workspace {
+            version: codeview_core::SCHEMA_VERSION,
+            crates: vec![CrateGraph {
+                id: "demo".to_string(),
+                name: "demo".to_string(),
+                version: "0.1.0".to_string(),
+                nodes: vec![
+                    test_node("demo", NodeKind::Crate),
+                    test_node("demo::Point", NodeKind::Struct),
+                    test_node("demo::Shape", NodeKind::Enum),
+                    test_node("demo::impl-1", NodeKind::Impl),
+                    test_node("demo::draw", NodeKind::Function),
+                ],
+                edges: vec![
+                    test_edge("demo", "demo::Point", EdgeKind::Contains),
+                    test_edge("demo", "demo::draw", EdgeKind::Contains),
+                    test_edge("demo::Shape", "demo::Point", EdgeKind::UsesType),
+                    test_edge("demo::Point", "demo::impl-1", EdgeKind::Defines),
+                    test_edge("demo::draw", "demo::Shape", EdgeKind::UsesType),
+                ],
+                aliases: HashMap::from([
+                    ("demo::P".to_string(), "demo::Point".to_string()),
+                    ("demo::paint".to_string(), "demo::draw".to_string()),
+                ]),
+                doc_link_table: Vec::new(),
+            }],
+            external_crates: vec![ExternalCrate {
+                id: "dep".to_string(),
+                name: "dep".to_string(),
+                version: None,
+                nodes: vec![external_fn, external_trait],
+            }],
+            cross_crate_edges: vec![
+                test_edge("demo::Point", "dep::Show", EdgeKind::Implements),
+                test_edge("demo::draw", "dep::run", EdgeKind::CallsStatic),
+            ],
+            repo: None,
+            ref_: None,
+        };
+
+        workspace.retain_kinds(&HashSet::from([
+            NodeKind::Struct,
+            NodeKind::Enum,
+            NodeKind::Trait,
+        ]));
+
+        let krate = &workspace.crates[0];
+        let ids: Vec<&str> = krate.nodes.iter().map(|node| node.id.as_str()).collect();
+        assert_eq!(ids, ["demo::Point", "demo::Shape"]);
+        let edges: Vec<(&str, &str)> = krate
+            .edges
+            .iter()
+            .map(|edge| (edge.from.as_str(), edge.to.as_str()))
+            .collect();
+        assert_eq!(edges, [("demo::Shape", "demo::Point")]);
+        assert_eq!(
+            krate.aliases,
+            HashMap::from([("demo::P".to_string(), "demo::Point".to_string())])
+        );
+        let external: Vec<&str> = workspace.external_crates[0]
+            .nodes
+            .iter()
+            .map(|node| node.id.as_str())
+            .collect();
+        assert_eq!(external, ["dep::Show"]);
+        assert_eq!(workspace.cross_crate_edges.len(), 1);
+        assert_eq!(workspace.cross_crate_edges[0].to, "dep::Show");
+    }
+
+    #[test]
+    fn contract_private_calls_links_callers_through_private_helpers() {
+        let private = |id: &str, visibility: Visibility| {
+            let mut node = test_node(id, NodeKind::Function);
+            node.visibility = visibility;
+            node
+        };
+        let mut main = private("demo::main", Visibility::Inherited);
+        main.is_entry = true;
+        let mut graph = Graph::new();
+        graph.nodes = vec![
+            test_node("demo", NodeKind::Crate),
+            test_node("demo::a", NodeKind::Function),
+            test_node("demo::b", NodeKind::Function),
+            test_node("demo::c", NodeKind::Function),
+            private("demo::helper", Visibility::Inherited),
+            private("demo::inner", Visibility::Crate),
+            test_node("demo::Shape", NodeKind::Trait),
+            private("demo::Shape::draw", Visibility::Inherited),
+            main,
+        ];
+        graph.edges = vec![
+            test_edge("demo", "demo::helper", EdgeKind::Contains),
+            test_edge("demo::Shape", "demo::Shape::draw", EdgeKind::Defines),
+            test_edge("demo::a", "demo::helper", EdgeKind::CallsStatic),
+            test_edge("demo::a", "demo::c", EdgeKind::CallsStatic),
+            test_edge("demo::helper", "demo::inner", EdgeKind::CallsRuntime),
+            test_edge("demo::helper", "demo::c", EdgeKind::CallsStatic),
+            test_edge("demo::helper", "demo::Shape::draw", EdgeKind::CallsStatic),
+            test_edge("demo::inner", "demo::b", EdgeKind::CallsStatic),
+            test_edge("demo::inner", "demo::a", EdgeKind::CallsStatic),
+            test_edge("demo::main", "demo::helper", EdgeKind::CallsStatic),
+        ];
+
+        graph.contract_private_calls();
+
+        let ids: Vec<&str> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "demo",
+                "demo::a",
+                "demo::b",
+                "demo::c",
+                "demo::Shape",
+                "demo::Shape::draw",
+                "demo::main",
+            ]
+        );
+        let mut calls: Vec<(&str, &str, EdgeKind, Confidence)> = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.kind.is_call())
+            .map(|edge| {
+                (
+                    edge.from.as_str(),
+                    edge.to.as_str(),
+                    edge.kind,
+                    edge.confidence,
+                )
+            })
+            .collect();
+        calls.sort_by(|x, y| (x.0, x.1).cmp(&(y.0, y.1)));
+        use Confidence::{Inferred, Static};
+        use EdgeKind::{CallsRuntime, CallsStatic};
+        assert_eq!(
+            calls,
+            [
+                ("demo::a", "demo::Shape::draw", CallsStatic, Inferred),
+                ("demo::a", "demo::b", CallsRuntime, Inferred),
+                ("demo::a", "demo::c", CallsStatic, Static),
+                ("demo::main", "demo::Shape::draw", CallsStatic, Inferred),
+                ("demo::main", "demo::a", CallsRuntime, Inferred),
+                ("demo::main", "demo::b", CallsRuntime, Inferred),
+                ("demo::main", "demo::c", CallsStatic, Inferred),
+            ]
+        );
+    }
+
+    #[test]
+    fn link_impl_items_points_types_at_their_methods() {
+        let impl_node = |id: &str, impl_type| {
+            let mut node = test_node(id, NodeKind::Impl);
+            node.impl_type = Some(impl_type);
+            node
+        };
+        let method = |id: &str, parent: &str| {
+            let mut node = test_node(id, NodeKind::Function);
+            node.parent_impl = Some(parent.to_string());
+            node
+        };
+        let build = || Workspace {
+            version: codeview_core::SCHEMA_VERSION,
+            crates: vec![CrateGraph {
+                id: "demo".to_string(),
+                name: "demo".to_string(),
+                version: "0.1.0".to_string(),
+                nodes: vec![
+                    test_node("demo", NodeKind::Crate),
+                    test_node("demo::Point", NodeKind::Struct),
+                    impl_node("demo::impl-1", ImplType::Inherent),
+                    method("demo::Point::norm", "demo::impl-1"),
+                    impl_node("demo::impl-2", ImplType::Trait),
+                    method("demo::impl-2::method-3", "demo::impl-2"),
+                ],
+                edges: vec![
+                    test_edge("demo", "demo::Point", EdgeKind::Contains),
+                    test_edge("demo", "demo::impl-1", EdgeKind::Contains),
+                    test_edge("demo::Point", "demo::impl-1", EdgeKind::Defines),
+                    test_edge("demo::impl-1", "demo::Point::norm", EdgeKind::Defines),
+                    test_edge("demo", "demo::impl-2", EdgeKind::Contains),
+                    test_edge("demo::impl-2", "demo::impl-2::method-3", EdgeKind::Defines),
+                ],
+                aliases: HashMap::new(),
+                doc_link_table: Vec::new(),
+            }],
+            external_crates: vec![ExternalCrate {
+                id: "dep".to_string(),
+                name: "dep".to_string(),
+                version: None,
+                nodes: vec![test_node("dep::Ext", NodeKind::Struct)],
+            }],
+            cross_crate_edges: vec![test_edge("dep::Ext", "demo::impl-2", EdgeKind::Defines)],
+            repo: None,
+            ref_: None,
+        };
+        let defines = |edges: &[Edge]| -> Vec<(String, String)> {
+            edges
+                .iter()
+                .filter(|edge| edge.kind == EdgeKind::Defines)
+                .map(|edge| (edge.from.clone(), edge.to.clone()))
+                .collect()
+        };
+        let pair = |from: &str, to: &str| (from.to_string(), to.to_string());
+
+        let mut linked = build();
+        linked.link_impl_items(false);
+        linked.link_impl_items(false);
+        assert_eq!(
+            defines(&linked.crates[0].edges),
+            [
+                pair("demo::Point", "demo::impl-1"),
+                pair("demo::impl-1", "demo::Point::norm"),
+                pair("demo::impl-2", "demo::impl-2::method-3"),
+                pair("demo::Point", "demo::Point::norm"),
+            ]
+        );
+        assert_eq!(
+            defines(&linked.cross_crate_edges),
+            [
+                pair("dep::Ext", "demo::impl-2"),
+                pair("dep::Ext", "demo::impl-2::method-3"),
+            ]
+        );
+        assert_eq!(linked.crates[0].nodes.len(), 6);
+
+        let mut reparented = build();
+        reparented.link_impl_items(true);
+        let krate = &reparented.crates[0];
+        assert!(krate.nodes.iter().all(|node| node.id != "demo::impl-1"));
+        assert!(krate.nodes.iter().any(|node| node.id == "demo::impl-2"));
+        let norm = krate
+            .nodes
+            .iter()
+            .find(|node| node.id == "demo::Point::norm")
+            .unwrap();
+        assert_eq!(norm.parent_impl, None);
+        assert_eq!(
+            defines(&krate.edges),
+            [
+                pair("demo::impl-2", "demo::impl-2::method-3"),
+                pair("demo::Point", "demo::Point::norm"),
+            ]
+        );
+        assert!(
+            krate
+                .edges
+                .iter()
+                .all(|edge| edge.from != "demo::impl-1" && edge.to != "demo::impl-1")
+        );
+        assert_eq!(reparented.merged_graph().validate(), Vec::new());
+    }
+
+    #[test]
+    fn doc_links_resolve_trait_methods_not_listed_in_paths() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1]);
+        value["index"]["1"] = rustdoc_item(
+            1,
+            0,
+            "distr",
+            serde_json::json!({
+                "module": {
+                    "is_crate": false,
+                    "items": [2],
+                    "is_stripped": false
+                }
+            }),
+        );
+        value["index"]["1"]["docs"] =
+            serde_json::json!("Use [`Rng::random_range(Range)`](RngExt::random_range).");
+        value["index"]["1"]["links"] = serde_json::json!({
+            "RngExt::random_range": 3
+        });
+        value["index"]["2"] = rustdoc_trait_item(2, "RngExt", vec![3]);
+        value["index"]["3"] = rustdoc_function_item(3, "random_range");
+        value["paths"]["1"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "distr"],
+            "kind": "module"
+        });
+        value["paths"]["2"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "RngExt"],
+            "kind": "trait"
+        });
+
+        let graph = extract_graph(&value.to_string(), "fixture").expect("graph extracts");
+
+        let module = graph
+            .nodes
             .iter()
             .find(|node| node.id == "fixture::distr")
             .expect("module node exists");
@@ -6370,50 +10570,179 @@ mod tests {
     }
 
     #[test]
-    fn doc_link_resolution_stats_report_opaque_rustdoc_targets() {
+    fn fields_and_variants_keep_declaration_order() {
+        let field = |id: u32, name: &str| {
+            rustdoc_item(
+                id,
+                0,
+                name,
+                serde_json::json!({ "struct_field": { "primitive": "u8" } }),
+            )
+        };
+        let variant = |id: u32, name: &str| {
+            rustdoc_item(
+                id,
+                0,
+                name,
+                serde_json::json!({ "variant": { "kind": "plain", "discriminant": null } }),
+            )
+        };
         let mut value = minimal_rustdoc_value("fixture");
-        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1]);
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2]);
+        // Ids deliberately out of step with the declaration order.
         value["index"]["1"] = rustdoc_item(
             1,
             0,
-            "docs",
+            "Color",
             serde_json::json!({
-                "module": {
-                    "is_crate": false,
-                    "items": [],
-                    "is_stripped": false
+                "struct": {
+                    "kind": {
+                        "plain": { "fields": [5, 3, 4], "has_stripped_fields": false }
+                    },
+                    "generics": empty_generics(),
+                    "impls": []
                 }
             }),
         );
-        value["index"]["1"]["docs"] = serde_json::json!("See [`Opaque::missing`].");
-        value["index"]["1"]["links"] = serde_json::json!({
-            "`Opaque::missing`": 99
-        });
+        value["index"]["5"] = field(5, "red");
+        value["index"]["3"] = field(3, "green");
+        value["index"]["4"] = field(4, "blue");
+        value["index"]["2"] = rustdoc_item(
+            2,
+            0,
+            "Level",
+            serde_json::json!({
+                "enum": {
+                    "generics": empty_generics(),
+                    "has_stripped_variants": false,
+                    "variants": [8, 6, 7],
+                    "impls": []
+                }
+            }),
+        );
+        value["index"]["8"] = variant(8, "Low");
+        value["index"]["6"] = variant(6, "Mid");
+        value["index"]["7"] = variant(7, "High");
         value["paths"]["1"] = serde_json::json!({
             "crate_id": 0,
-            "path": ["fixture", "docs"],
-            "kind": "module"
+            "path": ["fixture", "Color"],
+            "kind": "struct"
+        });
+        value["paths"]["2"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "Level"],
+            "kind": "enum"
         });
 
-        let (graph, report) = extract_graph_validated(
-            &value.to_string(),
-            "fixture",
-            &RustdocFormatPolicy::strict(),
-        )
-        .expect("graph extracts");
+        let graph = extract_graph(&value.to_string(), "fixture").expect("graph extracts");
 
-        let module = graph
-            .nodes
+        let node = |id: &str| {
+            graph
+                .nodes
+                .iter()
+                .find(|node| node.id == id)
+                .unwrap_or_else(|| panic!("{id} exists"))
+        };
+        let fields: Vec<&str> = node("fixture::Color")
+            .fields
             .iter()
-            .find(|node| node.id == "fixture::docs")
-            .expect("module node exists");
-        assert!(!module.doc_links.contains_key("`Opaque::missing`"));
-        assert_eq!(report.raw_doc_links, 1);
-        assert_eq!(report.resolved_doc_links, 0);
-        assert_eq!(report.unresolved_doc_links, 1);
-        assert!(report.warnings.iter().any(|warning| {
-            warning.contains("left 1 of 1 rustdoc intra-doc link targets unresolved")
-        }));
+            .flatten()
+            .map(|field| field.name.as_str())
+            .collect();
+        assert_eq!(fields, ["red", "green", "blue"]);
+        let variants: Vec<&str> = node("fixture::Level")
+            .variants
+            .iter()
+            .flatten()
+            .map(|variant| variant.name.as_str())
+            .collect();
+        assert_eq!(variants, ["Low", "Mid", "High"]);
+    }
+
+    #[test]
+    fn provided_trait_methods_are_flagged_default() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1]);
+        value["index"]["1"] = rustdoc_trait_item(1, "Shape", vec![2, 3]);
+        value["index"]["2"] = rustdoc_function_item(2, "area");
+        value["index"]["2"]["inner"]["function"]["has_body"] = serde_json::json!(false);
+        value["index"]["3"] = rustdoc_function_item(3, "describe");
+        value["paths"]["1"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "Shape"],
+            "kind": "trait"
+        });
+
+        let graph = extract_graph(&value.to_string(), "fixture").expect("graph extracts");
+
+        let node = |id: &str| {
+            graph
+                .nodes
+                .iter()
+                .find(|node| node.id == id)
+                .unwrap_or_else(|| panic!("{id} exists"))
+        };
+        assert!(!node("fixture::Shape::area").is_default);
+        assert!(node("fixture::Shape::describe").is_default);
+        for method in ["fixture::Shape::area", "fixture::Shape::describe"] {
+            assert!(graph.edges.iter().any(|edge| edge.from == "fixture::Shape"
+                && edge.to == method
+                && edge.kind == EdgeKind::Defines));
+        }
+        let shape = node("fixture::Shape");
+        assert!(!shape.is_default);
+        assert_eq!(shape.required_trait_methods, Some(vec!["area".to_string()]));
+        assert_eq!(
+            shape.default_trait_methods,
+            Some(vec!["describe".to_string()])
+        );
+    }
+
+    #[test]
+    fn doc_link_resolution_stats_report_opaque_rustdoc_targets() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1]);
+        value["index"]["1"] = rustdoc_item(
+            1,
+            0,
+            "docs",
+            serde_json::json!({
+                "module": {
+                    "is_crate": false,
+                    "items": [],
+                    "is_stripped": false
+                }
+            }),
+        );
+        value["index"]["1"]["docs"] = serde_json::json!("See [`Opaque::missing`].");
+        value["index"]["1"]["links"] = serde_json::json!({
+            "`Opaque::missing`": 99
+        });
+        value["paths"]["1"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "docs"],
+            "kind": "module"
+        });
+
+        let (graph, report) = extract_graph_validated(
+            &value.to_string(),
+            "fixture",
+            &RustdocFormatPolicy::strict(),
+        )
+        .expect("graph extracts");
+
+        let module = graph
+            .nodes
+            .iter()
+            .find(|node| node.id == "fixture::docs")
+            .expect("module node exists");
+        assert!(!module.doc_links.contains_key("`Opaque::missing`"));
+        assert_eq!(report.raw_doc_links, 1);
+        assert_eq!(report.resolved_doc_links, 0);
+        assert_eq!(report.unresolved_doc_links, 1);
+        assert!(report.warnings.iter().any(|warning| {
+            warning.contains("left 1 of 1 rustdoc intra-doc link targets unresolved")
+        }));
     }
 
     #[cfg(feature = "native")]
@@ -6479,6 +10808,252 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "native")]
+    #[test]
+    fn read_span_slices_columns_and_clamps_to_the_file() {
+        let root = std::env::temp_dir().join(format!("codeview-read-span-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).expect("create temp src dir");
+        fs::write(
+            root.join("src/lib.rs"),
+            "// é header\npub fn add(a: u8) -> u8 {\n    a + 1\n}\n",
+        )
+        .expect("write source");
+        let span = |line, column, end_line, end_column| Span {
+            file: "src/lib.rs".to_string(),
+            line,
+            column,
+            end_line,
+            end_column,
+        };
+
+        let read = |span: Span| read_span(&span, &root).expect("span reads");
+        assert_eq!(
+            read(span(2, 1, Some(4), Some(2))),
+            "pub fn add(a: u8) -> u8 {\n    a + 1\n}"
+        );
+        assert_eq!(read(span(2, 8, Some(2), Some(11))), "add");
+        assert_eq!(read(span(1, 4, Some(1), Some(5))), "é");
+        assert_eq!(read(span(3, 5, None, None)), "a + 1");
+        assert_eq!(read(span(3, 5, Some(9), Some(9))), "a + 1\n}");
+        assert_eq!(read(span(4, 1, Some(4), Some(40))), "}");
+        assert_eq!(read(span(7, 1, Some(8), Some(1))), "");
+        assert!(matches!(
+            read_span(
+                &Span {
+                    file: "src/missing.rs".to_string(),
+                    ..span(1, 1, None, None)
+                },
+                &root
+            ),
+            Err(RustdocError::Io(_))
+        ));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn rustdoc_cache_tracks_sources_per_crate_and_cargo_args() {
+        let root =
+            std::env::temp_dir().join(format!("codeview-rustdoc-cache-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).expect("create temp src dir");
+        fs::create_dir_all(root.join("target")).expect("create temp target dir");
+        fs::create_dir_all(root.join("dep")).expect("create temp dep dir");
+        fs::write(root.join("src/lib.rs"), "pub struct Small;\n").expect("write source");
+        fs::write(root.join("src/old.rs"), "").expect("write source");
+        fs::write(root.join("dep/lib.rs"), "").expect("write dep source");
+        fs::write(root.join("target/ignored.rs"), "").expect("write target file");
+        let dirs = [root.clone(), root.join("dep")];
+        let fingerprint = source_fingerprint(&dirs).expect("sources found");
+        assert!(source_fingerprint(&[root.join("target")]).is_some());
+
+        // A file under `target/` doesn't count; deleting one that isn't the
+        // newest, or touching a path dependency, does.
+        fs::write(root.join("target/ignored.rs"), "fn f() {}").expect("write target file");
+        assert_eq!(source_fingerprint(&dirs), Some(fingerprint));
+        fs::remove_file(root.join("src/old.rs")).expect("remove old source");
+        let without_old = source_fingerprint(&dirs).expect("sources found");
+        assert_ne!(without_old, fingerprint);
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(60);
+        fs::File::options()
+            .write(true)
+            .open(root.join("dep/lib.rs"))
+            .and_then(|file| file.set_modified(later))
+            .expect("touch dep source");
+        assert_ne!(source_fingerprint(&dirs), Some(without_old));
+
+        fs::remove_dir_all(root.join("src")).expect("remove src dir");
+        fs::remove_dir_all(root.join("dep")).expect("remove dep dir");
+        assert_eq!(source_fingerprint(&dirs), None);
+
+        let cache_path = root.join("codeview").join(RUSTDOC_CACHE_FILE);
+        let all_features = vec!["--all-features".to_string()];
+        let entry = |crate_name: &str, cargo_args: &[String]| CachedRustdoc {
+            crate_name: crate_name.to_string(),
+            cargo_args: cargo_args.to_vec(),
+            rustdoc_name: crate_name.to_string(),
+            json_path: root.join(format!("{crate_name}.json")),
+            src_path: root.join("src/lib.rs"),
+            source_fingerprint: 1,
+        };
+        let mut cache = RustdocCache::load(&cache_path);
+        cache.insert(entry("small", &all_features));
+        cache.insert(entry("other", &[]));
+        cache.save(&cache_path).expect("save cache");
+
+        // Other args miss without wiping what was recorded for the rest.
+        let mut cache = RustdocCache::load(&cache_path);
+        assert!(cache.get("small", &all_features).is_some());
+        assert!(cache.get("small", &[]).is_none());
+        assert!(cache.get("other", &[]).is_some());
+
+        // Rebuilding with other args overwrites the same JSON, so the entry
+        // for the old args goes.
+        cache.insert(entry("small", &[]));
+        assert!(cache.get("small", &all_features).is_none());
+        assert!(cache.get("small", &[]).is_some());
+        assert!(cache.forget(&root.join("other.json")));
+        assert!(cache.get("other", &[]).is_none());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn local_source_dirs_follow_path_dependencies() {
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        let metadata = MetadataCommand::new()
+            .manifest_path(&manifest)
+            .no_deps()
+            .exec()
+            .expect("cargo metadata runs");
+        let package = |name: &str| {
+            metadata
+                .packages
+                .iter()
+                .find(|package| package.name.as_str() == name)
+                .expect("workspace member")
+        };
+        let dir = |name: &str| {
+            package(name)
+                .manifest_path
+                .parent()
+                .expect("manifest dir")
+                .as_std_path()
+                .to_path_buf()
+        };
+
+        let mut dirs = local_source_dirs(&metadata, package("codeview-rustdoc"));
+        dirs.sort();
+        let mut expected = vec![dir("codeview-rustdoc"), dir("codeview-core")];
+        expected.sort();
+        assert_eq!(dirs, expected);
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn map_parallel_keeps_order_and_reports_panics() {
+        let items: Vec<usize> = (0..32).collect();
+        let doubled = map_parallel(&items, None, |item| Ok(item * 2)).expect("no failures");
+        assert_eq!(
+            doubled,
+            items.iter().map(|item| item * 2).collect::<Vec<_>>()
+        );
+
+        let result = map_parallel(&items, None, |&item| {
+            if item == 7 {
+                panic!("bad item {item}");
+            }
+            Ok(item)
+        });
+        assert!(
+            matches!(&result, Err(RustdocError::WorkerPanicked(message)) if message == "bad item 7"),
+            "{result:?}"
+        );
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn map_parallel_stops_starting_items_after_a_panic() {
+        let items: Vec<usize> = (0..1000).collect();
+        let started = std::sync::atomic::AtomicUsize::new(0);
+        let running = std::sync::atomic::AtomicUsize::new(0);
+        let most_running = std::sync::atomic::AtomicUsize::new(0);
+        let result = map_parallel(&items, Some(2), |&item| {
+            started.fetch_add(1, Ordering::SeqCst);
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            most_running.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            running.fetch_sub(1, Ordering::SeqCst);
+            if item == 3 {
+                panic!("bad item {item}");
+            }
+            Ok(item)
+        });
+
+        assert!(matches!(result, Err(RustdocError::WorkerPanicked(_))));
+        assert!(started.load(Ordering::SeqCst) < 10);
+        assert!(most_running.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn crate_filter_matches_normalised_globs() {
+        let everything = CrateFilter::default();
+        assert!(everything.matches("anything-at-all"));
+
+        let filter = CrateFilter {
+            include: vec!["codeview-*".to_string(), "tool?".to_string()],
+            exclude: vec!["*_cli".to_string()],
+        };
+        assert!(filter.matches("codeview_core"));
+        assert!(filter.matches("codeview-rustdoc"));
+        assert!(filter.matches("tool1"));
+        assert!(!filter.matches("tool12"));
+        assert!(!filter.matches("codeview-cli"));
+        assert!(!filter.matches("other"));
+        assert!(glob_matches("a*b*c", "axxbyybzc"));
+        assert!(!glob_matches("a*b*c", "axxbyy"));
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn workspace_from_json_treats_every_listed_crate_as_a_member() {
+        let root =
+            std::env::temp_dir().join(format!("codeview-rustdoc-from-json-{}", std::process::id()));
+        fs::create_dir_all(&root).expect("create temp dir");
+        let mut app = minimal_rustdoc_value("app");
+        app["crate_version"] = serde_json::json!("1.2.0");
+        let app_path = root.join("app.json");
+        let lib_path = root.join("my_lib.json");
+        fs::write(&app_path, app.to_string()).expect("write app json");
+        fs::write(&lib_path, minimal_rustdoc_json("my_lib")).expect("write lib json");
+
+        let workspace = load_workspace_graph_from_json(
+            &[
+                ("app".to_string(), app_path),
+                ("my-lib".to_string(), lib_path),
+            ],
+            &ExtractOptions::default(),
+        )
+        .expect("workspace builds");
+        let _ = fs::remove_dir_all(&root);
+
+        let crates: Vec<(&str, &str, usize)> = workspace
+            .crates
+            .iter()
+            .map(|krate| {
+                (
+                    krate.name.as_str(),
+                    krate.version.as_str(),
+                    krate.nodes.len(),
+                )
+            })
+            .collect();
+        assert_eq!(crates, vec![("app", "1.2.0", 1), ("my_lib", "0.0.0", 1)]);
+        assert!(workspace.external_crates.is_empty());
+    }
+
     #[cfg(feature = "native")]
     #[test]
     fn docs_rs_command_plan_omits_missing_debugger_visualizer_feature() {
@@ -6573,6 +11148,46 @@ pub struct Small;
         ));
     }
 
+    #[cfg(feature = "native")]
+    #[test]
+    fn rustdoc_json_streams_from_readers_and_files() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1]);
+        value["index"]["1"] = rustdoc_function_item(1, "run");
+        value["paths"]["1"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "run"],
+            "kind": "function"
+        });
+        let ids = |graph: Graph| {
+            let mut ids: Vec<String> = graph.nodes.into_iter().map(|node| node.id).collect();
+            ids.sort();
+            ids
+        };
+        let json = value.to_string();
+        let streamed = extract_graph_reader(json.as_bytes(), "fixture").expect("reader graph");
+        assert_eq!(ids(streamed), ["fixture", "fixture::run"]);
+
+        // Before v44 `target` was missing: streaming can't take it, while
+        // the file loader falls back to the compatibility layer.
+        value["format_version"] = serde_json::json!(43);
+        value.as_object_mut().expect("object").remove("target");
+        let older = value.to_string();
+        assert!(matches!(
+            extract_graph_reader(older.as_bytes(), "fixture"),
+            Err(RustdocError::Json(_))
+        ));
+        let path =
+            std::env::temp_dir().join(format!("codeview-reader-{}.json", std::process::id()));
+        fs::write(&path, &older).expect("write rustdoc json");
+        let loaded = load_graph_from_path(&path, "fixture");
+        let _ = fs::remove_file(&path);
+        assert_eq!(
+            ids(loaded.expect("older file loads")),
+            ["fixture", "fixture::run"]
+        );
+    }
+
     #[test]
     fn rustdoc_compat_fills_required_fields_through_v60() {
         let mut value = minimal_rustdoc_value("fixture");
@@ -6640,11 +11255,8 @@ pub struct Small;
             &validated.krate,
             "fixture",
             BuildGraphOptions {
-                workspace_members: None,
-                source: None,
                 call_mode: CallMode::Ambiguous,
-                skip_external_nodes: false,
-                rustdoc_name: None,
+                ..Default::default()
             },
         )
         .expect("graph builds");
@@ -6919,10 +11531,7 @@ pub struct Small;
             "fixture",
             BuildGraphOptions {
                 workspace_members: Some(HashSet::from(["fixture".to_string()])),
-                source: None,
-                call_mode: CallMode::Strict,
-                skip_external_nodes: false,
-                rustdoc_name: None,
+                ..Default::default()
             },
         )
         .expect("fixture graph builds");
@@ -6965,107 +11574,1923 @@ pub struct Small;
     }
 
     #[test]
-    fn source_backed_call_edges_record_occurrence_spans() {
+    fn diagnostics_report_calls_that_resolve_to_nothing() {
         let mut value = minimal_rustdoc_value("fixture");
         value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2]);
         value["index"]["1"] = rustdoc_function_item(1, "caller");
         value["index"]["2"] = rustdoc_function_item(2, "callee");
-        value["paths"]["1"] = serde_json::json!({
-            "crate_id": 0,
-            "path": ["fixture", "caller"],
-            "kind": "function"
-        });
-        value["paths"]["2"] = serde_json::json!({
-            "crate_id": 0,
-            "path": ["fixture", "callee"],
-            "kind": "function"
-        });
+        for (id, name) in [("1", "caller"), ("2", "callee")] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": "function"
+            });
+        }
+        let provider = MemorySourceProvider::new(HashMap::from([(
+            "src/lib.rs".to_string(),
+            "pub fn caller() {\n    callee();\n    let f = || 1;\n    f();\n    \
+             other_crate::run();\n    missing!();\n}\n\npub fn callee() {}\n"
+                .to_string(),
+        )]));
 
-        let graph = extract_graph_with_source_map(
+        let (graph, unresolved) = extract_graph_with_diagnostics(
             &value.to_string(),
             "fixture",
-            HashMap::from([(
-                "src/lib.rs".to_string(),
-                "pub fn caller() {\n    callee();\n    callee();\n}\n\npub fn callee() {}\n"
-                    .to_string(),
-            )]),
+            &provider,
             "src/lib.rs",
             CallMode::Strict,
+            &ExtractOptions::default(),
         )
         .expect("source-backed graph extracts");
 
-        let call_edge = graph
-            .edges
-            .iter()
-            .find(|edge| {
-                edge.from == "fixture::caller"
-                    && edge.to == "fixture::callee"
-                    && edge.kind == EdgeKind::CallsStatic
-            })
-            .expect("caller to callee call edge");
-
-        assert_eq!(call_edge.occurrences.len(), 2);
-        assert_eq!(
-            call_edge.occurrences[0],
-            Span {
-                file: "src/lib.rs".to_string(),
-                line: 2,
-                column: 5,
-                end_line: Some(2),
-                end_column: Some(11),
-            }
+        assert!(
+            graph
+                .edges
+                .iter()
+                .any(|edge| edge.to == "fixture::callee" && edge.kind == EdgeKind::CallsStatic)
         );
+        let names: Vec<(&str, &str)> = unresolved
+            .iter()
+            .map(|call| (call.caller.as_str(), call.name.as_str()))
+            .collect();
         assert_eq!(
-            call_edge.occurrences[1],
-            Span {
-                file: "src/lib.rs".to_string(),
-                line: 3,
-                column: 5,
-                end_line: Some(3),
-                end_column: Some(11),
-            }
+            names,
+            [
+                ("fixture::caller", "f"),
+                ("fixture::caller", "other_crate::run"),
+                ("fixture::caller", "missing!")
+            ]
         );
     }
 
     #[test]
-    fn non_module_path_prefix_is_not_created_as_module() {
-        let mut graph = Graph::new();
-        let mut node_cache = HashSet::from(["fixture".to_string()]);
-        let mut edge_cache = EdgeIndex::default();
-        let mut placeholder_modules = HashSet::new();
-        let path_index = path_index(
-            &["fixture", "fixture::Trait", "fixture::Trait::method"],
-            &["fixture"],
-        );
-        let path = ["fixture", "Trait", "method"].map(str::to_string);
+    fn graphs_come_out_in_a_stable_order() {
+        let mut value = minimal_rustdoc_value("fixture");
+        let names = ["walk", "alpha", "zeta", "mid", "beta"];
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3, 4, 5]);
+        for (id, name) in (1..).zip(names) {
+            value["index"][id.to_string()] = rustdoc_function_item(id, name);
+            value["paths"][id.to_string()] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": "function"
+            });
+        }
+        let json = value.to_string();
+        let build = || {
+            extract_graph_with_source_map(
+                &json,
+                "fixture",
+                HashMap::from([(
+                    "src/lib.rs".to_string(),
+                    "pub fn walk() { zeta(); alpha(); mid(); beta(); }\n\
+                     pub fn alpha() { zeta(); }\n\
+                     pub fn zeta() {}\n\
+                     pub fn mid() { beta(); alpha(); }\n\
+                     pub fn beta() {}\n"
+                        .to_string(),
+                )]),
+                "src/lib.rs",
+                CallMode::Strict,
+            )
+            .expect("fixture graph builds")
+        };
 
-        ensure_module_nodes(
-            &mut graph,
-            &mut node_cache,
-            &mut edge_cache,
-            &mut placeholder_modules,
-            "fixture",
-            &path,
-            &path_index,
-            false,
-        );
+        let graph = build();
+        assert!(graph.nodes.is_sorted_by(|a, b| a.id <= b.id));
+        assert!(graph.edges.is_sorted_by_key(|edge| (
+            edge.from.clone(),
+            edge.to.clone(),
+            edge.kind
+        )));
+        let first = serde_json::to_string(&graph).expect("graph serializes");
+        for _ in 0..4 {
+            assert_eq!(
+                serde_json::to_string(&build()).expect("graph serializes"),
+                first
+            );
+        }
+    }
 
-        assert!(!node_cache.contains("fixture::Trait"));
-        assert!(!placeholder_modules.contains("fixture::Trait"));
-        assert!(graph.nodes.is_empty());
+    #[test]
+    fn schema_only_requires_fields_serialization_always_writes() {
+        let graph = extract_graph(&minimal_rustdoc_json("fixture"), "fixture")
+            .expect("fixture graph builds");
+        let mut sparse = graph.clone();
+        sparse.nodes.push(Node::new(
+            "fixture::Bare",
+            "Bare",
+            NodeKind::Struct,
+            Visibility::Unknown,
+        ));
+        sparse
+            .edges
+            .push(test_edge("fixture", "fixture::Bare", EdgeKind::Contains));
+        let value = serde_json::to_value(&sparse).expect("graph serializes");
+        let schema =
+            serde_json::to_value(codeview_core::graph_schema()).expect("schema serializes");
         assert_eq!(
-            structural_edge_kind("fixture::Trait", "fixture", &path_index),
-            EdgeKind::Defines
+            schema["x-schema-version"],
+            serde_json::json!(codeview_core::SCHEMA_VERSION)
         );
+
+        let required = |schema: &serde_json::Value| -> Vec<String> {
+            schema["required"]
+                .as_array()
+                .map(|keys| {
+                    keys.iter()
+                        .filter_map(|key| key.as_str())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let check = |name: &str, object: &serde_json::Value| {
+            for key in required(&schema["$defs"][name]) {
+                assert!(
+                    object.get(&key).is_some(),
+                    "{name} is missing required `{key}`"
+                );
+            }
+        };
+        for key in required(&schema) {
+            assert!(
+                value.get(&key).is_some(),
+                "graph is missing required `{key}`"
+            );
+        }
+        for node in value["nodes"].as_array().expect("nodes array") {
+            check("Node", node);
+        }
+        for edge in value["edges"].as_array().expect("edges array") {
+            check("Edge", edge);
+        }
     }
 
     #[test]
-    fn placeholder_module_is_replaced_by_real_module_node() {
-        let mut graph = Graph::new();
-        let mut node_cache = HashSet::from(["fixture".to_string()]);
-        let mut edge_cache = EdgeIndex::default();
-        let mut placeholder_modules = HashSet::new();
-        let path_index = path_index(
+    fn awaited_calls_get_runtime_confidence() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3, 4]);
+        for (id, name) in [(1, "caller"), (2, "fetch"), (3, "parse"), (4, "helper")] {
+            value["index"][id.to_string()] = rustdoc_function_item(id, name);
+            value["paths"][id.to_string()] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": "function"
+            });
+        }
+
+        let graph = extract_graph_with_source_map(
+            &value.to_string(),
+            "fixture",
+            HashMap::from([(
+                "src/lib.rs".to_string(),
+                "pub async fn caller() {\n    (fetch(parse())).await;\n    helper();\n}\n\
+                 pub async fn fetch(_: u8) {}\n\
+                 pub fn parse() -> u8 { 0 }\n\
+                 pub fn helper() {}\n"
+                    .to_string(),
+            )]),
+            "src/lib.rs",
+            CallMode::Strict,
+        )
+        .expect("source-backed graph extracts");
+
+        let mut calls: Vec<(&str, Confidence)> = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.from == "fixture::caller" && edge.kind == EdgeKind::CallsStatic)
+            .map(|edge| (edge.to.as_str(), edge.confidence))
+            .collect();
+        calls.sort_unstable_by_key(|(to, _)| *to);
+        assert_eq!(
+            calls,
+            [
+                ("fixture::fetch", Confidence::Runtime),
+                ("fixture::helper", Confidence::Static),
+                ("fixture::parse", Confidence::Static)
+            ]
+        );
+    }
+
+    #[test]
+    fn functions_passed_as_arguments_get_runtime_call_edges() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3, 4, 5]);
+        for (id, name) in [
+            (1, "caller"),
+            (2, "spawn"),
+            (3, "task"),
+            (4, "double"),
+            (5, "main"),
+        ] {
+            value["index"][id.to_string()] = rustdoc_function_item(id, name);
+            value["paths"][id.to_string()] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": "function"
+            });
+        }
+
+        // `task` and `double` are passed by name; in `main`, `task` is a
+        // local binding and gets no edge.
+        let graph = extract_graph_with_source_map(
+            &value.to_string(),
+            "fixture",
+            HashMap::from([(
+                "src/lib.rs".to_string(),
+                "pub fn caller(xs: Vec<u8>) -> Vec<u8> {\n    spawn(task);\n    \
+                 xs.into_iter().map(self::double).collect()\n}\n\
+                 pub fn spawn(_: fn()) {}\n\
+                 pub fn task() {}\n\
+                 pub fn double(x: u8) -> u8 { x * 2 }\n\
+                 fn main() { let task = 1; spawn(task); }\n"
+                    .to_string(),
+            )]),
+            "src/lib.rs",
+            CallMode::Strict,
+        )
+        .expect("source-backed graph extracts");
+
+        let mut calls: Vec<(&str, &str, EdgeKind, Confidence)> = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.kind.is_call())
+            .map(|edge| {
+                (
+                    edge.from.as_str(),
+                    edge.to.as_str(),
+                    edge.kind,
+                    edge.confidence,
+                )
+            })
+            .collect();
+        calls.sort();
+        assert_eq!(
+            calls,
+            [
+                (
+                    "fixture::caller",
+                    "fixture::double",
+                    EdgeKind::CallsRuntime,
+                    Confidence::Inferred
+                ),
+                (
+                    "fixture::caller",
+                    "fixture::spawn",
+                    EdgeKind::CallsStatic,
+                    Confidence::Static
+                ),
+                (
+                    "fixture::caller",
+                    "fixture::task",
+                    EdgeKind::CallsRuntime,
+                    Confidence::Inferred
+                ),
+                (
+                    "fixture::main",
+                    "fixture::spawn",
+                    EdgeKind::CallsStatic,
+                    Confidence::Static
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn calls_through_use_aliases_resolve_to_the_imported_path() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3]);
+        for (id, path) in [
+            (1, vec!["fixture", "caller"]),
+            (2, vec!["fixture", "util", "bar"]),
+            (3, vec!["fixture", "util", "inner", "deep"]),
+        ] {
+            value["index"][id.to_string()] = rustdoc_function_item(id, path[path.len() - 1]);
+            value["paths"][id.to_string()] = serde_json::json!({
+                "crate_id": 0,
+                "path": path,
+                "kind": "function"
+            });
+        }
+
+        // `baz`, `u` and `d` name nothing in the crate except through the
+        // imports, which come after the function that uses them.
+        let graph = extract_graph_with_source_map(
+            &value.to_string(),
+            "fixture",
+            HashMap::from([(
+                "src/lib.rs".to_string(),
+                "pub fn caller() { baz(); u::bar(); d(); }\n\
+                 use crate::util::bar as baz;\n\
+                 use self::util::{self as u, inner::{deep as d}};\n\
+                 pub mod util {\n    pub fn bar() {}\n    \
+                 pub mod inner { pub fn deep() {} }\n}\n"
+                    .to_string(),
+            )]),
+            "src/lib.rs",
+            CallMode::Strict,
+        )
+        .expect("source-backed graph extracts");
+
+        let mut calls: Vec<(&str, &str, Confidence)> = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.kind.is_call())
+            .map(|edge| (edge.from.as_str(), edge.to.as_str(), edge.confidence))
+            .collect();
+        calls.sort();
+        assert_eq!(
+            calls,
+            [
+                ("fixture::caller", "fixture::util::bar", Confidence::Static),
+                (
+                    "fixture::caller",
+                    "fixture::util::inner::deep",
+                    Confidence::Static
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn bare_calls_resolve_under_glob_imports() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3, 4]);
+        for (id, path) in [
+            (1, vec!["fixture", "caller"]),
+            (2, vec!["fixture", "helpers", "helper"]),
+            (3, vec!["fixture", "tools", "helper"]),
+            (4, vec!["fixture", "both", "twice"]),
+        ] {
+            value["index"][id.to_string()] = rustdoc_function_item(id, path[path.len() - 1]);
+            value["paths"][id.to_string()] = serde_json::json!({
+                "crate_id": 0,
+                "path": path,
+                "kind": "function"
+            });
+        }
+        let source = "use crate::helpers::*;\n\
+                      pub fn caller() { helper(); }\n\
+                      pub mod helpers { pub fn helper() {} }\n\
+                      pub mod tools { pub fn helper() {} }\n\
+                      pub mod both {\n    use crate::helpers::*;\n    use super::tools::*;\n    \
+                      pub fn twice() { helper(); }\n}\n";
+
+        let calls = |mode| {
+            let graph = extract_graph_with_source_map(
+                &value.to_string(),
+                "fixture",
+                HashMap::from([("src/lib.rs".to_string(), source.to_string())]),
+                "src/lib.rs",
+                mode,
+            )
+            .expect("source-backed graph extracts");
+            let mut calls: Vec<(String, String, Confidence)> = graph
+                .edges
+                .iter()
+                .filter(|edge| edge.kind.is_call())
+                .map(|edge| (edge.from.clone(), edge.to.clone(), edge.confidence))
+                .collect();
+            calls.sort();
+            calls
+        };
+        let edge =
+            |from: &str, to: &str, confidence| (from.to_string(), to.to_string(), confidence);
+
+        // Two `helper`s exist, but only one is glob-imported into the root.
+        assert_eq!(
+            calls(CallMode::Strict),
+            [edge(
+                "fixture::caller",
+                "fixture::helpers::helper",
+                Confidence::Static
+            )]
+        );
+        // `both` glob-imports both of them.
+        assert_eq!(
+            calls(CallMode::Ambiguous),
+            [
+                edge(
+                    "fixture::both::twice",
+                    "fixture::helpers::helper",
+                    Confidence::Inferred
+                ),
+                edge(
+                    "fixture::both::twice",
+                    "fixture::tools::helper",
+                    Confidence::Inferred
+                ),
+                edge(
+                    "fixture::caller",
+                    "fixture::helpers::helper",
+                    Confidence::Static
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn source_backed_call_edges_record_occurrence_spans() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2]);
+        value["index"]["1"] = rustdoc_function_item(1, "caller");
+        value["index"]["2"] = rustdoc_function_item(2, "callee");
+        value["paths"]["1"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "caller"],
+            "kind": "function"
+        });
+        value["paths"]["2"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "callee"],
+            "kind": "function"
+        });
+
+        let graph = extract_graph_with_source_map(
+            &value.to_string(),
+            "fixture",
+            HashMap::from([(
+                "src/lib.rs".to_string(),
+                "pub fn caller() {\n    callee();\n    callee();\n}\n\npub fn callee() {}\n"
+                    .to_string(),
+            )]),
+            "src/lib.rs",
+            CallMode::Strict,
+        )
+        .expect("source-backed graph extracts");
+
+        let call_edge = graph
+            .edges
+            .iter()
+            .find(|edge| {
+                edge.from == "fixture::caller"
+                    && edge.to == "fixture::callee"
+                    && edge.kind == EdgeKind::CallsStatic
+            })
+            .expect("caller to callee call edge");
+
+        assert_eq!(call_edge.occurrences.len(), 2);
+        assert_eq!(
+            call_edge.occurrences[0],
+            Span {
+                file: "src/lib.rs".to_string(),
+                line: 2,
+                column: 5,
+                end_line: Some(2),
+                end_column: Some(11),
+            }
+        );
+        assert_eq!(
+            call_edge.occurrences[1],
+            Span {
+                file: "src/lib.rs".to_string(),
+                line: 3,
+                column: 5,
+                end_line: Some(3),
+                end_column: Some(11),
+            }
+        );
+    }
+
+    #[test]
+    fn function_return_types_emit_returns_edges() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3]);
+        value["index"]["1"] = rustdoc_function_item(1, "build");
+        value["index"]["1"]["inner"]["function"]["sig"]["inputs"] = serde_json::json!([
+            ["options", { "resolved_path": { "path": "Options", "id": 3, "args": null } }]
+        ]);
+        value["index"]["1"]["inner"]["function"]["sig"]["output"] = serde_json::json!({
+            "resolved_path": { "path": "Config", "id": 2, "args": null }
+        });
+        value["index"]["2"] = rustdoc_struct_item(2, "Config", Vec::new());
+        value["index"]["3"] = rustdoc_struct_item(3, "Options", Vec::new());
+        for (id, name, kind) in [
+            ("1", "build", "function"),
+            ("2", "Config", "struct"),
+            ("3", "Options", "struct"),
+        ] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": kind
+            });
+        }
+
+        let graph = extract_graph(&value.to_string(), "fixture").expect("fixture graph builds");
+        let has_edge = |to: &str, kind: EdgeKind| {
+            graph
+                .edges
+                .iter()
+                .any(|edge| edge.from == "fixture::build" && edge.to == to && edge.kind == kind)
+        };
+
+        assert!(has_edge("fixture::Config", EdgeKind::Returns));
+        assert!(!has_edge("fixture::Config", EdgeKind::UsesType));
+        assert!(has_edge("fixture::Options", EdgeKind::UsesType));
+        assert!(!has_edge("fixture::Options", EdgeKind::Returns));
+    }
+
+    #[test]
+    fn impl_associated_consts_and_types_are_defined_by_the_impl() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1]);
+        value["index"]["1"] = rustdoc_struct_item(1, "Foo", vec![2]);
+        value["index"]["2"] = rustdoc_impl_item(2, 1, "Foo", vec![3, 4]);
+        value["index"]["3"] = rustdoc_assoc_const_item(3, "N");
+        value["index"]["3"]["inner"]["assoc_const"]["type"] =
+            serde_json::json!({ "primitive": "usize" });
+        value["index"]["3"]["inner"]["assoc_const"]["value"] = serde_json::json!("3");
+        value["index"]["4"] = rustdoc_assoc_type_item(4, "Out");
+        value["paths"]["1"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "Foo"],
+            "kind": "struct"
+        });
+
+        let graph = extract_graph(&value.to_string(), "fixture").expect("graph extracts");
+
+        let impl_id = graph
+            .edges
+            .iter()
+            .find(|edge| edge.from == "fixture::Foo" && edge.kind == EdgeKind::Defines)
+            .map(|edge| edge.to.clone())
+            .expect("impl node");
+        let defined: Vec<&Node> = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.from == impl_id && edge.kind == EdgeKind::Defines)
+            .filter_map(|edge| graph.nodes.iter().find(|node| node.id == edge.to))
+            .collect();
+        let constant = defined
+            .iter()
+            .find(|node| node.name == "N")
+            .expect("associated const node");
+        assert_eq!(constant.kind, NodeKind::AssocConst);
+        assert_eq!(constant.parent_impl.as_deref(), Some(impl_id.as_str()));
+        assert_eq!(
+            constant.type_,
+            Some(TypeRef::Primitive {
+                name: "usize".to_string()
+            })
+        );
+        assert_eq!(constant.const_value.as_deref(), Some("3"));
+        assert!(
+            defined
+                .iter()
+                .any(|node| node.name == "Out" && node.kind == NodeKind::AssocType)
+        );
+    }
+
+    #[test]
+    fn blanket_impl_items_can_be_collapsed_or_skipped() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 3, 5]);
+        value["index"]["1"] = rustdoc_struct_item(1, "A", vec![2]);
+        value["index"]["2"] = rustdoc_impl_item(2, 1, "A", vec![6]);
+        value["index"]["3"] = rustdoc_struct_item(3, "B", vec![4]);
+        value["index"]["4"] = rustdoc_impl_item(4, 3, "B", vec![6]);
+        value["index"]["5"] = rustdoc_trait_item(5, "Show", Vec::new());
+        value["index"]["6"] = rustdoc_function_item(6, "show");
+        for impl_id in ["2", "4"] {
+            let inner = &mut value["index"][impl_id]["inner"]["impl"];
+            inner["trait"] = serde_json::json!({ "path": "Show", "id": 5, "args": null });
+            inner["blanket_impl"] = serde_json::json!({ "generic": "T" });
+        }
+        for (id, name, kind) in [
+            ("1", "A", "struct"),
+            ("3", "B", "struct"),
+            ("5", "Show", "trait"),
+        ] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": kind
+            });
+        }
+        let krate = parse_rustdoc_lenient(&value.to_string()).expect("fixture parses");
+        let build = |blanket_impls| {
+            build_graph(
+                &krate,
+                "fixture",
+                BuildGraphOptions {
+                    workspace_members: Some(HashSet::from(["fixture".to_string()])),
+                    extract: ExtractOptions {
+                        blanket_impls,
+                        ..ExtractOptions::default()
+                    },
+                    ..Default::default()
+                },
+            )
+            .expect("fixture graph builds")
+        };
+        let show_nodes = |graph: &Graph| {
+            graph
+                .nodes
+                .iter()
+                .filter(|node| node.name == "show")
+                .map(|node| node.id.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(show_nodes(&build(BlanketImpls::PerImpl)).len(), 2);
+
+        let collapsed = build(BlanketImpls::Collapse);
+        assert_eq!(show_nodes(&collapsed), ["fixture::Show::blanket::show"]);
+        let shared = collapsed
+            .nodes
+            .iter()
+            .find(|node| node.id == "fixture::Show::blanket::show")
+            .expect("shared node");
+        assert_eq!(shared.parent_impl, None);
+        assert!(!shared.is_external);
+        assert_eq!(
+            collapsed
+                .edges
+                .iter()
+                .filter(|edge| {
+                    edge.to == "fixture::Show::blanket::show" && edge.kind == EdgeKind::Defines
+                })
+                .count(),
+            2
+        );
+
+        let skipped = build(BlanketImpls::Skip);
+        assert!(show_nodes(&skipped).is_empty());
+        assert!(!skipped.nodes.iter().any(|node| node.kind == NodeKind::Impl));
+        for graph in [&collapsed, &skipped] {
+            assert!(graph.edges.iter().all(|edge| {
+                graph.nodes.iter().any(|node| node.id == edge.from)
+                    && graph.nodes.iter().any(|node| node.id == edge.to)
+            }));
+        }
+    }
+
+    #[test]
+    fn doc_hidden_items_are_dropped_unless_included() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3]);
+        value["index"]["1"] = rustdoc_function_item(1, "hidden");
+        value["index"]["1"]["attrs"] = serde_json::json!([{ "other": "#[doc(hidden)]" }]);
+        value["index"]["2"] = rustdoc_function_item(2, "__shown");
+        value["index"]["3"] = rustdoc_function_item(3, "helper");
+        for (id, path) in [
+            ("1", vec!["fixture", "hidden"]),
+            ("2", vec!["fixture", "__shown"]),
+            // Re-exported out of a hidden module rustdoc stripped from the
+            // index, so only the `__` prefix marks it.
+            ("3", vec!["fixture", "__private", "helper"]),
+        ] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": path,
+                "kind": "function"
+            });
+        }
+        let provider =
+            MemorySourceProvider::new(HashMap::from([("src/lib.rs".to_string(), String::new())]));
+        let functions = |include_hidden| {
+            let graph = extract_graph_with_source_provider(
+                &value.to_string(),
+                "fixture",
+                &provider,
+                "src/lib.rs",
+                CallMode::Strict,
+                &ExtractOptions {
+                    include_hidden,
+                    ..ExtractOptions::default()
+                },
+            )
+            .expect("fixture graph builds");
+            let mut ids: Vec<String> = graph
+                .nodes
+                .iter()
+                .filter(|node| node.kind == NodeKind::Function)
+                .map(|node| node.id.clone())
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        assert_eq!(functions(false), ["fixture::__shown"]);
+        assert_eq!(
+            functions(true),
+            [
+                "fixture::__private::helper",
+                "fixture::__shown",
+                "fixture::hidden"
+            ]
+        );
+    }
+
+    #[test]
+    fn doctest_code_blocks_unhide_lines_and_skip_non_rust() {
+        let docs = "Intro.\n\n```\n# use fixture::helper;\nhelper();\n##[attr]\n```\n\n\
+                    ```ignore\nbroken(\n```\n\n~~~text\nnot rust\n~~~\n\n\
+                    ````rust,no_run\n```\nfixture::run();\n````";
+        assert_eq!(
+            doctest_code_blocks(docs),
+            [
+                "use fixture::helper;\nhelper();\n#[attr]\n",
+                "```\nfixture::run();\n"
+            ]
+        );
+    }
+
+    #[test]
+    fn doctest_calls_link_documented_items_to_their_examples() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3]);
+        value["index"]["1"] = rustdoc_function_item(1, "make");
+        value["index"]["1"]["docs"] = serde_json::json!(
+            "```\n# fn main() {\nlet w = fixture::make();\nfixture::helper(w);\n# }\n```\n\n\
+             ```ignore\nfixture::other();\n```"
+        );
+        value["index"]["2"] = rustdoc_function_item(2, "helper");
+        value["index"]["3"] = rustdoc_function_item(3, "other");
+        for (id, name) in [("1", "make"), ("2", "helper"), ("3", "other")] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": "function"
+            });
+        }
+        let krate = parse_rustdoc_lenient(&value.to_string()).expect("fixture parses");
+        let build = |doctest_calls| {
+            build_graph(
+                &krate,
+                "fixture",
+                BuildGraphOptions {
+                    workspace_members: Some(HashSet::from(["fixture".to_string()])),
+                    extract: ExtractOptions {
+                        doctest_calls,
+                        ..ExtractOptions::default()
+                    },
+                    ..Default::default()
+                },
+            )
+            .expect("fixture graph builds")
+        };
+
+        assert!(!build(false).edges.iter().any(|edge| edge.kind.is_call()));
+
+        let graph = build(true);
+        let calls: Vec<(&str, &str, Confidence)> = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.kind == EdgeKind::CallsStatic)
+            .map(|edge| (edge.from.as_str(), edge.to.as_str(), edge.confidence))
+            .collect();
+        assert_eq!(
+            calls,
+            [("fixture::make", "fixture::helper", Confidence::Inferred)]
+        );
+    }
+
+    #[test]
+    fn union_field_types_link_to_local_structs() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2]);
+        value["index"]["1"] = rustdoc_item(
+            1,
+            0,
+            "Raw",
+            serde_json::json!({ "union": {
+                "generics": empty_generics(),
+                "has_stripped_fields": false,
+                "fields": [3],
+                "impls": []
+            }}),
+        );
+        value["index"]["1"]["attrs"] = serde_json::json!([
+            { "repr": { "kind": "c", "align": null, "packed": null, "int": null } }
+        ]);
+        value["index"]["2"] = rustdoc_struct_item(2, "Header", Vec::new());
+        value["index"]["3"] = rustdoc_item(
+            3,
+            0,
+            "header",
+            serde_json::json!({ "struct_field": {
+                "resolved_path": { "path": "Header", "id": 2, "args": null }
+            }}),
+        );
+        for (id, name, kind) in [("1", "Raw", "union"), ("2", "Header", "struct")] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": kind
+            });
+        }
+
+        let graph = extract_graph(&value.to_string(), "fixture").expect("fixture graph builds");
+        let raw = graph
+            .nodes
+            .iter()
+            .find(|node| node.id == "fixture::Raw")
+            .expect("union node");
+        assert_eq!(raw.attrs, ["#[repr(C)]"]);
+        let fields = raw.fields.as_deref().expect("union fields");
+        assert_eq!(fields.len(), 1);
+        assert!(matches!(
+            &fields[0].type_,
+            TypeRef::ResolvedPath { id, path, .. } if id == "2" && path == "Header"
+        ));
+        assert!(graph.edges.iter().any(|edge| {
+            edge.from == "fixture::Raw"
+                && edge.to == "fixture::Header"
+                && edge.kind == EdgeKind::UsesType
+        }));
+    }
+
+    #[test]
+    fn impls_for_a_bare_type_parameter_are_blanket() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2]);
+        value["index"]["1"] = rustdoc_trait_item(1, "Describe", Vec::new());
+        value["index"]["2"] = rustdoc_impl_item(2, 0, "", Vec::new());
+        let impl_block = &mut value["index"]["2"]["inner"]["impl"];
+        impl_block["trait"] = serde_json::json!({ "path": "Describe", "id": 1, "args": null });
+        impl_block["for"] = serde_json::json!({ "generic": "T" });
+        impl_block["generics"]["params"] = serde_json::json!([{
+            "name": "T",
+            "kind": { "type": { "bounds": [], "default": null, "is_synthetic": false } }
+        }]);
+        value["paths"]["1"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "Describe"],
+            "kind": "trait"
+        });
+
+        let graph = extract_graph(&value.to_string(), "fixture").expect("fixture graph builds");
+        let impl_node = graph
+            .nodes
+            .iter()
+            .find(|node| node.id == "fixture::impl-2")
+            .expect("impl node");
+        assert_eq!(impl_node.impl_type, Some(ImplType::Trait));
+        assert_eq!(impl_node.impl_category, Some(ImplCategory::Blanket));
+    }
+
+    #[test]
+    fn impls_on_primitives_hang_off_primitive_nodes() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3]);
+        value["index"]["1"] = rustdoc_trait_item(1, "Describe", Vec::new());
+        let trait_ref = serde_json::json!({ "path": "Describe", "id": 1, "args": null });
+        value["index"]["2"] = rustdoc_impl_item(2, 0, "", Vec::new());
+        value["index"]["2"]["inner"]["impl"]["trait"] = trait_ref.clone();
+        value["index"]["2"]["inner"]["impl"]["for"] = serde_json::json!({ "primitive": "u32" });
+        value["index"]["3"] = rustdoc_impl_item(3, 0, "", Vec::new());
+        value["index"]["3"]["inner"]["impl"]["trait"] = trait_ref;
+        value["index"]["3"]["inner"]["impl"]["for"] = serde_json::json!({ "borrowed_ref": {
+            "lifetime": null,
+            "is_mutable": false,
+            "type": { "slice": { "generic": "T" } }
+        }});
+        value["paths"]["1"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "Describe"],
+            "kind": "trait"
+        });
+
+        let graph = extract_graph(&value.to_string(), "fixture").expect("fixture graph builds");
+        let has_edge = |from: &str, to: &str, kind: EdgeKind| {
+            graph
+                .edges
+                .iter()
+                .any(|edge| edge.from == from && edge.to == to && edge.kind == kind)
+        };
+        for (primitive, impl_id, name) in [
+            ("core::u32", "fixture::impl-2", "impl Describe for u32"),
+            ("core::slice", "fixture::impl-3", "impl Describe for slice"),
+        ] {
+            let node = graph
+                .nodes
+                .iter()
+                .find(|node| node.id == primitive)
+                .unwrap_or_else(|| panic!("missing primitive node {primitive}"));
+            assert_eq!(node.kind, NodeKind::Primitive);
+            assert!(node.is_external);
+            assert!(has_edge(primitive, impl_id, EdgeKind::Defines));
+            assert!(has_edge(
+                primitive,
+                "fixture::Describe",
+                EdgeKind::Implements
+            ));
+            let impl_node = graph.nodes.iter().find(|node| node.id == impl_id);
+            assert_eq!(impl_node.map(|node| node.name.as_str()), Some(name));
+        }
+    }
+
+    #[test]
+    fn impls_of_unresolvable_external_traits_link_to_stub_traits() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3]);
+        value["index"]["1"] = rustdoc_struct_item(1, "Config", vec![2, 3]);
+        value["index"]["2"] = rustdoc_impl_item(2, 1, "Config", Vec::new());
+        value["index"]["2"]["inner"]["impl"]["trait"] =
+            serde_json::json!({ "path": "serde::Serialize", "id": 40, "args": null });
+        value["index"]["3"] = rustdoc_impl_item(3, 1, "Config", Vec::new());
+        value["index"]["3"]["inner"]["impl"]["trait"] =
+            serde_json::json!({ "path": "Deserialize", "id": 41, "args": null });
+        value["paths"]["1"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "Config"],
+            "kind": "struct"
+        });
+
+        let graph = extract_graph(&value.to_string(), "fixture").expect("fixture graph builds");
+        let stub = graph
+            .nodes
+            .iter()
+            .find(|node| node.id == "serde::Serialize")
+            .expect("stub trait node");
+        assert_eq!(stub.kind, NodeKind::Trait);
+        assert!(stub.is_external);
+        assert!(
+            graph
+                .nodes
+                .iter()
+                .any(|node| node.id == "serde" && node.kind == NodeKind::Crate && node.is_external)
+        );
+        assert!(graph.edges.iter().any(|edge| {
+            edge.from == "fixture::Config"
+                && edge.to == "serde::Serialize"
+                && edge.kind == EdgeKind::Implements
+        }));
+        let impl_node = graph
+            .nodes
+            .iter()
+            .find(|node| node.id == "fixture::impl-2")
+            .expect("impl node");
+        assert_eq!(impl_node.impl_trait.as_deref(), Some("serde::Serialize"));
+        // A bare path doesn't name its crate, so it gets no stub.
+        assert!(!graph.nodes.iter().any(|node| node.name == "Deserialize"));
+    }
+
+    #[test]
+    fn graph_metrics_summarize_kinds_calls_and_module_depth() {
+        let mut graph = Graph::new();
+        for (id, kind) in [
+            ("fixture", NodeKind::Crate),
+            ("fixture::a", NodeKind::Module),
+            ("fixture::a::b", NodeKind::Module),
+            ("fixture::a::b::run", NodeKind::Function),
+            ("fixture::a::step", NodeKind::Function),
+            ("fixture::main", NodeKind::Function),
+        ] {
+            graph.add_node(test_node(id, kind));
+        }
+        let mut clone = test_node("core::clone::Clone", NodeKind::Trait);
+        clone.is_external = true;
+        graph.add_node(clone);
+        for (from, to) in [
+            ("fixture", "fixture::a"),
+            ("fixture::a", "fixture::a::b"),
+            ("fixture::a::b", "fixture::a::b::run"),
+            ("fixture::a", "fixture::a::step"),
+            ("fixture", "fixture::main"),
+        ] {
+            graph.add_edge(test_edge(from, to, EdgeKind::Contains));
+        }
+        graph.add_edge(test_edge(
+            "fixture::main",
+            "fixture::a::b::run",
+            EdgeKind::CallsStatic,
+        ));
+        graph.add_edge(test_edge(
+            "fixture::main",
+            "fixture::a::step",
+            EdgeKind::CallsStatic,
+        ));
+        let mut inferred = test_edge(
+            "fixture::a::step",
+            "fixture::a::b::run",
+            EdgeKind::CallsStatic,
+        );
+        inferred.confidence = Confidence::Inferred;
+        graph.add_edge(inferred);
+        let mut runtime = test_edge("fixture::main", "fixture::a::step", EdgeKind::CallsRuntime);
+        runtime.confidence = Confidence::Runtime;
+        graph.add_edge(runtime);
+
+        let metrics = graph.graph_metrics();
+
+        assert_eq!(metrics.nodes.get(&NodeKind::Function), Some(&3));
+        assert_eq!(metrics.nodes.get(&NodeKind::Module), Some(&2));
+        assert_eq!(metrics.external_nodes, 1);
+        assert_eq!(metrics.edges.get(&EdgeKind::Contains), Some(&5));
+        assert_eq!(metrics.edges.get(&EdgeKind::CallsStatic), Some(&3));
+        assert_eq!(metrics.confidence.get(&Confidence::Static), Some(&7));
+        assert_eq!(metrics.confidence.get(&Confidence::Inferred), Some(&1));
+        // `main` reaches `step` both statically and at runtime: one callee.
+        assert_eq!(metrics.fan_out.max, 2);
+        assert_eq!(metrics.fan_out.max_id.as_deref(), Some("fixture::main"));
+        assert_eq!(metrics.fan_in.max, 2);
+        assert_eq!(metrics.fan_in.max_id.as_deref(), Some("fixture::a::b::run"));
+        assert!((metrics.fan_in.average - 1.0).abs() < f64::EPSILON);
+        assert_eq!(metrics.max_module_depth, 2);
+    }
+
+    #[test]
+    fn validate_reports_dangling_duplicate_empty_looping_and_orphan_nodes() {
+        use codeview_core::GraphWarning;
+
+        let mut graph = Graph::new();
+        for (id, kind) in [
+            ("fixture", NodeKind::Crate),
+            ("fixture::run", NodeKind::Function),
+            ("fixture::run", NodeKind::Function),
+            ("fixture::Lonely", NodeKind::Struct),
+            ("", NodeKind::Function),
+        ] {
+            graph.add_node(test_node(id, kind));
+        }
+        graph.add_edge(test_edge("fixture", "fixture::run", EdgeKind::Contains));
+        graph.add_edge(test_edge(
+            "fixture::run",
+            "fixture::run",
+            EdgeKind::CallsStatic,
+        ));
+        graph.add_edge(test_edge(
+            "fixture",
+            "fixture::Missing",
+            EdgeKind::ReExports,
+        ));
+
+        let warnings = graph.validate();
+
+        assert_eq!(
+            warnings,
+            vec![
+                GraphWarning::DuplicateNodeId {
+                    id: "fixture::run".to_string(),
+                },
+                GraphWarning::EmptyNodeId {
+                    name: graph.nodes[4].name.clone(),
+                },
+                GraphWarning::SelfLoop {
+                    id: "fixture::run".to_string(),
+                    edge_kind: EdgeKind::CallsStatic,
+                },
+                GraphWarning::DanglingEdge {
+                    from: "fixture".to_string(),
+                    to: "fixture::Missing".to_string(),
+                    edge_kind: EdgeKind::ReExports,
+                    missing: "fixture::Missing".to_string(),
+                },
+                GraphWarning::OrphanNode {
+                    id: "fixture::Lonely".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            warnings[3].to_string(),
+            "dangling ReExports edge fixture -> fixture::Missing: no node fixture::Missing"
+        );
+        assert!(Graph::new().validate().is_empty());
+    }
+
+    #[test]
+    fn module_tree_nests_contains_edges_by_kind_then_name() {
+        let mut graph = Graph::new();
+        for (id, kind) in [
+            ("fixture", NodeKind::Crate),
+            ("fixture::zeta", NodeKind::Module),
+            ("fixture::alpha", NodeKind::Module),
+            ("fixture::alpha::inner", NodeKind::Module),
+            ("fixture::alpha::Point", NodeKind::Struct),
+            ("fixture::alpha::run", NodeKind::Function),
+            ("fixture::alpha::inner::deep", NodeKind::Function),
+            ("fixture::main", NodeKind::Function),
+        ] {
+            graph.add_node(test_node(id, kind));
+        }
+        for (from, to) in [
+            ("fixture", "fixture::zeta"),
+            ("fixture", "fixture::main"),
+            ("fixture", "fixture::alpha"),
+            ("fixture::alpha", "fixture::alpha::run"),
+            ("fixture::alpha", "fixture::alpha::Point"),
+            ("fixture::alpha", "fixture::alpha::inner"),
+            ("fixture::alpha::inner", "fixture::alpha::inner::deep"),
+            // A second parent, and a cycle back to the root: both ignored.
+            ("fixture::zeta", "fixture::alpha::run"),
+            ("fixture::alpha::inner", "fixture"),
+        ] {
+            graph.add_edge(test_edge(from, to, EdgeKind::Contains));
+        }
+        graph.add_edge(test_edge(
+            "fixture::main",
+            "fixture::alpha::run",
+            EdgeKind::CallsStatic,
+        ));
+        fn render(tree: &codeview_core::TreeNode, depth: usize, out: &mut Vec<String>) {
+            let marker = if tree.truncated { " …" } else { "" };
+            out.push(format!("{}{}{marker}", "  ".repeat(depth), tree.name));
+            for child in &tree.children {
+                render(child, depth + 1, out);
+            }
+        }
+        let rendered = |max_depth| {
+            let mut out = Vec::new();
+            render(
+                &graph.module_tree(max_depth).expect("crate root"),
+                0,
+                &mut out,
+            );
+            out
+        };
+
+        assert_eq!(
+            rendered(None),
+            [
+                "fixture",
+                "  alpha",
+                "    inner",
+                "      deep",
+                "    Point",
+                "    run",
+                "  zeta",
+                "  main",
+            ]
+        );
+        assert_eq!(
+            rendered(Some(1)),
+            ["fixture", "  alpha …", "  zeta", "  main"]
+        );
+        assert!(Graph::new().module_tree(None).is_none());
+    }
+
+    #[test]
+    fn crate_versions_come_from_rustdoc_metadata() {
+        let mut value = minimal_rustdoc_value("drizzle-core");
+        value["crate_version"] = serde_json::json!("0.1.4");
+        value["external_crates"] = serde_json::json!({
+            "1": { "name": "serde", "html_root_url": "https://docs.rs/serde/1.0.197/", "path": "" },
+            "2": { "name": "tokio", "html_root_url": "https://docs.rs/tokio/latest/", "path": "" },
+            "3": { "name": "std", "html_root_url": "https://doc.rust-lang.org/nightly/", "path": "" },
+            "4": { "name": "anyhow", "html_root_url": null, "path": "" }
+        });
+
+        let graph =
+            extract_graph(&value.to_string(), "drizzle-core").expect("fixture graph builds");
+
+        assert_eq!(
+            graph.crate_versions,
+            HashMap::from([
+                ("drizzle_core".to_string(), "0.1.4".to_string()),
+                ("serde".to_string(), "1.0.197".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn heuristic_mode_dispatches_dyn_calls_to_every_impl() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3, 6]);
+        value["index"]["1"] = rustdoc_trait_item(1, "Shape", vec![10, 11]);
+        value["index"]["2"] = rustdoc_struct_item(2, "Square", vec![4]);
+        value["index"]["3"] = rustdoc_struct_item(3, "Circle", vec![5]);
+        let trait_ref = serde_json::json!({ "path": "Shape", "id": 1, "args": null });
+        value["index"]["4"] = rustdoc_impl_item(4, 2, "Square", vec![12]);
+        value["index"]["4"]["inner"]["impl"]["trait"] = trait_ref.clone();
+        value["index"]["4"]["inner"]["impl"]["provided_trait_methods"] =
+            serde_json::json!(["name"]);
+        value["index"]["5"] = rustdoc_impl_item(5, 3, "Circle", vec![13, 14]);
+        value["index"]["5"]["inner"]["impl"]["trait"] = trait_ref;
+        value["index"]["6"] = rustdoc_function_item(6, "describe");
+        for (id, name) in [
+            (10, "area"),
+            (11, "name"),
+            (12, "area"),
+            (13, "area"),
+            (14, "name"),
+        ] {
+            value["index"][id.to_string()] = rustdoc_function_item(id, name);
+        }
+        for (id, name, kind) in [
+            ("1", "Shape", "trait"),
+            ("2", "Square", "struct"),
+            ("3", "Circle", "struct"),
+            ("6", "describe", "function"),
+        ] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": kind
+            });
+        }
+        let source = "pub fn describe(shape: &dyn Shape) {\n    shape.area();\n    \
+                      let boxed: Box<dyn Shape> = make();\n    boxed.name();\n    \
+                      let boxed = 1;\n    boxed.area();\n}\n";
+        let files = HashMap::from([("src/lib.rs".to_string(), source.to_string())]);
+        let json = value.to_string();
+
+        let graph = extract_graph_with_source_map(
+            &json,
+            "fixture",
+            files.clone(),
+            "src/lib.rs",
+            CallMode::Heuristic,
+        )
+        .expect("fixture graph builds");
+        let mut calls: Vec<(&str, usize)> = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.from == "fixture::describe")
+            .filter(|edge| {
+                edge.kind == EdgeKind::CallsRuntime && edge.confidence == Confidence::Runtime
+            })
+            .map(|edge| (edge.to.as_str(), edge.occurrences[0].line as usize))
+            .collect();
+        calls.sort();
+        let name_of = |id: &str| {
+            let node = graph.nodes.iter().find(|node| node.id == id).unwrap();
+            (node.name.clone(), node.parent_impl.clone())
+        };
+        let described: Vec<_> = calls
+            .iter()
+            .map(|(id, line)| (name_of(id), *line))
+            .collect();
+        assert_eq!(
+            described,
+            vec![
+                (("name".to_string(), None), 4),
+                (("area".to_string(), Some("fixture::impl-4".to_string())), 2),
+                (("area".to_string(), Some("fixture::impl-5".to_string())), 2),
+                (("name".to_string(), Some("fixture::impl-5".to_string())), 4),
+            ]
+        );
+
+        let strict =
+            extract_graph_with_source_map(&json, "fixture", files, "src/lib.rs", CallMode::Strict)
+                .expect("fixture graph builds");
+        assert!(
+            strict
+                .edges
+                .iter()
+                .all(|edge| edge.kind != EdgeKind::CallsRuntime)
+        );
+    }
+
+    #[test]
+    fn qualified_paths_resolve_through_their_self_type_or_trait() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 6, 8]);
+        value["index"]["1"] = rustdoc_trait_item(1, "Shape", vec![10]);
+        value["index"]["2"] = rustdoc_struct_item(2, "Square", vec![7]);
+        value["index"]["7"] = rustdoc_impl_item(7, 2, "Square", vec![15]);
+        value["index"]["6"] = rustdoc_function_item(6, "describe");
+        value["index"]["8"] = rustdoc_function_item(8, "len");
+        for (id, name) in [(10, "area"), (15, "new")] {
+            value["index"][id.to_string()] = rustdoc_function_item(id, name);
+        }
+        for (id, path, kind) in [
+            ("1", vec!["fixture", "Shape"], "trait"),
+            ("2", vec!["fixture", "Square"], "struct"),
+            ("6", vec!["fixture", "describe"], "function"),
+            ("8", vec!["fixture", "len"], "function"),
+            ("10", vec!["fixture", "Shape", "area"], "function"),
+            ("15", vec!["fixture", "Square", "new"], "function"),
+        ] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": path,
+                "kind": kind
+            });
+        }
+        let source = "pub fn describe() {\n    <Square as Shape>::area();\n    \
+                      <Square>::new();\n    <Vec<u8> as Default>::default();\n    \
+                      <[u8]>::len(&[]);\n}\npub fn len() {}\n";
+        let provider = MemorySourceProvider::new(HashMap::from([(
+            "src/lib.rs".to_string(),
+            source.to_string(),
+        )]));
+
+        let (graph, unresolved) = extract_graph_with_diagnostics(
+            &value.to_string(),
+            "fixture",
+            &provider,
+            "src/lib.rs",
+            CallMode::Strict,
+            &ExtractOptions::default(),
+        )
+        .expect("fixture graph builds");
+        let mut calls: Vec<&str> = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.from == "fixture::describe" && edge.kind.is_call())
+            .map(|edge| edge.to.as_str())
+            .collect();
+        calls.sort_unstable();
+        assert_eq!(calls, ["fixture::Shape::area", "fixture::Square::new"]);
+        // `<[u8]>::len` isn't taken for the free `len`, and the std trait
+        // call is reported under the trait's path.
+        let names: Vec<&str> = unresolved.iter().map(|call| call.name.as_str()).collect();
+        assert_eq!(names, ["Default::default"]);
+    }
+
+    #[test]
+    fn field_edges_are_labelled_with_the_field_name() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3]);
+        value["index"]["1"] = rustdoc_struct_item(1, "Line", Vec::new());
+        value["index"]["1"]["inner"]["struct"]["kind"] =
+            serde_json::json!({ "plain": { "fields": [4, 5], "has_stripped_fields": false } });
+        value["index"]["2"] = rustdoc_struct_item(2, "Wrap", Vec::new());
+        value["index"]["2"]["inner"]["struct"]["kind"] = serde_json::json!({ "tuple": [6] });
+        value["index"]["3"] = rustdoc_struct_item(3, "Point", Vec::new());
+        let point = serde_json::json!({ "struct_field": {
+            "resolved_path": { "path": "Point", "id": 3, "args": null }
+        }});
+        value["index"]["4"] = rustdoc_item(4, 0, "start", point.clone());
+        value["index"]["5"] = rustdoc_item(5, 0, "end", point.clone());
+        value["index"]["6"] = rustdoc_item(6, 0, "0", point);
+        for (id, name) in [("1", "Line"), ("2", "Wrap"), ("3", "Point")] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": "struct"
+            });
+        }
+
+        let graph = extract_graph(&value.to_string(), "fixture").expect("fixture graph builds");
+        let mut fields: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.kind == EdgeKind::HasField)
+            .map(|edge| {
+                assert_eq!(edge.to, "fixture::Point");
+                (
+                    edge.from.as_str(),
+                    edge.label.as_deref().expect("field label"),
+                )
+            })
+            .collect();
+        fields.sort();
+        assert_eq!(
+            fields,
+            [
+                ("fixture::Line", "end"),
+                ("fixture::Line", "start"),
+                ("fixture::Wrap", "0")
+            ]
+        );
+        let uses = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.from == "fixture::Line" && edge.kind == EdgeKind::UsesType)
+            .count();
+        assert_eq!(uses, 1);
+    }
+
+    #[test]
+    fn dyn_trait_uses_are_labelled_apart_from_concrete_ones() {
+        let boxed_dyn = |path: &str, id: u32| {
+            serde_json::json!({ "resolved_path": {
+                "path": "Box",
+                "id": 10,
+                "args": { "angle_bracketed": { "args": [{ "type": { "dyn_trait": {
+                    "traits": [{
+                        "trait": { "path": path, "id": id, "args": null },
+                        "generic_params": []
+                    }],
+                    "lifetime": null
+                } } }], "constraints": [] } }
+            } })
+        };
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3, 4, 5]);
+        value["index"]["1"] = rustdoc_trait_item(1, "Error", Vec::new());
+        value["index"]["2"] = rustdoc_trait_item(2, "Sink", Vec::new());
+        value["index"]["3"] = rustdoc_struct_item(3, "Config", Vec::new());
+        value["index"]["4"] = rustdoc_function_item(4, "report");
+        value["index"]["4"]["inner"]["function"]["sig"]["inputs"] = serde_json::json!([
+            ["err", boxed_dyn("Error", 1)],
+            ["config", { "borrowed_ref": {
+                "lifetime": null,
+                "is_mutable": false,
+                "type": { "resolved_path": { "path": "Config", "id": 3, "args": null } }
+            } }]
+        ]);
+        value["index"]["4"]["inner"]["function"]["sig"]["output"] = boxed_dyn("Sink", 2);
+        value["index"]["5"] = rustdoc_struct_item(5, "Handler", Vec::new());
+        value["index"]["5"]["inner"]["struct"]["kind"] = serde_json::json!({ "tuple": [6] });
+        value["index"]["6"] = rustdoc_item(
+            6,
+            0,
+            "0",
+            serde_json::json!({ "struct_field": boxed_dyn("Error", 1) }),
+        );
+        for (id, name, kind) in [
+            ("1", "Error", "trait"),
+            ("2", "Sink", "trait"),
+            ("3", "Config", "struct"),
+            ("4", "report", "function"),
+            ("5", "Handler", "struct"),
+        ] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": kind
+            });
+        }
+
+        let graph = extract_graph(&value.to_string(), "fixture").expect("fixture graph builds");
+        let mut uses: Vec<(&str, &str, EdgeKind, Option<&str>)> = graph
+            .edges
+            .iter()
+            .filter(|edge| matches!(edge.kind, EdgeKind::UsesType | EdgeKind::Returns))
+            .map(|edge| {
+                (
+                    edge.from.as_str(),
+                    edge.to.as_str(),
+                    edge.kind,
+                    edge.label.as_deref(),
+                )
+            })
+            .collect();
+        uses.sort();
+        assert_eq!(
+            uses,
+            [
+                (
+                    "fixture::Handler",
+                    "fixture::Error",
+                    EdgeKind::UsesType,
+                    Some("dyn")
+                ),
+                (
+                    "fixture::report",
+                    "fixture::Config",
+                    EdgeKind::UsesType,
+                    None
+                ),
+                (
+                    "fixture::report",
+                    "fixture::Error",
+                    EdgeKind::UsesType,
+                    Some("dyn")
+                ),
+                (
+                    "fixture::report",
+                    "fixture::Sink",
+                    EdgeKind::Returns,
+                    Some("dyn")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn generic_arguments_are_labelled_apart_from_direct_uses() {
+        let table_of = |args: serde_json::Value| {
+            serde_json::json!({ "resolved_path": {
+                "path": "Table",
+                "id": 1,
+                "args": { "angle_bracketed": { "args": args, "constraints": [] } }
+            } })
+        };
+        let path_to = |path: &str, id: u32| serde_json::json!({ "type": { "resolved_path": { "path": path, "id": id, "args": null } } });
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3, 4, 5]);
+        value["index"]["1"] = rustdoc_struct_item(1, "Table", Vec::new());
+        value["index"]["2"] = rustdoc_struct_item(2, "Key", Vec::new());
+        value["index"]["3"] = rustdoc_struct_item(3, "Value", Vec::new());
+        // `struct Cache { map: Table<Key, Value>, last: Key }`
+        value["index"]["4"] = rustdoc_struct_item(4, "Cache", Vec::new());
+        value["index"]["4"]["inner"]["struct"]["kind"] = serde_json::json!({ "tuple": [6, 7] });
+        value["index"]["6"] = rustdoc_item(
+            6,
+            0,
+            "map",
+            serde_json::json!({ "struct_field": table_of(serde_json::json!([
+                path_to("Key", 2),
+                path_to("Value", 3)
+            ])) }),
+        );
+        value["index"]["7"] = rustdoc_item(
+            7,
+            0,
+            "last",
+            serde_json::json!({ "struct_field": path_to("Key", 2)["type"] }),
+        );
+        // `fn values() -> Table<Value>`
+        value["index"]["5"] = rustdoc_function_item(5, "values");
+        value["index"]["5"]["inner"]["function"]["sig"]["output"] =
+            table_of(serde_json::json!([path_to("Value", 3)]));
+        for (id, name, kind) in [
+            ("1", "Table", "struct"),
+            ("2", "Key", "struct"),
+            ("3", "Value", "struct"),
+            ("4", "Cache", "struct"),
+            ("5", "values", "function"),
+        ] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": kind
+            });
+        }
+
+        let graph = extract_graph(&value.to_string(), "fixture").expect("fixture graph builds");
+        let mut uses: Vec<(&str, &str, EdgeKind, Option<&str>)> = graph
+            .edges
+            .iter()
+            .filter(|edge| matches!(edge.kind, EdgeKind::UsesType | EdgeKind::Returns))
+            .map(|edge| {
+                (
+                    edge.from.as_str(),
+                    edge.to.as_str(),
+                    edge.kind,
+                    edge.label.as_deref(),
+                )
+            })
+            .collect();
+        uses.sort();
+        assert_eq!(
+            uses,
+            [
+                ("fixture::Cache", "fixture::Key", EdgeKind::UsesType, None),
+                ("fixture::Cache", "fixture::Table", EdgeKind::UsesType, None),
+                (
+                    "fixture::Cache",
+                    "fixture::Value",
+                    EdgeKind::UsesType,
+                    Some("generic")
+                ),
+                ("fixture::values", "fixture::Table", EdgeKind::Returns, None),
+                (
+                    "fixture::values",
+                    "fixture::Value",
+                    EdgeKind::Returns,
+                    Some("generic")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn async_trait_desugaring_is_flagged_on_the_signature() {
+        // What rustdoc emits for `async fn get(&self) -> Option<String>` under
+        // `#[async_trait]`: the attribute is gone and `is_async` is false.
+        let boxed_future = |lifetime: &str| {
+            let wrap = |path: &str, id: u32, arg: serde_json::Value| {
+                serde_json::json!({ "resolved_path": {
+                    "path": path,
+                    "id": id,
+                    "args": { "angle_bracketed": { "args": [{ "type": arg }], "constraints": [] } }
+                } })
+            };
+            let future = serde_json::json!({
+                "path": "::core::future::Future",
+                "id": 12,
+                "args": { "angle_bracketed": { "args": [], "constraints": [{
+                    "name": "Output",
+                    "args": null,
+                    "binding": { "equality": { "type": { "primitive": "u32" } } }
+                }] } }
+            });
+            let send =
+                serde_json::json!({ "path": "::core::marker::Send", "id": 13, "args": null });
+            let dyn_future = serde_json::json!({ "dyn_trait": {
+                "traits": [
+                    { "trait": future, "generic_params": [] },
+                    { "trait": send, "generic_params": [] }
+                ],
+                "lifetime": lifetime
+            } });
+            wrap("::core::pin::Pin", 10, wrap("Box", 11, dyn_future))
+        };
+
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2]);
+        value["index"]["1"] = rustdoc_function_item(1, "get");
+        value["index"]["1"]["inner"]["function"]["sig"]["output"] = boxed_future("'async_trait");
+        value["index"]["1"]["inner"]["function"]["generics"] = serde_json::json!({
+            "params": [
+                { "name": "'life0", "kind": { "lifetime": { "outlives": [] } } },
+                { "name": "'async_trait", "kind": { "lifetime": { "outlives": [] } } }
+            ],
+            "where_predicates": [
+                { "lifetime_predicate": { "lifetime": "'life0", "outlives": ["'async_trait"] } }
+            ]
+        });
+        // A hand-written boxed future without the macro's lifetime is not
+        // reported.
+        value["index"]["2"] = rustdoc_function_item(2, "boxed");
+        value["index"]["2"]["inner"]["function"]["sig"]["output"] = boxed_future("'static");
+        for (id, name) in [("1", "get"), ("2", "boxed")] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0, "path": ["fixture", name], "kind": "function"
+            });
+        }
+
+        let graph = extract_graph(&value.to_string(), "fixture").expect("fixture graph builds");
+        let signature = |id: &str| {
+            graph
+                .nodes
+                .iter()
+                .find(|node| node.id == id)
+                .and_then(|node| node.signature.clone())
+                .expect("function has a signature")
+        };
+
+        let get = signature("fixture::get");
+        assert!(get.is_async_trait);
+        assert!(!get.is_async);
+        assert!(!signature("fixture::boxed").is_async_trait);
+    }
+
+    #[test]
+    fn trait_bounds_emit_supertrait_edges() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3, 4]);
+        value["index"]["1"] = rustdoc_trait_item(1, "Service", Vec::new());
+        value["index"]["1"]["inner"]["trait"]["bounds"] = serde_json::json!([
+            {
+                "trait_bound": {
+                    "trait": { "path": "Base", "id": 2, "args": null },
+                    "generic_params": [],
+                    "modifier": "none"
+                }
+            },
+            {
+                "trait_bound": {
+                    "trait": {
+                        "path": "Convert",
+                        "id": 3,
+                        "args": {
+                            "angle_bracketed": {
+                                "args": [{
+                                    "type": {
+                                        "resolved_path": { "path": "Config", "id": 4, "args": null }
+                                    }
+                                }],
+                                "constraints": []
+                            }
+                        }
+                    },
+                    "generic_params": [],
+                    "modifier": "none"
+                }
+            }
+        ]);
+        value["index"]["2"] = rustdoc_trait_item(2, "Base", Vec::new());
+        value["index"]["3"] = rustdoc_trait_item(3, "Convert", Vec::new());
+        value["index"]["4"] = rustdoc_struct_item(4, "Config", Vec::new());
+        for (id, name, kind) in [
+            ("1", "Service", "trait"),
+            ("2", "Base", "trait"),
+            ("3", "Convert", "trait"),
+            ("4", "Config", "struct"),
+        ] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": kind
+            });
+        }
+
+        let graph = extract_graph(&value.to_string(), "fixture").expect("fixture graph builds");
+        let has_edge = |to: &str, kind: EdgeKind| {
+            graph
+                .edges
+                .iter()
+                .any(|edge| edge.from == "fixture::Service" && edge.to == to && edge.kind == kind)
+        };
+
+        assert!(has_edge("fixture::Base", EdgeKind::Supertrait));
+        assert!(has_edge("fixture::Convert", EdgeKind::Supertrait));
+        assert!(!has_edge("fixture::Base", EdgeKind::UsesType));
+        assert!(!has_edge("fixture::Convert", EdgeKind::UsesType));
+        assert!(has_edge("fixture::Config", EdgeKind::UsesType));
+        assert!(!has_edge("fixture::Config", EdgeKind::Supertrait));
+    }
+
+    #[test]
+    fn trait_default_body_calls_are_capped_at_inferred() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 3, 4]);
+        value["index"]["1"] = rustdoc_trait_item(1, "Service", vec![2]);
+        value["index"]["2"] = rustdoc_function_item(2, "run");
+        value["index"]["3"] = rustdoc_function_item(3, "helper");
+        value["index"]["4"] = rustdoc_function_item(4, "direct");
+        for (id, path, kind) in [
+            ("1", vec!["fixture", "Service"], "trait"),
+            ("2", vec!["fixture", "Service", "run"], "function"),
+            ("3", vec!["fixture", "helper"], "function"),
+            ("4", vec!["fixture", "direct"], "function"),
+        ] {
+            value["paths"][id] = serde_json::json!({
+                "crate_id": 0,
+                "path": path,
+                "kind": kind
+            });
+        }
+
+        let graph = extract_graph_with_source_map(
+            &value.to_string(),
+            "fixture",
+            HashMap::from([(
+                "src/lib.rs".to_string(),
+                "pub trait Service {\n    fn run(&self) { helper(); }\n}\n\
+                 pub fn helper() {}\n\
+                 pub fn direct() { helper(); }\n"
+                    .to_string(),
+            )]),
+            "src/lib.rs",
+            CallMode::Strict,
+        )
+        .expect("source-backed graph extracts");
+
+        let confidence_from = |from: &str| {
+            graph
+                .edges
+                .iter()
+                .find(|edge| {
+                    edge.from == from
+                        && edge.to == "fixture::helper"
+                        && edge.kind == EdgeKind::CallsStatic
+                })
+                .map(|edge| edge.confidence)
+        };
+
+        assert_eq!(
+            confidence_from("fixture::Service::run"),
+            Some(Confidence::Inferred)
+        );
+        assert_eq!(confidence_from("fixture::direct"), Some(Confidence::Static));
+    }
+
+    #[test]
+    fn source_backed_macro_invocations_emit_calls_macro_edges() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2]);
+        value["index"]["1"] = rustdoc_function_item(1, "caller");
+        value["index"]["2"] = rustdoc_item(
+            2,
+            0,
+            "make",
+            serde_json::json!({ "macro": "macro_rules! make { () => {} }" }),
+        );
+        value["paths"]["1"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "caller"],
+            "kind": "function"
+        });
+        value["paths"]["2"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "make"],
+            "kind": "macro"
+        });
+
+        let graph = extract_graph_with_source_map(
+            &value.to_string(),
+            "fixture",
+            HashMap::from([(
+                "src/lib.rs".to_string(),
+                "pub fn caller() {\n    make!();\n    println!(\"hi\");\n}\n".to_string(),
+            )]),
+            "src/lib.rs",
+            CallMode::Strict,
+        )
+        .expect("source-backed graph extracts");
+
+        let macro_edges: Vec<_> = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.kind == EdgeKind::CallsMacro)
+            .collect();
+        assert_eq!(macro_edges.len(), 1);
+        assert_eq!(macro_edges[0].from, "fixture::caller");
+        assert_eq!(macro_edges[0].to, "fixture::make");
+        assert_eq!(macro_edges[0].confidence, Confidence::Static);
+        assert_eq!(macro_edges[0].occurrences[0].line, 2);
+    }
+
+    #[test]
+    fn function_like_proc_macro_invocations_emit_calls_macro_edges() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2]);
+        value["index"]["1"] = rustdoc_function_item(1, "caller");
+        value["index"]["2"] = rustdoc_item(
+            2,
+            0,
+            "make",
+            serde_json::json!({ "proc_macro": { "kind": "bang", "helpers": [] } }),
+        );
+        value["paths"]["1"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "caller"],
+            "kind": "function"
+        });
+        value["paths"]["2"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "make"],
+            "kind": "macro"
+        });
+
+        let graph = extract_graph_with_source_map(
+            &value.to_string(),
+            "fixture",
+            HashMap::from([(
+                "src/lib.rs".to_string(),
+                "pub fn caller() {\n    make!();\n}\n".to_string(),
+            )]),
+            "src/lib.rs",
+            CallMode::Strict,
+        )
+        .expect("source-backed graph extracts");
+
+        let macro_edges: Vec<_> = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.kind == EdgeKind::CallsMacro)
+            .collect();
+        assert_eq!(macro_edges.len(), 1);
+        assert_eq!(macro_edges[0].from, "fixture::caller");
+        assert_eq!(macro_edges[0].to, "fixture::make");
+    }
+
+    #[test]
+    fn edges_found_twice_keep_the_stronger_confidence() {
+        let mut graph = Graph::new();
+        let mut edge_cache = EdgeIndex::default();
+        let mut push = |to: &str, confidence| {
+            push_edge(
+                &mut graph,
+                &mut edge_cache,
+                "fixture::main".to_string(),
+                to.to_string(),
+                EdgeKind::CallsStatic,
+                confidence,
+            )
+        };
+        push("fixture::a", Confidence::Inferred);
+        push("fixture::a", Confidence::Static);
+        push("fixture::b", Confidence::Static);
+        push("fixture::b", Confidence::Inferred);
+        push("fixture::c", Confidence::Static);
+        push("fixture::c", Confidence::Runtime);
+
+        let confidences: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|edge| (edge.to.as_str(), edge.confidence))
+            .collect();
+        assert_eq!(
+            confidences,
+            vec![
+                ("fixture::a", Confidence::Static),
+                ("fixture::b", Confidence::Static),
+                ("fixture::c", Confidence::Runtime),
+            ]
+        );
+    }
+
+    #[test]
+    fn non_module_path_prefix_is_not_created_as_module() {
+        let mut graph = Graph::new();
+        let mut node_cache = HashSet::from(["fixture".to_string()]);
+        let mut edge_cache = EdgeIndex::default();
+        let mut placeholder_modules = HashSet::new();
+        let path_index = path_index(
+            &["fixture", "fixture::Trait", "fixture::Trait::method"],
+            &["fixture"],
+        );
+        let path = ["fixture", "Trait", "method"].map(str::to_string);
+
+        ensure_module_nodes(
+            &mut graph,
+            &mut node_cache,
+            &mut edge_cache,
+            &mut placeholder_modules,
+            "fixture",
+            &path,
+            &path_index,
+            None,
+            false,
+        );
+
+        assert!(!node_cache.contains("fixture::Trait"));
+        assert!(!placeholder_modules.contains("fixture::Trait"));
+        assert!(graph.nodes.is_empty());
+        assert_eq!(
+            structural_edge_kind("fixture::Trait", "fixture", &path_index),
+            EdgeKind::Defines
+        );
+    }
+
+    #[test]
+    fn placeholder_module_is_replaced_by_real_module_node() {
+        let mut graph = Graph::new();
+        let mut node_cache = HashSet::from(["fixture".to_string()]);
+        let mut edge_cache = EdgeIndex::default();
+        let mut placeholder_modules = HashSet::new();
+        let path_index = path_index(
             &["fixture", "fixture::module", "fixture::module::Item"],
             &["fixture", "fixture::module"],
         );
@@ -7079,6 +13504,7 @@ pub struct Small;
             "fixture",
             &path,
             &path_index,
+            None,
             false,
         );
         assert!(placeholder_modules.contains("fixture::module"));
@@ -7097,6 +13523,222 @@ pub struct Small;
         assert!(!placeholder_modules.contains("fixture::module"));
     }
 
+    #[test]
+    fn placeholder_module_keeps_declared_visibility() {
+        // `mod private { pub fn f() {} }`: rustdoc lists `f` in `paths` but
+        // not the private module, so the module node is a placeholder.
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1]);
+        value["index"]["1"] = rustdoc_item(
+            1,
+            0,
+            "private",
+            serde_json::json!({
+                "module": {
+                    "is_crate": false,
+                    "items": [2],
+                    "is_stripped": false
+                }
+            }),
+        );
+        value["index"]["1"]["visibility"] = serde_json::json!("default");
+        value["index"]["2"] = rustdoc_function_item(2, "f");
+        value["paths"]["2"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "private", "f"],
+            "kind": "function"
+        });
+
+        let (graph, _) = extract_graph_validated(
+            &value.to_string(),
+            "fixture",
+            &RustdocFormatPolicy::strict(),
+        )
+        .expect("private module fixture parses");
+
+        let module = graph
+            .nodes
+            .iter()
+            .find(|node| node.id == "fixture::private")
+            .expect("module node");
+        assert_eq!(module.visibility, Visibility::Inherited);
+        let f = graph
+            .nodes
+            .iter()
+            .find(|node| node.id == "fixture::private::f")
+            .expect("function node");
+        assert_eq!(f.visibility, Visibility::Public);
+    }
+
+    #[test]
+    fn skipped_external_crates_leave_no_nodes_or_edges() {
+        let field = |id: u32, name: &str, path: &str, target: u32| {
+            rustdoc_item(
+                id,
+                0,
+                name,
+                serde_json::json!({ "struct_field": { "resolved_path": {
+                    "path": path,
+                    "id": target,
+                    "args": null
+                } } }),
+            )
+        };
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1]);
+        value["index"]["1"] = rustdoc_struct_item(1, "Config", Vec::new());
+        value["index"]["1"]["inner"]["struct"]["kind"] = serde_json::json!({ "tuple": [2, 3] });
+        value["index"]["2"] = field(2, "0", "String", 10);
+        value["index"]["3"] = field(3, "1", "serde_json::Value", 11);
+        value["paths"]["1"] =
+            serde_json::json!({ "crate_id": 0, "path": ["fixture", "Config"], "kind": "struct" });
+        value["paths"]["10"] = serde_json::json!({
+            "crate_id": 1,
+            "path": ["std", "string", "String"],
+            "kind": "struct"
+        });
+        value["paths"]["11"] = serde_json::json!({
+            "crate_id": 2,
+            "path": ["serde_json", "Value"],
+            "kind": "enum"
+        });
+        value["external_crates"] = serde_json::json!({
+            "1": { "name": "std", "html_root_url": null, "path": "" },
+            "2": { "name": "serde_json", "html_root_url": null, "path": "" }
+        });
+        let krate = deserialize_rustdoc_value(value).expect("fixture deserializes");
+
+        let graph = build_graph(
+            &krate,
+            "fixture",
+            BuildGraphOptions {
+                extract: ExtractOptions {
+                    skip_external_crates: DEFAULT_SKIPPED_EXTERNAL_CRATES
+                        .iter()
+                        .map(|name| name.to_string())
+                        .collect(),
+                    ..ExtractOptions::default()
+                },
+                ..Default::default()
+            },
+        )
+        .expect("fixture graph builds");
+
+        let crate_of = |id: &str| id.split("::").next().unwrap_or(id).to_string();
+        assert!(graph.nodes.iter().all(|node| crate_of(&node.id) != "std"));
+        assert!(
+            graph
+                .nodes
+                .iter()
+                .any(|node| node.id == "serde_json::Value")
+        );
+        let uses: Vec<&str> = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.from == "fixture::Config" && edge.kind == EdgeKind::UsesType)
+            .map(|edge| edge.to.as_str())
+            .collect();
+        assert_eq!(uses, ["serde_json::Value"]);
+        assert!(
+            graph
+                .edges
+                .iter()
+                .all(|edge| crate_of(&edge.from) != "std" && crate_of(&edge.to) != "std")
+        );
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn calls_into_other_members_become_cross_crate_edges() {
+        let app = minimal_crate([(rdt::Id(1), vec!["app", "run"], rdt::ItemKind::Function)]);
+        let util = minimal_crate([(rdt::Id(1), vec!["util", "helper"], rdt::ItemKind::Function)]);
+        let mut workspace_functions = FunctionIndex::new();
+        workspace_functions.extend_from_crate(
+            &build_function_index(
+                &util,
+                &collect_method_ids(&util),
+                "util",
+                &HiddenPaths::new(&util, false),
+            ),
+            "util",
+        );
+        let members = HashSet::from(["app".to_string(), "util".to_string()]);
+        let provider = MemorySourceProvider::new(HashMap::from([(
+            "src/lib.rs".to_string(),
+            "pub fn run() { util::helper(); }\n".to_string(),
+        )]));
+        let options = |workspace_functions| BuildGraphOptions {
+            workspace_members: Some(members.clone()),
+            source: Some((Path::new("src/lib.rs"), &provider)),
+            workspace_functions,
+            ..Default::default()
+        };
+
+        let alone = build_graph(&app, "app", options(None)).expect("app graph builds");
+        assert!(!alone.edges.iter().any(|edge| edge.kind.is_call()));
+
+        let graphs = vec![
+            build_graph(&app, "app", options(Some(Arc::new(workspace_functions))))
+                .expect("app graph builds"),
+            build_graph(&util, "util", options(None)).expect("util graph builds"),
+        ];
+        let workspace = partition_workspace(graphs, &members, &HashMap::new(), None);
+        let calls: Vec<(&str, &str, EdgeKind)> = workspace
+            .cross_crate_edges
+            .iter()
+            .map(|edge| (edge.from.as_str(), edge.to.as_str(), edge.kind))
+            .collect();
+        assert_eq!(calls, [("app::run", "util::helper", EdgeKind::CallsStatic)]);
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn bare_names_link_across_members_only_when_unique() {
+        let app = minimal_crate([(rdt::Id(1), vec!["app", "run"], rdt::ItemKind::Function)]);
+        let util = minimal_crate([(rdt::Id(1), vec!["util", "helper"], rdt::ItemKind::Function)]);
+        let other = minimal_crate([(rdt::Id(1), vec!["other", "helper"], rdt::ItemKind::Function)]);
+        let index_of = |members: &[(&rdt::Crate, &str)]| {
+            let mut index = FunctionIndex::new();
+            for (krate, name) in members {
+                index.extend_from_crate(
+                    &build_function_index(
+                        krate,
+                        &collect_method_ids(krate),
+                        name,
+                        &HiddenPaths::new(krate, false),
+                    ),
+                    name,
+                );
+            }
+            Arc::new(index)
+        };
+        let provider = MemorySourceProvider::new(HashMap::from([(
+            "src/lib.rs".to_string(),
+            "pub fn run() { helper(); }\n".to_string(),
+        )]));
+        let calls = |workspace_functions| {
+            build_graph(
+                &app,
+                "app",
+                BuildGraphOptions {
+                    source: Some((Path::new("src/lib.rs"), &provider)),
+                    call_mode: CallMode::Ambiguous,
+                    workspace_functions: Some(workspace_functions),
+                    ..Default::default()
+                },
+            )
+            .expect("app graph builds")
+            .edges
+            .into_iter()
+            .filter(|edge| edge.kind.is_call())
+            .map(|edge| edge.to)
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(calls(index_of(&[(&util, "util")])), ["util::helper"]);
+        assert!(calls(index_of(&[(&util, "util"), (&other, "other")])).is_empty());
+    }
+
     #[test]
     fn namespace_collision_keeps_module_branch_and_disambiguates_value_item() {
         let krate = minimal_crate([
@@ -7118,10 +13760,7 @@ pub struct Small;
             "fixture",
             BuildGraphOptions {
                 workspace_members: Some(HashSet::from(["fixture".to_string()])),
-                source: None,
-                call_mode: CallMode::Strict,
-                skip_external_nodes: false,
-                rustdoc_name: None,
+                ..Default::default()
             },
         )
         .expect("fixture graph builds");
@@ -7149,6 +13788,57 @@ pub struct Small;
         }));
     }
 
+    #[test]
+    fn colliding_type_links_keep_their_full_paths() {
+        let path = |path: &str, id: u32| {
+            rdt::Type::ResolvedPath(rdt::Path {
+                path: path.to_string(),
+                id: rdt::Id(id),
+                args: None,
+            })
+        };
+        let sig = rdt::FunctionSignature {
+            inputs: vec![
+                ("io".to_string(), path("std::io::Error", 1)),
+                ("local".to_string(), path("Error", 2)),
+                ("again".to_string(), path("Error", 2)),
+                ("config".to_string(), path("Config", 3)),
+            ],
+            output: Some(path("fixture::Error", 2)),
+            is_c_variadic: false,
+        };
+        let mut krate = minimal_crate([
+            (
+                rdt::Id(1),
+                vec!["std", "io", "Error"],
+                rdt::ItemKind::Struct,
+            ),
+            (rdt::Id(2), vec!["fixture", "Error"], rdt::ItemKind::Enum),
+            (rdt::Id(3), vec!["fixture", "Config"], rdt::ItemKind::Struct),
+        ]);
+        krate.paths.get_mut(&rdt::Id(1)).expect("std path").crate_id = 1;
+        krate.external_crates.insert(
+            1,
+            rdt::ExternalCrate {
+                name: "std".to_string(),
+                html_root_url: None,
+                path: PathBuf::new(),
+            },
+        );
+        let path_index = build_path_index(&krate, "fixture");
+
+        let links = extract_signature_links(&sig, &krate, "fixture", &path_index);
+
+        assert_eq!(
+            links,
+            HashMap::from([
+                ("std::io::Error".to_string(), "std::io::Error".to_string()),
+                ("fixture::Error".to_string(), "fixture::Error".to_string()),
+                ("Config".to_string(), "fixture::Config".to_string()),
+            ])
+        );
+    }
+
     #[test]
     fn generic_arg_constraints_are_formatted_and_linked() {
         let output_path = rdt::Path {
@@ -7205,6 +13895,7 @@ pub struct Small;
             confidence: Confidence::Static,
             occurrences: Vec::new(),
             is_glob: false,
+            label: None,
         });
         graph.add_edge(Edge {
             from: "fixture::missing_generated".to_string(),
@@ -7213,6 +13904,7 @@ pub struct Small;
             confidence: Confidence::Static,
             occurrences: Vec::new(),
             is_glob: false,
+            label: None,
         });
         let mut node_cache = HashSet::from(["fixture::Type".to_string()]);
         let mut path_index = path_index(&["core::clone::Clone"], &[]);
@@ -7227,7 +13919,7 @@ pub struct Small;
             &workspace_members,
             &path_index,
         );
-        prune_dangling_edges(&mut graph, &node_cache);
+        prune_dangling_edges(&mut graph, &node_cache, &FunctionIndex::new());
 
         let external = graph
             .nodes
@@ -7240,4 +13932,670 @@ pub struct Small;
         assert_eq!(graph.edges.len(), 1);
         assert_eq!(graph.edges[0].to, "core::clone::Clone");
     }
+
+    #[test]
+    fn source_provider_is_only_asked_for_reachable_files() {
+        struct RecordingProvider {
+            inner: MemorySourceProvider,
+            reads: std::cell::RefCell<Vec<String>>,
+        }
+
+        impl SourceProvider for RecordingProvider {
+            fn read_file(&self, path: &Path) -> Result<String, RustdocError> {
+                self.reads.borrow_mut().push(normalize_memory_path(path));
+                self.inner.read_file(path)
+            }
+            fn file_exists(&self, path: &Path) -> Result<bool, RustdocError> {
+                self.inner.file_exists(path)
+            }
+        }
+
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1]);
+        value["index"]["1"] = rustdoc_function_item(1, "caller");
+        value["paths"]["1"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "caller"],
+            "kind": "function"
+        });
+
+        let provider = RecordingProvider {
+            inner: MemorySourceProvider::new(HashMap::from([
+                (
+                    "src/lib.rs".to_string(),
+                    "mod inner;\npub fn caller() {}\n".to_string(),
+                ),
+                (
+                    "src/inner/mod.rs".to_string(),
+                    "pub fn helper() {}\n".to_string(),
+                ),
+                (
+                    "src/bin/unused.rs".to_string(),
+                    "fn main() {}\n".to_string(),
+                ),
+            ])),
+            reads: Default::default(),
+        };
+
+        extract_graph_with_source_provider(
+            &value.to_string(),
+            "fixture",
+            &provider,
+            "src/lib.rs",
+            CallMode::Strict,
+            &ExtractOptions::default(),
+        )
+        .expect("fixture graph builds");
+
+        assert_eq!(
+            *provider.reads.borrow(),
+            vec!["src/lib.rs".to_string(), "src/inner/mod.rs".to_string()]
+        );
+    }
+
+    #[test]
+    fn path_overrides_with_parent_segments_are_normalized() {
+        let provider = MemorySourceProvider::new(HashMap::from([(
+            "shared/helpers.rs".to_string(),
+            "pub fn helper() {}\n".to_string(),
+        )]));
+        let item_mod: syn::ItemMod =
+            syn::parse_str("#[path = \"../../shared/./helpers.rs\"] mod helpers;")
+                .expect("mod item parses");
+
+        assert_eq!(
+            resolve_module_file(Path::new("src/sys"), &item_mod, &provider).expect("probe"),
+            Some(PathBuf::from("shared/helpers.rs"))
+        );
+        assert_eq!(
+            normalize_lexically(Path::new("src/../../vendor/lib.rs")),
+            PathBuf::from("../vendor/lib.rs")
+        );
+    }
+
+    #[test]
+    fn cfg_attr_path_overrides_resolve_to_the_first_existing_file() {
+        let provider = MemorySourceProvider::new(HashMap::from([(
+            "src/sys/windows.rs".to_string(),
+            "pub fn open() {}\n".to_string(),
+        )]));
+        let item_mod: syn::ItemMod = syn::parse_str(
+            "#[cfg_attr(unix, path = \"sys/unix.rs\")] \
+             #[cfg_attr(windows, cfg_attr(target_env = \"msvc\", path = \"sys/windows.rs\"))] \
+             mod sys;",
+        )
+        .expect("mod item parses");
+
+        assert_eq!(
+            module_path_override(&item_mod),
+            vec![
+                PathBuf::from("sys/unix.rs"),
+                PathBuf::from("sys/windows.rs")
+            ]
+        );
+        assert_eq!(
+            resolve_module_file(Path::new("src"), &item_mod, &provider).expect("probe"),
+            Some(PathBuf::from("src/sys/windows.rs"))
+        );
+    }
+
+    #[test]
+    fn module_files_are_resolved_once_per_declaration() {
+        struct ProbeCounter {
+            inner: MemorySourceProvider,
+            probes: std::cell::RefCell<Vec<String>>,
+        }
+
+        impl SourceProvider for ProbeCounter {
+            fn read_file(&self, path: &Path) -> Result<String, RustdocError> {
+                self.inner.read_file(path)
+            }
+            fn file_exists(&self, path: &Path) -> Result<bool, RustdocError> {
+                self.probes.borrow_mut().push(normalize_memory_path(path));
+                self.inner.file_exists(path)
+            }
+        }
+
+        let value = minimal_rustdoc_value("fixture");
+        let provider = ProbeCounter {
+            inner: MemorySourceProvider::new(HashMap::from([
+                (
+                    "src/lib.rs".to_string(),
+                    "#[cfg(unix)]\nmod sys;\n#[cfg(windows)]\nmod sys;\n".to_string(),
+                ),
+                ("src/sys/mod.rs".to_string(), String::new()),
+            ])),
+            probes: Default::default(),
+        };
+
+        extract_graph_with_source_provider(
+            &value.to_string(),
+            "fixture",
+            &provider,
+            "src/lib.rs",
+            CallMode::Strict,
+            &ExtractOptions::default(),
+        )
+        .expect("fixture graph builds");
+
+        assert_eq!(
+            *provider.probes.borrow(),
+            vec!["src/sys.rs".to_string(), "src/sys/mod.rs".to_string()]
+        );
+    }
+
+    #[test]
+    fn framework_attributes_mark_entry_points() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3, 4, 5]);
+        for (id, name) in [
+            (1, "main"),
+            (2, "index"),
+            (3, "serve"),
+            (4, "helper"),
+            (5, "on_event"),
+        ] {
+            value["index"][id.to_string()] = rustdoc_function_item(id, name);
+            value["paths"][id.to_string()] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": "function"
+            });
+        }
+        let provider = MemorySourceProvider::new(HashMap::from([(
+            "src/lib.rs".to_string(),
+            "fn main() { serve(); }\n\
+             #[actix_web::get(\"/\")]\n\
+             async fn index() -> &'static str { helper() }\n\
+             #[tokio::main]\n\
+             async fn serve() {}\n\
+             #[inline]\n\
+             fn helper() -> &'static str { \"hi\" }\n\
+             #[bus::subscribe(\"event\")]\n\
+             fn on_event() {}\n"
+                .to_string(),
+        )]));
+        let entries = |options: &ExtractOptions| {
+            let graph = extract_graph_with_source_provider(
+                &value.to_string(),
+                "fixture",
+                &provider,
+                "src/lib.rs",
+                CallMode::Strict,
+                options,
+            )
+            .expect("fixture graph builds");
+            let mut entries: Vec<String> = graph
+                .entry_points()
+                .into_iter()
+                .map(|node| node.id.clone())
+                .collect();
+            entries.sort_unstable();
+            entries
+        };
+
+        let mut options = ExtractOptions::default();
+        assert_eq!(
+            entries(&options),
+            ["fixture::index", "fixture::main", "fixture::serve"]
+        );
+        // A caller's own attribute, matched by its trailing path segments.
+        options.entry_point_attributes.push("subscribe".to_string());
+        assert_eq!(
+            entries(&options),
+            [
+                "fixture::index",
+                "fixture::main",
+                "fixture::on_event",
+                "fixture::serve"
+            ]
+        );
+    }
+
+    #[test]
+    fn complexity_counts_branches_in_parsed_function_bodies() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2]);
+        for (id, name) in [(1, "branchy"), (2, "straight")] {
+            value["index"][id.to_string()] = rustdoc_function_item(id, name);
+            value["paths"][id.to_string()] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": "function"
+            });
+        }
+        let krate = parse_rustdoc_lenient(&value.to_string()).expect("fixture parses");
+        let provider = MemorySourceProvider::new(HashMap::from([(
+            "src/lib.rs".to_string(),
+            "fn branchy(x: Option<u8>) -> Result<u8, ()> {
+                 if x.is_some() && x != Some(0) { return Ok(1); }
+                 let Some(v) = x else { return Err(()) };
+                 for _ in 0..v { while false {} }
+                 match v { 0 => {}, n if n > 9 => {}, _ => {} }
+                 fn nested(b: bool) -> bool { if b { b } else { !b } }
+                 Ok(u8::try_from(v).map_err(|_| ())?)
+             }
+             fn straight() {}
+"
+            .to_string(),
+        )]));
+        let build = |complexity| {
+            build_graph(
+                &krate,
+                "fixture",
+                BuildGraphOptions {
+                    workspace_members: Some(HashSet::from(["fixture".to_string()])),
+                    source: Some((Path::new("src/lib.rs"), &provider)),
+                    extract: ExtractOptions {
+                        complexity,
+                        ..ExtractOptions::default()
+                    },
+                    ..Default::default()
+                },
+            )
+            .expect("fixture graph builds")
+        };
+        let complexity_of = |graph: &Graph, id: &str| {
+            graph
+                .nodes
+                .iter()
+                .find(|node| node.id == id)
+                .and_then(|node| node.complexity)
+        };
+
+        assert_eq!(complexity_of(&build(false), "fixture::branchy"), None);
+
+        // 1 + if + && + let-else + for + while + two extra arms + guard + `?`;
+        // the nested fn's `if` isn't counted.
+        let graph = build(true);
+        assert_eq!(complexity_of(&graph, "fixture::branchy"), Some(10));
+        assert_eq!(complexity_of(&graph, "fixture::straight"), Some(1));
+    }
+
+    #[test]
+    fn cfg_attributes_are_recorded_per_node() {
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3, 4]);
+        for (id, name) in [(1, "gated"), (2, "platform"), (3, "legacy"), (4, "sourced")] {
+            value["index"][id.to_string()] = rustdoc_function_item(id, name);
+            value["paths"][id.to_string()] = serde_json::json!({
+                "crate_id": 0,
+                "path": ["fixture", name],
+                "kind": "function"
+            });
+        }
+        value["index"]["1"]["attrs"] = serde_json::json!([{
+            "other": "#[attr = CfgTrace([NameValue { name: \"feature\", value: Some(\"x\"), span: src/lib.rs:1:7: 1:20 (#0) }])]"
+        }]);
+        value["index"]["2"]["attrs"] = serde_json::json!([{
+            "other": "#[attr = CfgTrace([All([NameValue { name: \"unix\", value: None, span: src/lib.rs:3:11: 3:15 (#0) }, Not(NameValue { name: \"test\", value: None, span: src/lib.rs:3:21: 3:25 (#0) }, src/lib.rs:3:17: 3:26 (#0))], src/lib.rs:3:10: 3:27 (#0)),\nNameValue { name: \"feature\", value: Some(\"y\"), span: src/lib.rs:4:7: 4:20 (#0) }])]"
+        }]);
+        value["index"]["3"]["attrs"] = serde_json::json!([{
+            "other": "#[cfg(feature = \"legacy\")]"
+        }]);
+
+        let graph = extract_graph_with_source_map(
+            &value.to_string(),
+            "fixture",
+            HashMap::from([(
+                "src/lib.rs".to_string(),
+                "#[cfg(feature = \"x\")]\n\
+                 fn gated() {}\n\
+                 fn platform() {}\n\
+                 fn legacy() {}\n\
+                 #[cfg(any(windows, target_os = \"macos\"))]\n\
+                 fn sourced() {}\n"
+                    .to_string(),
+            )]),
+            "src/lib.rs",
+            CallMode::Strict,
+        )
+        .expect("fixture graph builds");
+
+        let cfg_of = |id: &str| {
+            graph
+                .nodes
+                .iter()
+                .find(|node| node.id == id)
+                .and_then(|node| node.cfg.as_deref())
+        };
+        assert_eq!(cfg_of("fixture::gated"), Some("feature = \"x\""));
+        assert_eq!(
+            cfg_of("fixture::platform"),
+            Some("all(all(unix, not(test)), feature = \"y\")")
+        );
+        assert_eq!(cfg_of("fixture::legacy"), Some("feature = \"legacy\""));
+        assert_eq!(
+            cfg_of("fixture::sourced"),
+            Some("any(windows, target_os = \"macos\")")
+        );
+        assert!(
+            graph
+                .nodes
+                .iter()
+                .all(|node| node.attrs.iter().all(|attr| !attr.contains("Cfg")))
+        );
+    }
+
+    #[cfg(feature = "github")]
+    #[test]
+    fn github_source_provider_fetches_lazily_and_caches() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::{TcpListener, TcpStream};
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let base = format!("http://{}", listener.local_addr().expect("addr"));
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for stream in listener.incoming() {
+                let mut stream = stream.expect("accept");
+                let mut request_line = String::new();
+                let mut reader = BufReader::new(stream.try_clone().expect("clone"));
+                reader.read_line(&mut request_line).expect("read");
+                if request_line.starts_with("DONE") {
+                    break;
+                }
+                while reader.read_line(&mut String::new()).expect("read") > 2 {}
+                let response = if request_line.contains("/contents/src/lib.rs?ref=v1 ") {
+                    "HTTP/1.1 200 OK\r\nContent-Length: 12\r\n\r\nmod missing;"
+                } else if request_line.contains("/contents/src/flaky.rs?ref=v1 ") {
+                    "HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\n\r\n"
+                } else {
+                    "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n"
+                };
+                stream.write_all(response.as_bytes()).expect("write");
+                requests.push(request_line.trim_end().to_string());
+            }
+            requests
+        });
+
+        let provider = GitHubSourceProvider::new("owner/repo", "v1").with_api_base(&base);
+        assert!(
+            provider
+                .file_exists(Path::new("./src/lib.rs"))
+                .expect("found")
+        );
+        assert_eq!(
+            provider.read_file(Path::new("src/lib.rs")).expect("cached"),
+            "mod missing;"
+        );
+        assert!(
+            !provider
+                .file_exists(Path::new("src/missing.rs"))
+                .expect("404")
+        );
+        assert!(
+            !provider
+                .file_exists(Path::new("src/missing.rs"))
+                .expect("cached")
+        );
+        let error = provider
+            .file_exists(Path::new("src/flaky.rs"))
+            .expect_err("a failed request isn't a missing file");
+        assert!(error.to_string().contains("502"), "{error}");
+
+        let mut done = TcpStream::connect(base.trim_start_matches("http://")).expect("connect");
+        done.write_all(b"DONE\r\n").expect("write");
+        assert_eq!(
+            server.join().expect("server"),
+            [
+                "GET /repos/owner/repo/contents/src/lib.rs?ref=v1 HTTP/1.1",
+                "GET /repos/owner/repo/contents/src/missing.rs?ref=v1 HTTP/1.1",
+                "GET /repos/owner/repo/contents/src/flaky.rs?ref=v1 HTTP/1.1",
+            ]
+        );
+    }
+
+    #[test]
+    fn source_parsing_stops_between_files_once_cancelled() {
+        struct CancelOnRead<'a> {
+            inner: MemorySourceProvider,
+            cancel: &'a AtomicBool,
+            reads: std::cell::RefCell<Vec<String>>,
+        }
+
+        impl SourceProvider for CancelOnRead<'_> {
+            fn read_file(&self, path: &Path) -> Result<String, RustdocError> {
+                self.reads.borrow_mut().push(normalize_memory_path(path));
+                self.cancel.store(true, Ordering::Relaxed);
+                self.inner.read_file(path)
+            }
+            fn file_exists(&self, path: &Path) -> Result<bool, RustdocError> {
+                self.inner.file_exists(path)
+            }
+        }
+
+        let mut value = minimal_rustdoc_value("fixture");
+        value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1]);
+        value["index"]["1"] = rustdoc_function_item(1, "caller");
+        value["paths"]["1"] = serde_json::json!({
+            "crate_id": 0,
+            "path": ["fixture", "caller"],
+            "kind": "function"
+        });
+        let krate = parse_rustdoc_lenient(&value.to_string()).expect("fixture parses");
+
+        let cancel = AtomicBool::new(false);
+        let provider = CancelOnRead {
+            inner: MemorySourceProvider::new(HashMap::from([
+                (
+                    "src/lib.rs".to_string(),
+                    "mod inner;\npub fn caller() {}\n".to_string(),
+                ),
+                (
+                    "src/inner.rs".to_string(),
+                    "pub fn helper() {}\n".to_string(),
+                ),
+            ])),
+            cancel: &cancel,
+            reads: Default::default(),
+        };
+
+        let result = build_graph(
+            &krate,
+            "fixture",
+            BuildGraphOptions {
+                source: Some((Path::new("src/lib.rs"), &provider)),
+                skip_external_nodes: true,
+                cancel: Some(&cancel),
+                ..Default::default()
+            },
+        );
+
+        assert!(matches!(result, Err(RustdocError::Cancelled)));
+        assert_eq!(*provider.reads.borrow(), vec!["src/lib.rs".to_string()]);
+    }
+
+    #[cfg(all(feature = "native", unix))]
+    #[test]
+    fn cancelling_kills_in_flight_child_promptly() {
+        let cancel = std::sync::Arc::new(AtomicBool::new(false));
+        let setter = {
+            let cancel = cancel.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                cancel.store(true, Ordering::Relaxed);
+            })
+        };
+
+        let started = std::time::Instant::now();
+        let mut cmd = Command::new("sleep");
+        cmd.arg("30").stderr(std::process::Stdio::piped());
+        let result = run_cancellable(&mut cmd, Some(&cancel));
+        setter.join().unwrap();
+
+        assert!(matches!(result, Err(RustdocError::Cancelled)));
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[cfg(all(feature = "native", target_os = "linux"))]
+    #[test]
+    fn cancelling_kills_the_childs_own_children() {
+        let pid_file = std::env::temp_dir().join(format!(
+            "codeview-cancel-grandchild-{}.pid",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&pid_file);
+        let cancel = std::sync::Arc::new(AtomicBool::new(false));
+        let setter = {
+            let cancel = cancel.clone();
+            let pid_file = pid_file.clone();
+            std::thread::spawn(move || {
+                while !pid_file.exists() {
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                }
+                cancel.store(true, Ordering::Relaxed);
+            })
+        };
+
+        // Like cargo waiting on rustdoc: the grandchild does the work.
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg("sleep 30 & echo $! > \"$0\"; wait")
+            .arg(&pid_file);
+        let result = run_cancellable(&mut cmd, Some(&cancel));
+        setter.join().unwrap();
+        assert!(matches!(result, Err(RustdocError::Cancelled)));
+
+        let pid = std::fs::read_to_string(&pid_file).expect("pid written");
+        let stat = format!("/proc/{}/stat", pid.trim());
+        let started = std::time::Instant::now();
+        // Gone, or a zombie waiting for init to reap it.
+        let dead = || {
+            std::fs::read_to_string(&stat).map_or(true, |stat| {
+                stat.rsplit(") ")
+                    .next()
+                    .is_some_and(|rest| rest.starts_with('Z'))
+            })
+        };
+        while !dead() {
+            assert!(
+                started.elapsed() < std::time::Duration::from_secs(5),
+                "grandchild {} still running",
+                pid.trim()
+            );
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let _ = std::fs::remove_file(&pid_file);
+    }
+
+    #[test]
+    fn deeply_nested_modules_stop_at_the_depth_limit() {
+        fn nested_source(depth: usize) -> String {
+            let mut source = "pub fn helper() {}\n".to_string();
+            source.push_str("mod m0 { pub fn shallow() { crate::helper(); }\n");
+            for level in 1..depth {
+                source.push_str(&format!("mod m{level} {{\n"));
+            }
+            source.push_str("pub fn deep() { crate::helper(); }\n");
+            source.push_str(&"}\n".repeat(depth));
+            source
+        }
+
+        fn calls_to_helper(depth: usize, max_module_depth: usize) -> Vec<String> {
+            let mut deep_path = vec!["fixture".to_string()];
+            deep_path.extend((0..depth).map(|level| format!("m{level}")));
+            deep_path.push("deep".to_string());
+
+            let mut value = minimal_rustdoc_value("fixture");
+            value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3]);
+            value["index"]["1"] = rustdoc_function_item(1, "helper");
+            value["index"]["2"] = rustdoc_function_item(2, "shallow");
+            value["index"]["3"] = rustdoc_function_item(3, "deep");
+            value["paths"]["1"] = serde_json::json!({
+                "crate_id": 0, "path": ["fixture", "helper"], "kind": "function"
+            });
+            value["paths"]["2"] = serde_json::json!({
+                "crate_id": 0, "path": ["fixture", "m0", "shallow"], "kind": "function"
+            });
+            value["paths"]["3"] = serde_json::json!({
+                "crate_id": 0, "path": deep_path, "kind": "function"
+            });
+            let provider = MemorySourceProvider::new(HashMap::from([(
+                "src/lib.rs".to_string(),
+                nested_source(depth),
+            )]));
+            let graph = extract_graph_with_source_provider(
+                &value.to_string(),
+                "fixture",
+                &provider,
+                "src/lib.rs",
+                CallMode::Strict,
+                &ExtractOptions {
+                    max_module_depth,
+                    ..ExtractOptions::default()
+                },
+            )
+            .expect("deep nesting is skipped, not an error");
+            let mut callers: Vec<_> = graph
+                .edges
+                .iter()
+                .filter(|edge| edge.kind == EdgeKind::CallsStatic && edge.to == "fixture::helper")
+                .map(|edge| edge.from.rsplit("::").next().unwrap().to_string())
+                .collect();
+            callers.sort();
+            callers
+        }
+
+        assert_eq!(calls_to_helper(4, 8), vec!["deep", "shallow"]);
+        assert_eq!(calls_to_helper(4, 2), vec!["shallow"]);
+        assert_eq!(
+            calls_to_helper(DEFAULT_MAX_MODULE_DEPTH + 8, DEFAULT_MAX_MODULE_DEPTH),
+            vec!["shallow"]
+        );
+    }
+
+    #[test]
+    fn deep_expression_nesting_keeps_calls_below_the_token_guard() {
+        // Blocks nest one group per level, and each carries an attribute so
+        // the cut-off point lands on attribute brackets too.
+        fn nested_source(depth: usize) -> String {
+            let mut source = "pub fn helper() {}\n".to_string();
+            source.push_str("pub fn shallow() { crate::helper(); }\n");
+            source.push_str("pub fn deep() ");
+            source.push_str(&"{ #[allow(unused)] let _x = [0u8; 1];\n".repeat(depth));
+            source.push_str("crate::helper();\n");
+            source.push_str(&"}\n".repeat(depth));
+            source
+        }
+
+        fn calls_to_helper(depth: usize) -> Vec<String> {
+            let mut value = minimal_rustdoc_value("fixture");
+            value["index"]["0"]["inner"]["module"]["items"] = serde_json::json!([1, 2, 3]);
+            value["index"]["1"] = rustdoc_function_item(1, "helper");
+            value["index"]["2"] = rustdoc_function_item(2, "shallow");
+            value["index"]["3"] = rustdoc_function_item(3, "deep");
+            for (id, name) in [(1, "helper"), (2, "shallow"), (3, "deep")] {
+                value["paths"][id.to_string()] = serde_json::json!({
+                    "crate_id": 0, "path": ["fixture", name], "kind": "function"
+                });
+            }
+            let provider = MemorySourceProvider::new(HashMap::from([(
+                "src/lib.rs".to_string(),
+                nested_source(depth),
+            )]));
+            let graph = extract_graph_with_source_provider(
+                &value.to_string(),
+                "fixture",
+                &provider,
+                "src/lib.rs",
+                CallMode::Strict,
+                &ExtractOptions::default(),
+            )
+            .expect("deep nesting is skipped, not an error");
+            let mut callers: Vec<_> = graph
+                .edges
+                .iter()
+                .filter(|edge| edge.kind == EdgeKind::CallsStatic && edge.to == "fixture::helper")
+                .map(|edge| edge.from.rsplit("::").next().unwrap().to_string())
+                .collect();
+            callers.sort();
+            callers
+        }
+
+        // Far deeper than any module limit, and still on a default-sized
+        // test thread.
+        assert_eq!(calls_to_helper(500), vec!["deep", "shallow"]);
+        assert_eq!(calls_to_helper(MAX_TOKEN_NESTING + 8), vec!["shallow"]);
+    }
 }
@@ -1,7 +1,9 @@
 //! WASM entry point for codeview-rustdoc graph extraction.
 //!
-//! Exposes `extract_graph` and `extract_graph_with_sources` to JavaScript
-//! via wasm-bindgen.
+//! Exposes `extract_graph`, `extract_graph_with_sources`,
+//! `extract_graph_with_sources_and_call_mode` and
+//! `extract_graph_with_source_callback` to JavaScript via wasm-bindgen,
+//! along with `graph_metrics` for summarizing their output.
 //!
 //! Build with: `wasm-pack build --target web --features wasm --no-default-features`
 
@@ -58,7 +60,8 @@ pub fn extract_graph(json: &[u8], crate_name: &str) -> Result<String, JsValue> {
 ///
 /// `source_files_json` is a JSON object mapping file paths to source content,
 /// e.g. `{"src/lib.rs": "fn main() { ... }", "src/utils.rs": "..."}`.
-/// `root_file` is the entry point (e.g. "src/lib.rs").
+/// `root_file` is the entry point (e.g. "src/lib.rs"). Calls resolve in
+/// strict mode; see [`extract_graph_with_sources_and_call_mode`].
 #[cfg(feature = "wasm")]
 #[wasm_bindgen]
 pub fn extract_graph_with_sources(
@@ -67,6 +70,30 @@ pub fn extract_graph_with_sources(
     source_files_json: &[u8],
     root_file: &str,
 ) -> Result<String, JsValue> {
+    extract_graph_with_sources_and_call_mode(
+        json,
+        crate_name,
+        source_files_json,
+        root_file,
+        "strict",
+    )
+}
+
+/// [`extract_graph_with_sources`] with the call resolution mode spelled out:
+/// `"strict"` drops calls that match more than one function, `"ambiguous"`
+/// links every candidate with inferred confidence, and `"heuristic"` is
+/// strict except for methods called through `dyn Trait`, which link to every
+/// impl of the trait as runtime calls.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn extract_graph_with_sources_and_call_mode(
+    json: &[u8],
+    crate_name: &str,
+    source_files_json: &[u8],
+    root_file: &str,
+    call_mode: &str,
+) -> Result<String, JsValue> {
+    let call_mode = parse_call_mode(call_mode)?;
     let json_str =
         std::str::from_utf8(json).map_err(|e| JsValue::from_str(&format!("invalid UTF-8: {e}")))?;
     let source_files_str = std::str::from_utf8(source_files_json)
@@ -99,7 +126,7 @@ pub fn extract_graph_with_sources(
         crate_name,
         source_files,
         root_file,
-        crate::CallMode::Strict,
+        call_mode,
     )
     .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
@@ -116,3 +143,164 @@ pub fn extract_graph_with_sources(
     wasm_log!("[wasm] serialized: {:.0}ms", js_sys::Date::now() - t2);
     Ok(result)
 }
+
+#[cfg(feature = "wasm")]
+fn parse_call_mode(value: &str) -> Result<crate::CallMode, JsValue> {
+    match value {
+        "strict" => Ok(crate::CallMode::Strict),
+        "ambiguous" => Ok(crate::CallMode::Ambiguous),
+        "heuristic" => Ok(crate::CallMode::Heuristic),
+        other => Err(JsValue::from_str(&format!(
+            "unknown call mode {other:?}, expected \"strict\", \"ambiguous\" or \"heuristic\""
+        ))),
+    }
+}
+
+/// Extract a crate graph with call edges, fetching source files on demand.
+///
+/// Instead of shipping the whole source tree up front, `read_fn(path)` is
+/// called for each file module traversal reaches (e.g. `"src/lib.rs"`,
+/// then `"src/utils.rs"` or `"src/utils/mod.rs"`), and should return the
+/// file's content, or `null`/`undefined` if it doesn't exist.
+///
+/// `read_fn` must answer synchronously: extraction can't await JS promises.
+/// For remote sources (e.g. GitHub at the graph's `repo`/`ref`), prefetch
+/// into a cache first and serve from it; files the traversal never reaches
+/// are still never copied into WASM memory.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn extract_graph_with_source_callback(
+    json: &[u8],
+    crate_name: &str,
+    root_file: &str,
+    read_fn: js_sys::Function,
+) -> Result<String, JsValue> {
+    let json_str =
+        std::str::from_utf8(json).map_err(|e| JsValue::from_str(&format!("invalid UTF-8: {e}")))?;
+
+    wasm_log!(
+        "[wasm] extract_graph_with_source_callback: {} bytes rustdoc",
+        json_str.len()
+    );
+    let t0 = js_sys::Date::now();
+
+    let provider = JsSourceProvider::new(read_fn);
+    let graph = crate::extract_graph_with_source_provider(
+        json_str,
+        crate_name,
+        &provider,
+        root_file,
+        crate::CallMode::Strict,
+        &crate::ExtractOptions::default(),
+    )
+    .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    let t1 = js_sys::Date::now();
+    wasm_log!(
+        "[wasm] graph built: {} nodes, {} edges, {} files fetched, {:.0}ms",
+        graph.nodes.len(),
+        graph.edges.len(),
+        provider.fetched.get(),
+        t1 - t0
+    );
+
+    let result = serde_json::to_string(&graph).map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    wasm_log!("[wasm] serialized: {:.0}ms", js_sys::Date::now() - t1);
+    Ok(result)
+}
+
+/// Summary statistics (see `Graph::graph_metrics`) for a graph JSON as
+/// returned by the `extract_graph*` functions, serialized as JSON. This is
+/// the stats endpoint for UI headers: per-`NodeKind` and per-`EdgeKind`
+/// counts (their sums are the totals, `nodes.Crate` the crate count) and
+/// `external_nodes` for the local/external split, all counted in Rust.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn graph_metrics(graph_json: &[u8]) -> Result<String, JsValue> {
+    let graph: codeview_core::Graph = serde_json::from_slice(graph_json)
+        .map_err(|e| JsValue::from_str(&format!("invalid graph JSON: {e}")))?;
+    serde_json::to_string(&graph.graph_metrics()).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// The nested `Contains` tree (see `Graph::module_tree`) of a graph JSON
+/// as returned by the `extract_graph*` functions, serialized as JSON;
+/// `null` when the graph has no crate root.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn module_tree(graph_json: &[u8], max_depth: Option<usize>) -> Result<String, JsValue> {
+    let graph: codeview_core::Graph = serde_json::from_slice(graph_json)
+        .map_err(|e| JsValue::from_str(&format!("invalid graph JSON: {e}")))?;
+    serde_json::to_string(&graph.module_tree(max_depth))
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// [`crate::SourceProvider`] backed by a synchronous JS callback.
+///
+/// Module resolution probes `file_exists` before reading, so probe results
+/// are held until the matching `read_file` to avoid calling back twice.
+#[cfg(feature = "wasm")]
+struct JsSourceProvider {
+    read_fn: js_sys::Function,
+    pending: std::cell::RefCell<std::collections::HashMap<String, Option<String>>>,
+    fetched: std::cell::Cell<usize>,
+}
+
+#[cfg(feature = "wasm")]
+impl JsSourceProvider {
+    fn new(read_fn: js_sys::Function) -> Self {
+        Self {
+            read_fn,
+            pending: Default::default(),
+            fetched: std::cell::Cell::new(0),
+        }
+    }
+
+    fn fetch(&self, key: &str) -> Result<Option<String>, crate::RustdocError> {
+        self.fetched.set(self.fetched.get() + 1);
+        let value = self
+            .read_fn
+            .call1(&JsValue::NULL, &JsValue::from_str(key))
+            .map_err(|err| source_error(format!("read_fn threw for {key}: {err:?}")))?;
+        if value.is_null() || value.is_undefined() {
+            return Ok(None);
+        }
+        value
+            .as_string()
+            .map(Some)
+            .ok_or_else(|| source_error(format!("read_fn returned a non-string for {key}")))
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl crate::SourceProvider for JsSourceProvider {
+    fn read_file(&self, path: &std::path::Path) -> Result<String, crate::RustdocError> {
+        let key = crate::normalize_memory_path(path);
+        let content = match self.pending.borrow_mut().remove(&key) {
+            Some(content) => content,
+            None => self.fetch(&key)?,
+        };
+        content.ok_or_else(|| {
+            crate::RustdocError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("read_fn has no source for {key}"),
+            ))
+        })
+    }
+
+    fn file_exists(&self, path: &std::path::Path) -> Result<bool, crate::RustdocError> {
+        let key = crate::normalize_memory_path(path);
+        if let Some(content) = self.pending.borrow().get(&key) {
+            return Ok(content.is_some());
+        }
+        let content = self.fetch(&key)?;
+        let exists = content.is_some();
+        self.pending.borrow_mut().insert(key, content);
+        Ok(exists)
+    }
+}
+
+#[cfg(feature = "wasm")]
+fn source_error(message: String) -> crate::RustdocError {
+    crate::RustdocError::Io(std::io::Error::other(message))
+}
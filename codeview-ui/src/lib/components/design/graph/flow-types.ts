@@ -1,6 +1,7 @@
 import type { Edge as FlowEdge, Node as FlowNode } from '@xyflow/svelte';
 import type { EdgeKind, Node } from '$lib/schema';
 import type { DesignNode, DesignRelation } from '$lib/design/live-node';
+import type { ArrowheadStyle } from '$lib/graph/visual';
 import type { FocusDirection } from './focus-layout';
 
 export type GraphNodePillFlowData = {
@@ -38,6 +39,7 @@ export type RelationshipEdgeData = {
 	color: string;
 	path: string;
 	arrowPath: string;
+	arrowhead: ArrowheadStyle;
 	dim: boolean;
 	active: boolean;
 };
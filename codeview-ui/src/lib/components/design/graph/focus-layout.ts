@@ -1,5 +1,11 @@
 import type { Edge, EdgeKind, Node } from '$lib/schema';
 import type { DesignNode, DesignRelation } from '$lib/design/live-node';
+import {
+	arrowheadPath,
+	arrowheadStyle,
+	type ArrowheadOverrides,
+	type ArrowheadStyle,
+} from '$lib/graph/visual';
 
 export type FocusDirection = 'incoming' | 'outgoing';
 
@@ -60,6 +66,7 @@ export type FocusLayoutEdge = {
 	color: string;
 	path: string;
 	arrowPath: string;
+	arrowhead: ArrowheadStyle;
 	activeNodeIds: [string, string];
 };
 
@@ -114,6 +121,7 @@ export function measureFocusPill(node: DesignNode<Node>, isFocus = false): numbe
 export function layoutFocusGraph(
 	model: FocusGraphModel,
 	size: FocusGraphSize,
+	arrowheads?: ArrowheadOverrides,
 ): FocusGraphLayout {
 	const baseWidth = Math.max(size.compact ? 560 : 720, Math.round(size.width || 0));
 	const baseHeight = Math.max(size.compact ? 320 : 460, Math.round(size.height || 0));
@@ -161,6 +169,7 @@ export function layoutFocusGraph(
 				const startY = item.y;
 				const endX = focusLeftEdge;
 				const endY = centerY;
+				const arrowhead = arrowheadStyle(edge.kind, arrowheads);
 				edges.push({
 					id: edgeId(edge, 'incoming', index),
 					source: flowNodeId('incoming', item.node.id),
@@ -170,7 +179,8 @@ export function layoutFocusGraph(
 					rel: group.rel,
 					color: group.color,
 					path: bundledPath(startX, startY, hubInX, group.hubY, endX, endY),
-					arrowPath: incomingArrow(endX, endY),
+					arrowPath: arrowheadPath(arrowhead, endX + 6, endY),
+					arrowhead,
 					activeNodeIds: [flowNodeId('incoming', item.node.id), focusFlowNodeId(model.focus.id)],
 				});
 			}
@@ -189,6 +199,7 @@ export function layoutFocusGraph(
 				const startY = centerY;
 				const endX = rightX - 8;
 				const endY = item.y;
+				const arrowhead = arrowheadStyle(edge.kind, arrowheads);
 				edges.push({
 					id: edgeId(edge, 'outgoing', index),
 					source: focusFlowNodeId(model.focus.id),
@@ -198,7 +209,8 @@ export function layoutFocusGraph(
 					rel: group.rel,
 					color: group.color,
 					path: bundledPath(startX, startY, hubOutX, group.hubY, endX, endY),
-					arrowPath: outgoingArrow(endX, endY),
+					arrowPath: arrowheadPath(arrowhead, endX, endY),
+					arrowhead,
 					activeNodeIds: [focusFlowNodeId(model.focus.id), flowNodeId('outgoing', item.node.id)],
 				});
 			}
@@ -344,14 +356,6 @@ function bundledPath(
 	].join(' ');
 }
 
-function incomingArrow(x: number, y: number): string {
-	return `M ${x - 2} ${y - 4.5} L ${x + 6} ${y} L ${x - 2} ${y + 4.5} Z`;
-}
-
-function outgoingArrow(x: number, y: number): string {
-	return `M ${x - 8} ${y - 4.5} L ${x} ${y} L ${x - 8} ${y + 4.5} Z`;
-}
-
 function sumEdges(groups: FocusGraphGroup[]): number {
 	return groups.reduce((sum, group) => sum + group.items.reduce((inner, item) => inner + item.edges.length, 0), 0);
 }
@@ -167,11 +167,15 @@ export const EDGE_KIND_TO_RELATION = {
 	Contains: 'contains',
 	Defines: 'defines',
 	Implements: 'implements',
+	Supertrait: 'implements',
 	UsesType: 'uses',
+	Returns: 'uses',
 	ReExports: 'reexports',
 	CallsStatic: 'calls',
 	CallsRuntime: 'calls-runtime',
+	CallsMacro: 'calls',
 	Derives: 'derives',
+	HasField: 'uses',
 } as const satisfies Record<EdgeKind, DesignRelation>;
 
 export interface DesignNodeContext {
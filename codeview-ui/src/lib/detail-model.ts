@@ -86,7 +86,7 @@ function collectDirectItems(detail: NodeDetail, relatedNodeMap: Map<string, Node
 			!node ||
 			node.is_external ||
 			!DIRECT_ITEM_KINDS.has(node.kind) ||
-			(publicOnly && !isPublic(node.visibility)) ||
+			(publicOnly && !isPublic(node.effective_visibility ?? node.visibility)) ||
 			(node.kind === 'Module' && node.name === detail.node.name)
 		) {
 			continue;
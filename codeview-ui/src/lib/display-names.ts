@@ -153,11 +153,15 @@ export const edgeLabels: Record<EdgeKind, string> = {
 	Contains: 'Contains',
 	Defines: 'Defines',
 	UsesType: 'Uses type',
+	Returns: 'Returns',
 	Implements: 'Implements',
+	Supertrait: 'Supertrait',
 	CallsStatic: 'Calls',
 	CallsRuntime: 'Runtime calls',
+	CallsMacro: 'Macro calls',
 	Derives: 'Derives',
 	ReExports: 'Re-exports',
+	HasField: 'Has field',
 };
 
 export const nodeKindOrder: NodeKind[] = [
@@ -220,6 +220,29 @@ describe('edgeToExcalidraw', () => {
 		expect(edgeToExcalidraw(inEdge, nodeMap).strokeColor).toBe('#94a3b8');
 	});
 
+	it('draws inferred edges dashed and faded', () => {
+		const inferred = { ...makeVisEdge(from, to, 'CallsRuntime'), confidence: 'Inferred' as const };
+		const el = edgeToExcalidraw(inferred, nodeMap);
+		expect(el.strokeStyle).toBe('dashed');
+		expect(el.opacity).toBeLessThan(100);
+
+		const fixed = edgeToExcalidraw(makeVisEdge(from, to, 'CallsStatic'), nodeMap);
+		expect(fixed.strokeStyle).toBe('solid');
+		expect(fixed.opacity).toBe(100);
+	});
+
+	it('bends offset edges through a shifted midpoint', () => {
+		const edge = makeVisEdge(from, to, 'CallsStatic');
+		const straight = edgeToExcalidraw(edge, nodeMap) as ExcalidrawArrowElement;
+		const bent = edgeToExcalidraw(edge, nodeMap, [], undefined, 14) as ExcalidrawArrowElement;
+
+		expect(straight.points).toHaveLength(2);
+		expect(bent.points).toHaveLength(3);
+		// A horizontal edge bows straight up or down by the full offset.
+		expect(Math.abs(bent.points[1][1])).toBeCloseTo(14);
+		expect(bent.points[2]).toEqual(straight.points[1]);
+	});
+
 	it('has start/end bindings referencing source and target node shape IDs', () => {
 		const edge = makeVisEdge(from, to, 'UsesType', 'out');
 		const el = edgeToExcalidraw(edge, nodeMap) as ExcalidrawArrowElement;
@@ -253,9 +276,17 @@ describe('edgeToExcalidraw', () => {
 		expect(el.boundElements).toContainEqual({ id: lblId, type: 'text' });
 	});
 
-	it('uses triangle arrowhead for Implements edge', () => {
+	it('uses hollow triangle arrowhead for Implements edge', () => {
 		const edge = makeVisEdge(from, to, 'Implements', 'out');
 		const el = edgeToExcalidraw(edge, nodeMap) as ExcalidrawArrowElement;
+		expect(el.endArrowhead).toBe('triangle_outline');
+	});
+
+	it('applies arrowhead overrides', () => {
+		const edge = makeVisEdge(from, to, 'Implements', 'out');
+		const el = edgeToExcalidraw(edge, nodeMap, [], {
+			Implements: 'triangle',
+		}) as ExcalidrawArrowElement;
 		expect(el.endArrowhead).toBe('triangle');
 	});
 
@@ -286,8 +317,8 @@ describe('arrowheadForEdgeKind', () => {
 	it.each([
 		['Contains', 'diamond'],
 		['Defines', 'diamond_outline'],
-		['Implements', 'triangle'],
-		['Derives', 'triangle_outline'],
+		['Implements', 'triangle_outline'],
+		['Derives', 'triangle'],
 		['UsesType', 'arrow'],
 		['CallsStatic', 'arrow'],
 		['CallsRuntime', 'dot'],
@@ -1,8 +1,15 @@
 import type { GraphRenderer, GraphScene, SceneGroup } from '$lib/renderers/graph';
+import { parallelEdgeOffsets } from '$lib/renderers/graph';
 import type { VisNode, VisEdge } from '$lib/graph/layout';
 import type { LabelPosition } from '$lib/graph/labels';
-import { getNodeVisual, getVisNodeEdgeAnchor } from '$lib/graph/visual';
-import type { NodeVisual } from '$lib/graph/visual';
+import {
+	arrowheadStyle,
+	confidenceDashArray,
+	confidenceOpacity,
+	getNodeVisual,
+	getVisNodeEdgeAnchor,
+} from '$lib/graph/visual';
+import type { ArrowheadOverrides, NodeVisual } from '$lib/graph/visual';
 import { nodeUrl } from '$lib/url';
 
 import type { ExcalidrawElement, Arrowhead } from '@excalidraw/excalidraw/element/types';
@@ -14,6 +21,8 @@ export type ExcalidrawExportOptions = {
 	baseUrl?: string;
 	/** Crate→version map for URL generation. Falls back to 'latest'. */
 	crateVersions?: Record<string, string>;
+	/** Per-edge-kind arrowhead overrides on top of `DEFAULT_ARROWHEADS`. */
+	arrowheads?: ArrowheadOverrides;
 };
 
 // Matches the .excalidraw JSON format. We keep this local since
@@ -65,26 +74,9 @@ export function edgeLabelId(fromId: string, toId: string, kind: string): string
 	return deterministicId('elbl', fromId, toId, kind);
 }
 
-/** Map edge kinds to distinct arrowhead styles. */
-export function arrowheadForEdgeKind(kind: string): Arrowhead {
-	switch (kind) {
-		case 'Contains':
-			return 'diamond';
-		case 'Defines':
-			return 'diamond_outline';
-		case 'Implements':
-			return 'triangle';
-		case 'Derives':
-			return 'triangle_outline';
-		case 'CallsRuntime':
-			return 'dot';
-		case 'ReExports':
-			return 'bar';
-		case 'UsesType':
-		case 'CallsStatic':
-		default:
-			return 'arrow';
-	}
+/** Map edge kinds to distinct arrowhead styles (shared with the focus graph). */
+export function arrowheadForEdgeKind(kind: string, overrides?: ArrowheadOverrides): Arrowhead {
+	return arrowheadStyle(kind, overrides);
 }
 
 function baseElement(
@@ -164,7 +156,7 @@ export function nodeToExcalidraw(
 	groupIds: string[] = [],
 	opts?: ExcalidrawExportOptions,
 ): ExcalidrawElement[] {
-	const visual = getNodeVisual(node.node.kind, node.isCenter);
+	const visual = getNodeVisual(node.node.kind, node.isCenter, node.scale);
 	const id = nodeShapeId(node.node.id);
 	const textId = nodeLabelId(node.node.id);
 	const excaShape = shapeToExcalidraw(visual);
@@ -230,14 +222,26 @@ export function nodeToExcalidraw(
 	return [shapeEl, textEl];
 }
 
+/** Unit vector left of the `from` → `to` direction, scaled by `offset`. */
+function perpendicular(from: VisNode, to: VisNode, offset: number): { dx: number; dy: number } {
+	const x = to.x - from.x;
+	const y = to.y - from.y;
+	const length = Math.hypot(x, y);
+	if (offset === 0 || length === 0) return { dx: 0, dy: 0 };
+	return { dx: (-y / length) * offset, dy: (x / length) * offset };
+}
+
 /**
  * Convert a VisEdge to an Excalidraw arrow element.
  * @param groupIds - Excalidraw group IDs this edge belongs to
+ * @param offset - How far to bow the arrow sideways (see `parallelEdgeOffsets`)
  */
 export function edgeToExcalidraw(
 	edge: VisEdge,
 	nodeMap: Map<string, VisNode>,
 	groupIds: string[] = [],
+	arrowheads?: ArrowheadOverrides,
+	offset = 0,
 ): ExcalidrawElement {
 	const fromNode = nodeMap.get(edge.from.node.id) ?? edge.from;
 	const toNode = nodeMap.get(edge.to.node.id) ?? edge.to;
@@ -247,6 +251,19 @@ export function edgeToExcalidraw(
 
 	const relX = endAnchor.x - startAnchor.x;
 	const relY = endAnchor.y - startAnchor.y;
+	// Parallel edges bend through an offset midpoint so each stays visible.
+	const bend = perpendicular(fromNode, toNode, offset);
+	const points: [number, number][] =
+		offset === 0
+			? [
+					[0, 0],
+					[relX, relY],
+				]
+			: [
+					[0, 0],
+					[relX / 2 + bend.dx, relY / 2 + bend.dy],
+					[relX, relY],
+				];
 
 	const edgeColor = edge.direction === 'out' ? '#5b8abf' : '#94a3b8';
 	const fromShapeId = nodeShapeId(edge.from.node.id);
@@ -263,16 +280,16 @@ export function edgeToExcalidraw(
 		width: relX,
 		height: relY,
 		strokeColor: edgeColor,
+		strokeStyle: confidenceDashArray(edge.confidence) ? 'dashed' : 'solid',
+		opacity: Math.round(confidenceOpacity(edge.confidence) * 100),
 		groupIds,
-		points: [
-			[0, 0],
-			[relX, relY],
-		],
+		points,
+		roundness: offset === 0 ? null : { type: 2 },
 		lastCommittedPoint: null,
 		startBinding: { elementId: fromShapeId, focus: 0, gap: 4 },
 		endBinding: { elementId: toShapeId, focus: 0, gap: 4 },
 		startArrowhead: null,
-		endArrowhead: arrowheadForEdgeKind(edge.kind),
+		endArrowhead: arrowheadForEdgeKind(edge.kind, arrowheads),
 		elbowed: false,
 		boundElements: [{ id: labelId, type: 'text' }],
 		customData: {
@@ -287,11 +304,13 @@ export function edgeToExcalidraw(
 /**
  * Convert an edge label to an Excalidraw text element.
  * @param groupIds - Excalidraw group IDs this label belongs to
+ * @param offset - The edge's sideways offset, so the label follows its arrow
  */
 export function labelToExcalidraw(
 	edge: VisEdge,
 	label: LabelPosition,
 	groupIds: string[] = [],
+	offset = 0,
 ): ExcalidrawElement {
 	const fontSize = 9;
 	const text = edge.kind;
@@ -299,12 +318,14 @@ export function labelToExcalidraw(
 	const textHeight = fontSize * 1.4;
 	const id = edgeLabelId(edge.from.node.id, edge.to.node.id, edge.kind);
 	const arrowId = edgeArrowId(edge.from.node.id, edge.to.node.id, edge.kind);
+	// Half the bend, which is where a quadratic curve through the midpoint peaks.
+	const shift = perpendicular(edge.from, edge.to, offset / 2);
 
 	return baseElement({
 		id,
 		type: 'text',
-		x: label.x - textWidth / 2,
-		y: label.y - textHeight / 2,
+		x: label.x + shift.dx - textWidth / 2,
+		y: label.y + shift.dy - textHeight / 2,
 		width: textWidth,
 		height: textHeight,
 		text,
@@ -381,11 +402,12 @@ export function renderExcalidraw(
 	}
 
 	// 2. Edges (arrows) + labels
+	const offsets = parallelEdgeOffsets(scene.edges);
 	for (let i = 0; i < scene.edges.length; i++) {
 		const edge = scene.edges[i];
 		const gids = edgeGroupIds.get(i) ?? [];
 
-		const arrowEl = edgeToExcalidraw(edge, nodeMap, gids);
+		const arrowEl = edgeToExcalidraw(edge, nodeMap, gids, opts?.arrowheads, offsets[i]);
 		addElement(arrowEl);
 
 		// Register arrow as a bound element on both endpoint shapes.
@@ -408,7 +430,7 @@ export function renderExcalidraw(
 		// Edge label (same group as arrow)
 		const label = scene.labels[i];
 		if (label) {
-			addElement(labelToExcalidraw(edge, label, gids));
+			addElement(labelToExcalidraw(edge, label, gids, offsets[i]));
 		}
 	}
 
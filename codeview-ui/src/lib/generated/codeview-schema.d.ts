@@ -5,11 +5,15 @@ export type EdgeKind =
 	| 'Contains'
 	| 'Defines'
 	| 'Implements'
+	| 'Supertrait'
 	| 'UsesType'
+	| 'Returns'
 	| 'CallsStatic'
 	| 'CallsRuntime'
+	| 'CallsMacro'
 	| 'Derives'
-	| 'ReExports';
+	| 'ReExports'
+	| 'HasField';
 export type GenericBound =
 	| {
 			hrtb_params?: GenericParam[];
@@ -307,6 +311,10 @@ export interface CrateGraph {
 	aliases?: {
 		[k: string]: string;
 	};
+	/**
+	 * Shared doc-link target table (see `Graph::doc_link_table`).
+	 */
+	doc_link_table?: string[];
 	/**
 	 * Internal edges (both from+to within this crate)
 	 */
@@ -333,19 +341,41 @@ export interface Edge {
 	from: string;
 	is_glob?: boolean;
 	kind: EdgeKind;
+	/**
+	 * What the relationship is called at the source: the field of a
+	 * `HasField` edge, the derive as written for `Derives`, the alias a
+	 * renamed `ReExports` introduces, `dyn` on a `UsesType`/`Returns` edge
+	 * that names its trait through `dyn Trait`, or `generic` on one whose
+	 * target only appears as a generic argument, like `T` in `Vec<T>`. Edges
+	 * that differ only in label are distinct.
+	 */
+	label?: string | null;
 	/**
 	 * Exact source locations that justify this relationship. Empty when
 	 * source-backed occurrence extraction is unavailable; `Node::span`
 	 * remains the declaration target for go-to-definition/declaration.
+	 * Call edges carry one entry per call site, spanning the callee name.
 	 */
 	occurrences?: Span[];
 	to: string;
 }
 export interface Span {
+	/**
+	 * 1-based column of the first character
+	 */
 	column: number;
+	/**
+	 * 1-based column one past the last character, as rustdoc reports it
+	 */
 	end_column?: number | null;
+	/**
+	 * 1-based line of the last character
+	 */
 	end_line?: number | null;
 	file: string;
+	/**
+	 * 1-based line of the first character
+	 */
 	line: number;
 }
 export interface Node {
@@ -355,12 +385,31 @@ export interface Node {
 	 * declarations, assoc-type bounds, etc.). Structured.
 	 */
 	bounds?: GenericBound[];
+	/**
+	 * The `#[cfg(...)]` predicate gating the item, e.g. `feature = "x"` or
+	 * `all(unix, feature = "x")`. Several `cfg` attributes are joined with
+	 * `all(...)`.
+	 */
+	cfg?: string | null;
+	/**
+	 * Approximate cyclomatic complexity of the function body: one plus
+	 * each `if`, loop, extra `match` arm, guard, `&&`/`||` and `?`. Only
+	 * set when sources were parsed with complexity enabled.
+	 */
+	complexity?: number | null;
 	const_stability?: StabilityInfo | null;
 	const_value?: string | null;
 	default_trait_methods?: string[] | null;
 	default_unstable?: ProvidedDefaultUnstable | null;
 	deprecation?: Deprecation | null;
 	discriminant?: string | null;
+	/**
+	 * Interned form of `doc_links`: link text → index into the owning
+	 * graph's `doc_link_table`. Only populated in interned graphs.
+	 */
+	doc_link_refs?: {
+		[k: string]: number;
+	};
 	/**
 	 * Resolved intra-doc links: maps link text (e.g., "Vec") to node ID (e.g., "std::vec::Vec")
 	 */
@@ -368,8 +417,21 @@ export interface Node {
 		[k: string]: string;
 	};
 	docs?: string | null;
+	/**
+	 * Visibility as seen from outside the crate: `pub` items count as
+	 * public only when reachable from the crate root through public
+	 * modules or `pub use` re-exports, and are narrowed to `Crate`
+	 * otherwise. Only computed for the analyzed crate's own nodes.
+	 */
+	effective_visibility?: Visibility | null;
 	extern_crate_name?: string | null;
 	extern_crate_rename?: string | null;
+	/**
+	 * Features that must be enabled for the item to exist: the smallest
+	 * compared feature set it appeared under. Empty for items built with
+	 * the default features, or when no feature sets were compared.
+	 */
+	features?: string[];
 	fields?: FieldInfo[] | null;
 	generics?: Generics;
 	has_stripped_fields?: boolean;
@@ -384,11 +446,28 @@ export interface Node {
 	import_name?: string | null;
 	import_source?: string | null;
 	is_auto?: boolean;
+	/**
+	 * Trait method with a default body that implementors may leave out;
+	 * `false` for required trait methods and every other node.
+	 */
+	is_default?: boolean;
 	is_deprecated?: boolean;
 	is_dyn_compatible?: boolean | null;
+	/**
+	 * Invoked from outside the crate's own call graph: a crate-root
+	 * `fn main`, or a function carrying a recognised framework attribute
+	 * such as `#[tokio::main]`, `#[test]`, `#[wasm_bindgen]` or a route
+	 * macro like `#[get("/")]`. Only set when sources were parsed.
+	 */
+	is_entry?: boolean;
 	is_external?: boolean;
 	is_glob?: boolean;
 	is_mutable?: boolean;
+	/**
+	 * Publicly reachable only through a `pub use` re-export; the module
+	 * path the item is declared under is private.
+	 */
+	is_public_via_reexport?: boolean;
 	is_stripped?: boolean;
 	is_unsafe?: boolean;
 	kind: NodeKind;
@@ -488,6 +567,13 @@ export interface FunctionSignature {
 	generics?: Generics1;
 	inputs: ArgumentInfo[];
 	is_async: boolean;
+	/**
+	 * Written as `async fn` under `#[async_trait]`. rustdoc only sees the
+	 * macro's desugaring (a `Pin<Box<dyn Future + 'async_trait>>` return and
+	 * `is_async == false`), so this is inferred from that shape and is a
+	 * heuristic, not a record of the attribute.
+	 */
+	is_async_trait?: boolean;
 	is_c_variadic?: boolean;
 	is_const: boolean;
 	is_unsafe: boolean;
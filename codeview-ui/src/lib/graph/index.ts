@@ -48,7 +48,15 @@ export {
 } from './visual';
 
 // Layout
-export type { LayoutMode, VisNode, VisEdge, LayoutNode, LayoutLink, LayoutState } from './layout';
+export type {
+	LayoutMode,
+	LayoutOptions,
+	VisNode,
+	VisEdge,
+	LayoutNode,
+	LayoutLink,
+	LayoutState,
+} from './layout';
 export {
 	LAYOUT_WIDTH,
 	LAYOUT_HEIGHT,
@@ -58,6 +66,7 @@ export {
 	FLOW_ROW_GAP,
 	MAX_NODES_PER_COLUMN,
 	FORCE_RADIUS,
+	DEFAULT_FORCE_SEED,
 	RADIAL_RADIUS,
 	MIN_NODE_SPACING,
 	LABEL_CHAR_WIDTH,
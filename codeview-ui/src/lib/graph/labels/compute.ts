@@ -15,8 +15,10 @@ export function getLabelProvider(mode: LayoutMode): LabelPositionProvider {
 		case 'hierarchical':
 			return hierarchicalLabelProvider;
 		case 'radial':
+		case 'circular':
 			return radialLabelProvider;
 		case 'force':
+		case 'grid':
 			return forceLabelProvider;
 		default:
 			return egoLabelProvider;
@@ -0,0 +1,66 @@
+import { describe, expect, it } from 'vite-plus/test';
+import type { Edge, Graph, Node } from '$lib/graph';
+import { CENTER_X, CENTER_Y } from './types';
+import { computeCircularLayout } from './circular';
+
+function makeNode(id: string, name: string, kind: Node['kind'] = 'Function'): Node {
+	return {
+		id,
+		name,
+		kind,
+		visibility: { kind: 'Public' },
+		attrs: [],
+	};
+}
+
+function makeEdge(from: string, to: string, kind: Edge['kind'] = 'UsesType'): Edge {
+	return {
+		from,
+		to,
+		kind,
+		confidence: 'Static',
+	};
+}
+
+function positions(graph: Graph, selected: Node): Array<[string, number, number]> {
+	return computeCircularLayout(graph, selected)
+		.nodes.map((node): [string, number, number] => [node.node.id, node.x, node.y])
+		.sort(([a], [b]) => (a < b ? -1 : a > b ? 1 : 0));
+}
+
+describe('computeCircularLayout', () => {
+	const selected = makeNode('crate::m', 'm', 'Struct');
+	const neighbours = ['crate::z', 'crate::a', 'crate::q'].map((id) => makeNode(id, id.slice(7)));
+	const graph = {
+		nodes: [selected, ...neighbours],
+		edges: neighbours.map((node) => makeEdge(selected.id, node.id)),
+	};
+
+	it('places every node on one circle in id order, starting at the top', () => {
+		const result = computeCircularLayout(graph, selected);
+
+		expect(result.nodes.map((node) => node.node.id)).toEqual([
+			'crate::a',
+			'crate::m',
+			'crate::q',
+			'crate::z',
+		]);
+		const radius = result.nodes[0].layoutRadius;
+		expect(radius).toBeGreaterThan(0);
+		for (const node of result.nodes) {
+			expect(Math.hypot(node.x - CENTER_X, node.y - CENTER_Y)).toBeCloseTo(radius);
+		}
+		expect(result.nodes[0].x).toBeCloseTo(CENTER_X);
+		expect(result.nodes[0].y).toBeCloseTo(CENTER_Y - radius);
+		expect(result.edges).toHaveLength(3);
+	});
+
+	it('does not depend on input order', () => {
+		const reversed = {
+			nodes: [...graph.nodes].reverse(),
+			edges: [...graph.edges].reverse(),
+		};
+
+		expect(positions(reversed, selected)).toEqual(positions(graph, selected));
+	});
+});
@@ -0,0 +1,91 @@
+import type { Graph, Node } from '$lib/graph';
+import type { VisNode, VisEdge } from './types';
+import { CENTER_X, CENTER_Y, RADIAL_RADIUS, MIN_NODE_SPACING } from './types';
+import { getNodeBoundingBox } from './collision';
+import { nodeScale, type NodeScales } from '$lib/graph/visual/node-visual';
+import { compareIds } from './grid';
+
+/**
+ * Deterministic layout for snapshots: the selected node and its neighbours
+ * all sit on one circle, clockwise from the top in node-id order. Unlike
+ * `radial`, the selected node is not pulled into the middle, so positions
+ * depend only on which ids are present.
+ */
+export function computeCircularLayout(
+	graph: Graph,
+	selected: Node,
+	nodeScales?: NodeScales,
+): { nodes: VisNode[]; edges: VisEdge[] } {
+	const nodeMap = new Map<string, Node>();
+	for (const node of graph.nodes) {
+		nodeMap.set(node.id, node);
+	}
+
+	const connectedIds = new Set<string>();
+	const incomingIds = new Set<string>();
+	connectedIds.add(selected.id);
+	for (const edge of graph.edges) {
+		if (edge.from === selected.id) connectedIds.add(edge.to);
+		if (edge.to === selected.id) {
+			connectedIds.add(edge.from);
+			incomingIds.add(edge.from);
+		}
+	}
+
+	const ringNodes = Array.from(connectedIds)
+		.sort(compareIds)
+		.map((id) => nodeMap.get(id) ?? (id === selected.id ? selected : undefined))
+		.filter((n): n is Node => n !== undefined);
+
+	let totalArcLength = 0;
+	for (const node of ringNodes) {
+		const box = getNodeBoundingBox(node, node.id === selected.id, nodeScale(nodeScales, node.kind));
+		totalArcLength += box.width + MIN_NODE_SPACING;
+	}
+	const radius = ringNodes.length > 1 ? Math.max(RADIAL_RADIUS, totalArcLength / (2 * Math.PI)) : 0;
+
+	const visNodes: VisNode[] = [];
+	const visNodeMap = new Map<string, VisNode>();
+	ringNodes.forEach((node, i) => {
+		const isCenter = node.id === selected.id;
+		const angle = (2 * Math.PI * i) / ringNodes.length - Math.PI / 2;
+		const x = CENTER_X + radius * Math.cos(angle);
+		const y = CENTER_Y + radius * Math.sin(angle);
+		const visNode: VisNode = {
+			node,
+			x,
+			y,
+			baseX: x,
+			baseY: y,
+			angle,
+			isCenter,
+			edgeKind: '',
+			direction: isCenter ? 'center' : incomingIds.has(node.id) ? 'in' : 'out',
+			layer: 0,
+			indexInLayer: i,
+			totalInLayer: ringNodes.length,
+			layoutRadius: radius,
+			scale: nodeScale(nodeScales, node.kind),
+		};
+		visNodes.push(visNode);
+		visNodeMap.set(node.id, visNode);
+	});
+
+	const visEdges: VisEdge[] = [];
+	for (const edge of graph.edges) {
+		const from = visNodeMap.get(edge.from);
+		const to = visNodeMap.get(edge.to);
+		if (from && to) {
+			visEdges.push({
+				from,
+				to,
+				kind: edge.kind,
+				confidence: edge.confidence,
+				is_glob: edge.is_glob,
+				direction: edge.from === selected.id ? 'out' : 'in',
+			});
+		}
+	}
+
+	return { nodes: visNodes, edges: visEdges };
+}
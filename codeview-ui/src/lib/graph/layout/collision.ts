@@ -8,8 +8,9 @@ import { LABEL_CHAR_WIDTH, ARROWHEAD_LENGTH } from './types';
 export function getNodeBoundingBox(
 	node: Node,
 	isCenter: boolean,
+	scale = 1,
 ): { width: number; height: number } {
-	const visual = getNodeVisual(node.kind, isCenter);
+	const visual = getNodeVisual(node.kind, isCenter, scale);
 	const labelWidth = node.name.length * LABEL_CHAR_WIDTH;
 
 	const effectiveWidth = isRectLike(visual.shape)
@@ -76,7 +77,7 @@ export function resolveCollisions(
 ): void {
 	const n = visNodes.length;
 
-	const boxes = visNodes.map((v) => getNodeBoundingBox(v.node, v.isCenter));
+	const boxes = visNodes.map((v) => getNodeBoundingBox(v.node, v.isCenter, v.scale));
 
 	if (n < 30) {
 		for (let iter = 0; iter < iterations; iter++) {
@@ -1,33 +1,48 @@
 import type { Graph, Node } from '$lib/graph';
-import type { LayoutMode, VisNode, VisEdge } from './types';
+import type { LayoutMode, LayoutOptions, VisNode, VisEdge } from './types';
 import { computeEgoLayout } from './ego';
 import { computeForceLayout } from './force';
 import { computeHierarchicalLayout } from './hierarchical';
 import { computeRadialLayout } from './radial';
+import { computeGridLayout } from './grid';
+import { computeCircularLayout } from './circular';
 import { getPerfLogger } from '$lib/log';
 
 export function computeLayout(
 	graph: Graph,
 	selected: Node,
 	mode: LayoutMode,
+	options: LayoutOptions = {},
 ): { nodes: VisNode[]; edges: VisEdge[] } {
 	const t0 = performance.now();
 	let result: { nodes: VisNode[]; edges: VisEdge[] };
 	switch (mode) {
 		case 'ego':
-			result = computeEgoLayout(graph, selected);
+			result = computeEgoLayout(graph, selected, options.nodeScales);
 			break;
 		case 'force':
-			result = computeForceLayout(graph, selected);
+			result = computeForceLayout(
+				graph,
+				selected,
+				options.seed,
+				options.minConfidence,
+				options.nodeScales,
+			);
 			break;
 		case 'hierarchical':
-			result = computeHierarchicalLayout(graph, selected);
+			result = computeHierarchicalLayout(graph, selected, options.nodeScales);
 			break;
 		case 'radial':
-			result = computeRadialLayout(graph, selected);
+			result = computeRadialLayout(graph, selected, options.nodeScales);
+			break;
+		case 'grid':
+			result = computeGridLayout(graph, selected, options.nodeScales);
+			break;
+		case 'circular':
+			result = computeCircularLayout(graph, selected, options.nodeScales);
 			break;
 		default:
-			result = computeEgoLayout(graph, selected);
+			result = computeEgoLayout(graph, selected, options.nodeScales);
 	}
 	const dt = performance.now() - t0;
 	getPerfLogger('layout')
@@ -1,7 +1,7 @@
 import type { Confidence, Graph, Node } from '$lib/graph';
 import type { VisNode, VisEdge } from './types';
 import { CENTER_X, CENTER_Y, FLOW_COLUMN_GAP, FLOW_ROW_GAP, MAX_NODES_PER_COLUMN } from './types';
-import { getNodeVisual } from '$lib/graph/visual/node-visual';
+import { getNodeVisual, nodeScale, type NodeScales } from '$lib/graph/visual/node-visual';
 
 function formatEdgeKinds(kinds: string[]): string {
 	return Array.from(new Set(kinds)).join(', ');
@@ -16,6 +16,7 @@ function aggregateConfidence(confidences: Confidence[]): Confidence {
 export function computeEgoLayout(
 	graph: Graph,
 	selected: Node,
+	nodeScales?: NodeScales,
 ): { nodes: VisNode[]; edges: VisEdge[] } {
 	const nodeMap = new Map<string, Node>();
 	for (const node of graph.nodes) {
@@ -85,6 +86,7 @@ export function computeEgoLayout(
 		indexInLayer: 0,
 		totalInLayer: 1,
 		layoutRadius: 0,
+		scale: nodeScale(nodeScales, selected.kind),
 	};
 	visNodes.push(centerNode);
 
@@ -102,9 +104,11 @@ export function computeEgoLayout(
 	const inArray = Array.from(incomingNodes.values()).slice(0, MAX_NODES_PER_COLUMN);
 	const outArray = Array.from(outgoingNodes.values()).slice(0, MAX_NODES_PER_COLUMN);
 
-	const centerDims = getNodeVisual(selected.kind, true);
-	const incomingWidths = inArray.map((entry) => getNodeVisual(entry.node.kind, false).width);
-	const outgoingWidths = outArray.map((entry) => getNodeVisual(entry.node.kind, false).width);
+	const visual = (node: Node, isCenter: boolean) =>
+		getNodeVisual(node.kind, isCenter, nodeScale(nodeScales, node.kind));
+	const centerDims = visual(selected, true);
+	const incomingWidths = inArray.map((entry) => visual(entry.node, false).width);
+	const outgoingWidths = outArray.map((entry) => visual(entry.node, false).width);
 	const maxIncomingWidth = incomingWidths.length > 0 ? Math.max(...incomingWidths) : 0;
 	const maxOutgoingWidth = outgoingWidths.length > 0 ? Math.max(...outgoingWidths) : 0;
 	const leftX = CENTER_X - (centerDims.width / 2 + FLOW_COLUMN_GAP + maxIncomingWidth / 2);
@@ -121,7 +125,7 @@ export function computeEgoLayout(
 		x: number,
 	): VisNode[] {
 		if (entries.length === 0) return [];
-		const heights = entries.map((entry) => getNodeVisual(entry.node.kind, false).height);
+		const heights = entries.map((entry) => visual(entry.node, false).height);
 		const totalHeight =
 			heights.reduce((sum, height) => sum + height, 0) +
 			Math.max(0, entries.length - 1) * FLOW_ROW_GAP;
@@ -145,6 +149,7 @@ export function computeEgoLayout(
 				indexInLayer: i,
 				totalInLayer: entries.length,
 				layoutRadius: 0,
+				scale: nodeScale(nodeScales, entry.node.kind),
 			};
 		});
 	}
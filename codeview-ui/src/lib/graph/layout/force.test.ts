@@ -57,6 +57,44 @@ describe('computeForceLayout', () => {
 		expect(roundedPositions(graph, selected)).toEqual(roundedPositions(graph, selected));
 	});
 
+	it('reproduces the exact layout for a given seed', () => {
+		const selected = makeNode('crate::Center', 'Center', 'Struct');
+		const deps = Array.from({ length: 12 }, (_, index) =>
+			makeNode(`crate::Dep${index}`, `Dep${index}`),
+		);
+		const graph = {
+			nodes: [selected, ...deps],
+			edges: deps.map((dep) => makeEdge(selected.id, dep.id)),
+		};
+		const layout = (seed: number) =>
+			JSON.stringify(computeForceLayout(graph, selected, seed).nodes.map(({ x, y }) => [x, y]));
+
+		expect(layout(42)).toBe(layout(42));
+		expect(layout(42)).not.toBe(layout(7));
+	});
+
+	it('lays out edges below minConfidence without letting them pull', () => {
+		const selected = makeNode('crate::Center', 'Center', 'Struct');
+		const deps = Array.from({ length: 6 }, (_, index) =>
+			makeNode(`crate::Dep${index}`, `Dep${index}`),
+		);
+		const nodes = [selected, ...deps];
+		const edges = deps.map((dep) => makeEdge(selected.id, dep.id));
+		const guess: Edge = {
+			...makeEdge(deps[0].id, deps[3].id, 'CallsRuntime'),
+			confidence: 'Inferred',
+		};
+		const positions = (graphEdges: Edge[], minConfidence?: Edge['confidence']) =>
+			computeForceLayout({ nodes, edges: graphEdges }, selected, 42, minConfidence).nodes.map(
+				({ x, y }) => [x, y],
+			);
+
+		const ignored = computeForceLayout({ nodes, edges: [...edges, guess] }, selected, 42, 'Runtime');
+		expect(ignored.edges).toHaveLength(edges.length + 1);
+		expect(positions([...edges, guess], 'Runtime')).toEqual(positions(edges));
+		expect(positions([...edges, guess])).not.toEqual(positions(edges));
+	});
+
 	it('keeps laid out nodes inside the viewport margins', () => {
 		const selected = makeNode('crate::Center', 'Center', 'Struct');
 		const deps = Array.from({ length: 40 }, (_, index) =>
@@ -9,7 +9,7 @@ import {
 	type SimulationLinkDatum,
 	type SimulationNodeDatum,
 } from 'd3-force';
-import type { Edge, Graph, Node } from '$lib/graph';
+import type { Confidence, Edge, Graph, Node } from '$lib/graph';
 import type { VisNode, VisEdge } from './types';
 import {
 	CENTER_X,
@@ -18,8 +18,11 @@ import {
 	LAYOUT_HEIGHT,
 	FORCE_RADIUS,
 	MIN_NODE_SPACING,
+	DEFAULT_FORCE_SEED,
 } from './types';
 import { getNodeBoundingBox, resolveCollisions } from './collision';
+import { nodeScale, type NodeScales } from '$lib/graph/visual/node-visual';
+import { meetsConfidence } from '$lib/graph/visual/confidence';
 
 type ForceDatum = SimulationNodeDatum & {
 	id: string;
@@ -34,9 +37,18 @@ type ForceLink = SimulationLinkDatum<ForceDatum> & {
 const FORCE_TICKS = 90;
 const VIEWPORT_MARGIN = 50;
 
+/**
+ * d3-force simulation around the selected node. `seed` drives both the
+ * starting rotation of the orbit and d3's own jiggle, so a given seed
+ * reproduces an arrangement exactly and a different one reshuffles it.
+ * Edges below `minConfidence` keep their nodes in the layout but exert no pull.
+ */
 export function computeForceLayout(
 	graph: Graph,
 	selected: Node,
+	seed: number = DEFAULT_FORCE_SEED,
+	minConfidence?: Confidence,
+	nodeScales?: NodeScales,
 ): { nodes: VisNode[]; edges: VisEdge[] } {
 	const nodeMap = new Map<string, Node>();
 	for (const node of graph.nodes) {
@@ -59,10 +71,12 @@ export function computeForceLayout(
 		return { nodes: [], edges: [] };
 	}
 
+	const random = seededRandom(seed);
 	const orbitCount = Math.max(1, nodesToLayout.length - 1);
+	const orbitPhase = (random() * 2 * Math.PI) / orbitCount;
 	let orbitIndex = 0;
 	const layoutNodes: ForceDatum[] = nodesToLayout.map((node) => {
-		const box = getNodeBoundingBox(node, node.id === selected.id);
+		const box = getNodeBoundingBox(node, node.id === selected.id, nodeScale(nodeScales, node.kind));
 		const radius = Math.max(box.width, box.height) / 2;
 		if (node.id === selected.id) {
 			return {
@@ -76,7 +90,7 @@ export function computeForceLayout(
 			};
 		}
 
-		const angle = (2 * Math.PI * orbitIndex) / orbitCount - Math.PI / 2;
+		const angle = (2 * Math.PI * orbitIndex) / orbitCount - Math.PI / 2 + orbitPhase;
 		orbitIndex += 1;
 		return {
 			id: node.id,
@@ -90,11 +104,13 @@ export function computeForceLayout(
 	const layoutNodeMap = new Map(layoutNodes.map((node) => [node.id, node]));
 	const layoutLinks: ForceLink[] = graph.edges
 		.filter((edge) => connectedIds.has(edge.from) && connectedIds.has(edge.to))
+		.filter((edge) => meetsConfidence(edge.confidence, minConfidence))
 		.map((edge) => ({ source: edge.from, target: edge.to, edge }));
 	const resolveLinkDatum = (endpoint: ForceLink['source']): ForceDatum | undefined =>
 		typeof endpoint === 'object' ? endpoint : layoutNodeMap.get(String(endpoint));
 
 	const simulation = forceSimulation(layoutNodes)
+		.randomSource(random)
 		.force(
 			'link',
 			forceLink<ForceDatum, ForceLink>(layoutLinks)
@@ -147,6 +163,7 @@ export function computeForceLayout(
 			indexInLayer: 0,
 			totalInLayer: 1,
 			layoutRadius: 0,
+			scale: nodeScale(nodeScales, node.kind),
 		};
 		visNodes.push(visNode);
 		visNodeMap.set(node.id, visNode);
@@ -186,3 +203,15 @@ export function computeForceLayout(
 
 	return { nodes: visNodes, edges: visEdges };
 }
+
+/** mulberry32: tiny, fast, and plenty for nudging a layout. Uses the low 32 bits of `seed`. */
+function seededRandom(seed: number): () => number {
+	let state = seed >>> 0;
+	return () => {
+		state = (state + 0x6d2b79f5) >>> 0;
+		let t = state;
+		t = Math.imul(t ^ (t >>> 15), t | 1);
+		t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+		return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+	};
+}
@@ -0,0 +1,65 @@
+import { describe, expect, it } from 'vite-plus/test';
+import type { Edge, Graph, Node } from '$lib/graph';
+import { computeGridLayout } from './grid';
+
+function makeNode(id: string, name: string, kind: Node['kind'] = 'Function'): Node {
+	return {
+		id,
+		name,
+		kind,
+		visibility: { kind: 'Public' },
+		attrs: [],
+	};
+}
+
+function makeEdge(from: string, to: string, kind: Edge['kind'] = 'UsesType'): Edge {
+	return {
+		from,
+		to,
+		kind,
+		confidence: 'Static',
+	};
+}
+
+function positions(graph: Graph, selected: Node): Array<[string, number, number]> {
+	return computeGridLayout(graph, selected)
+		.nodes.map((node): [string, number, number] => [node.node.id, node.x, node.y])
+		.sort(([a], [b]) => (a < b ? -1 : a > b ? 1 : 0));
+}
+
+describe('computeGridLayout', () => {
+	const selected = makeNode('crate::Center', 'Center', 'Struct');
+	const neighbours = [
+		makeNode('crate::b_fn', 'b_fn'),
+		makeNode('crate::a_fn', 'a_fn'),
+		makeNode('crate::Shape', 'Shape', 'Enum'),
+		makeNode('crate::Other', 'Other', 'Struct'),
+	];
+	const graph = {
+		nodes: [selected, ...neighbours],
+		edges: neighbours.map((node) => makeEdge(selected.id, node.id)),
+	};
+
+	it('puts one kind per column, stacked by id', () => {
+		const result = computeGridLayout(graph, selected);
+		const byId = new Map(result.nodes.map((node) => [node.node.id, node]));
+
+		// nodeKindOrder puts Struct before Enum before Function.
+		const structX = byId.get('crate::Center')?.x ?? NaN;
+		expect(byId.get('crate::Other')?.x).toBe(structX);
+		expect(byId.get('crate::Shape')?.x).toBeGreaterThan(structX);
+		expect(byId.get('crate::a_fn')?.x).toBeGreaterThan(byId.get('crate::Shape')?.x ?? NaN);
+		expect(byId.get('crate::a_fn')?.x).toBe(byId.get('crate::b_fn')?.x);
+		expect(byId.get('crate::a_fn')?.y).toBeLessThan(byId.get('crate::b_fn')?.y ?? NaN);
+		expect(byId.get('crate::Center')?.y).toBeLessThan(byId.get('crate::Other')?.y ?? NaN);
+	});
+
+	it('does not depend on input order', () => {
+		const reversed = {
+			nodes: [...graph.nodes].reverse(),
+			edges: [...graph.edges].reverse(),
+		};
+
+		expect(positions(reversed, selected)).toEqual(positions(graph, selected));
+	});
+});
@@ -0,0 +1,115 @@
+import type { Graph, Node } from '$lib/graph';
+import { nodeKindOrder } from '$lib/display-names';
+import type { VisNode, VisEdge } from './types';
+import { CENTER_X, CENTER_Y, FLOW_COLUMN_GAP, FLOW_ROW_GAP } from './types';
+import { getNodeBoundingBox } from './collision';
+import { nodeScale, type NodeScales } from '$lib/graph/visual/node-visual';
+
+/**
+ * Deterministic layout for snapshots: the selected node's neighbourhood is
+ * binned into one column per `NodeKind` (in `nodeKindOrder`), and each
+ * column is stacked by node id. Positions depend only on the graph, never
+ * on input order or a simulation.
+ */
+export function computeGridLayout(
+	graph: Graph,
+	selected: Node,
+	nodeScales?: NodeScales,
+): { nodes: VisNode[]; edges: VisEdge[] } {
+	const nodeMap = new Map<string, Node>();
+	for (const node of graph.nodes) {
+		nodeMap.set(node.id, node);
+	}
+
+	const connectedIds = new Set<string>();
+	const incomingIds = new Set<string>();
+	connectedIds.add(selected.id);
+	for (const edge of graph.edges) {
+		if (edge.from === selected.id) connectedIds.add(edge.to);
+		if (edge.to === selected.id) {
+			connectedIds.add(edge.from);
+			incomingIds.add(edge.from);
+		}
+	}
+
+	const columns = new Map<Node['kind'], Node[]>();
+	for (const id of connectedIds) {
+		const node = nodeMap.get(id) ?? (id === selected.id ? selected : undefined);
+		if (!node) continue;
+		const column = columns.get(node.kind) ?? [];
+		column.push(node);
+		columns.set(node.kind, column);
+	}
+	const orderedKinds = nodeKindOrder.filter((kind) => columns.has(kind));
+	for (const kind of columns.keys()) {
+		if (!orderedKinds.includes(kind)) orderedKinds.push(kind);
+	}
+
+	const laidOut = orderedKinds.map((kind) => {
+		const nodes = (columns.get(kind) ?? []).sort((a, b) => compareIds(a.id, b.id));
+		const boxes = nodes.map((node) =>
+			getNodeBoundingBox(node, node.id === selected.id, nodeScale(nodeScales, node.kind)),
+		);
+		const width = Math.max(...boxes.map((box) => box.width));
+		const rowHeight = Math.max(...boxes.map((box) => box.height)) + FLOW_ROW_GAP;
+		return { nodes, width, rowHeight };
+	});
+
+	const totalWidth =
+		laidOut.reduce((sum, column) => sum + column.width, 0) +
+		Math.max(0, laidOut.length - 1) * FLOW_COLUMN_GAP;
+	let cursor = CENTER_X - totalWidth / 2;
+
+	const visNodes: VisNode[] = [];
+	const visNodeMap = new Map<string, VisNode>();
+	laidOut.forEach((column, layer) => {
+		const x = cursor + column.width / 2;
+		cursor += column.width + FLOW_COLUMN_GAP;
+		const top = CENTER_Y - ((column.nodes.length - 1) * column.rowHeight) / 2;
+		column.nodes.forEach((node, row) => {
+			const isCenter = node.id === selected.id;
+			const y = top + row * column.rowHeight;
+			const visNode: VisNode = {
+				node,
+				x,
+				y,
+				baseX: x,
+				baseY: y,
+				angle: 0,
+				isCenter,
+				edgeKind: '',
+				direction: isCenter ? 'center' : incomingIds.has(node.id) ? 'in' : 'out',
+				layer,
+				indexInLayer: row,
+				totalInLayer: column.nodes.length,
+				layoutRadius: 0,
+				scale: nodeScale(nodeScales, node.kind),
+			};
+			visNodes.push(visNode);
+			visNodeMap.set(node.id, visNode);
+		});
+	});
+
+	const visEdges: VisEdge[] = [];
+	for (const edge of graph.edges) {
+		const from = visNodeMap.get(edge.from);
+		const to = visNodeMap.get(edge.to);
+		if (from && to) {
+			visEdges.push({
+				from,
+				to,
+				kind: edge.kind,
+				confidence: edge.confidence,
+				is_glob: edge.is_glob,
+				direction: edge.from === selected.id ? 'out' : 'in',
+			});
+		}
+	}
+
+	return { nodes: visNodes, edges: visEdges };
+}
+
+/** Code-unit order, so results don't vary with the runtime's locale. */
+export function compareIds(a: string, b: string): number {
+	return a < b ? -1 : a > b ? 1 : 0;
+}
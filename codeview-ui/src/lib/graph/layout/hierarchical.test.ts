@@ -0,0 +1,131 @@
+import { describe, expect, it } from 'vite-plus/test';
+import type { Edge, Graph, Node } from '$lib/graph';
+import { computeHierarchicalLayout, LAYER_GAP } from './hierarchical';
+
+function makeNode(id: string, name: string, kind: Node['kind'] = 'Module'): Node {
+	return {
+		id,
+		name,
+		kind,
+		visibility: { kind: 'Public' },
+		attrs: [],
+	};
+}
+
+function makeEdge(from: string, to: string, kind: Edge['kind'] = 'Contains'): Edge {
+	return {
+		from,
+		to,
+		kind,
+		confidence: 'Static',
+	};
+}
+
+function layoutById(graph: Graph, selected: Node) {
+	const result = computeHierarchicalLayout(graph, selected);
+	return new Map(result.nodes.map((node) => [node.node.id, node]));
+}
+
+describe('computeHierarchicalLayout', () => {
+	const root = makeNode('demo', 'demo', 'Crate');
+
+	it('puts each module one layer below its parent', () => {
+		const graph = {
+			nodes: [
+				root,
+				makeNode('demo::a', 'a'),
+				makeNode('demo::a::inner', 'inner'),
+				makeNode('demo::b', 'b'),
+			],
+			edges: [
+				makeEdge('demo', 'demo::a'),
+				makeEdge('demo', 'demo::b'),
+				makeEdge('demo::a', 'demo::a::inner'),
+				makeEdge('demo::b', 'demo::a::inner', 'UsesType'),
+			],
+		};
+		const byId = layoutById(graph, root);
+
+		expect(byId.get('demo')?.layer).toBe(0);
+		expect(byId.get('demo::a')?.layer).toBe(1);
+		expect(byId.get('demo::b')?.layer).toBe(1);
+		expect(byId.get('demo::a::inner')?.layer).toBe(2);
+		const rootY = byId.get('demo')?.y ?? NaN;
+		expect(byId.get('demo::a')?.y).toBeCloseTo(rootY + LAYER_GAP);
+		expect(byId.get('demo::a::inner')?.y).toBeCloseTo(rootY + 2 * LAYER_GAP);
+	});
+
+	it('orders layers to avoid crossings', () => {
+		// In id order the children would sit under the wrong parents.
+		const graph = {
+			nodes: [
+				root,
+				makeNode('demo::a', 'a'),
+				makeNode('demo::b', 'b'),
+				makeNode('demo::y', 'y'),
+				makeNode('demo::z', 'z'),
+			],
+			edges: [
+				makeEdge('demo', 'demo::a'),
+				makeEdge('demo', 'demo::b'),
+				makeEdge('demo::a', 'demo::z'),
+				makeEdge('demo::b', 'demo::y'),
+			],
+		};
+		const byId = layoutById(graph, root);
+		const x = (id: string) => byId.get(id)?.x ?? NaN;
+
+		expect(Math.sign(x('demo::a') - x('demo::b'))).toBe(Math.sign(x('demo::z') - x('demo::y')));
+	});
+
+	it('spreads each layer evenly around the centre', () => {
+		const children = ['one', 'two', 'six'].map((name) => makeNode(`demo::${name}`, name));
+		const graph = {
+			nodes: [root, ...children],
+			edges: children.map((child) => makeEdge(root.id, child.id)),
+		};
+		const row = computeHierarchicalLayout(graph, root)
+			.nodes.filter((node) => node.layer === 1)
+			.sort((a, b) => a.indexInLayer - b.indexInLayer);
+
+		expect(row).toHaveLength(3);
+		expect(row[1].x - row[0].x).toBeCloseTo(row[2].x - row[1].x);
+		expect(row[1].x).toBeCloseTo(layoutById(graph, root).get('demo')?.x ?? NaN);
+	});
+
+	it('ignores back edges and lays out every node of a cycle', () => {
+		const a = makeNode('demo::a', 'a');
+		const graph = {
+			nodes: [a, makeNode('demo::b', 'b'), makeNode('demo::c', 'c')],
+			edges: [
+				makeEdge('demo::a', 'demo::b'),
+				makeEdge('demo::b', 'demo::c'),
+				makeEdge('demo::c', 'demo::a'),
+			],
+		};
+		const byId = layoutById(graph, a);
+
+		expect([...byId.values()].map((node) => node.layer).sort()).toEqual([0, 1, 2]);
+	});
+
+	it('falls back to every edge when nothing is contained', () => {
+		const selected = makeNode('demo::run', 'run', 'Function');
+		const graph = {
+			nodes: [
+				makeNode('demo::main', 'main', 'Function'),
+				selected,
+				makeNode('demo::step', 'step', 'Function'),
+			],
+			edges: [
+				makeEdge('demo::main', 'demo::run', 'CallsStatic'),
+				makeEdge('demo::run', 'demo::step', 'CallsStatic'),
+			],
+		};
+		const byId = layoutById(graph, selected);
+
+		expect(byId.get('demo::main')?.direction).toBe('in');
+		expect(byId.get('demo::run')?.isCenter).toBe(true);
+		expect(byId.get('demo::main')?.y).toBeLessThan(byId.get('demo::run')?.y ?? NaN);
+		expect(byId.get('demo::run')?.y).toBeLessThan(byId.get('demo::step')?.y ?? NaN);
+	});
+});
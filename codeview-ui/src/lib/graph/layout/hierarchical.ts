@@ -1,135 +1,83 @@
 import type { Graph, Node } from '$lib/graph';
 import type { VisNode, VisEdge } from './types';
-import { CENTER_X, CENTER_Y, LAYOUT_HEIGHT, MIN_NODE_SPACING } from './types';
-import { getNodeVisual } from '$lib/graph/visual/node-visual';
-import { resolveCollisions } from './collision';
+import { CENTER_X, CENTER_Y, MIN_NODE_SPACING } from './types';
+import { getNodeBoundingBox } from './collision';
+import { nodeScale, type NodeScales } from '$lib/graph/visual/node-visual';
+import { compareIds } from './grid';
 
+/** Vertical distance between adjacent layers. */
+export const LAYER_GAP = 90;
+
+/** Barycenter passes (one down and one up sweep each) before giving up. */
+const ORDERING_PASSES = 8;
+
+/**
+ * Sugiyama-style layered layout. `Contains` edges put each parent one layer
+ * above its children (longest path, with back edges from cycles ignored);
+ * a graph without any falls back to layering by every edge, which puts
+ * callers above the selected node and callees below. Nodes within a layer
+ * are ordered by barycenter sweeps to cut edge crossings, then spread
+ * evenly across the row.
+ */
 export function computeHierarchicalLayout(
 	graph: Graph,
 	selected: Node,
+	nodeScales?: NodeScales,
 ): { nodes: VisNode[]; edges: VisEdge[] } {
 	const nodeMap = new Map<string, Node>();
 	for (const node of graph.nodes) {
 		nodeMap.set(node.id, node);
 	}
-
-	const connectedIds = new Set<string>();
-	connectedIds.add(selected.id);
-
-	for (const edge of graph.edges) {
-		if (edge.from === selected.id) connectedIds.add(edge.to);
-		if (edge.to === selected.id) connectedIds.add(edge.from);
-	}
+	if (!nodeMap.has(selected.id)) nodeMap.set(selected.id, selected);
+	const ids = [...nodeMap.keys()].sort(compareIds);
 
 	const incomingIds = new Set<string>();
-	const outgoingIds = new Set<string>();
-
 	for (const edge of graph.edges) {
-		if (edge.to === selected.id && connectedIds.has(edge.from)) {
-			incomingIds.add(edge.from);
-		}
-		if (edge.from === selected.id && connectedIds.has(edge.to)) {
-			outgoingIds.add(edge.to);
-		}
-	}
-
-	for (const id of outgoingIds) {
-		incomingIds.delete(id);
+		if (edge.to === selected.id) incomingIds.add(edge.from);
 	}
 
-	const incomingNodes = Array.from(incomingIds)
-		.map((id) => nodeMap.get(id))
-		.filter((n): n is Node => n !== undefined);
-	const outgoingNodes = Array.from(outgoingIds)
-		.map((id) => nodeMap.get(id))
-		.filter((n): n is Node => n !== undefined);
-
-	const layerY = [LAYOUT_HEIGHT * 0.15, CENTER_Y, LAYOUT_HEIGHT * 0.85];
+	const containsPairs = edgePairs(graph, nodeMap, (kind) => kind === 'Contains');
+	const pairs = containsPairs.length > 0 ? containsPairs : edgePairs(graph, nodeMap, () => true);
+	const children = adjacency(ids, acyclicPairs(ids, pairs));
+	const layers = orderLayers(assignLayers(ids, children), children);
 
 	const visNodes: VisNode[] = [];
 	const visNodeMap = new Map<string, VisNode>();
-
-	const centerVisNode: VisNode = {
-		node: selected,
-		x: CENTER_X,
-		y: layerY[1],
-		baseX: CENTER_X,
-		baseY: layerY[1],
-		angle: 0,
-		isCenter: true,
-		edgeKind: '',
-		direction: 'center',
-		layer: 1,
-		indexInLayer: 0,
-		totalInLayer: 1,
-		layoutRadius: 0,
-	};
-	visNodes.push(centerVisNode);
-	visNodeMap.set(selected.id, centerVisNode);
-
-	function layoutRow(nodes: Node[], y: number, direction: 'in' | 'out', layer: number): VisNode[] {
-		if (nodes.length === 0) return [];
-		if (nodes.length === 1) {
-			const node = nodes[0];
-			return [
-				{
-					node,
-					x: CENTER_X,
-					y,
-					baseX: CENTER_X,
-					baseY: y,
-					angle: 0,
-					isCenter: false,
-					edgeKind: '',
-					direction,
-					layer,
-					indexInLayer: 0,
-					totalInLayer: 1,
-					layoutRadius: 0,
-				},
-			];
-		}
-
-		const widths = nodes.map((n) => getNodeVisual(n.kind, false).width);
+	const top = CENTER_Y - ((layers.length - 1) * LAYER_GAP) / 2;
+	layers.forEach((layerIds, layer) => {
+		const y = top + layer * LAYER_GAP;
+		const nodes = layerIds.map((id) => nodeMap.get(id) as Node);
+		const widths = nodes.map(
+			(node) =>
+				getNodeBoundingBox(node, node.id === selected.id, nodeScale(nodeScales, node.kind)).width,
+		);
 		const totalWidth =
-			widths.reduce((sum, w) => sum + w, 0) + (nodes.length - 1) * MIN_NODE_SPACING;
+			widths.reduce((sum, width) => sum + width, 0) + (nodes.length - 1) * MIN_NODE_SPACING;
 		let cursor = CENTER_X - totalWidth / 2;
-
-		return nodes.map((node, i) => {
-			const x = cursor + widths[i] / 2;
-			cursor += widths[i] + MIN_NODE_SPACING;
-			return {
+		nodes.forEach((node, index) => {
+			const x = cursor + widths[index] / 2;
+			cursor += widths[index] + MIN_NODE_SPACING;
+			const isCenter = node.id === selected.id;
+			const visNode: VisNode = {
 				node,
 				x,
 				y,
 				baseX: x,
 				baseY: y,
 				angle: 0,
-				isCenter: false,
+				isCenter,
 				edgeKind: '',
-				direction,
+				direction: isCenter ? 'center' : incomingIds.has(node.id) ? 'in' : 'out',
 				layer,
-				indexInLayer: i,
+				indexInLayer: index,
 				totalInLayer: nodes.length,
 				layoutRadius: 0,
+				scale: nodeScale(nodeScales, node.kind),
 			};
+			visNodes.push(visNode);
+			visNodeMap.set(node.id, visNode);
 		});
-	}
-
-	const inVisNodes = layoutRow(incomingNodes, layerY[0], 'in', 0);
-	const outVisNodes = layoutRow(outgoingNodes, layerY[2], 'out', 2);
-
-	for (const visNode of inVisNodes) {
-		visNodes.push(visNode);
-		visNodeMap.set(visNode.node.id, visNode);
-	}
-
-	for (const visNode of outVisNodes) {
-		visNodes.push(visNode);
-		visNodeMap.set(visNode.node.id, visNode);
-	}
-
-	resolveCollisions(visNodes, selected.id);
+	});
 
 	const visEdges: VisEdge[] = [];
 	for (const edge of graph.edges) {
@@ -149,3 +97,172 @@ export function computeHierarchicalLayout(
 
 	return { nodes: visNodes, edges: visEdges };
 }
+
+type Pair = [from: string, to: string];
+
+/** Distinct `from → to` pairs between laid-out nodes, self-loops dropped. */
+function edgePairs(
+	graph: Graph,
+	nodeMap: Map<string, Node>,
+	include: (kind: string) => boolean,
+): Pair[] {
+	const seen = new Set<string>();
+	const pairs: Pair[] = [];
+	for (const edge of graph.edges) {
+		if (!include(edge.kind) || edge.from === edge.to) continue;
+		if (!nodeMap.has(edge.from) || !nodeMap.has(edge.to)) continue;
+		const key = `${edge.from}\n${edge.to}`;
+		if (seen.has(key)) continue;
+		seen.add(key);
+		pairs.push([edge.from, edge.to]);
+	}
+	return pairs;
+}
+
+function adjacency(ids: string[], pairs: Pair[]): Map<string, string[]> {
+	const out = new Map<string, string[]>(ids.map((id) => [id, []]));
+	for (const [from, to] of pairs) out.get(from)?.push(to);
+	for (const targets of out.values()) targets.sort(compareIds);
+	return out;
+}
+
+/** `pairs` minus the back edges a depth-first walk (in id order) finds. */
+function acyclicPairs(ids: string[], pairs: Pair[]): Pair[] {
+	const out = adjacency(ids, pairs);
+	const state = new Map<string, 'open' | 'done'>();
+	const back = new Set<string>();
+	for (const root of ids) {
+		if (state.has(root)) continue;
+		state.set(root, 'open');
+		const stack: Array<[string, number]> = [[root, 0]];
+		while (stack.length > 0) {
+			const frame = stack[stack.length - 1];
+			const [id, next] = frame;
+			const targets = out.get(id) ?? [];
+			if (next === targets.length) {
+				state.set(id, 'done');
+				stack.pop();
+				continue;
+			}
+			frame[1] += 1;
+			const target = targets[next];
+			const targetState = state.get(target);
+			if (targetState === 'open') {
+				back.add(`${id}\n${target}`);
+			} else if (targetState === undefined) {
+				state.set(target, 'open');
+				stack.push([target, 0]);
+			}
+		}
+	}
+	return pairs.filter(([from, to]) => !back.has(`${from}\n${to}`));
+}
+
+/** Longest-path layering: every node sits one layer below its lowest parent. */
+function assignLayers(ids: string[], children: Map<string, string[]>): string[][] {
+	const indegree = new Map<string, number>(ids.map((id) => [id, 0]));
+	for (const targets of children.values()) {
+		for (const target of targets) indegree.set(target, (indegree.get(target) ?? 0) + 1);
+	}
+	const layerOf = new Map<string, number>();
+	const queue = ids.filter((id) => indegree.get(id) === 0);
+	for (const id of queue) layerOf.set(id, 0);
+	for (let head = 0; head < queue.length; head++) {
+		const id = queue[head];
+		const layer = layerOf.get(id) ?? 0;
+		for (const target of children.get(id) ?? []) {
+			layerOf.set(target, Math.max(layerOf.get(target) ?? 0, layer + 1));
+			const remaining = (indegree.get(target) ?? 0) - 1;
+			indegree.set(target, remaining);
+			if (remaining === 0) queue.push(target);
+		}
+	}
+
+	const layers: string[][] = [];
+	for (const id of ids) {
+		const layer = layerOf.get(id) ?? 0;
+		while (layers.length <= layer) layers.push([]);
+		layers[layer].push(id);
+	}
+	return layers;
+}
+
+/**
+ * Reorder each layer by the mean position of its neighbours in the layer
+ * above (down sweep) or below (up sweep), keeping the ordering with the
+ * fewest crossings seen.
+ */
+function orderLayers(layers: string[][], children: Map<string, string[]>): string[][] {
+	const parents = new Map<string, string[]>();
+	for (const [from, targets] of children) {
+		for (const target of targets) {
+			const list = parents.get(target) ?? [];
+			list.push(from);
+			parents.set(target, list);
+		}
+	}
+
+	let current = layers.map((layer) => [...layer]);
+	let best = current;
+	let bestCrossings = countCrossings(current, children);
+	for (let pass = 0; pass < ORDERING_PASSES && bestCrossings > 0; pass++) {
+		current = current.map((layer) => [...layer]);
+		for (let layer = 1; layer < current.length; layer++) {
+			current[layer] = byBarycenter(current[layer], current[layer - 1], parents);
+		}
+		for (let layer = current.length - 2; layer >= 0; layer--) {
+			current[layer] = byBarycenter(current[layer], current[layer + 1], children);
+		}
+		const crossings = countCrossings(current, children);
+		if (crossings < bestCrossings) {
+			best = current;
+			bestCrossings = crossings;
+		}
+	}
+	return best;
+}
+
+/** Nodes with no neighbour in `fixed` keep their current position. */
+function byBarycenter(
+	layer: string[],
+	fixed: string[],
+	neighbours: Map<string, string[]>,
+): string[] {
+	const position = new Map(fixed.map((id, index) => [id, index]));
+	const keyed = layer.map((id, index) => {
+		const positions = (neighbours.get(id) ?? [])
+			.map((neighbour) => position.get(neighbour))
+			.filter((value): value is number => value !== undefined);
+		const barycenter =
+			positions.length > 0
+				? positions.reduce((sum, value) => sum + value, 0) / positions.length
+				: (index * Math.max(fixed.length - 1, 0)) / Math.max(layer.length - 1, 1);
+		return { id, barycenter, index };
+	});
+	keyed.sort((a, b) => a.barycenter - b.barycenter || a.index - b.index);
+	return keyed.map(({ id }) => id);
+}
+
+/** Crossings between edges that join adjacent layers. */
+function countCrossings(layers: string[][], children: Map<string, string[]>): number {
+	let crossings = 0;
+	for (let layer = 0; layer + 1 < layers.length; layer++) {
+		const upper = new Map(layers[layer].map((id, index) => [id, index]));
+		const lower = new Map(layers[layer + 1].map((id, index) => [id, index]));
+		const segments: Array<[number, number]> = [];
+		for (const [from, fromIndex] of upper) {
+			for (const to of children.get(from) ?? []) {
+				const toIndex = lower.get(to);
+				if (toIndex !== undefined) segments.push([fromIndex, toIndex]);
+			}
+		}
+		for (let i = 0; i < segments.length; i++) {
+			for (let j = i + 1; j < segments.length; j++) {
+				const [a1, a2] = segments[i];
+				const [b1, b2] = segments[j];
+				if ((a1 - b1) * (a2 - b2) < 0) crossings++;
+			}
+		}
+	}
+	return crossings;
+}
@@ -1,4 +1,12 @@
-export type { LayoutMode, VisNode, VisEdge, LayoutNode, LayoutLink, LayoutState } from './types';
+export type {
+	LayoutMode,
+	LayoutOptions,
+	VisNode,
+	VisEdge,
+	LayoutNode,
+	LayoutLink,
+	LayoutState,
+} from './types';
 export {
 	LAYOUT_WIDTH,
 	LAYOUT_HEIGHT,
@@ -8,6 +16,7 @@ export {
 	FLOW_ROW_GAP,
 	MAX_NODES_PER_COLUMN,
 	FORCE_RADIUS,
+	DEFAULT_FORCE_SEED,
 	RADIAL_RADIUS,
 	MIN_NODE_SPACING,
 	LABEL_CHAR_WIDTH,
@@ -18,4 +27,6 @@ export { computeEgoLayout } from './ego';
 export { computeForceLayout } from './force';
 export { computeHierarchicalLayout } from './hierarchical';
 export { computeRadialLayout } from './radial';
+export { computeGridLayout } from './grid';
+export { computeCircularLayout } from './circular';
 export { computeLayout } from './compute';
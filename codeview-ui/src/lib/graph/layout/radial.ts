@@ -2,10 +2,12 @@ import type { Graph, Node } from '$lib/graph';
 import type { VisNode, VisEdge } from './types';
 import { CENTER_X, CENTER_Y, RADIAL_RADIUS, MIN_NODE_SPACING, ARROWHEAD_LENGTH } from './types';
 import { getNodeBoundingBox, resolveCollisions } from './collision';
+import { nodeScale, type NodeScales } from '$lib/graph/visual/node-visual';
 
 export function computeRadialLayout(
 	graph: Graph,
 	selected: Node,
+	nodeScales?: NodeScales,
 ): { nodes: VisNode[]; edges: VisEdge[] } {
 	const nodeMap = new Map<string, Node>();
 	for (const node of graph.nodes) {
@@ -42,15 +44,16 @@ export function computeRadialLayout(
 		indexInLayer: 0,
 		totalInLayer: 1,
 		layoutRadius: 0,
+		scale: nodeScale(nodeScales, selected.kind),
 	};
 	visNodes.push(centerVisNode);
 	visNodeMap.set(selected.id, centerVisNode);
 
-	const centerBox = getNodeBoundingBox(selected, true);
+	const centerBox = getNodeBoundingBox(selected, true, centerVisNode.scale);
 	const centerRadius = Math.max(centerBox.width, centerBox.height) / 2;
 	let totalArcLength = 0;
 	for (const node of surroundingNodes) {
-		const box = getNodeBoundingBox(node, false);
+		const box = getNodeBoundingBox(node, false, nodeScale(nodeScales, node.kind));
 		totalArcLength += box.width + MIN_NODE_SPACING;
 	}
 	const minRadiusForSpacing = totalArcLength / (2 * Math.PI);
@@ -61,7 +64,7 @@ export function computeRadialLayout(
 		(surroundingNodes.length > 0
 			? Math.max(
 					...surroundingNodes.map((n) => {
-						const box = getNodeBoundingBox(n, false);
+						const box = getNodeBoundingBox(n, false, nodeScale(nodeScales, n.kind));
 						return Math.max(box.width, box.height) / 2;
 					}),
 				)
@@ -95,6 +98,7 @@ export function computeRadialLayout(
 			indexInLayer: i,
 			totalInLayer: surroundingNodes.length,
 			layoutRadius: radius,
+			scale: nodeScale(nodeScales, node.kind),
 		};
 		visNodes.push(visNode);
 		visNodeMap.set(node.id, visNode);
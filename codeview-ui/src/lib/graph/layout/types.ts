@@ -1,6 +1,23 @@
 import type { Confidence, Edge, Node } from '$lib/graph';
+import type { NodeScales } from '$lib/graph/visual/node-visual';
 
-export type LayoutMode = 'ego' | 'force' | 'hierarchical' | 'radial';
+export type LayoutMode = 'ego' | 'force' | 'hierarchical' | 'radial' | 'grid' | 'circular';
+
+export type LayoutOptions = {
+	/**
+	 * Seed for layouts with a random component (currently only `force`).
+	 * The same seed, graph and options always give the same positions.
+	 */
+	seed?: number;
+	/**
+	 * Edges below this confidence are still returned but don't pull on node
+	 * positions, so speculative call edges can't drag the layout around.
+	 * Only `force` weighs individual edges; the other modes ignore it.
+	 */
+	minConfidence?: Confidence;
+	/** Per-kind size multipliers; every mode spaces nodes by the scaled sizes. */
+	nodeScales?: NodeScales;
+};
 
 export type VisNode = {
 	node: Node;
@@ -16,6 +33,8 @@ export type VisNode = {
 	indexInLayer: number;
 	totalInLayer: number;
 	layoutRadius: number;
+	/** This node's multiplier from `LayoutOptions.nodeScales`; absent means 1. */
+	scale?: number;
 };
 
 export type VisEdge = {
@@ -35,6 +54,7 @@ export const FLOW_COLUMN_GAP = 96;
 export const FLOW_ROW_GAP = 18;
 export const MAX_NODES_PER_COLUMN = 18;
 export const FORCE_RADIUS = 180;
+export const DEFAULT_FORCE_SEED = 1;
 export const RADIAL_RADIUS = 180;
 export const MIN_NODE_SPACING = 16;
 export const LABEL_CHAR_WIDTH = 6.6;
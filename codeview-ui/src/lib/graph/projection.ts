@@ -54,12 +54,16 @@ type GraphProjectionOptions = {
 function edgeKindPriority(kind: EdgeKind): number {
 	switch (kind) {
 		case 'UsesType':
+		case 'Returns':
+		case 'HasField':
 			return 0;
 		case 'CallsStatic':
 			return 1;
 		case 'CallsRuntime':
+		case 'CallsMacro':
 			return 2;
 		case 'Implements':
+		case 'Supertrait':
 			return 3;
 		case 'Derives':
 			return 4;
@@ -0,0 +1,60 @@
+import type { EdgeKind } from '$lib/schema';
+
+/** Arrowhead glyphs, named after Excalidraw's arrowhead vocabulary. */
+export type ArrowheadStyle =
+	| 'arrow'
+	| 'triangle'
+	| 'triangle_outline'
+	| 'diamond'
+	| 'diamond_outline'
+	| 'dot'
+	| 'bar';
+
+export type ArrowheadOverrides = Partial<Record<EdgeKind, ArrowheadStyle>>;
+
+/**
+ * UML-style defaults, mirroring Mermaid class-diagram semantics: containment
+ * as a diamond (`*--`), implementation as a hollow triangle (`..|>`), calls
+ * and type use as a plain arrow (`-->`).
+ */
+export const DEFAULT_ARROWHEADS = {
+	Contains: 'diamond',
+	Defines: 'diamond_outline',
+	Implements: 'triangle_outline',
+	Supertrait: 'triangle_outline',
+	Derives: 'triangle',
+	UsesType: 'arrow',
+	Returns: 'arrow',
+	CallsStatic: 'arrow',
+	CallsRuntime: 'dot',
+	CallsMacro: 'arrow',
+	ReExports: 'bar',
+	HasField: 'arrow',
+} as const satisfies Record<EdgeKind, ArrowheadStyle>;
+
+export function arrowheadStyle(kind: string, overrides?: ArrowheadOverrides): ArrowheadStyle {
+	return overrides?.[kind as EdgeKind] ?? DEFAULT_ARROWHEADS[kind as EdgeKind] ?? 'arrow';
+}
+
+export function isHollowArrowhead(style: ArrowheadStyle): boolean {
+	return style === 'triangle_outline' || style === 'diamond_outline';
+}
+
+/** SVG path for a right-pointing arrowhead whose tip sits at (`tipX`, `y`). */
+export function arrowheadPath(style: ArrowheadStyle, tipX: number, y: number): string {
+	switch (style) {
+		case 'triangle':
+		case 'triangle_outline':
+			return `M ${tipX - 8} ${y - 4.5} L ${tipX} ${y} L ${tipX - 8} ${y + 4.5} Z`;
+		case 'diamond':
+		case 'diamond_outline':
+			return `M ${tipX - 11} ${y} L ${tipX - 5.5} ${y - 4} L ${tipX} ${y} L ${tipX - 5.5} ${y + 4} Z`;
+		case 'dot':
+			return `M ${tipX - 7} ${y} A 3.5 3.5 0 1 0 ${tipX} ${y} A 3.5 3.5 0 1 0 ${tipX - 7} ${y} Z`;
+		case 'bar':
+			return `M ${tipX - 2} ${y - 5} L ${tipX} ${y - 5} L ${tipX} ${y + 5} L ${tipX - 2} ${y + 5} Z`;
+		case 'arrow':
+		default:
+			return `M ${tipX - 8} ${y - 4.5} L ${tipX} ${y} L ${tipX - 8} ${y + 4.5} L ${tipX - 6} ${y} Z`;
+	}
+}
@@ -0,0 +1,27 @@
+import type { Confidence } from '$lib/schema';
+
+/** How sure the extractor is of an edge, from least to most. */
+export const CONFIDENCE_ORDER = [
+	'Inferred',
+	'Runtime',
+	'Static',
+] as const satisfies readonly Confidence[];
+
+/** True when `confidence` is at least `min`; everything passes when `min` is unset. */
+export function meetsConfidence(confidence: Confidence, min?: Confidence): boolean {
+	if (!min) return true;
+	return CONFIDENCE_ORDER.indexOf(confidence) >= CONFIDENCE_ORDER.indexOf(min);
+}
+
+/**
+ * Stroke opacity multiplier for an edge. Inferred edges are guesses
+ * (e.g. a function passed as an argument), so they fade back behind the rest.
+ */
+export function confidenceOpacity(confidence: Confidence): number {
+	return confidence === 'Inferred' ? 0.45 : 1;
+}
+
+/** SVG `stroke-dasharray` for an edge, or `undefined` for a solid line. */
+export function confidenceDashArray(confidence: Confidence): string | undefined {
+	return confidence === 'Inferred' ? '4 3' : undefined;
+}
@@ -118,9 +118,9 @@ export function shapeEdgeAnchor(
  * Compute the edge anchor point on the perimeter of `from` in the direction of `to`.
  */
 export function getVisNodeEdgeAnchor(
-	from: { node: { kind: NodeKind }; isCenter: boolean; x: number; y: number },
+	from: { node: { kind: NodeKind }; isCenter: boolean; x: number; y: number; scale?: number },
 	to: { x: number; y: number },
 ): Point {
-	const visual = getNodeVisual(from.node.kind, from.isCenter);
+	const visual = getNodeVisual(from.node.kind, from.isCenter, from.scale);
 	return shapeEdgeAnchor(visual, from.x, from.y, to.x, to.y);
 }
@@ -1,5 +1,19 @@
 export type { NodeShape, NodeVisual, ShapeSpec } from './types';
 export { kindVisuals } from './palette';
 export { BASE_SPECS, nodeSvgPath, buildHeaderPath, isRectLike, isHeaderShape } from './shapes';
-export { getNodeVisual } from './node-visual';
+export { getNodeVisual, nodeScale } from './node-visual';
+export type { NodeScales } from './node-visual';
 export { shapeEdgeAnchor, getVisNodeEdgeAnchor } from './edge-anchor';
+export type { ArrowheadOverrides, ArrowheadStyle } from './arrowheads';
+export {
+	DEFAULT_ARROWHEADS,
+	arrowheadPath,
+	arrowheadStyle,
+	isHollowArrowhead,
+} from './arrowheads';
+export {
+	CONFIDENCE_ORDER,
+	confidenceDashArray,
+	confidenceOpacity,
+	meetsConfidence,
+} from './confidence';
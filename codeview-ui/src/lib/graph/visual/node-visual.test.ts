@@ -0,0 +1,63 @@
+import { describe, expect, it } from 'vite-plus/test';
+import type { Edge, Node } from '$lib/graph';
+import { getNodeBoundingBox } from '$lib/graph/layout/collision';
+import { buildScene } from '$lib/renderers/graph';
+import { getVisNodeEdgeAnchor } from './edge-anchor';
+import { getNodeVisual, nodeScale } from './node-visual';
+
+function node(id: string, kind: Node['kind']): Node {
+	const name = id.split('::').at(-1) ?? id;
+	return { id, name, kind, visibility: { kind: 'Public' }, attrs: [] } as Node;
+}
+
+describe('node scales', () => {
+	it('scales one kind and leaves the others alone', () => {
+		const module = getNodeVisual('Module', false);
+		const fn = getNodeVisual('Function', false);
+		const scales = { Module: 1.5 };
+
+		expect(nodeScale(scales, 'Module')).toBe(1.5);
+		expect(nodeScale(scales, 'Function')).toBe(1);
+		const scaled = getNodeVisual('Module', false, nodeScale(scales, 'Module'));
+		expect(scaled.width).toBe(Math.round(module.width * 1.5));
+		expect(scaled.height).toBe(Math.round(module.height * 1.5));
+		expect(getNodeVisual('Function', false, nodeScale(scales, 'Function'))).toEqual(fn);
+		expect(getNodeVisual('Module', false)).toEqual(module);
+	});
+
+	it('keeps collision boxes and edge anchors in step with the scaled size', () => {
+		const config = node('demo::Config', 'Struct');
+		const visual = getNodeVisual('Struct', false, 2);
+
+		expect(getNodeBoundingBox(config, false, 2).height).toBe(visual.height);
+		const anchor = getVisNodeEdgeAnchor(
+			{ node: config, isCenter: false, x: 0, y: 0, scale: 2 },
+			{ x: 1000, y: 0 },
+		);
+		expect(anchor.x).toBeCloseTo(visual.width / 2);
+	});
+
+	it('only applies to the scene they are passed to', () => {
+		const crate = node('demo', 'Crate');
+		const run = node('demo::run', 'Function');
+		const edges: Edge[] = [
+			{ from: 'demo', to: 'demo::run', kind: 'Contains', confidence: 'Static' } as Edge,
+		];
+		const graph = { nodes: [crate, run], edges };
+		const opts = { showStructural: true, showSemantic: true };
+
+		const scaled = buildScene(graph, crate, 'grid', { ...opts, nodeScales: { Function: 0.5 } });
+		const plain = buildScene(graph, crate, 'grid', opts);
+
+		const scaledRun = scaled.nodes.find((visNode) => visNode.node.id === 'demo::run');
+		const plainRun = plain.nodes.find((visNode) => visNode.node.id === 'demo::run');
+		expect(scaledRun?.scale).toBe(0.5);
+		expect(plainRun?.scale).toBe(1);
+		expect(scaled.nodes.find((visNode) => visNode.isCenter)?.scale).toBe(1);
+	});
+
+	it('rejects scales that are not positive numbers', () => {
+		expect(() => nodeScale({ Crate: 0 }, 'Crate')).toThrow(RangeError);
+		expect(() => nodeScale({ Crate: Number.NaN }, 'Crate')).toThrow(RangeError);
+	});
+});
@@ -6,11 +6,28 @@ import { BASE_SPECS, nodeSvgPath, buildHeaderPath } from './shapes';
 const CENTER_SCALE = 1.15;
 const visualCache = new Map<string, NodeVisual>();
 
-function buildNodeVisual(kind: NodeKind, isCenter: boolean): NodeVisual {
+/**
+ * Per-kind size multipliers (1 is the default size), e.g. to make crates and
+ * modules stand out over leaf functions. Layouts take them through
+ * `LayoutOptions.nodeScales` and record each node's on `VisNode.scale`;
+ * kinds left out keep their default size.
+ */
+export type NodeScales = Readonly<Partial<Record<NodeKind, number>>>;
+
+/** The multiplier `scales` gives `kind`, or 1. */
+export function nodeScale(scales: NodeScales | undefined, kind: NodeKind): number {
+	const scale = scales?.[kind] ?? 1;
+	if (!Number.isFinite(scale) || scale <= 0) {
+		throw new RangeError(`node scale must be a positive number, got ${String(scale)}`);
+	}
+	return scale;
+}
+
+function buildNodeVisual(kind: NodeKind, isCenter: boolean, scale: number): NodeVisual {
 	const spec = BASE_SPECS[kind];
-	const scale = isCenter ? CENTER_SCALE : 1;
-	const w = Math.round(spec.width * scale);
-	const h = Math.round(spec.height * scale);
+	const size = (isCenter ? CENTER_SCALE : 1) * scale;
+	const w = Math.round(spec.width * size);
+	const h = Math.round(spec.height * size);
 	const cr = spec.cornerRadius;
 	const colors = kindVisuals[kind];
 	const { headerPath, headerHeight } = buildHeaderPath(spec.shape, w, h, cr, isCenter);
@@ -33,14 +50,17 @@ function buildNodeVisual(kind: NodeKind, isCenter: boolean): NodeVisual {
 }
 
 /**
- * Get full visual descriptor for a node kind + center flag.
+ * Get full visual descriptor for a node kind + center flag, sized by the
+ * node's `scale` (see `NodeScales`). Layout spacing, collision boxes, edge
+ * anchors and exported shapes all pass the same `VisNode.scale`, so they
+ * stay in step.
  * Deterministic and cached — safe to call in tight loops and derived computations.
  */
-export function getNodeVisual(kind: NodeKind, isCenter: boolean): NodeVisual {
-	const key = `${kind}:${isCenter}`;
+export function getNodeVisual(kind: NodeKind, isCenter: boolean, scale = 1): NodeVisual {
+	const key = `${kind}:${isCenter}:${scale}`;
 	let v = visualCache.get(key);
 	if (!v) {
-		v = buildNodeVisual(kind, isCenter);
+		v = buildNodeVisual(kind, isCenter, scale);
 		visualCache.set(key, v);
 	}
 	return v;
@@ -6,6 +6,8 @@ import {
 	computeSceneLabels,
 	computeEdgeSimilarityGroups,
 	filterEdges,
+	filterNodeKinds,
+	parallelEdgeOffsets,
 	structuralEdgeKinds,
 	semanticEdgeKinds,
 } from './graph';
@@ -98,6 +100,72 @@ describe('filterEdges', () => {
 		const result = filterEdges(edges, { showStructural: false, showSemantic: false });
 		expect(result).toHaveLength(0);
 	});
+
+	it('keeps only the enabled edge kinds', () => {
+		const result = filterEdges(edges, {
+			showStructural: true,
+			showSemantic: true,
+			edgeKinds: new Set(['Contains', 'Implements']),
+		});
+		expect(result.map((e) => e.kind)).toEqual(['Contains', 'Implements']);
+	});
+
+	it('does not let edge kinds override the structural/semantic toggles', () => {
+		const result = filterEdges(edges, {
+			showStructural: false,
+			showSemantic: true,
+			edgeKinds: new Set(['Contains', 'UsesType']),
+		});
+		expect(result.map((e) => e.kind)).toEqual(['UsesType']);
+	});
+
+	it('drops edges below the minimum confidence', () => {
+		const calls: Edge[] = [
+			makeEdge('a', 'b', 'CallsStatic'),
+			{ ...makeEdge('a', 'c', 'CallsRuntime'), confidence: 'Runtime' },
+			{ ...makeEdge('a', 'd', 'CallsRuntime'), confidence: 'Inferred' },
+		];
+		const targets = (minConfidence?: Edge['confidence']) =>
+			filterEdges(calls, { showStructural: true, showSemantic: true, minConfidence }).map(
+				(e) => e.to,
+			);
+
+		expect(targets()).toEqual(['b', 'c', 'd']);
+		expect(targets('Runtime')).toEqual(['b', 'c']);
+		expect(targets('Static')).toEqual(['b']);
+	});
+});
+
+// ---------------------------------------------------------------------------
+// filterNodeKinds
+// ---------------------------------------------------------------------------
+
+describe('filterNodeKinds', () => {
+	const graph = makeGraph(
+		[
+			makeNode('my_crate', 'my_crate', 'Crate'),
+			makeNode('my_crate::Foo', 'Foo', 'Struct'),
+			makeNode('my_crate::Kind', 'Kind', 'Enum'),
+			makeNode('my_crate::run', 'run', 'Function'),
+		],
+		[
+			makeEdge('my_crate', 'my_crate::Foo', 'Contains'),
+			makeEdge('my_crate::Foo', 'my_crate::Kind', 'UsesType'),
+			makeEdge('my_crate::run', 'my_crate::Foo', 'UsesType'),
+		],
+	);
+
+	it('drops other kinds and the edges touching them', () => {
+		const result = filterNodeKinds(graph, new Set(['Struct', 'Enum']), 'my_crate::Foo');
+		expect(result.nodes.map((n) => n.id)).toEqual(['my_crate::Foo', 'my_crate::Kind']);
+		expect(result.edges.map((e) => [e.from, e.to])).toEqual([['my_crate::Foo', 'my_crate::Kind']]);
+	});
+
+	it('keeps the selected node whatever its kind', () => {
+		const result = filterNodeKinds(graph, new Set(['Struct']), 'my_crate::run');
+		expect(result.nodes.map((n) => n.id)).toEqual(['my_crate::Foo', 'my_crate::run']);
+		expect(result.edges).toHaveLength(1);
+	});
 });
 
 // ---------------------------------------------------------------------------
@@ -145,6 +213,46 @@ describe('computeEdgeSimilarityGroups', () => {
 	});
 });
 
+// ---------------------------------------------------------------------------
+// parallelEdgeOffsets
+// ---------------------------------------------------------------------------
+
+describe('parallelEdgeOffsets', () => {
+	it('fans out edges joining the same pair of nodes and leaves lone edges straight', () => {
+		const center = makeNode('a', 'A');
+		const graph = makeGraph(
+			[center, makeNode('b', 'B'), makeNode('c', 'C')],
+			[
+				makeEdge('a', 'b', 'UsesType'),
+				makeEdge('a', 'b', 'CallsStatic'),
+				makeEdge('b', 'a', 'CallsRuntime'),
+				makeEdge('a', 'c', 'UsesType'),
+			],
+		);
+		const base = buildBaseScene(graph, center, 'force', {
+			showStructural: true,
+			showSemantic: true,
+		});
+		const offsets = parallelEdgeOffsets(base.edges);
+		const offsetOf = (from: string, to: string, kind: string) =>
+			offsets[
+				base.edges.findIndex(
+					(e) => e.from.node.id === from && e.to.node.id === to && e.kind === kind,
+				)
+			];
+
+		expect(offsetOf('a', 'c', 'UsesType')).toBe(0);
+		// Measured against a single a → b direction, the three sit at distinct offsets.
+		const alongAB = [
+			offsetOf('a', 'b', 'UsesType'),
+			offsetOf('a', 'b', 'CallsStatic'),
+			-offsetOf('b', 'a', 'CallsRuntime'),
+		];
+		expect(new Set(alongAB).size).toBe(3);
+		expect(alongAB.reduce((sum, offset) => sum + offset, 0)).toBe(0);
+	});
+});
+
 // ---------------------------------------------------------------------------
 // buildBaseScene (Stage 1)
 // ---------------------------------------------------------------------------
@@ -192,6 +300,32 @@ describe('buildBaseScene', () => {
 		const edgeKinds = base.edges.map((e) => e.kind);
 		expect(edgeKinds).not.toContain('Contains');
 	});
+
+	it('lays out only the enabled edge kinds', () => {
+		const module = makeNode('my_crate::m', 'm', 'Module');
+		const run = makeNode('my_crate::m::run', 'run');
+		const step = makeNode('my_crate::m::step', 'step');
+		const graph = makeGraph(
+			[module, run, step],
+			[
+				makeEdge('my_crate::m', 'my_crate::m::run', 'Contains'),
+				makeEdge('my_crate::m', 'my_crate::m::step', 'Contains'),
+				makeEdge('my_crate::m::run', 'my_crate::m::step', 'CallsStatic'),
+			],
+		);
+		const layers = (edgeKinds?: Set<Edge['kind']>) =>
+			new Map(
+				buildBaseScene(graph, run, 'hierarchical', {
+					showStructural: true,
+					showSemantic: true,
+					edgeKinds,
+				}).nodes.map((node) => [node.node.id, node.layer]),
+			);
+
+		expect(layers().get('my_crate::m::step')).toBe(layers().get('my_crate::m::run'));
+		const callsOnly = layers(new Set(['CallsStatic']));
+		expect(callsOnly.get('my_crate::m::step')).toBe((callsOnly.get('my_crate::m::run') ?? NaN) + 1);
+	});
 });
 
 // ---------------------------------------------------------------------------
@@ -361,7 +495,7 @@ describe('buildScene', () => {
 			[makeEdge('my_crate::Foo', 'my_crate::Bar', 'UsesType')],
 		);
 
-		for (const mode of ['ego', 'force', 'hierarchical', 'radial'] as const) {
+		for (const mode of ['ego', 'force', 'hierarchical', 'radial', 'grid', 'circular'] as const) {
 			const scene = buildScene(graph, center, mode, {
 				showStructural: true,
 				showSemantic: true,
@@ -1,8 +1,9 @@
-import type { Edge, EdgeKind, Graph, Node } from '$lib/graph';
+import type { Confidence, Edge, EdgeKind, Graph, Node, NodeKind } from '$lib/graph';
 import type { LayoutMode, VisEdge, VisNode } from '$lib/graph/layout';
 import type { LabelPosition, SimilarityInfo } from '$lib/graph/labels';
 import { computeLayout } from '$lib/graph/layout';
 import { getLabelProvider, computeAllLabelPositions } from '$lib/graph/labels';
+import { meetsConfidence, type NodeScales } from '$lib/graph/visual';
 
 /**
  * A logical group of scene elements that belong together.
@@ -50,29 +51,58 @@ export interface GraphRenderer<T> {
 export const structuralEdgeKinds: readonly EdgeKind[] = ['Contains', 'Defines'];
 export const semanticEdgeKinds: readonly EdgeKind[] = [
 	'UsesType',
+	'Returns',
 	'Implements',
+	'Supertrait',
 	'CallsStatic',
 	'CallsRuntime',
+	'CallsMacro',
 	'Derives',
 	'ReExports',
+	'HasField',
 ];
 
 // ---------------------------------------------------------------------------
 // Utilities
 // ---------------------------------------------------------------------------
 
-/** Filter edges by structural/semantic toggles. */
+/**
+ * Filter edges by structural/semantic toggles and, when given, the set of
+ * individually enabled edge kinds and the lowest confidence worth drawing.
+ */
 export function filterEdges(
 	edges: Edge[],
-	opts: { showStructural: boolean; showSemantic: boolean },
+	opts: {
+		showStructural: boolean;
+		showSemantic: boolean;
+		edgeKinds?: ReadonlySet<EdgeKind>;
+		minConfidence?: Confidence;
+	},
 ): Edge[] {
 	return edges.filter((edge: Edge) => {
+		if (opts.edgeKinds && !opts.edgeKinds.has(edge.kind)) return false;
+		if (!meetsConfidence(edge.confidence, opts.minConfidence)) return false;
 		if ((structuralEdgeKinds as readonly string[]).includes(edge.kind)) return opts.showStructural;
 		if ((semanticEdgeKinds as readonly string[]).includes(edge.kind)) return opts.showSemantic;
 		return true;
 	});
 }
 
+/**
+ * Keep only nodes of the given kinds, and the edges between them. The
+ * selected node always stays so the layout keeps its anchor.
+ */
+export function filterNodeKinds(
+	graph: Graph,
+	nodeKinds: ReadonlySet<NodeKind>,
+	selectedId: string,
+): Graph {
+	const nodes = graph.nodes.filter((node) => node.id === selectedId || nodeKinds.has(node.kind));
+	const kept = new Set(nodes.map((node) => node.id));
+	const edges = graph.edges.filter((edge) => kept.has(edge.from) && kept.has(edge.to));
+	return { nodes, edges };
+}
+
 /** Build a lookup map from node id → VisNode. */
 export function buildNodeMap(nodes: VisNode[]): Map<string, VisNode> {
 	const map = new Map<string, VisNode>();
@@ -82,6 +112,37 @@ export function buildNodeMap(nodes: VisNode[]): Map<string, VisNode> {
 	return map;
 }
 
+/** Perpendicular distance between neighbouring parallel edges. */
+export const PARALLEL_EDGE_GAP = 14;
+
+/**
+ * Perpendicular offset for each edge so that edges joining the same pair of
+ * nodes, in either direction, fan out instead of drawing over one another.
+ * A lone edge gets 0. Offsets are signed relative to the edge's own direction
+ * (positive bends left of travel), so an `a → b` and a `b → a` edge separate.
+ */
+export function parallelEdgeOffsets(edges: VisEdge[]): number[] {
+	const pairs = new Map<string, number[]>();
+	edges.forEach((edge, index) => {
+		const ids = [edge.from.node.id, edge.to.node.id].sort();
+		const key = `${ids[0]}\u0000${ids[1]}`;
+		const indices = pairs.get(key);
+		if (indices) indices.push(index);
+		else pairs.set(key, [index]);
+	});
+
+	const offsets = edges.map(() => 0);
+	for (const indices of pairs.values()) {
+		if (indices.length < 2) continue;
+		indices.forEach((edgeIndex, k) => {
+			const edge = edges[edgeIndex];
+			const offset = (k - (indices.length - 1) / 2) * PARALLEL_EDGE_GAP;
+			offsets[edgeIndex] = edge.from.node.id <= edge.to.node.id ? offset : -offset;
+		});
+	}
+	return offsets;
+}
+
 /** Compute edge angle similarity groups for label layout. */
 export function computeEdgeSimilarityGroups(
 	edges: VisEdge[],
@@ -139,20 +200,42 @@ export type BaseScene = {
 };
 
 /**
- * Build the base scene: filter edges, run layout, compute similarity groups and scene groups.
+ * Build the base scene: filter nodes and edges, run layout, compute similarity groups and
+ * scene groups.
+ * Both are filtered before layout, so toggling kinds reflows the nodes around the ones left.
+ * Edges below `minConfidence` are dropped outright; those below `layoutMinConfidence` are
+ * still drawn but don't shape the layout (see `LayoutOptions.minConfidence`). `nodeScales`
+ * sizes nodes per kind (see `NodeScales`).
  * This is the expensive stage — cache it behind a KeyedMemo keyed on
- * (graph, selected.id, layoutMode, showStructural, showSemantic).
+ * (graph, selected.id, layoutMode, showStructural, showSemantic, edgeKinds, nodeKinds,
+ * minConfidence, layoutMinConfidence, seed, nodeScales).
  */
 export function buildBaseScene(
 	graph: Graph,
 	selected: Node,
 	layoutMode: LayoutMode,
-	opts: { showStructural: boolean; showSemantic: boolean },
+	opts: {
+		showStructural: boolean;
+		showSemantic: boolean;
+		edgeKinds?: ReadonlySet<EdgeKind>;
+		nodeKinds?: ReadonlySet<NodeKind>;
+		minConfidence?: Confidence;
+		layoutMinConfidence?: Confidence;
+		seed?: number;
+		nodeScales?: NodeScales;
+	},
 ): BaseScene {
-	const filteredEdges = filterEdges(graph.edges, opts);
-	const filteredGraph: Graph = { nodes: graph.nodes, edges: filteredEdges };
+	const kindGraph = opts.nodeKinds
+		? filterNodeKinds(graph, opts.nodeKinds, selected.id)
+		: graph;
+	const filteredEdges = filterEdges(kindGraph.edges, opts);
+	const filteredGraph: Graph = { nodes: kindGraph.nodes, edges: filteredEdges };
 
-	const visData = computeLayout(filteredGraph, selected, layoutMode);
+	const visData = computeLayout(filteredGraph, selected, layoutMode, {
+		seed: opts.seed,
+		minConfidence: opts.layoutMinConfidence,
+		nodeScales: opts.nodeScales,
+	});
 
 	const nodeMap = buildNodeMap(visData.nodes);
 	const similarityGroups = computeEdgeSimilarityGroups(visData.edges, nodeMap);
@@ -248,7 +331,16 @@ export function buildScene(
 	graph: Graph,
 	selected: Node,
 	layoutMode: LayoutMode,
-	opts: { showStructural: boolean; showSemantic: boolean },
+	opts: {
+		showStructural: boolean;
+		showSemantic: boolean;
+		edgeKinds?: ReadonlySet<EdgeKind>;
+		nodeKinds?: ReadonlySet<NodeKind>;
+		minConfidence?: Confidence;
+		layoutMinConfidence?: Confidence;
+		seed?: number;
+		nodeScales?: NodeScales;
+	},
 ): GraphScene {
 	const base = buildBaseScene(graph, selected, layoutMode, opts);
 	const nodeMap = buildNodeMap(base.nodes);
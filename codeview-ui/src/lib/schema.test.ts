@@ -0,0 +1,60 @@
+import { describe, expect, it } from 'vitest';
+import { GRAPH_SCHEMA_VERSION, parseWorkspace } from '$lib/schema';
+import generatedSchema from '$lib/generated/codeview.schema.json';
+
+const publicVisibility = { kind: 'Public' } as const;
+
+describe('parseWorkspace', () => {
+	it('expands interned doc links back onto each node', () => {
+		const workspace = parseWorkspace({
+			crates: [
+				{
+					id: 'demo',
+					name: 'demo',
+					version: '0.1.0',
+					nodes: [
+						{
+							id: 'demo::run',
+							name: 'run',
+							kind: 'Function',
+							visibility: publicVisibility,
+							attrs: [],
+							doc_link_refs: { Config: 0, 'Vec<T>': 1 },
+						},
+						{
+							id: 'demo::Config',
+							name: 'Config',
+							kind: 'Struct',
+							visibility: publicVisibility,
+							attrs: [],
+							doc_links: { run: 'demo::run' },
+						},
+					],
+					edges: [],
+					doc_link_table: ['demo::Config', 'alloc::vec::Vec'],
+				},
+			],
+			external_crates: [],
+			cross_crate_edges: [],
+		});
+
+		const [crate] = workspace.crates;
+		expect(crate.doc_link_table).toBeUndefined();
+		expect(crate.nodes[0].doc_links).toEqual({
+			Config: 'demo::Config',
+			'Vec<T>': 'alloc::vec::Vec',
+		});
+		expect(crate.nodes[0].doc_link_refs).toBeUndefined();
+		expect(crate.nodes[1].doc_links).toEqual({ run: 'demo::run' });
+	});
+
+	it('reads graphs up to the generated schema version', () => {
+		const empty = { crates: [], external_crates: [], cross_crate_edges: [] };
+		expect(GRAPH_SCHEMA_VERSION).toBe(generatedSchema['x-schema-version']);
+		expect(parseWorkspace(empty).version).toBe(GRAPH_SCHEMA_VERSION);
+		expect(parseWorkspace({ ...empty, version: 0 }).version).toBe(GRAPH_SCHEMA_VERSION);
+		expect(() => parseWorkspace({ ...empty, version: GRAPH_SCHEMA_VERSION + 1 })).toThrow(
+			/upgrade codeview/,
+		);
+	});
+});
@@ -102,11 +102,15 @@ export const EdgeKindSchema = v.picklist([
 	'Contains',
 	'Defines',
 	'Implements',
+	'Supertrait',
 	'UsesType',
+	'Returns',
 	'CallsStatic',
 	'CallsRuntime',
+	'CallsMacro',
 	'Derives',
 	'ReExports',
+	'HasField',
 ]);
 
 export const ConfidenceSchema = v.picklist(['Static', 'Runtime', 'Inferred']);
@@ -346,6 +350,7 @@ export const FunctionSignatureSchema = v.object({
 	is_const: v.boolean(),
 	abi: v.optional(v.nullable(v.string())),
 	is_c_variadic: v.optional(v.boolean()),
+	is_async_trait: v.optional(v.boolean()),
 	generics: v.optional(GenericsSchema),
 });
 
@@ -380,9 +385,12 @@ export const NodeSchema = v.object({
 	name: v.string(),
 	kind: NodeKindSchema,
 	visibility: VisibilitySchema,
+	effective_visibility: v.optional(v.nullable(VisibilitySchema)),
 	span: v.optional(v.nullable(SpanSchema)),
 	line_count: v.optional(v.nullable(v.number())),
 	attrs: v.array(v.string()),
+	cfg: v.optional(v.nullable(v.string())),
+	features: v.optional(v.array(v.string())),
 	is_external: v.optional(v.boolean()),
 	is_deprecated: v.optional(v.boolean()),
 	is_unsafe: v.optional(v.boolean()),
@@ -391,6 +399,10 @@ export const NodeSchema = v.object({
 	is_stripped: v.optional(v.boolean()),
 	has_stripped_fields: v.optional(v.boolean()),
 	has_stripped_variants: v.optional(v.boolean()),
+	is_public_via_reexport: v.optional(v.boolean()),
+	is_default: v.optional(v.boolean()),
+	is_entry: v.optional(v.boolean()),
+	complexity: v.optional(v.nullable(v.number())),
 	is_dyn_compatible: v.optional(v.nullable(v.boolean())),
 	deprecation: v.optional(v.nullable(DeprecationSchema)),
 	stability: v.optional(v.nullable(StabilityInfoSchema)),
@@ -403,6 +415,8 @@ export const NodeSchema = v.object({
 	generics: v.optional(GenericsSchema),
 	docs: v.optional(v.nullable(v.string())),
 	doc_links: v.optional(v.record(v.string(), v.string())),
+	// Interned doc links: link text → index into the crate's `doc_link_table`.
+	doc_link_refs: v.optional(v.record(v.string(), v.number())),
 	impl_type: v.optional(v.nullable(ImplTypeSchema)),
 	impl_category: v.optional(v.nullable(ImplCategorySchema)),
 	parent_impl: v.optional(v.nullable(v.string())),
@@ -438,6 +452,7 @@ export const EdgeSchema = v.object({
 	confidence: ConfidenceSchema,
 	occurrences: v.optional(v.array(SpanSchema)),
 	is_glob: v.optional(v.boolean()),
+	label: v.optional(v.nullable(v.string())),
 });
 
 // --- Per-crate graph ---
@@ -451,6 +466,8 @@ export const CrateGraphSchema = v.object({
 	// public_path → canonical_node_id; skipped in JSON when empty (matches
 	// `#[serde(skip_serializing_if = "HashMap::is_empty")]` on the Rust side).
 	aliases: v.optional(v.record(v.string(), v.string())),
+	// Present only in graphs written with `--intern-doc-links`.
+	doc_link_table: v.optional(v.array(v.string())),
 });
 
 export const ExternalCrateSchema = v.object({
@@ -851,9 +868,50 @@ export const StaticNodeDetailShardSchema = v.object({
 export type StaticNodeDetailShard = v.InferOutput<typeof StaticNodeDetailShardSchema>;
 
 /**
- * Parse and validate a raw JSON object as a Workspace.
- * Throws a ValiError if validation fails.
+ * Graph schema version this UI reads, matching `codeview_core::SCHEMA_VERSION`
+ * (the `x-schema-version` of the generated JSON schema).
+ */
+export const GRAPH_SCHEMA_VERSION = 1;
+
+/**
+ * Parse and validate a raw JSON object as a Workspace, migrating graphs
+ * from older schema versions like `codeview_core::migrate_workspace`.
+ * Throws if the graph comes from a newer codeview or fails validation.
  */
 export function parseWorkspace(data: unknown): WorkspaceOutput {
-	return v.parse(WorkspaceSchema, data);
+	const workspace = v.parse(WorkspaceSchema, data);
+	const version = workspace.version ?? GRAPH_SCHEMA_VERSION;
+	if (version > GRAPH_SCHEMA_VERSION) {
+		throw new Error(
+			`graph has schema version ${version}, but this codeview reads up to ${GRAPH_SCHEMA_VERSION}; upgrade codeview or regenerate the graph`,
+		);
+	}
+	// One step per format change goes here, oldest first.
+	workspace.version = GRAPH_SCHEMA_VERSION;
+	for (const crate of workspace.crates) expandDocLinks(crate);
+	return workspace;
+}
+
+/**
+ * Restore inline `doc_links` on a graph written with `--intern-doc-links`
+ * and clear its `doc_link_table`, mirroring `Graph::expand_doc_links`.
+ * The rest of the UI only reads `doc_links`. A no-op for graphs that were
+ * never interned.
+ */
+export function expandDocLinks(graph: {
+	nodes: Pick<Node, 'doc_links' | 'doc_link_refs'>[];
+	doc_link_table?: string[];
+}): void {
+	const table = graph.doc_link_table ?? [];
+	for (const node of graph.nodes) {
+		if (!node.doc_link_refs) continue;
+		const links = { ...node.doc_links };
+		for (const [text, slot] of Object.entries(node.doc_link_refs)) {
+			const target = table[slot];
+			if (target !== undefined) links[text] = target;
+		}
+		node.doc_links = links;
+		delete node.doc_link_refs;
+	}
+	delete graph.doc_link_table;
 }
@@ -1,5 +1,5 @@
 import { Result } from 'better-result';
-import { readFile } from 'node:fs/promises';
+import { readFile, stat } from 'node:fs/promises';
 import { join, resolve } from 'node:path';
 import type { RequestEvent } from '@sveltejs/kit';
 import type {
@@ -162,6 +162,8 @@ export function getProviderInternals(): LocalProviderInternals | null {
 
 export function createLocalProvider(): DataProvider {
 	let cached: Workspace | null = null;
+	/** Modification time of the graph file `cached` was read from. */
+	let cachedMtimeMs: number | null = null;
 	const registry = createCratesIoAdapter();
 	const sourceFileCache = new Map<string, string>();
 	const SOURCE_FILE_CACHE_MAX = 512;
@@ -891,22 +893,31 @@ export function createLocalProvider(): DataProvider {
 	let loadingPromise: Promise<Workspace | null> | null = null;
 
 	async function loadWorkspace(): Promise<Workspace | null> {
-		if (cached) return cached;
+		const graphPath = process.env.CODEVIEW_GRAPH;
+		if (!graphPath) return null;
+		// `codeview ui --watch` rewrites the graph in place; reload when it changes.
+		const statResult = await Result.tryPromise(() => stat(graphPath));
+		const mtimeMs = statResult.isOk() ? statResult.value.mtimeMs : null;
+		if (cached && mtimeMs === cachedMtimeMs) return cached;
 		if (loadingPromise) return loadingPromise;
 		loadingPromise = (async () => {
-			const graphPath = process.env.CODEVIEW_GRAPH;
-			if (!graphPath) return null;
 			const readResult = await Result.tryPromise(() => readFile(graphPath, 'utf-8'));
 			if (readResult.isErr()) {
 				log.error`Failed to read workspace file: ${readResult.error}`;
-				return null;
+				return cached;
 			}
 			const parseResult = Result.try(() => JSON.parse(readResult.value));
 			if (parseResult.isErr()) {
 				log.error`Failed to parse workspace JSON`;
-				return null;
+				return cached;
 			}
-			cached = parseWorkspace(parseResult.value) as Workspace;
+			const workspaceResult = Result.try(() => parseWorkspace(parseResult.value));
+			if (workspaceResult.isErr()) {
+				log.error`Failed to load workspace graph: ${workspaceResult.error}`;
+				return cached;
+			}
+			cached = workspaceResult.value as Workspace;
+			cachedMtimeMs = mtimeMs;
 			return cached;
 		})().finally(() => {
 			loadingPromise = null;
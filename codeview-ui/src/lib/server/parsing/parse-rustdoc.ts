@@ -50,7 +50,7 @@ export interface ParseProgress {
 export interface ParseRustdocOptions {
 	manifestPath?: string;
 	rootFile?: string;
-	callMode?: 'strict' | 'ambiguous';
+	callMode?: 'strict' | 'ambiguous' | 'heuristic';
 	rustdocName?: string;
 	cargoWorkingDir?: string;
 	onProgress?: (progress: ParseProgress) => void;
@@ -125,8 +125,6 @@ export async function parseWithRustBinary(
 			jsonPath,
 			'--crate-name',
 			normalizedName,
-			'--version',
-			'0.0.0',
 			'--out',
 			graphPath,
 			'--call-mode',
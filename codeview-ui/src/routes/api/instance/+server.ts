@@ -0,0 +1,13 @@
+import { isHosted } from '$lib/platform';
+import { json } from '@sveltejs/kit';
+import type { RequestHandler } from './$types';
+
+/**
+ * Identifies a local server to `codeview ps --kill`, which only signals a
+ * registered pid once the server on its port answers with that same pid.
+ */
+export const GET: RequestHandler = () => {
+	const pid = isHosted ? Number.NaN : Number(process.env.CODEVIEW_INSTANCE);
+	if (!Number.isInteger(pid) || pid <= 0) return new Response(null, { status: 404 });
+	return json({ pid });
+};